@@ -0,0 +1,8 @@
+CREATE TABLE pending_verifications (
+    token VARCHAR(64) NOT NULL,
+    user_id INT NOT NULL,
+    email VARCHAR(100) NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    CONSTRAINT pending_verifications_pkey PRIMARY KEY (token),
+    CONSTRAINT pending_verifications_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+ALTER TABLE users DROP COLUMN sessions_revoked_at;
+
+DROP TABLE password_resets;
\ No newline at end of file
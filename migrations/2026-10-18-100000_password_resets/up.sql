@@ -0,0 +1,9 @@
+CREATE TABLE password_resets (
+    token VARCHAR(64) NOT NULL,
+    user_id INT NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    CONSTRAINT password_resets_pkey PRIMARY KEY (token),
+    CONSTRAINT password_resets_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+ALTER TABLE users ADD COLUMN sessions_revoked_at TIMESTAMP;
@@ -0,0 +1,8 @@
+CREATE TABLE login_failures (
+    scope VARCHAR(20) NOT NULL,
+    identifier VARCHAR(255) NOT NULL,
+    failed_attempts INT NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMP NOT NULL,
+    locked_until TIMESTAMP,
+    CONSTRAINT login_failures_pkey PRIMARY KEY (scope, identifier)
+);
@@ -0,0 +1,3 @@
+ALTER TABLE user_messages DROP COLUMN nonce;
+
+DROP TABLE user_keys;
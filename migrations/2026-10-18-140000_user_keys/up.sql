@@ -0,0 +1,9 @@
+CREATE TABLE user_keys (
+    user_id INT PRIMARY KEY,
+    public_key VARCHAR(64) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT user_keys_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Messages with a nonce are encrypted, older messages without one are stored as plain text
+ALTER TABLE user_messages ADD COLUMN nonce VARCHAR(24);
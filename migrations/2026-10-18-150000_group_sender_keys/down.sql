@@ -0,0 +1,6 @@
+DROP TABLE group_sender_keys;
+
+ALTER TABLE group_messages DROP COLUMN nonce;
+ALTER TABLE group_messages DROP COLUMN key_epoch;
+
+ALTER TABLE groups DROP COLUMN key_epoch;
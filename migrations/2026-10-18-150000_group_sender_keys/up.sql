@@ -0,0 +1,22 @@
+-- Every change of the members starts a new key epoch, in which each sender distributes a new sender key
+ALTER TABLE groups ADD COLUMN key_epoch INT NOT NULL DEFAULT 0;
+
+-- Messages with a nonce are encrypted with the sender key of their epoch, older messages are stored as plain text
+ALTER TABLE group_messages ADD COLUMN key_epoch INT;
+ALTER TABLE group_messages ADD COLUMN nonce VARCHAR(24);
+
+CREATE TABLE group_sender_keys (
+    group_id INT NOT NULL,
+    key_epoch INT NOT NULL,
+    sender_id INT NOT NULL,
+    recipient_id INT NOT NULL,
+    distributor_id INT NOT NULL,
+    encrypted_key VARCHAR(96) NOT NULL,
+    nonce VARCHAR(24) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_id, key_epoch, sender_id, recipient_id),
+    CONSTRAINT group_sender_keys_group_id_fkey FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    CONSTRAINT group_sender_keys_sender_id_fkey FOREIGN KEY (sender_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT group_sender_keys_recipient_id_fkey FOREIGN KEY (recipient_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT group_sender_keys_distributor_id_fkey FOREIGN KEY (distributor_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+ALTER TABLE group_messages DROP COLUMN client_id;
+ALTER TABLE user_messages DROP COLUMN client_id;
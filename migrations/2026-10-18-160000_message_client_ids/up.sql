@@ -0,0 +1,3 @@
+-- Messages sent from the outbox carry a key generated by the client, so a retried message is only stored once
+ALTER TABLE user_messages ADD COLUMN client_id VARCHAR(32) UNIQUE;
+ALTER TABLE group_messages ADD COLUMN client_id VARCHAR(32) UNIQUE;
@@ -0,0 +1,10 @@
+-- Do-not-disturb schedule and muted conversations of a user. The schedule may span midnight, e.g. from 22:00 to 07:00
+CREATE TABLE notification_settings (
+    user_id INT PRIMARY KEY,
+    dnd_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    dnd_start TIME NOT NULL DEFAULT '22:00',
+    dnd_end TIME NOT NULL DEFAULT '07:00',
+    muted_user_ids INT[] NOT NULL DEFAULT '{}',
+    muted_group_ids INT[] NOT NULL DEFAULT '{}',
+    CONSTRAINT notification_settings_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
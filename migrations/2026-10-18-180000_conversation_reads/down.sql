@@ -0,0 +1,2 @@
+DROP INDEX group_messages_receiver_id_send_date_idx;
+DROP TABLE conversation_reads;
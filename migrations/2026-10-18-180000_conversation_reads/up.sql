@@ -0,0 +1,12 @@
+-- When a user last read a chat with another user or a group, messages sent after that are unread
+CREATE TABLE conversation_reads (
+    user_id INT NOT NULL,
+    is_group BOOLEAN NOT NULL,
+    conversation_id INT NOT NULL,
+    last_read_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT conversation_reads_pkey PRIMARY KEY (user_id, is_group, conversation_id),
+    CONSTRAINT conversation_reads_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- The conversation list looks up the newest messages of every group
+CREATE INDEX group_messages_receiver_id_send_date_idx ON group_messages (receiver_id, send_date);
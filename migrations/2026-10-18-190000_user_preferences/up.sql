@@ -0,0 +1,7 @@
+-- Preferences a user syncs to the account as TOML, e.g. the theme and the notification preferences
+CREATE TABLE user_preferences (
+    user_id INT PRIMARY KEY,
+    preferences TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT user_preferences_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
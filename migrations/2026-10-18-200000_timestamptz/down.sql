@@ -0,0 +1,3 @@
+ALTER TABLE conversation_reads ALTER COLUMN last_read_at TYPE TIMESTAMP;
+ALTER TABLE group_messages ALTER COLUMN send_date TYPE TIMESTAMP;
+ALTER TABLE user_messages ALTER COLUMN send_date TYPE TIMESTAMP;
@@ -0,0 +1,5 @@
+-- Message times are stored with their time zone, so every user sees them in their own time zone.
+-- The existing times were stored in the time zone of the database session and are converted from it
+ALTER TABLE user_messages ALTER COLUMN send_date TYPE TIMESTAMPTZ;
+ALTER TABLE group_messages ALTER COLUMN send_date TYPE TIMESTAMPTZ;
+ALTER TABLE conversation_reads ALTER COLUMN last_read_at TYPE TIMESTAMPTZ;
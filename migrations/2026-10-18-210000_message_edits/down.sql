@@ -0,0 +1,2 @@
+ALTER TABLE group_messages DROP COLUMN edited_at;
+ALTER TABLE user_messages DROP COLUMN edited_at;
@@ -0,0 +1,3 @@
+-- Set when the sender edits a message, so the edit is synced to the other members of the chat
+ALTER TABLE user_messages ADD COLUMN edited_at TIMESTAMPTZ;
+ALTER TABLE group_messages ADD COLUMN edited_at TIMESTAMPTZ;
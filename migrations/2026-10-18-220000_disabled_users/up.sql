@@ -0,0 +1,2 @@
+-- Set when an operator disables the account, a disabled user can no longer log in
+ALTER TABLE users ADD COLUMN disabled_at TIMESTAMP;
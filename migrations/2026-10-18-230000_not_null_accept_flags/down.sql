@@ -0,0 +1,3 @@
+ALTER TABLE user_to_user_friends ALTER COLUMN accepted_user_two DROP NOT NULL;
+ALTER TABLE user_to_user_friends ALTER COLUMN accepted_user_one DROP NOT NULL;
+ALTER TABLE user_to_groups ALTER COLUMN accepted_invite DROP NOT NULL;
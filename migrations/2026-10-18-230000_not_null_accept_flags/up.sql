@@ -0,0 +1,8 @@
+-- The accept flags were nullable, the application always treated a missing value as not accepted
+UPDATE user_to_groups SET accepted_invite = FALSE WHERE accepted_invite IS NULL;
+ALTER TABLE user_to_groups ALTER COLUMN accepted_invite SET NOT NULL;
+
+UPDATE user_to_user_friends SET accepted_user_one = FALSE WHERE accepted_user_one IS NULL;
+UPDATE user_to_user_friends SET accepted_user_two = FALSE WHERE accepted_user_two IS NULL;
+ALTER TABLE user_to_user_friends ALTER COLUMN accepted_user_one SET NOT NULL;
+ALTER TABLE user_to_user_friends ALTER COLUMN accepted_user_two SET NOT NULL;
@@ -0,0 +1,3 @@
+DROP TABLE password_history;
+ALTER TABLE recovery_codes ALTER COLUMN code_hash TYPE VARCHAR(100);
+ALTER TABLE users ALTER COLUMN password TYPE VARCHAR(100);
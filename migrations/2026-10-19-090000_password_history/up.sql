@@ -0,0 +1,14 @@
+-- Argon2id hashes are longer than bcrypt hashes
+ALTER TABLE users ALTER COLUMN password TYPE VARCHAR(255);
+ALTER TABLE recovery_codes ALTER COLUMN code_hash TYPE VARCHAR(255);
+
+-- The hashes of the previous passwords of a user, so they cannot be used again
+CREATE TABLE password_history (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    password_hash VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT password_history_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX password_history_user_id_idx ON password_history (user_id, created_at);
@@ -0,0 +1,3 @@
+-- The hashes cannot be turned back into codes, new codes have to be requested
+DELETE FROM pending_verifications;
+ALTER TABLE pending_verifications RENAME COLUMN token_hash TO token;
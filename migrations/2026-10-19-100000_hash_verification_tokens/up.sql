@@ -0,0 +1,4 @@
+-- Verification tokens are stored as SHA-256 hashes like session tokens, so a leaked database does not contain usable codes.
+-- The pending tokens are hashed in place, so codes that were already sent stay valid
+ALTER TABLE pending_verifications RENAME COLUMN token TO token_hash;
+UPDATE pending_verifications SET token_hash = encode(sha256(convert_to(token_hash, 'UTF8')), 'hex');
@@ -0,0 +1,3 @@
+-- The hashes cannot be turned back into codes, new codes have to be requested
+DELETE FROM password_resets;
+ALTER TABLE password_resets RENAME COLUMN token_hash TO token;
@@ -0,0 +1,4 @@
+-- Password reset tokens are stored as SHA-256 hashes like session tokens, so reading the table does not allow taking over accounts.
+-- The pending tokens are hashed in place, so codes that were already sent stay valid
+ALTER TABLE password_resets RENAME COLUMN token TO token_hash;
+UPDATE password_resets SET token_hash = encode(sha256(convert_to(token_hash, 'UTF8')), 'hex');
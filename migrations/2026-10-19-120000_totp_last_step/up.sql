@@ -0,0 +1,2 @@
+-- The time step of the last accepted two-factor code, so a code cannot be used twice
+ALTER TABLE users ADD COLUMN totp_last_step BIGINT;
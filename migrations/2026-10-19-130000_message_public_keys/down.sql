@@ -0,0 +1,7 @@
+ALTER TABLE group_sender_keys
+    DROP COLUMN recipient_public_key,
+    DROP COLUMN distributor_public_key;
+
+ALTER TABLE user_messages
+    DROP COLUMN receiver_public_key,
+    DROP COLUMN sender_public_key;
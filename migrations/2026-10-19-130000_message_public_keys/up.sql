@@ -0,0 +1,8 @@
+-- The public keys a message or a sender key was encrypted with, so it can still be read after a key was replaced
+ALTER TABLE user_messages
+    ADD COLUMN sender_public_key VARCHAR(64),
+    ADD COLUMN receiver_public_key VARCHAR(64);
+
+ALTER TABLE group_sender_keys
+    ADD COLUMN distributor_public_key VARCHAR(64),
+    ADD COLUMN recipient_public_key VARCHAR(64);
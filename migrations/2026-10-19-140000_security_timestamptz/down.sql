@@ -0,0 +1,9 @@
+ALTER TABLE pending_verifications ALTER COLUMN expires_at TYPE TIMESTAMP;
+ALTER TABLE password_resets ALTER COLUMN expires_at TYPE TIMESTAMP;
+ALTER TABLE login_failures ALTER COLUMN locked_until TYPE TIMESTAMP;
+ALTER TABLE login_failures ALTER COLUMN last_failed_at TYPE TIMESTAMP;
+ALTER TABLE users ALTER COLUMN disabled_at TYPE TIMESTAMP;
+ALTER TABLE users ALTER COLUMN sessions_revoked_at TYPE TIMESTAMP;
+ALTER TABLE sessions ALTER COLUMN revoked_at TYPE TIMESTAMP;
+ALTER TABLE sessions ALTER COLUMN last_seen_at TYPE TIMESTAMP;
+ALTER TABLE sessions ALTER COLUMN created_at TYPE TIMESTAMP;
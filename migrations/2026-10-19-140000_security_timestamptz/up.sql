@@ -0,0 +1,11 @@
+-- Session, lock and token times are stored with their time zone, so they compare correctly between devices in different time zones.
+-- The existing times were stored in the time zone of the database session and are converted from it
+ALTER TABLE sessions ALTER COLUMN created_at TYPE TIMESTAMPTZ;
+ALTER TABLE sessions ALTER COLUMN last_seen_at TYPE TIMESTAMPTZ;
+ALTER TABLE sessions ALTER COLUMN revoked_at TYPE TIMESTAMPTZ;
+ALTER TABLE users ALTER COLUMN sessions_revoked_at TYPE TIMESTAMPTZ;
+ALTER TABLE users ALTER COLUMN disabled_at TYPE TIMESTAMPTZ;
+ALTER TABLE login_failures ALTER COLUMN last_failed_at TYPE TIMESTAMPTZ;
+ALTER TABLE login_failures ALTER COLUMN locked_until TYPE TIMESTAMPTZ;
+ALTER TABLE password_resets ALTER COLUMN expires_at TYPE TIMESTAMPTZ;
+ALTER TABLE pending_verifications ALTER COLUMN expires_at TYPE TIMESTAMPTZ;
@@ -0,0 +1,187 @@
+use crate::config::loader::usage;
+
+/// Usage of the admin tool, printed for `help` and after invalid arguments
+pub const USAGE: &str = "\
+Usage: chatadmin [--json] [--yes] <command>
+
+Commands:
+  users list                                List all users
+  users search <query>                      Find users by name or email
+  users create <first> <last> <email>       Create a user, the password is read from stdin
+  users disable <user-id>                   Disable a user and revoke all sessions
+  users enable <user-id>                    Enable a disabled user
+  users reset-password <user-id>            Set a new password, read from stdin
+  users unlock <email>                      Unlock an account locked after failed logins
+  users delete <user-id>                    Delete a user and all data of the user (needs --yes)
+  groups list                               List all groups with the number of members
+  groups members <group-id>                 List the members of a group
+  groups remove-member <group-id> <user-id> Remove a member from a group
+  purge --older-than <days>                 Delete all messages older than the given days (needs --yes)
+  stats                                     Print the number of users, groups, messages and sessions
+  migrate                                   Apply the migrations that have not been applied yet
+  check-schema                              Check that the database schema matches this version
+  help                                      Print this help
+
+Options:
+  --json       Print the output as JSON
+  --yes, -y    Confirm commands that delete data";
+
+/// Returns the usage of the admin tool with the config flags
+pub fn help() -> String {
+    format!("{}\n\nConfiguration:\n{}", USAGE, usage())
+}
+
+/// Represents the commands of the admin tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminCommand {
+    ListUsers,
+    SearchUsers {
+        query: String,
+    },
+    CreateUser {
+        first_name: String,
+        last_name: String,
+        email: String,
+    },
+    DisableUser {
+        user_id: i32,
+    },
+    EnableUser {
+        user_id: i32,
+    },
+    ResetPassword {
+        user_id: i32,
+    },
+    UnlockUser {
+        email: String,
+    },
+    DeleteUser {
+        user_id: i32,
+    },
+    ListGroups,
+    ListMembers {
+        group_id: i32,
+    },
+    RemoveMember {
+        group_id: i32,
+        user_id: i32,
+    },
+    PurgeMessages {
+        older_than_days: u32,
+    },
+    Stats,
+    Migrate,
+    CheckSchema,
+    Help,
+}
+
+/// The parsed arguments of the admin tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminArgs {
+    pub command: AdminCommand,
+    /// Print the output as JSON instead of text
+    pub json: bool,
+    /// Commands that delete data are only run if they are confirmed
+    pub confirmed: bool,
+}
+
+/// Parse the arguments of the admin tool without the program name. Options may appear anywhere.
+/// Returns the arguments or an error message
+pub fn parse_args(args: &[String]) -> Result<AdminArgs, String> {
+    let mut json = false;
+    let mut confirmed = false;
+    let mut older_than_days = None;
+    let mut words = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--yes" | "-y" => confirmed = true,
+            "--help" | "-h" => words.insert(0, "help"),
+            "--older-than" => {
+                let days = iter
+                    .next()
+                    .ok_or_else(|| "Missing number of days after --older-than".to_string())?;
+                older_than_days = Some(parse_days(days)?);
+            }
+            _ if arg.starts_with("--older-than=") => {
+                older_than_days = Some(parse_days(&arg["--older-than=".len()..])?);
+            }
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                return Err(format!("Unknown option: {}", arg))
+            }
+            _ => words.push(arg.as_str()),
+        }
+    }
+
+    let command = match words.as_slice() {
+        [] | ["help", ..] => AdminCommand::Help,
+        ["users", "list"] => AdminCommand::ListUsers,
+        ["users", "search", query] => AdminCommand::SearchUsers {
+            query: query.to_string(),
+        },
+        ["users", "create", first_name, last_name, email] => AdminCommand::CreateUser {
+            first_name: first_name.to_string(),
+            last_name: last_name.to_string(),
+            email: email.to_string(),
+        },
+        ["users", "disable", user_id] => AdminCommand::DisableUser {
+            user_id: parse_id("user", user_id)?,
+        },
+        ["users", "enable", user_id] => AdminCommand::EnableUser {
+            user_id: parse_id("user", user_id)?,
+        },
+        ["users", "reset-password", user_id] => AdminCommand::ResetPassword {
+            user_id: parse_id("user", user_id)?,
+        },
+        ["users", "unlock", email] => AdminCommand::UnlockUser {
+            email: email.to_lowercase(),
+        },
+        ["users", "delete", user_id] => AdminCommand::DeleteUser {
+            user_id: parse_id("user", user_id)?,
+        },
+        ["groups", "list"] => AdminCommand::ListGroups,
+        ["groups", "members", group_id] => AdminCommand::ListMembers {
+            group_id: parse_id("group", group_id)?,
+        },
+        ["groups", "remove-member", group_id, user_id] => AdminCommand::RemoveMember {
+            group_id: parse_id("group", group_id)?,
+            user_id: parse_id("user", user_id)?,
+        },
+        ["purge"] => AdminCommand::PurgeMessages {
+            older_than_days: older_than_days
+                .ok_or_else(|| "The purge command needs --older-than <days>".to_string())?,
+        },
+        ["stats"] => AdminCommand::Stats,
+        ["migrate"] => AdminCommand::Migrate,
+        ["check-schema"] => AdminCommand::CheckSchema,
+        _ => return Err(format!("Unknown command: {}", words.join(" "))),
+    };
+
+    if older_than_days.is_some() && !matches!(command, AdminCommand::PurgeMessages { .. }) {
+        return Err("The option --older-than is only valid for the purge command".to_string());
+    }
+
+    Ok(AdminArgs {
+        command,
+        json,
+        confirmed,
+    })
+}
+
+/// Parse the id of a user or group. Returns the id or an error message
+fn parse_id(kind: &str, value: &str) -> Result<i32, String> {
+    value
+        .parse::<i32>()
+        .ok()
+        .filter(|id| *id > 0)
+        .ok_or_else(|| format!("Invalid {} id: {}", kind, value))
+}
+
+/// Parse a number of days. Returns the days or an error message
+fn parse_days(value: &str) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid number of days: {}", value))
+}
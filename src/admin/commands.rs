@@ -0,0 +1,330 @@
+use std::io::BufRead;
+
+use chrono::{Duration, Local, Utc};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::backend::database::db::is_valid_email;
+use crate::backend::database::migrations::{check_schema, run_pending_migrations};
+use crate::backend::database::models::{GroupOverview, UserAccount};
+use crate::backend::entities::{
+    admin_ops::{find_group_overviews, find_statistics, purge_messages_before},
+    group_ops::find_groups_by_ids,
+    login_throttle_ops::unlock_account,
+    user_group_ops::{delete_user_group, find_all_user_groups_of_group},
+    user_ops::{
+        create_user, delete_user, find_all_user_accounts, find_user_email_by_id, find_users_by_ids,
+        search_user_accounts, set_password, set_user_disabled,
+    },
+};
+use crate::config::config;
+
+use super::cli::{help, AdminArgs, AdminCommand};
+
+/// A member of a group as printed by the admin tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GroupMember {
+    pub id: i32,
+    pub first_name: String,
+    pub last_name: String,
+    /// False while the user has not accepted the invite to the group
+    pub accepted_invite: bool,
+}
+
+/// Run a command of the admin tool. Commands that use the database refuse to run if its schema does not match.
+/// Passwords are read from the first line of the input, so they do not end up in the shell history.
+/// Returns the text or JSON to print or an error message
+pub fn run(args: &AdminArgs, input: &mut dyn BufRead) -> Result<String, String> {
+    if !matches!(
+        args.command,
+        AdminCommand::Help | AdminCommand::Migrate | AdminCommand::CheckSchema
+    ) {
+        check_schema()?;
+    }
+
+    match &args.command {
+        AdminCommand::Help => Ok(help()),
+        AdminCommand::Migrate => {
+            let applied = run_pending_migrations()?;
+            Ok(if args.json {
+                to_json(&json!({ "applied": applied }))?
+            } else if applied.is_empty() {
+                "The database is up to date".to_string()
+            } else {
+                format!("Applied migrations: {}", applied.join(", "))
+            })
+        }
+        AdminCommand::CheckSchema => {
+            check_schema()?;
+            Ok(confirmation(
+                args.json,
+                json!({ "schema_matches": true }),
+                "The database schema matches the application".to_string(),
+            ))
+        }
+        AdminCommand::ListUsers => format_accounts(&find_all_user_accounts()?, args.json),
+        AdminCommand::SearchUsers { query } => {
+            format_accounts(&search_user_accounts(query)?, args.json)
+        }
+        AdminCommand::CreateUser {
+            first_name,
+            last_name,
+            email,
+        } => {
+            if !is_valid_email(email) {
+                return Err(format!("Invalid email: {}", email));
+            }
+            let password = read_password(input)?;
+            config().password_policy.check(&password)?;
+
+            // Created accounts do not need to verify the email
+            let user = create_user(first_name, last_name, email, &password)?;
+            Ok(if args.json {
+                to_json(&user)?
+            } else {
+                format!(
+                    "Created user {} ({} {})",
+                    user.id, user.first_name, user.last_name
+                )
+            })
+        }
+        AdminCommand::DisableUser { user_id } => {
+            set_user_disabled(*user_id, true)?;
+            Ok(confirmation(
+                args.json,
+                json!({ "user_id": user_id, "disabled": true }),
+                format!("Disabled user {}, all sessions were revoked", user_id),
+            ))
+        }
+        AdminCommand::EnableUser { user_id } => {
+            set_user_disabled(*user_id, false)?;
+            Ok(confirmation(
+                args.json,
+                json!({ "user_id": user_id, "disabled": false }),
+                format!("Enabled user {}", user_id),
+            ))
+        }
+        AdminCommand::ResetPassword { user_id } => {
+            let password = read_password(input)?;
+            set_password(*user_id, &password)?;
+            Ok(confirmation(
+                args.json,
+                json!({ "user_id": user_id, "password_reset": true }),
+                format!(
+                    "Reset the password of user {}, all sessions were revoked",
+                    user_id
+                ),
+            ))
+        }
+        AdminCommand::UnlockUser { email } => {
+            // Emails without an account are throttled as well, so they can be unlocked too
+            unlock_account(email)?;
+            Ok(confirmation(
+                args.json,
+                json!({ "email": email, "unlocked": true }),
+                format!("Unlocked {}, the failed login attempts were reset", email),
+            ))
+        }
+        AdminCommand::DeleteUser { user_id } => {
+            require_confirmation(args, "delete the user and all of their messages")?;
+            // Fail for unknown users instead of deleting nothing
+            find_user_email_by_id(*user_id)?;
+            delete_user(*user_id)?;
+            Ok(confirmation(
+                args.json,
+                json!({ "user_id": user_id, "deleted": true }),
+                format!("Deleted user {}", user_id),
+            ))
+        }
+        AdminCommand::ListGroups => format_groups(&find_group_overviews()?, args.json),
+        AdminCommand::ListMembers { group_id } => {
+            let members = find_group_members(*group_id)?;
+            Ok(if args.json {
+                to_json(&members)?
+            } else {
+                members
+                    .iter()
+                    .map(|member| {
+                        format!(
+                            "{:>6}  {} {}{}",
+                            member.id,
+                            member.first_name,
+                            member.last_name,
+                            if member.accepted_invite {
+                                ""
+                            } else {
+                                " (invited)"
+                            }
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+        }
+        AdminCommand::RemoveMember { group_id, user_id } => {
+            if delete_user_group(*user_id, *group_id)? == 0 {
+                return Err(format!(
+                    "User {} is not a member of group {}",
+                    user_id, group_id
+                ));
+            }
+            Ok(confirmation(
+                args.json,
+                json!({ "group_id": group_id, "user_id": user_id, "removed": true }),
+                format!(
+                    "Removed user {} from group {}, the group key was rotated",
+                    user_id, group_id
+                ),
+            ))
+        }
+        AdminCommand::PurgeMessages { older_than_days } => {
+            require_confirmation(args, "delete messages")?;
+            let before = Utc::now() - Duration::days(i64::from(*older_than_days));
+            let purged = purge_messages_before(before)?;
+            Ok(if args.json {
+                to_json(&purged)?
+            } else {
+                format!(
+                    "Deleted {} direct messages and {} group messages sent before {}",
+                    purged.direct_messages,
+                    purged.group_messages,
+                    before.format("%Y-%m-%d %H:%M:%S UTC")
+                )
+            })
+        }
+        AdminCommand::Stats => {
+            let statistics = find_statistics()?;
+            Ok(if args.json {
+                to_json(&statistics)?
+            } else {
+                format!(
+                    "Users:           {} ({} disabled)\nGroups:          {}\nDirect messages: {}\nGroup messages:  {}\nActive sessions: {}",
+                    statistics.users,
+                    statistics.disabled_users,
+                    statistics.groups,
+                    statistics.direct_messages,
+                    statistics.group_messages,
+                    statistics.active_sessions
+                )
+            })
+        }
+    }
+}
+
+/// Format an error message for the output of the admin tool
+pub fn format_error(error: &str, json: bool) -> String {
+    if json {
+        json!({ "error": error }).to_string()
+    } else {
+        format!("Error: {}", error)
+    }
+}
+
+/// Find the members of a group, ordered by id. Returns the members or an error message
+fn find_group_members(group_id: i32) -> Result<Vec<GroupMember>, String> {
+    // Fail for unknown groups instead of listing no members
+    find_groups_by_ids(vec![group_id])?;
+
+    let user_groups = find_all_user_groups_of_group(group_id)?;
+    let mut users = find_users_by_ids(user_groups.iter().map(|ug| ug.user_id).collect())?;
+    users.sort_by_key(|user| user.id);
+
+    Ok(users
+        .into_iter()
+        .map(|user| GroupMember {
+            accepted_invite: user_groups
+                .iter()
+                .any(|ug| ug.user_id == user.id && ug.accepted_invite),
+            id: user.id,
+            first_name: user.first_name,
+            last_name: user.last_name,
+        })
+        .collect())
+}
+
+/// Format user accounts as a table or JSON. Returns the output or an error message
+fn format_accounts(accounts: &[UserAccount], json: bool) -> Result<String, String> {
+    if json {
+        return to_json(&accounts);
+    }
+
+    Ok(accounts
+        .iter()
+        .map(|account| {
+            let status = match account.disabled_at {
+                Some(disabled_at) => {
+                    format!(
+                        "disabled since {}",
+                        disabled_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                    )
+                }
+                None => "active".to_string(),
+            };
+            format!(
+                "{:>6}  {:<30}  {:<40}  {}",
+                account.id,
+                format!("{} {}", account.first_name, account.last_name),
+                account.email,
+                status
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Format group overviews as a table or JSON. Returns the output or an error message
+fn format_groups(groups: &[GroupOverview], json: bool) -> Result<String, String> {
+    if json {
+        return to_json(&groups);
+    }
+
+    Ok(groups
+        .iter()
+        .map(|group| {
+            format!(
+                "{:>6}  {:<30}  {} members",
+                group.id, group.name, group.member_count
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Returns the JSON object or the text that confirms a command
+fn confirmation(json: bool, object: serde_json::Value, text: String) -> String {
+    if json {
+        object.to_string()
+    } else {
+        text
+    }
+}
+
+/// Serialize a value as pretty JSON. Returns the JSON or an error message
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value)
+        .map_err(|err| format!("Failed to serialize output: {}", err))
+}
+
+/// Check that a command that deletes data was confirmed with --yes. Returns nothing or an error message
+fn require_confirmation(args: &AdminArgs, action: &str) -> Result<(), String> {
+    if args.confirmed {
+        Ok(())
+    } else {
+        Err(format!("Add --yes to {}", action))
+    }
+}
+
+/// Read a password from the first line of the input. Returns the password or an error message
+fn read_password(input: &mut dyn BufRead) -> Result<String, String> {
+    let mut line = String::new();
+    input
+        .read_line(&mut line)
+        .map_err(|err| format!("Failed to read password: {}", err))?;
+
+    let password = line.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        return Err("No password was given on stdin".to_string());
+    }
+
+    Ok(password.to_string())
+}
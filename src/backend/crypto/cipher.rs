@@ -0,0 +1,63 @@
+use aes_gcm::{
+    aead::{Aead, Payload},
+    Aes256Gcm, Nonce,
+};
+use rand::{rngs::OsRng, RngCore};
+
+use super::identity::EncryptedMessage;
+
+/// Length of the AES-GCM nonce in bytes
+const NONCE_LENGTH: usize = 12;
+
+/// Encrypt data with a random nonce. The associated data is authenticated, but not encrypted.
+/// Returns the hex encoded ciphertext and nonce or an error message
+pub(crate) fn seal(
+    cipher: &Aes256Gcm,
+    associated_data: &str,
+    plaintext: &[u8],
+) -> Result<EncryptedMessage, String> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce);
+
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: associated_data.as_bytes(),
+            },
+        )
+        .map_err(|_| "Failed to encrypt message".to_string())?;
+
+    Ok(EncryptedMessage {
+        ciphertext: hex::encode(ciphertext),
+        nonce: hex::encode(nonce),
+    })
+}
+
+/// Decrypt hex encoded data. Fails if the data or the associated data was changed or encrypted with another key.
+/// Returns the plain data or an error message
+pub(crate) fn open(
+    cipher: &Aes256Gcm,
+    associated_data: &str,
+    ciphertext: &str,
+    nonce: &str,
+) -> Result<Vec<u8>, String> {
+    let ciphertext =
+        hex::decode(ciphertext).map_err(|err| format!("Invalid ciphertext: {}", err))?;
+    let nonce = hex::decode(nonce).map_err(|err| format!("Invalid nonce: {}", err))?;
+
+    if nonce.len() != NONCE_LENGTH {
+        return Err("Invalid nonce length".to_string());
+    }
+
+    cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: associated_data.as_bytes(),
+            },
+        )
+        .map_err(|_| "Failed to decrypt message".to_string())
+}
@@ -0,0 +1,83 @@
+use crate::backend::{
+    database::models::{ConversationSummary, GroupMessage, UserMessage},
+    entities::conversation_ops::find_conversation_summaries,
+};
+
+use std::collections::HashMap;
+
+use super::{
+    direct_message::{decrypt_direct_message, MessageKeys},
+    group_message::decrypt_group_message,
+    key_store::KeyStore,
+};
+
+/// Load the chats of a user with the friends and the groups of the user, sorted by their newest message.
+/// The newest message of every chat is decrypted, so it can be previewed. A message that fails to decrypt is marked as
+/// undecryptable without failing the other chats. Returns the conversation summaries or an error message
+pub fn load_conversation_summaries(
+    key_store: &KeyStore,
+    user_id: i32,
+) -> Result<Vec<ConversationSummary>, String> {
+    let summaries = find_conversation_summaries(user_id)?;
+    let mut message_keys = MessageKeys::new(key_store, user_id)?;
+    let identity_key = message_keys.identity_key().clone();
+    let mut published_keys = HashMap::new();
+
+    Ok(summaries
+        .into_iter()
+        .map(|mut summary| {
+            let (Some(sender_id), Some(message), Some(send_date)) = (
+                summary.sender_id,
+                summary.message.clone(),
+                summary.send_date,
+            ) else {
+                return summary;
+            };
+
+            let decrypted = if summary.is_group {
+                decrypt_group_message(
+                    key_store,
+                    &identity_key,
+                    user_id,
+                    GroupMessage {
+                        sender_id,
+                        receiver_id: summary.conversation_id,
+                        message,
+                        send_date,
+                        key_epoch: summary.key_epoch,
+                        nonce: summary.nonce.clone(),
+                        edited_at: None,
+                        undecryptable: false,
+                    },
+                    &mut published_keys,
+                )
+                .map(|group_message| (group_message.message, group_message.undecryptable))
+            } else {
+                decrypt_direct_message(
+                    &mut message_keys,
+                    UserMessage {
+                        sender_id,
+                        receiver_id: if sender_id == user_id {
+                            summary.conversation_id
+                        } else {
+                            user_id
+                        },
+                        message,
+                        send_date,
+                        nonce: summary.nonce.clone(),
+                        edited_at: None,
+                        sender_public_key: summary.sender_public_key.clone(),
+                        receiver_public_key: summary.receiver_public_key.clone(),
+                        undecryptable: false,
+                    },
+                )
+                .map(|user_message| (user_message.message, user_message.undecryptable))
+            };
+            let (message, undecryptable) = decrypted.unwrap_or((String::new(), true));
+            summary.message = Some(message);
+            summary.undecryptable = undecryptable;
+
+            summary
+        })
+        .collect())
+}
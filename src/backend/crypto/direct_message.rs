@@ -0,0 +1,316 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::database::models::{MessageEncryption, UserMessage};
+use crate::backend::entities::{
+    user_key_ops::find_public_key,
+    user_message_ops::{
+        create_user_message, find_all_messages_between_users, find_last_message_between_users,
+        find_messages_between_users_since, find_messages_to_user_since, update_user_message,
+    },
+};
+
+use super::{
+    identity::{fingerprint, ConversationKey, EncryptedMessage, IdentityKey},
+    key_store::KeyStore,
+};
+
+/// ConversationFingerprints is a struct that represents the key fingerprints of both users of a conversation
+/// and whether a key changed since the newest message of the conversation was encrypted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversationFingerprints {
+    pub own: String,
+    pub other: Option<String>,
+    #[serde(default)]
+    pub own_changed: bool,
+    #[serde(default)]
+    pub other_changed: bool,
+}
+
+/// A message encrypted for the receiver together with the public keys of both users
+struct EncryptedDirectMessage {
+    encrypted: EncryptedMessage,
+    sender_public_key: String,
+    receiver_public_key: String,
+}
+
+/// Derives the keys of the conversations of a user with the public keys that were stored with each message, so messages stay
+/// readable after a key was replaced. Messages from before the public keys were stored use the current keys of both users
+pub(crate) struct MessageKeys<'a> {
+    key_store: &'a KeyStore,
+    user_id: i32,
+    identity_key: IdentityKey,
+    published_keys: HashMap<i32, Option<String>>,
+    conversation_keys: HashMap<(i32, String, String), Option<ConversationKey>>,
+}
+
+/// Implementation of the encrypted direct message
+impl EncryptedDirectMessage {
+    /// Returns how the message was encrypted, to store it with the ciphertext
+    fn encryption(&self) -> MessageEncryption<'_> {
+        MessageEncryption {
+            nonce: &self.encrypted.nonce,
+            sender_public_key: &self.sender_public_key,
+            receiver_public_key: &self.receiver_public_key,
+        }
+    }
+}
+
+/// Implementation of the message keys
+impl<'a> MessageKeys<'a> {
+    /// Create the message keys of a user. Returns the message keys or an error message
+    pub(crate) fn new(key_store: &'a KeyStore, user_id: i32) -> Result<Self, String> {
+        Ok(MessageKeys {
+            key_store,
+            user_id,
+            identity_key: key_store.load_or_create_identity(user_id)?,
+            published_keys: HashMap::new(),
+            conversation_keys: HashMap::new(),
+        })
+    }
+
+    /// Returns the current identity key of the user
+    pub(crate) fn identity_key(&self) -> &IdentityKey {
+        &self.identity_key
+    }
+
+    /// Find the key of the conversation a message was encrypted with. Returns the key, none if a key is missing,
+    /// e.g. because the own key was replaced on another device, or an error message
+    fn conversation_key(
+        &mut self,
+        user_message: &UserMessage,
+    ) -> Result<Option<&ConversationKey>, String> {
+        let (other_user_id, own_public_key, other_public_key) =
+            if user_message.sender_id == self.user_id {
+                (
+                    user_message.receiver_id,
+                    &user_message.sender_public_key,
+                    &user_message.receiver_public_key,
+                )
+            } else {
+                (
+                    user_message.sender_id,
+                    &user_message.receiver_public_key,
+                    &user_message.sender_public_key,
+                )
+            };
+
+        let own_public_key = own_public_key
+            .clone()
+            .unwrap_or_else(|| self.identity_key.public_key());
+        let other_public_key = match other_public_key {
+            Some(public_key) => public_key.clone(),
+            None => {
+                let published_key = match self.published_keys.entry(other_user_id) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(find_public_key(other_user_id)?),
+                };
+                match published_key {
+                    Some(public_key) => public_key.clone(),
+                    None => return Ok(None),
+                }
+            }
+        };
+
+        let conversation_key =
+            match self
+                .conversation_keys
+                .entry((other_user_id, own_public_key, other_public_key))
+            {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let (_, own_public_key, other_public_key) = entry.key();
+                    let identity_key = if *own_public_key == self.identity_key.public_key() {
+                        Some(self.identity_key.clone())
+                    } else {
+                        self.key_store
+                            .load_by_public_key(self.user_id, own_public_key)?
+                    };
+                    let conversation_key = identity_key
+                        .map(|identity_key| {
+                            identity_key.conversation_key(
+                                self.user_id,
+                                other_user_id,
+                                other_public_key,
+                            )
+                        })
+                        .transpose()?;
+                    entry.insert(conversation_key)
+                }
+            };
+
+        Ok(conversation_key.as_ref())
+    }
+}
+
+/// Encrypt a message for the receiver and store it. Only the ciphertext is sent to the server. A message with a client ID
+/// is only stored once, so it can be sent again if the result was lost. Returns the user message with the plain text or an error message
+pub fn send_direct_message(
+    key_store: &KeyStore,
+    sender_id: i32,
+    receiver_id: i32,
+    message: &str,
+    client_id: Option<&str>,
+) -> Result<UserMessage, String> {
+    let encrypted = encrypt_direct_message(key_store, sender_id, receiver_id, message)?;
+
+    let mut user_message = create_user_message(
+        sender_id,
+        receiver_id,
+        &encrypted.encrypted.ciphertext,
+        Some(&encrypted.encryption()),
+        client_id,
+    )?;
+    user_message.message = message.to_string();
+
+    Ok(user_message)
+}
+
+/// Encrypt the new text of a message the sender sent and replace the stored message. Returns the user message with the
+/// plain text or an error message
+pub fn edit_direct_message(
+    key_store: &KeyStore,
+    sender_id: i32,
+    receiver_id: i32,
+    send_date: DateTime<Utc>,
+    message: &str,
+) -> Result<UserMessage, String> {
+    let encrypted = encrypt_direct_message(key_store, sender_id, receiver_id, message)?;
+
+    let edited_at = update_user_message(
+        sender_id,
+        receiver_id,
+        send_date,
+        &encrypted.encrypted.ciphertext,
+        Some(&encrypted.encryption()),
+    )?;
+
+    Ok(UserMessage {
+        sender_id,
+        receiver_id,
+        message: message.to_string(),
+        send_date,
+        nonce: Some(encrypted.encrypted.nonce),
+        edited_at: Some(edited_at),
+        sender_public_key: Some(encrypted.sender_public_key),
+        receiver_public_key: Some(encrypted.receiver_public_key),
+        undecryptable: false,
+    })
+}
+
+/// Load and decrypt the messages between a user and another user, all of them or only those sent at or after the given date.
+/// Messages from before end-to-end encryption are shown as they are stored. Returns the user messages with the plain text or an error message
+pub fn load_direct_messages(
+    key_store: &KeyStore,
+    user_id: i32,
+    other_user_id: i32,
+    since: Option<DateTime<Utc>>,
+) -> Result<Vec<UserMessage>, String> {
+    let messages = match since {
+        Some(since) => find_messages_between_users_since(user_id, other_user_id, since)?,
+        None => find_all_messages_between_users(user_id, other_user_id)?,
+    };
+    let mut message_keys = MessageKeys::new(key_store, user_id)?;
+
+    messages
+        .into_iter()
+        .map(|user_message| decrypt_direct_message(&mut message_keys, user_message))
+        .collect()
+}
+
+/// Load and decrypt the messages other users sent to a user after the given date, e.g. to notify the user about them.
+/// Returns the user messages with the plain text or an error message
+pub fn load_received_direct_messages(
+    key_store: &KeyStore,
+    user_id: i32,
+    since: DateTime<Utc>,
+) -> Result<Vec<UserMessage>, String> {
+    let mut message_keys = MessageKeys::new(key_store, user_id)?;
+
+    find_messages_to_user_since(user_id, since)?
+        .into_iter()
+        .map(|user_message| decrypt_direct_message(&mut message_keys, user_message))
+        .collect()
+}
+
+/// Find the key fingerprints of a user and another user, so both can verify that they talk to the right person.
+/// A key that differs from the one the newest message of the conversation was encrypted with is marked as changed.
+/// Returns the fingerprints or an error message
+pub fn find_conversation_fingerprints(
+    key_store: &KeyStore,
+    user_id: i32,
+    other_user_id: i32,
+) -> Result<ConversationFingerprints, String> {
+    let own_public_key = key_store.load_or_create_identity(user_id)?.public_key();
+    let other_public_key = find_public_key(other_user_id)?;
+
+    let (own_used_key, other_used_key) =
+        match find_last_message_between_users(user_id, other_user_id)? {
+            Some(user_message) if user_message.sender_id == user_id => (
+                user_message.sender_public_key,
+                user_message.receiver_public_key,
+            ),
+            Some(user_message) => (
+                user_message.receiver_public_key,
+                user_message.sender_public_key,
+            ),
+            None => (None, None),
+        };
+
+    Ok(ConversationFingerprints {
+        own: fingerprint(&own_public_key)?,
+        other: other_public_key.as_deref().map(fingerprint).transpose()?,
+        own_changed: own_used_key.is_some_and(|used_key| used_key != own_public_key),
+        other_changed: other_used_key.is_some_and(|used_key| Some(used_key) != other_public_key),
+    })
+}
+
+/// Encrypt a message for the receiver with the current keys of both users. Returns the encrypted message
+/// with the public keys, or an error message if the receiver has not published a key
+fn encrypt_direct_message(
+    key_store: &KeyStore,
+    sender_id: i32,
+    receiver_id: i32,
+    message: &str,
+) -> Result<EncryptedDirectMessage, String> {
+    let identity_key = key_store.load_or_create_identity(sender_id)?;
+    let receiver_public_key = find_public_key(receiver_id)?
+        .ok_or("The other user has not published an encryption key yet")?;
+
+    let encrypted = identity_key
+        .conversation_key(sender_id, receiver_id, &receiver_public_key)?
+        .encrypt(sender_id, receiver_id, message)?;
+
+    Ok(EncryptedDirectMessage {
+        encrypted,
+        sender_public_key: identity_key.public_key(),
+        receiver_public_key,
+    })
+}
+
+/// Decrypt a stored message with the key of its conversation. Messages from before end-to-end encryption are returned as they are stored.
+/// A message that cannot be decrypted, e.g. because it was encrypted for a key of another device, is marked as undecryptable.
+/// Returns the user message with the plain text or an error message
+pub(crate) fn decrypt_direct_message(
+    message_keys: &mut MessageKeys,
+    mut user_message: UserMessage,
+) -> Result<UserMessage, String> {
+    if let Some(nonce) = user_message.nonce.as_deref() {
+        let message = message_keys
+            .conversation_key(&user_message)?
+            .and_then(|key| {
+                key.decrypt(
+                    user_message.sender_id,
+                    user_message.receiver_id,
+                    &user_message.message,
+                    nonce,
+                )
+                .ok()
+            });
+        user_message.undecryptable = message.is_none();
+        user_message.message = message.unwrap_or_default();
+    }
+    Ok(user_message)
+}
@@ -0,0 +1,500 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use aes_gcm::{aead::KeyInit, Aes256Gcm};
+use chrono::{DateTime, Local, Utc};
+use rand::{rngs::OsRng, RngCore};
+
+use super::{
+    cipher,
+    identity::{EncryptedMessage, IdentityKey},
+    key_store::KeyStore,
+};
+use crate::backend::{
+    database::models::{GroupMessage, GroupSenderKey},
+    entities::{
+        group_message_ops::{
+            create_group_message, find_all_messages_of_group, find_messages_of_group_since,
+            update_group_message,
+        },
+        group_ops::{find_group_key_epoch, start_new_key_epoch},
+        group_sender_key_ops::{
+            distribute_sender_keys, find_recipients_of_sender_key, find_sender_key,
+            find_sender_keys_of_recipient,
+        },
+        user_group_ops::find_all_user_groups_of_group,
+        user_key_ops::find_public_key,
+    },
+};
+
+/// The sender key of a group member in one key epoch. Every member that received it can read the messages
+/// the sender wrote in that epoch
+pub struct SenderKey {
+    key: [u8; 32],
+}
+
+/// Identifies the sender key of a member in a key epoch of a group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct SenderKeyId {
+    group_id: i32,
+    key_epoch: i32,
+    sender_id: i32,
+}
+
+/// Implementation of the sender key
+impl SenderKey {
+    /// Generate a new random sender key
+    pub fn generate() -> Self {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+
+        SenderKey { key }
+    }
+
+    /// Encrypt a message of the sender. The group, the key epoch and the sender are authenticated with the message.
+    /// Returns the encrypted message or an error message
+    pub fn encrypt(
+        &self,
+        group_id: i32,
+        key_epoch: i32,
+        sender_id: i32,
+        message: &str,
+    ) -> Result<EncryptedMessage, String> {
+        let id = SenderKeyId {
+            group_id,
+            key_epoch,
+            sender_id,
+        };
+
+        cipher::seal(&self.cipher(), &id.message_data(), message.as_bytes())
+    }
+
+    /// Decrypt a message of the sender. Returns the message or an error message
+    pub fn decrypt(
+        &self,
+        group_id: i32,
+        key_epoch: i32,
+        sender_id: i32,
+        ciphertext: &str,
+        nonce: &str,
+    ) -> Result<String, String> {
+        let id = SenderKeyId {
+            group_id,
+            key_epoch,
+            sender_id,
+        };
+        let message = cipher::open(&self.cipher(), &id.message_data(), ciphertext, nonce)?;
+
+        String::from_utf8(message).map_err(|err| format!("Invalid message: {}", err))
+    }
+
+    /// Returns the cipher of the sender key
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(&self.key.into())
+    }
+}
+
+/// Implementation of the sender key ID
+impl SenderKeyId {
+    /// Returns the data that is authenticated together with a message
+    fn message_data(&self) -> String {
+        format!(
+            "group {} epoch {} sender {}",
+            self.group_id, self.key_epoch, self.sender_id
+        )
+    }
+
+    /// Returns the data that is authenticated together with the sender key, when it is encrypted for a recipient
+    fn distribution_data(&self, distributor_id: i32, recipient_id: i32) -> String {
+        format!(
+            "{} from {} to {}",
+            self.message_data(),
+            distributor_id,
+            recipient_id
+        )
+    }
+}
+
+/// Encrypt a message with the sender key of the current key epoch and store it. The sender key is first distributed
+/// to every member of the group that has not received it yet. A message with a client ID is only stored once.
+/// Returns the group message with the plain text or an error message
+pub fn send_group_message(
+    key_store: &KeyStore,
+    sender_id: i32,
+    group_id: i32,
+    message: &str,
+    client_id: Option<&str>,
+) -> Result<GroupMessage, String> {
+    let identity_key = key_store.load_or_create_identity(sender_id)?;
+    let (id, sender_key) = current_sender_key(&identity_key, sender_id, group_id)?;
+
+    let mut recipient_ids = find_member_ids(group_id)?;
+    if !recipient_ids.contains(&sender_id) {
+        recipient_ids.push(sender_id);
+    }
+    share_sender_key(&identity_key, sender_id, id, &sender_key, &recipient_ids)?;
+
+    let encrypted = sender_key.encrypt(group_id, id.key_epoch, sender_id, message)?;
+    let mut group_message = create_group_message(
+        sender_id,
+        group_id,
+        &encrypted.ciphertext,
+        Some(id.key_epoch),
+        Some(&encrypted.nonce),
+        client_id,
+    )?;
+    group_message.message = message.to_string();
+
+    Ok(group_message)
+}
+
+/// Encrypt the new text of a message the sender sent to a group with the sender key of the current key epoch and replace
+/// the stored message, so members that left the group cannot read the edit. Returns the group message with the plain text
+/// or an error message
+pub fn edit_group_message(
+    key_store: &KeyStore,
+    sender_id: i32,
+    group_id: i32,
+    send_date: DateTime<Utc>,
+    message: &str,
+) -> Result<GroupMessage, String> {
+    let identity_key = key_store.load_or_create_identity(sender_id)?;
+    let (id, sender_key) = current_sender_key(&identity_key, sender_id, group_id)?;
+
+    let mut recipient_ids = find_member_ids(group_id)?;
+    if !recipient_ids.contains(&sender_id) {
+        recipient_ids.push(sender_id);
+    }
+    share_sender_key(&identity_key, sender_id, id, &sender_key, &recipient_ids)?;
+
+    let encrypted = sender_key.encrypt(group_id, id.key_epoch, sender_id, message)?;
+    let edited_at = update_group_message(
+        sender_id,
+        group_id,
+        send_date,
+        &encrypted.ciphertext,
+        Some(id.key_epoch),
+        Some(&encrypted.nonce),
+    )?;
+
+    Ok(GroupMessage {
+        sender_id,
+        receiver_id: group_id,
+        message: message.to_string(),
+        send_date,
+        key_epoch: Some(id.key_epoch),
+        nonce: Some(encrypted.nonce),
+        edited_at: Some(edited_at),
+        undecryptable: false,
+    })
+}
+
+/// Load and decrypt the messages of a group for a user, all of them or only those sent at or after the given date. Messages of
+/// key epochs in which the user did not receive the sender key, e.g. from before the user joined, cannot be read. Messages from
+/// before end-to-end encryption are shown as they are stored. Returns the group messages with the plain text or an error message
+pub fn load_group_messages(
+    key_store: &KeyStore,
+    user_id: i32,
+    group_id: i32,
+    since: Option<DateTime<Utc>>,
+) -> Result<Vec<GroupMessage>, String> {
+    let identity_key = key_store.load_or_create_identity(user_id)?;
+    let messages = match since {
+        Some(since) => find_messages_of_group_since(group_id, since)?,
+        None => find_all_messages_of_group(group_id)?,
+    };
+    let sender_keys = find_readable_sender_keys(key_store, &identity_key, user_id, group_id)?;
+
+    Ok(messages
+        .into_iter()
+        .map(|mut group_message| {
+            if let Some(nonce) = group_message.nonce.as_deref() {
+                let id = SenderKeyId {
+                    group_id,
+                    key_epoch: group_message.key_epoch.unwrap_or_default(),
+                    sender_id: group_message.sender_id,
+                };
+
+                let message = sender_keys.get(&id).and_then(|sender_key| {
+                    sender_key
+                        .decrypt(
+                            id.group_id,
+                            id.key_epoch,
+                            id.sender_id,
+                            &group_message.message,
+                            nonce,
+                        )
+                        .ok()
+                });
+                group_message.undecryptable = message.is_none();
+                group_message.message = message.unwrap_or_default();
+            }
+            group_message
+        })
+        .collect())
+}
+
+/// Decrypt a single message of a group for a user with the current identity key of the user, e.g. to preview it. The published
+/// keys of the distributors are cached. A message whose sender key the user did not receive is marked as undecryptable.
+/// Returns the group message with the plain text or an error message
+pub(crate) fn decrypt_group_message(
+    key_store: &KeyStore,
+    identity_key: &IdentityKey,
+    user_id: i32,
+    mut group_message: GroupMessage,
+    published_keys: &mut HashMap<i32, Option<String>>,
+) -> Result<GroupMessage, String> {
+    let Some(nonce) = group_message.nonce.clone() else {
+        return Ok(group_message);
+    };
+
+    let id = SenderKeyId {
+        group_id: group_message.receiver_id,
+        key_epoch: group_message.key_epoch.unwrap_or_default(),
+        sender_id: group_message.sender_id,
+    };
+
+    let sender_key = match find_sender_key(id.group_id, id.key_epoch, id.sender_id, user_id)? {
+        Some(stored_key) => open_received_sender_key(
+            key_store,
+            identity_key,
+            user_id,
+            &stored_key,
+            published_keys,
+        )?,
+        None => None,
+    };
+
+    let message = sender_key.and_then(|sender_key| {
+        sender_key
+            .decrypt(
+                id.group_id,
+                id.key_epoch,
+                id.sender_id,
+                &group_message.message,
+                &nonce,
+            )
+            .ok()
+    });
+    group_message.undecryptable = message.is_none();
+    group_message.message = message.unwrap_or_default();
+
+    Ok(group_message)
+}
+
+/// Share all sender keys a user can read with the members of the group that have not received them, so new members
+/// can read the history of the group. Returns the number of shared sender keys or an error message
+pub fn share_group_history(
+    key_store: &KeyStore,
+    user_id: i32,
+    group_id: i32,
+) -> Result<usize, String> {
+    let identity_key = key_store.load_or_create_identity(user_id)?;
+    let member_ids = find_member_ids(group_id)?;
+
+    let mut shared = 0;
+    for (id, sender_key) in find_readable_sender_keys(key_store, &identity_key, user_id, group_id)?
+    {
+        shared += share_sender_key(&identity_key, user_id, id, &sender_key, &member_ids)?;
+    }
+
+    Ok(shared)
+}
+
+/// Find the sender key of a member in the current key epoch of a group or create a new one. If the stored key cannot be
+/// read anymore, e.g. because the member logged in on a new device, a new key epoch is started.
+/// Returns the ID and the sender key or an error message
+fn current_sender_key(
+    identity_key: &IdentityKey,
+    sender_id: i32,
+    group_id: i32,
+) -> Result<(SenderKeyId, SenderKey), String> {
+    let mut id = SenderKeyId {
+        group_id,
+        key_epoch: find_group_key_epoch(group_id)?,
+        sender_id,
+    };
+
+    if let Some(stored_key) = find_sender_key(group_id, id.key_epoch, sender_id, sender_id)? {
+        match decrypt_sender_key(identity_key, sender_id, &stored_key) {
+            Ok(sender_key) => return Ok((id, sender_key)),
+            Err(_) => id.key_epoch = start_new_key_epoch(group_id)?,
+        }
+    }
+
+    Ok((id, SenderKey::generate()))
+}
+
+/// Encrypt a sender key for every recipient that has not received it yet and has published a public key.
+/// Returns the number of recipients the key was shared with or an error message
+fn share_sender_key(
+    identity_key: &IdentityKey,
+    distributor_id: i32,
+    id: SenderKeyId,
+    sender_key: &SenderKey,
+    recipient_ids: &[i32],
+) -> Result<usize, String> {
+    let received = find_recipients_of_sender_key(id.group_id, id.key_epoch, id.sender_id)?;
+
+    let mut sender_keys = Vec::new();
+    for &recipient_id in recipient_ids {
+        if received.contains(&recipient_id) {
+            continue;
+        }
+
+        let public_key = if recipient_id == distributor_id {
+            identity_key.public_key()
+        } else {
+            match find_public_key(recipient_id)? {
+                Some(public_key) => public_key,
+                // The member receives the key with the next message after publishing a public key
+                None => continue,
+            }
+        };
+
+        let encrypted = identity_key
+            .key_distribution_key(distributor_id, recipient_id, &public_key)?
+            .seal(
+                &id.distribution_data(distributor_id, recipient_id),
+                &sender_key.key,
+            )?;
+
+        sender_keys.push(GroupSenderKey {
+            group_id: id.group_id,
+            key_epoch: id.key_epoch,
+            sender_id: id.sender_id,
+            recipient_id,
+            distributor_id,
+            encrypted_key: encrypted.ciphertext,
+            nonce: encrypted.nonce,
+            created_at: Local::now().naive_local(),
+            distributor_public_key: Some(identity_key.public_key()),
+            recipient_public_key: Some(public_key),
+        });
+    }
+
+    if sender_keys.is_empty() {
+        return Ok(0);
+    }
+
+    distribute_sender_keys(&sender_keys)
+}
+
+/// Find and decrypt all sender keys of a group the user received. Keys that cannot be decrypted are skipped.
+/// Returns the sender keys by their ID or an error message
+fn find_readable_sender_keys(
+    key_store: &KeyStore,
+    identity_key: &IdentityKey,
+    user_id: i32,
+    group_id: i32,
+) -> Result<HashMap<SenderKeyId, SenderKey>, String> {
+    let mut public_keys: HashMap<i32, Option<String>> = HashMap::new();
+    let mut sender_keys = HashMap::new();
+
+    for stored_key in find_sender_keys_of_recipient(group_id, user_id)? {
+        if let Some(sender_key) = open_received_sender_key(
+            key_store,
+            identity_key,
+            user_id,
+            &stored_key,
+            &mut public_keys,
+        )? {
+            let id = SenderKeyId {
+                group_id,
+                key_epoch: stored_key.key_epoch,
+                sender_id: stored_key.sender_id,
+            };
+            sender_keys.insert(id, sender_key);
+        }
+    }
+
+    Ok(sender_keys)
+}
+
+/// Decrypt a sender key the user received with the public keys that were stored with it, so it stays readable after a key
+/// was replaced. Sender keys from before the public keys were stored use the current keys. The published keys of the
+/// distributors are cached. Returns the sender key, none if it cannot be decrypted, or an error message
+fn open_received_sender_key(
+    key_store: &KeyStore,
+    identity_key: &IdentityKey,
+    user_id: i32,
+    stored_key: &GroupSenderKey,
+    published_keys: &mut HashMap<i32, Option<String>>,
+) -> Result<Option<SenderKey>, String> {
+    let identity_key = match stored_key.recipient_public_key.as_deref() {
+        Some(public_key) if public_key != identity_key.public_key() => {
+            match key_store.load_by_public_key(user_id, public_key)? {
+                Some(identity_key) => identity_key,
+                None => return Ok(None),
+            }
+        }
+        _ => identity_key.clone(),
+    };
+
+    let distributor_public_key = match &stored_key.distributor_public_key {
+        Some(public_key) => public_key.clone(),
+        None if stored_key.distributor_id == user_id => identity_key.public_key(),
+        None => {
+            let published_key = match published_keys.entry(stored_key.distributor_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(find_public_key(stored_key.distributor_id)?),
+            };
+            match published_key {
+                Some(public_key) => public_key.clone(),
+                None => return Ok(None),
+            }
+        }
+    };
+
+    Ok(open_sender_key(&identity_key, user_id, &distributor_public_key, stored_key).ok())
+}
+
+/// Decrypt a sender key that was distributed by the user to itself. Returns the sender key or an error message
+fn decrypt_sender_key(
+    identity_key: &IdentityKey,
+    user_id: i32,
+    stored_key: &GroupSenderKey,
+) -> Result<SenderKey, String> {
+    open_sender_key(
+        identity_key,
+        user_id,
+        &identity_key.public_key(),
+        stored_key,
+    )
+}
+
+/// Decrypt a sender key the user received with the public key of its distributor. Returns the sender key or an error message
+fn open_sender_key(
+    identity_key: &IdentityKey,
+    user_id: i32,
+    distributor_public_key: &str,
+    stored_key: &GroupSenderKey,
+) -> Result<SenderKey, String> {
+    let id = SenderKeyId {
+        group_id: stored_key.group_id,
+        key_epoch: stored_key.key_epoch,
+        sender_id: stored_key.sender_id,
+    };
+
+    let key = identity_key
+        .key_distribution_key(user_id, stored_key.distributor_id, distributor_public_key)?
+        .open(
+            &id.distribution_data(stored_key.distributor_id, user_id),
+            &stored_key.encrypted_key,
+            &stored_key.nonce,
+        )?;
+
+    Ok(SenderKey {
+        key: key
+            .try_into()
+            .map_err(|_| "Invalid sender key length".to_string())?,
+    })
+}
+
+/// Find the IDs of all users that accepted the invite to a group. Returns the IDs or an error message
+fn find_member_ids(group_id: i32) -> Result<Vec<i32>, String> {
+    Ok(find_all_user_groups_of_group(group_id)?
+        .into_iter()
+        .filter(|user_group| user_group.accepted_invite)
+        .map(|user_group| user_group.user_id)
+        .collect())
+}
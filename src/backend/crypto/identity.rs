@@ -0,0 +1,201 @@
+use aes_gcm::{aead::KeyInit, Aes256Gcm};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use super::cipher;
+
+/// Context of the key derivation, so the shared secret is only used for direct messages
+const DIRECT_MESSAGE_INFO: &[u8] = b"secse24_group08 direct message v1";
+
+/// Context of the key derivation, so the shared secret is only used to distribute group sender keys
+const KEY_DISTRIBUTION_INFO: &[u8] = b"secse24_group08 group key distribution v1";
+
+/// Number of bytes of the public key hash shown as fingerprint
+const FINGERPRINT_LENGTH: usize = 16;
+
+/// The X25519 identity key of a user. The secret never leaves the device, only the public key is published
+#[derive(Clone)]
+pub struct IdentityKey {
+    secret: StaticSecret,
+}
+
+/// The key of a conversation between two users, derived from the identity key of one user and the public key of the other
+pub struct ConversationKey {
+    cipher: Aes256Gcm,
+}
+
+/// EncryptedMessage is a struct that represents the hex encoded ciphertext and nonce of a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedMessage {
+    pub ciphertext: String,
+    pub nonce: String,
+}
+
+/// Implementation of the identity key
+impl IdentityKey {
+    /// Generate a new random identity key
+    pub fn generate() -> Self {
+        IdentityKey {
+            secret: StaticSecret::random_from_rng(OsRng),
+        }
+    }
+
+    /// Read an identity key from its hex encoded secret. Returns the identity key or an error message
+    pub fn from_hex(secret: &str) -> Result<Self, String> {
+        let bytes = decode_key(secret).map_err(|err| format!("Invalid identity key: {}", err))?;
+
+        Ok(IdentityKey {
+            secret: StaticSecret::from(bytes),
+        })
+    }
+
+    /// Returns the hex encoded secret, which is only stored on this device
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.secret.to_bytes())
+    }
+
+    /// Returns the hex encoded public key, which is published to the server
+    pub fn public_key(&self) -> String {
+        hex::encode(PublicKey::from(&self.secret).as_bytes())
+    }
+
+    /// Derive the key of the conversation with another user. Both users derive the same key from their own
+    /// identity key and the public key of the other user. Returns the conversation key or an error message
+    pub fn conversation_key(
+        &self,
+        own_user_id: i32,
+        other_user_id: i32,
+        other_public_key: &str,
+    ) -> Result<ConversationKey, String> {
+        self.derive_key(
+            own_user_id,
+            other_user_id,
+            other_public_key,
+            DIRECT_MESSAGE_INFO,
+        )
+    }
+
+    /// Derive the key with which sender keys of groups are distributed to another user. It is independent of the key
+    /// of the direct conversation. Returns the key or an error message
+    pub fn key_distribution_key(
+        &self,
+        own_user_id: i32,
+        other_user_id: i32,
+        other_public_key: &str,
+    ) -> Result<ConversationKey, String> {
+        self.derive_key(
+            own_user_id,
+            other_user_id,
+            other_public_key,
+            KEY_DISTRIBUTION_INFO,
+        )
+    }
+
+    /// Derive a key shared with another user for the given context. Returns the key or an error message
+    fn derive_key(
+        &self,
+        own_user_id: i32,
+        other_user_id: i32,
+        other_public_key: &str,
+        info: &[u8],
+    ) -> Result<ConversationKey, String> {
+        let other_public_key = PublicKey::from(
+            decode_key(other_public_key).map_err(|err| format!("Invalid public key: {}", err))?,
+        );
+        let shared_secret = self.secret.diffie_hellman(&other_public_key);
+
+        if !shared_secret.was_contributory() {
+            return Err("Invalid public key: the key exchange is not contributory".to_string());
+        }
+
+        // The salt binds the key to the pair of users, independent of who derives it
+        let salt = format!(
+            "{}:{}",
+            own_user_id.min(other_user_id),
+            own_user_id.max(other_user_id)
+        );
+        let mut key = [0u8; 32];
+        Hkdf::<Sha256>::new(Some(salt.as_bytes()), shared_secret.as_bytes())
+            .expand(info, &mut key)
+            .map_err(|err| format!("Failed to derive conversation key: {}", err))?;
+
+        Ok(ConversationKey {
+            cipher: Aes256Gcm::new(&key.into()),
+        })
+    }
+}
+
+/// Implementation of the conversation key
+impl ConversationKey {
+    /// Encrypt a message with a random nonce. The sender and the receiver are authenticated with the message,
+    /// so a stored ciphertext cannot be passed off as a message in the other direction. Returns the encrypted message or an error message
+    pub fn encrypt(
+        &self,
+        sender_id: i32,
+        receiver_id: i32,
+        message: &str,
+    ) -> Result<EncryptedMessage, String> {
+        self.seal(&associated_data(sender_id, receiver_id), message.as_bytes())
+    }
+
+    /// Decrypt a message. Fails if the message was changed or encrypted with another key. Returns the message or an error message
+    pub fn decrypt(
+        &self,
+        sender_id: i32,
+        receiver_id: i32,
+        ciphertext: &str,
+        nonce: &str,
+    ) -> Result<String, String> {
+        let message = self.open(&associated_data(sender_id, receiver_id), ciphertext, nonce)?;
+
+        String::from_utf8(message).map_err(|err| format!("Invalid message: {}", err))
+    }
+
+    /// Encrypt data that is bound to the given associated data. Returns the encrypted data or an error message
+    pub(crate) fn seal(
+        &self,
+        associated_data: &str,
+        plaintext: &[u8],
+    ) -> Result<EncryptedMessage, String> {
+        cipher::seal(&self.cipher, associated_data, plaintext)
+    }
+
+    /// Decrypt data that is bound to the given associated data. Returns the data or an error message
+    pub(crate) fn open(
+        &self,
+        associated_data: &str,
+        ciphertext: &str,
+        nonce: &str,
+    ) -> Result<Vec<u8>, String> {
+        cipher::open(&self.cipher, associated_data, ciphertext, nonce)
+    }
+}
+
+/// Returns the fingerprint of a public key, which users compare to verify that they talk to the right person.
+/// The fingerprint is the beginning of the SHA-256 hash of the key in groups of four hex digits. Returns the fingerprint or an error message
+pub fn fingerprint(public_key: &str) -> Result<String, String> {
+    let bytes = decode_key(public_key).map_err(|err| format!("Invalid public key: {}", err))?;
+    let hash = hex::encode_upper(&Sha256::digest(bytes)[..FINGERPRINT_LENGTH]);
+
+    Ok(hash
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+/// Decode a hex encoded 32 byte key
+fn decode_key(key: &str) -> Result<[u8; 32], String> {
+    hex::decode(key.trim())
+        .map_err(|err| err.to_string())?
+        .try_into()
+        .map_err(|_| "expected 32 bytes".to_string())
+}
+
+/// Returns the data that is authenticated together with a message
+fn associated_data(sender_id: i32, receiver_id: i32) -> String {
+    format!("{}->{}", sender_id, receiver_id)
+}
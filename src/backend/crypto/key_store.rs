@@ -0,0 +1,183 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use log::warn;
+
+use crate::backend::entities::user_key_ops::{find_public_key, publish_public_key};
+
+use super::identity::{fingerprint, IdentityKey};
+
+/// Name of the directory in the configuration directory of the user
+const APP_DIRECTORY: &str = "secse24_group08";
+
+/// Stores the identity keys of the users that logged in on this device. Every key is written to a file
+/// that only the current user can read. The keys never leave the device, only their public keys are published.
+/// Replaced keys are kept, so messages that were encrypted for them can still be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyStore {
+    directory: PathBuf,
+}
+
+/// Default implementation for the KeyStore struct
+impl Default for KeyStore {
+    /// The directory is read from KEY_STORE_PATH, otherwise the configuration directory of the user is used
+    fn default() -> Self {
+        let directory = env::var("KEY_STORE_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                dirs::config_dir()
+                    .unwrap_or_else(env::temp_dir)
+                    .join(APP_DIRECTORY)
+                    .join("keys")
+            });
+
+        KeyStore::new(directory)
+    }
+}
+
+/// Implementation of the key store
+impl KeyStore {
+    /// Create a new key store that uses the given directory
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        KeyStore {
+            directory: directory.into(),
+        }
+    }
+
+    /// Load the identity key of a user. Returns the key, none if no key is stored for the user, or an error message
+    pub fn load(&self, user_id: i32) -> Result<Option<IdentityKey>, String> {
+        match fs::read_to_string(self.key_path(user_id)) {
+            Ok(secret) => IdentityKey::from_hex(&secret).map(Some),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format!("Failed to read identity key: {}", err)),
+        }
+    }
+
+    /// Load an identity key of a user by its public key, the current key or one that was replaced.
+    /// Returns the key, none if the key is not stored on this device, or an error message
+    pub fn load_by_public_key(
+        &self,
+        user_id: i32,
+        public_key: &str,
+    ) -> Result<Option<IdentityKey>, String> {
+        if let Some(identity_key) = self.load(user_id)? {
+            if identity_key.public_key() == public_key {
+                return Ok(Some(identity_key));
+            }
+        }
+
+        // The public key becomes part of a path, so only hex encoded keys are accepted
+        if public_key.len() != 64 || !public_key.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(None);
+        }
+
+        match fs::read_to_string(self.archived_key_path(user_id, public_key)) {
+            Ok(secret) => IdentityKey::from_hex(&secret).map(Some),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format!("Failed to read identity key: {}", err)),
+        }
+    }
+
+    /// Store the identity key of a user. An existing different key is kept under its public key, so messages
+    /// that were encrypted for it can still be read. Returns nothing or an error message
+    pub fn save(&self, user_id: i32, identity_key: &IdentityKey) -> Result<(), String> {
+        fs::create_dir_all(&self.directory)
+            .map_err(|err| format!("Failed to create key directory: {}", err))?;
+
+        if let Some(existing_key) = self.load(user_id)? {
+            let existing_public_key = existing_key.public_key();
+            if existing_public_key != identity_key.public_key() {
+                write_key_file(
+                    &self.archived_key_path(user_id, &existing_public_key),
+                    &existing_key,
+                )?;
+            }
+        }
+
+        write_key_file(&self.key_path(user_id), identity_key)
+    }
+
+    /// Generate a new identity key for a user, store it on this device and publish its public key.
+    /// Returns the identity key or an error message
+    pub fn create_identity(&self, user_id: i32) -> Result<IdentityKey, String> {
+        let identity_key = IdentityKey::generate();
+        self.save(user_id, &identity_key)?;
+        publish_public_key(user_id, &identity_key.public_key())?;
+
+        Ok(identity_key)
+    }
+
+    /// Load the identity key of a user and make sure its public key is published. A new key is created if none is
+    /// stored on this device, e.g. when the user logs in on a new device. Messages encrypted for a key of another device
+    /// can then no longer be read. Replacing a different published key is logged, the chats show that the fingerprint changed.
+    /// Returns the identity key or an error message
+    pub fn load_or_create_identity(&self, user_id: i32) -> Result<IdentityKey, String> {
+        let published_key = find_public_key(user_id)?;
+
+        let Some(identity_key) = self.load(user_id)? else {
+            let identity_key = self.create_identity(user_id)?;
+            if let Some(published_key) = published_key {
+                warn_key_replaced(user_id, &published_key, &identity_key)?;
+            }
+            return Ok(identity_key);
+        };
+
+        let public_key = identity_key.public_key();
+        if published_key.as_deref() != Some(public_key.as_str()) {
+            publish_public_key(user_id, &public_key)?;
+            if let Some(published_key) = published_key {
+                warn_key_replaced(user_id, &published_key, &identity_key)?;
+            }
+        }
+
+        Ok(identity_key)
+    }
+
+    /// Returns the path of the key file of a user
+    fn key_path(&self, user_id: i32) -> PathBuf {
+        self.directory.join(format!("{}.key", user_id))
+    }
+
+    /// Returns the path of the file of a replaced key of a user
+    fn archived_key_path(&self, user_id: i32, public_key: &str) -> PathBuf {
+        self.directory
+            .join(format!("{}-{}.key", user_id, public_key))
+    }
+}
+
+/// Write an identity key to a file that only the current user can read. Returns nothing or an error message
+fn write_key_file(path: &Path, identity_key: &IdentityKey) -> Result<(), String> {
+    let mut options = OpenOptions::new();
+    options.create(true).write(true).truncate(true);
+
+    // Only the current user may read the key
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options
+        .open(path)
+        .map_err(|err| format!("Failed to open identity key file: {}", err))?;
+
+    file.write_all(identity_key.to_hex().as_bytes())
+        .map_err(|err| format!("Failed to write identity key file: {}", err))
+}
+
+/// Log that the published key of a user was replaced by the key of this device. Returns nothing or an error message
+fn warn_key_replaced(
+    user_id: i32,
+    published_key: &str,
+    identity_key: &IdentityKey,
+) -> Result<(), String> {
+    warn!(
+        "The published key of user {} with the fingerprint {} was replaced by the key {} of this device",
+        user_id,
+        fingerprint(published_key)?,
+        fingerprint(&identity_key.public_key())?
+    );
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub(crate) mod cipher;
+pub mod conversation;
+pub mod direct_message;
+pub mod group_message;
+pub mod identity;
+pub mod key_store;
+pub mod password;
@@ -0,0 +1,74 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::rngs::OsRng;
+
+use crate::config::{config, SecurityConfig};
+
+/// Prefixes of the bcrypt hashes that were stored before passwords were hashed with Argon2id
+const BCRYPT_PREFIXES: [&str; 4] = ["$2a$", "$2b$", "$2x$", "$2y$"];
+
+/// Hash a password or code with Argon2id and the configured parameters.
+/// Returns the hash in the PHC string format or an error message
+pub fn hash_password(password: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    hasher(&config().security)?
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|err| format!("Failed to hash password: {}", err))
+}
+
+/// Verify a password or code against a stored Argon2id or bcrypt hash.
+/// Returns true if the password matches, false if it does not, or an error message if the hash cannot be read
+pub fn verify_password(password: &str, stored_hash: &str) -> Result<bool, String> {
+    if is_bcrypt_hash(stored_hash) {
+        return bcrypt::verify(password, stored_hash)
+            .map_err(|err| format!("Failed to verify password: {}", err));
+    }
+
+    let hash = PasswordHash::new(stored_hash)
+        .map_err(|err| format!("Failed to read password hash: {}", err))?;
+
+    // The parameters are read from the hash, so hashes with older parameters can still be verified
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &hash)
+        .is_ok())
+}
+
+/// Check if a stored hash should be replaced, because it is a bcrypt hash or was created with other Argon2id parameters.
+/// Returns true if the password should be hashed again, false otherwise
+pub fn needs_rehash(stored_hash: &str) -> bool {
+    let Ok(hash) = PasswordHash::new(stored_hash) else {
+        return true;
+    };
+    let Ok(params) = Params::try_from(&hash) else {
+        return true;
+    };
+
+    let security = config().security;
+    hash.algorithm != Algorithm::Argon2id.ident()
+        || hash.version != Some(Version::V0x13.into())
+        || params.m_cost() != security.argon2_memory_kib
+        || params.t_cost() != security.argon2_iterations
+        || params.p_cost() != security.argon2_parallelism
+}
+
+/// Check if a stored hash was created with bcrypt. Returns true if it was, false otherwise
+fn is_bcrypt_hash(stored_hash: &str) -> bool {
+    BCRYPT_PREFIXES
+        .iter()
+        .any(|prefix| stored_hash.starts_with(prefix))
+}
+
+/// Returns the Argon2id hasher with the configured parameters or an error message
+fn hasher(security: &SecurityConfig) -> Result<Argon2<'static>, String> {
+    let params = Params::new(
+        security.argon2_memory_kib,
+        security.argon2_iterations,
+        security.argon2_parallelism,
+        None,
+    )
+    .map_err(|err| format!("Invalid Argon2 parameters: {}", err))?;
+
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+}
@@ -1,33 +1,58 @@
-use chrono::{Datelike, Local, NaiveDateTime};
-use dotenv::dotenv;
+use chrono::{DateTime, Utc};
 use regex::Regex;
-use std::env;
 use std::sync::Mutex;
+use std::time::Duration;
 
 use diesel::pg::PgConnection;
-use diesel::Connection;
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
 use lazy_static::lazy_static;
 
+use crate::config::{config, DatabaseConfig};
+use crate::i18n;
+
+/// A connection to the database that is returned to the pool when it is dropped
+pub type DbConnection = PooledConnection<ConnectionManager<PgConnection>>;
+
 lazy_static! {
-    static ref CONNECTION_MUTEX: Mutex<()> = Mutex::new(());
+    /// The connection pool and the database configuration it was created with
+    static ref POOL: Mutex<Option<(DatabaseConfig, Pool<ConnectionManager<PgConnection>>)>> =
+        Mutex::new(None);
 }
 
-/// Establish a connection to the database. Returns the connection or an error message
-pub fn establish_connection() -> Result<PgConnection, diesel::ConnectionError> {
-    let _guard = CONNECTION_MUTEX
-        .lock()
-        .expect("Failed to acquire mutex lock");
-    dotenv().expect("Failed to read .env file");
+/// Establish a connection to the database. The connection is taken from a pool that is created with the database
+/// configuration and recreated when the configuration changes. Returns the connection or an error message
+pub fn establish_connection() -> Result<DbConnection, diesel::ConnectionError> {
+    let database = config().database;
+    if database.url.is_empty() {
+        return Err(diesel::ConnectionError::BadConnection(
+            "No database URL is configured, set DATABASE_URL or database.url in the config file"
+                .to_string(),
+        ));
+    }
 
-    if let Ok(database_url) = env::var("DATABASE_URL") {
-        if let Ok(connection) = PgConnection::establish(&database_url) {
-            return Ok(connection);
+    let pool = {
+        let mut guard = POOL.lock().expect("Failed to acquire mutex lock");
+        match guard.as_ref() {
+            Some((pool_database, pool)) if *pool_database == database => pool.clone(),
+            _ => {
+                // Connections are only opened when they are needed, so the pool can be created while the database is unavailable
+                let pool = Pool::builder()
+                    .max_size(database.pool_size)
+                    .min_idle(Some(0))
+                    .connection_timeout(Duration::from_secs(database.connect_timeout_secs))
+                    .build_unchecked(ConnectionManager::new(&database.url));
+                *guard = Some((database, pool.clone()));
+                pool
+            }
         }
-    }
+    };
 
-    Err(diesel::ConnectionError::BadConnection(
-        "Failed to establish connection to databases".to_string(),
-    ))
+    pool.get().map_err(|err| {
+        diesel::ConnectionError::BadConnection(format!(
+            "Failed to establish connection to databases: {}",
+            err
+        ))
+    })
 }
 
 /// Check if an email is valid.
@@ -41,44 +66,34 @@ pub fn is_valid_email(email: &str) -> bool {
     re.is_match(email)
 }
 
-/// Check if a password is strong.
-/// A strong password must:
+/// Check if a password follows the configured password policy. By default a strong password must:
 /// - Be at least 8 characters long
 /// - Contain at least one lowercase letter
 /// - Contain at least one uppercase letter
 /// - Contain at least one digit
 /// - Contain at least one special character
+/// - Not be in the bundled list of common and breached passwords
 pub fn is_strong_password(password: &str) -> bool {
-    // Define password strength criteria
-    let has_lowercase = password.chars().any(|c| c.is_lowercase());
-    let has_uppercase = password.chars().any(|c| c.is_uppercase());
-    let has_digit = password.chars().any(|c| c.is_ascii_digit());
-    let has_special = password.chars().any(|c| !c.is_alphanumeric());
-
-    // Password is considered strong if it meets all criteria
-    password.len() >= 8 && has_lowercase && has_uppercase && has_digit && has_special
+    config().password_policy.is_satisfied_by(password)
 }
 
-/// Format the send date of a message.
+/// Format the send date of a message in the language and the time zone of the user interface.
 /// The date is formatted conditionally based on the current date and time.
 /// - If the message was sent today, only the time is shown
 /// - If the message was sent this year, the month and day are shown without the year
 /// - If the message was sent in a different year, the full date and time are shown
-pub fn format_send_date(date: NaiveDateTime) -> String {
-    // Get the current date and time in the local timezone
-    let now = Local::now().naive_local();
+pub fn format_send_date(date: DateTime<Utc>) -> String {
+    let time_zone = i18n::time_zone();
 
-    // Format the date conditionally
-    let formatted_date = if date.date() == now.date() {
-        // Same day: show only time
-        date.format("%I:%M %p").to_string()
-    } else if date.year() == now.year() {
-        // Same year but different day: show month and day, but omit year
-        date.format("%A %b %e, %I:%M %p").to_string()
-    } else {
-        // Different year: show full date and time
-        date.format("%A %b %e, %Y %I:%M %p").to_string()
-    };
+    // Both dates are compared in the time zone of the viewer, so "today" is the day of the viewer
+    i18n::format_date(
+        i18n::language(),
+        i18n::local_time(date, time_zone),
+        i18n::local_time(Utc::now(), time_zone),
+    )
+}
 
-    formatted_date
+/// Check if the database can be reached. Returns true if a connection could be established
+pub fn is_database_available() -> bool {
+    establish_connection().is_ok()
 }
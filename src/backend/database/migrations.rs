@@ -0,0 +1,288 @@
+use diesel::migration::{Migration, MigrationSource};
+use diesel::pg::Pg;
+use diesel::sql_types::{Array, Bool, Int4, Int8, Nullable, Text, Time, Timestamp, Timestamptz};
+use diesel::{sql_query, Column, Expression, QueryableByName, RunQueryDsl, Table};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+
+use super::db::establish_connection;
+use super::schema::*;
+
+/// The migrations in the migrations directory, embedded at compile time
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+/// A column of a table, either as the application expects it or as it is found in the database
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, QueryableByName)]
+pub struct SchemaColumn {
+    #[diesel(sql_type = Text)]
+    pub table_name: String,
+    #[diesel(sql_type = Text)]
+    pub column_name: String,
+    /// The name of the type in PostgreSQL, e.g. int4 or _int4 for an array
+    #[diesel(sql_type = Text)]
+    pub data_type: String,
+    #[diesel(sql_type = Bool)]
+    pub nullable: bool,
+}
+
+/// The PostgreSQL type that the application expects for a SQL type of the schema
+trait ExpectedSqlType {
+    const NAME: &'static str;
+    const NULLABLE: bool = false;
+}
+
+macro_rules! expected_sql_types {
+    ($($sql_type:ty => $name:literal),+ $(,)?) => {
+        $(impl ExpectedSqlType for $sql_type {
+            const NAME: &'static str = $name;
+        })+
+    };
+}
+
+expected_sql_types!(
+    Int4 => "int4",
+    Int8 => "int8",
+    Bool => "bool",
+    // Varchar is an alias of Text in diesel, so both are compared as text
+    Text => "text",
+    Time => "time",
+    Timestamp => "timestamp",
+    Timestamptz => "timestamptz",
+    Array<Int4> => "_int4",
+);
+
+impl<T: ExpectedSqlType> ExpectedSqlType for Nullable<T> {
+    const NAME: &'static str = T::NAME;
+    const NULLABLE: bool = true;
+}
+
+/// The columns that the application expects for the columns of a table
+trait ExpectedColumns {
+    fn expected_columns(table_name: &str) -> Vec<SchemaColumn>;
+}
+
+macro_rules! expected_columns {
+    ($($column:ident),+) => {
+        impl<$($column),+> ExpectedColumns for ($($column,)+)
+        where
+            $($column: Column, <$column as Expression>::SqlType: ExpectedSqlType),+
+        {
+            fn expected_columns(table_name: &str) -> Vec<SchemaColumn> {
+                vec![$(SchemaColumn {
+                    table_name: table_name.to_string(),
+                    column_name: $column::NAME.to_string(),
+                    data_type: <<$column as Expression>::SqlType as ExpectedSqlType>::NAME.to_string(),
+                    nullable: <<$column as Expression>::SqlType as ExpectedSqlType>::NULLABLE,
+                }),+]
+            }
+        }
+    };
+}
+
+expected_columns!(A);
+expected_columns!(A, B);
+expected_columns!(A, B, C);
+expected_columns!(A, B, C, D);
+expected_columns!(A, B, C, D, E);
+expected_columns!(A, B, C, D, E, F);
+expected_columns!(A, B, C, D, E, F, G);
+expected_columns!(A, B, C, D, E, F, G, H);
+expected_columns!(A, B, C, D, E, F, G, H, I);
+expected_columns!(A, B, C, D, E, F, G, H, I, J);
+expected_columns!(A, B, C, D, E, F, G, H, I, J, K);
+expected_columns!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Returns the columns that the application expects for a table of the schema
+fn table_columns<T>(table_name: &str) -> Vec<SchemaColumn>
+where
+    T: Table,
+    T::AllColumns: ExpectedColumns,
+{
+    T::AllColumns::expected_columns(table_name)
+}
+
+macro_rules! expected_schema {
+    ($($table:ident),+ $(,)?) => {
+        vec![$(table_columns::<$table::table>(stringify!($table))),+].concat()
+    };
+}
+
+/// Returns the columns of all tables as the schema of the application expects them, ordered by table and column
+pub fn expected_schema() -> Vec<SchemaColumn> {
+    let mut columns = expected_schema!(
+        conversation_reads,
+        group_messages,
+        group_sender_keys,
+        groups,
+        login_failures,
+        notification_settings,
+        password_history,
+        password_resets,
+        pending_verifications,
+        recovery_codes,
+        sessions,
+        user_keys,
+        user_messages,
+        user_preferences,
+        user_to_groups,
+        user_to_user_friends,
+        users,
+    );
+    columns.sort();
+    columns
+}
+
+/// Find the columns of all tables in the database, ordered by table and column. Returns the columns or an error message
+pub fn find_database_schema() -> Result<Vec<SchemaColumn>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    sql_query(
+        "SELECT table_name::text AS table_name, column_name::text AS column_name, \
+         CASE udt_name WHEN 'varchar' THEN 'text' ELSE udt_name::text END AS data_type, is_nullable = 'YES' AS nullable \
+         FROM information_schema.columns \
+         WHERE table_schema = current_schema() AND table_name <> '__diesel_schema_migrations' \
+         ORDER BY table_name, column_name",
+    )
+    .load::<SchemaColumn>(&mut connection)
+    .map_err(|err| format!("Error loading database schema: {}", err))
+}
+
+/// Compare the expected columns with the columns of the database. Returns a description of every difference
+pub fn compare_schema(expected: &[SchemaColumn], actual: &[SchemaColumn]) -> Vec<String> {
+    let find = |columns: &[SchemaColumn], column: &SchemaColumn| {
+        columns
+            .iter()
+            .find(|other| {
+                other.table_name == column.table_name && other.column_name == column.column_name
+            })
+            .cloned()
+    };
+    let describe = |column: &SchemaColumn| {
+        format!(
+            "{}{}",
+            column.data_type,
+            if column.nullable {
+                " NULL"
+            } else {
+                " NOT NULL"
+            }
+        )
+    };
+
+    let mut differences = Vec::new();
+    for column in expected {
+        match find(actual, column) {
+            None => differences.push(format!(
+                "Column {}.{} is missing in the database",
+                column.table_name, column.column_name
+            )),
+            Some(other) if other != *column => differences.push(format!(
+                "Column {}.{} is {} in the database, expected {}",
+                column.table_name,
+                column.column_name,
+                describe(&other),
+                describe(column)
+            )),
+            Some(_) => {}
+        }
+    }
+    for column in actual {
+        if find(expected, column).is_none() {
+            differences.push(format!(
+                "Column {}.{} is unknown to the application",
+                column.table_name, column.column_name
+            ));
+        }
+    }
+
+    differences
+}
+
+/// Check that the database has the schema the application was built for. Returns nothing or an error message
+/// that lists the migrations unknown to the application and every column that differs
+pub fn check_schema() -> Result<(), String> {
+    let mut differences: Vec<String> = find_unknown_migrations()?
+        .into_iter()
+        .map(|version| format!("Migration {} is unknown to the application", version))
+        .collect();
+    differences.extend(compare_schema(&expected_schema(), &find_database_schema()?));
+
+    if differences.is_empty() {
+        return Ok(());
+    }
+
+    let pending = find_pending_migrations()?;
+    if !pending.is_empty() {
+        differences.push(format!(
+            "{} migrations have not been applied yet, run `chatadmin migrate`",
+            pending.len()
+        ));
+    }
+
+    Err(format!(
+        "The database schema does not match the application:\n{}",
+        differences.join("\n")
+    ))
+}
+
+/// Find the embedded migrations that have not been applied yet. Returns their versions or an error message
+pub fn find_pending_migrations() -> Result<Vec<String>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let pending = connection
+        .pending_migrations(MIGRATIONS)
+        .map_err(|err| format!("Error finding pending migrations: {}", err))?;
+
+    Ok(pending
+        .iter()
+        .map(|migration| migration.name().version().to_string())
+        .collect())
+}
+
+/// Apply all embedded migrations that have not been applied yet. Returns the versions of the applied migrations or an error message
+pub fn run_pending_migrations() -> Result<Vec<String>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let applied = connection
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|err| format!("Failed to run migrations: {}", err))?;
+
+    Ok(applied.iter().map(|version| version.to_string()).collect())
+}
+
+/// Check the schema when the application starts, so it only starts with a database it can use. Migrations are not applied here:
+/// clients would race on them and every user would need the right to change the schema, so they are left to `chatadmin migrate`.
+/// If the database cannot be reached nothing is checked, so the data cached on this device can still be read.
+/// Returns nothing or an error message
+pub fn check_database_at_startup() -> Result<(), String> {
+    if establish_connection().is_err() {
+        return Ok(());
+    }
+
+    check_schema()
+}
+
+/// Find the applied migrations that are not embedded, e.g. because the database was migrated by a newer version.
+/// Returns their versions or an error message
+fn find_unknown_migrations() -> Result<Vec<String>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let known: Vec<String> = MigrationSource::<Pg>::migrations(&MIGRATIONS)
+        .map_err(|err| format!("Error loading migrations: {}", err))?
+        .iter()
+        .map(|migration| migration.name().version().to_string())
+        .collect();
+
+    let applied = connection
+        .applied_migrations()
+        .map_err(|err| format!("Error finding applied migrations: {}", err))?;
+
+    Ok(applied
+        .iter()
+        .map(|version| version.to_string())
+        .filter(|version| !known.contains(version))
+        .collect())
+}
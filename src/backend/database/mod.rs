@@ -1,3 +1,4 @@
 pub mod db;
+pub mod migrations;
 pub mod models;
 pub mod schema;
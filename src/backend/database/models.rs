@@ -1,8 +1,19 @@
-use chrono::NaiveDateTime;
-use diesel::{deserialize::Queryable, prelude::Insertable, query_builder::AsChangeset};
+use chrono::{DateTime, NaiveDateTime, NaiveTime, Utc};
+use diesel::{
+    deserialize::{Queryable, QueryableByName},
+    dsl::AsExprOf,
+    prelude::{Insertable, IntoSql, Selectable},
+    query_builder::AsChangeset,
+    sql_types::{Bool, Int4, Int8, Nullable, Text, Timestamptz, Varchar},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
 
 use super::schema::{
-    group_messages, groups, user_messages, user_to_groups, user_to_user_friends, users,
+    group_messages, group_sender_keys, groups, login_failures, notification_settings,
+    password_history, password_resets, pending_verifications, recovery_codes, sessions, user_keys,
+    user_messages, user_preferences, user_to_groups, user_to_user_friends, users,
 };
 use std::fmt;
 
@@ -16,7 +27,7 @@ pub struct NewUser<'a> {
     pub password: &'a str,
 }
 
-#[derive(Debug, Queryable, AsChangeset, Clone, PartialEq, Eq)]
+#[derive(Debug, Queryable, AsChangeset, Clone, PartialEq, Eq, Serialize, Deserialize)]
 /// User is a struct that represents a user in the database
 pub struct User {
     pub id: i32,
@@ -37,18 +48,53 @@ pub struct NewUserMessage<'a> {
     pub sender_id: i32,
     pub receiver_id: i32,
     pub message: &'a str,
+    pub nonce: Option<&'a str>,
+    pub client_id: Option<&'a str>,
+    pub sender_public_key: Option<&'a str>,
+    pub receiver_public_key: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// MessageEncryption is a struct that represents how a direct message was encrypted: its nonce and the public keys of both users
+pub struct MessageEncryption<'a> {
+    pub nonce: &'a str,
+    pub sender_public_key: &'a str,
+    pub receiver_public_key: &'a str,
 }
 
-#[derive(Debug, Queryable, AsChangeset, Clone)]
+#[derive(Debug, Queryable, Selectable, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[diesel(table_name = user_messages)]
 /// UserMessage is a struct that represents a message in the database
 pub struct UserMessage {
     pub sender_id: i32,
     pub receiver_id: i32,
     pub message: String,
-    pub send_date: NaiveDateTime,
+    pub send_date: DateTime<Utc>,
+    pub nonce: Option<String>,
+    /// When the sender edited the message, none if it has not been edited
+    #[serde(default)]
+    pub edited_at: Option<DateTime<Utc>>,
+    /// The public keys the message was encrypted with, none for messages from before they were stored
+    #[serde(default)]
+    pub sender_public_key: Option<String>,
+    #[serde(default)]
+    pub receiver_public_key: Option<String>,
+    /// Set if the message could not be decrypted on this device, the message is then empty. Stored messages are not set
+    #[serde(default)]
+    #[diesel(select_expression = false.into_sql::<Bool>(), select_expression_type = AsExprOf<bool, Bool>)]
+    pub undecryptable: bool,
 }
 
-#[derive(Debug, Insertable, Queryable, AsChangeset, Clone)]
+impl UserMessage {
+    /// Returns the text of the message that is shown, a placeholder if it could not be decrypted
+    pub fn display_text(&self) -> String {
+        display_text(&self.message, self.undecryptable)
+    }
+}
+
+#[derive(
+    Debug, Insertable, Queryable, AsChangeset, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
 #[diesel(table_name = user_to_user_friends)]
 /// UserToUserFriend is a struct that represents a friendship between two users in the database
 pub struct UserToUserFriend {
@@ -65,7 +111,7 @@ pub struct NewGroup<'a> {
     pub name: &'a str,
 }
 
-#[derive(Debug, Queryable, AsChangeset, Clone, PartialEq, Eq)]
+#[derive(Debug, Queryable, AsChangeset, Clone, PartialEq, Eq, Serialize, Deserialize)]
 /// Group is a struct that represents a group in the database
 pub struct Group {
     pub id: i32,
@@ -88,15 +134,35 @@ pub struct NewGroupMessage<'a> {
     pub sender_id: i32,
     pub receiver_id: i32,
     pub message: &'a str,
+    pub key_epoch: Option<i32>,
+    pub nonce: Option<&'a str>,
+    pub client_id: Option<&'a str>,
 }
 
-#[derive(Debug, Queryable, AsChangeset, Clone, PartialEq, Eq)]
+#[derive(Debug, Queryable, Selectable, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[diesel(table_name = group_messages)]
 /// GroupMessage is a struct that represents a group message in the database
 pub struct GroupMessage {
     pub sender_id: i32,
     pub receiver_id: i32,
     pub message: String,
-    pub send_date: NaiveDateTime,
+    pub send_date: DateTime<Utc>,
+    pub key_epoch: Option<i32>,
+    pub nonce: Option<String>,
+    /// When the sender edited the message, none if it has not been edited
+    #[serde(default)]
+    pub edited_at: Option<DateTime<Utc>>,
+    /// Set if the message could not be decrypted on this device, the message is then empty. Stored messages are not set
+    #[serde(default)]
+    #[diesel(select_expression = false.into_sql::<Bool>(), select_expression_type = AsExprOf<bool, Bool>)]
+    pub undecryptable: bool,
+}
+
+impl GroupMessage {
+    /// Returns the text of the message that is shown, a placeholder if it could not be decrypted
+    pub fn display_text(&self) -> String {
+        display_text(&self.message, self.undecryptable)
+    }
 }
 
 #[derive(Debug)]
@@ -105,3 +171,263 @@ pub struct UserPassword {
     pub id: i32,
     pub password: String,
 }
+
+#[derive(Debug, Insertable, Queryable, Clone, PartialEq, Eq)]
+#[diesel(table_name = pending_verifications)]
+/// PendingVerification is a struct that represents an email verification token that has not been redeemed yet.
+/// Only the hash of the token is stored, the token itself is sent to the email
+pub struct PendingVerification {
+    pub token_hash: String,
+    pub user_id: i32,
+    pub email: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable, Queryable, Clone, PartialEq, Eq)]
+#[diesel(table_name = password_resets)]
+/// PasswordReset is a struct that represents a password reset token that has not been redeemed yet.
+/// Only the hash of the token is stored, the token itself is sent to the email of the user
+pub struct PasswordReset {
+    pub token_hash: String,
+    pub user_id: i32,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = password_history)]
+/// NewPasswordHistory is a struct that represents the hash of a replaced password that can be inserted into the database
+pub struct NewPasswordHistory<'a> {
+    pub user_id: i32,
+    pub password_hash: &'a str,
+}
+
+#[derive(Debug, Insertable, Queryable, Clone, PartialEq, Eq)]
+#[diesel(table_name = recovery_codes)]
+/// RecoveryCode is a struct that represents a hashed one-time recovery code for the two-factor authentication of a user
+pub struct RecoveryCode {
+    pub user_id: i32,
+    pub code_hash: String,
+}
+
+#[derive(Debug, Insertable, Queryable, Clone, PartialEq, Eq)]
+#[diesel(table_name = login_failures)]
+/// LoginFailure is a struct that represents the failed login attempts of an account or a source in the database
+pub struct LoginFailure {
+    pub scope: String,
+    pub identifier: String,
+    pub failed_attempts: i32,
+    pub last_failed_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = sessions)]
+/// NewSession is a struct that represents a new session that can be inserted into the database
+pub struct NewSession<'a> {
+    pub user_id: i32,
+    pub token_hash: &'a str,
+    pub device_name: &'a str,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Queryable, Clone, PartialEq, Eq)]
+/// Session is a struct that represents a login session of a user on a device in the database
+pub struct Session {
+    pub id: i32,
+    pub user_id: i32,
+    pub token_hash: String,
+    pub device_name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Insertable, Queryable, Clone, PartialEq, Eq)]
+#[diesel(table_name = user_keys)]
+/// UserKey is a struct that represents the published public identity key of a user in the database
+pub struct UserKey {
+    pub user_id: i32,
+    pub public_key: String,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Queryable, Clone, PartialEq, Eq)]
+#[diesel(table_name = group_sender_keys)]
+/// GroupSenderKey is a struct that represents the sender key of a group member in a key epoch, encrypted for one recipient
+pub struct GroupSenderKey {
+    pub group_id: i32,
+    pub key_epoch: i32,
+    pub sender_id: i32,
+    pub recipient_id: i32,
+    pub distributor_id: i32,
+    pub encrypted_key: String,
+    pub nonce: String,
+    pub created_at: NaiveDateTime,
+    /// The public keys the sender key was encrypted with, none for sender keys from before they were stored
+    pub distributor_public_key: Option<String>,
+    pub recipient_public_key: Option<String>,
+}
+
+#[derive(Debug, Insertable, Queryable, AsChangeset, Clone, PartialEq, Eq)]
+#[diesel(table_name = notification_settings)]
+/// NotificationSettings is a struct that represents the do-not-disturb schedule and the muted conversations of a user in the database
+pub struct NotificationSettings {
+    pub user_id: i32,
+    pub dnd_enabled: bool,
+    pub dnd_start: NaiveTime,
+    pub dnd_end: NaiveTime,
+    pub muted_user_ids: Vec<i32>,
+    pub muted_group_ids: Vec<i32>,
+}
+
+impl NotificationSettings {
+    /// Create the settings of a user that has not changed them: nothing is muted and do not disturb is off
+    pub fn new(user_id: i32) -> Self {
+        NotificationSettings {
+            user_id,
+            dnd_enabled: false,
+            dnd_start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            dnd_end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+            muted_user_ids: Vec::new(),
+            muted_group_ids: Vec::new(),
+        }
+    }
+
+    /// Returns if do not disturb is active at the given time. A schedule whose end is before its start spans midnight
+    pub fn is_do_not_disturb(&self, time: NaiveTime) -> bool {
+        if !self.dnd_enabled {
+            return false;
+        }
+
+        if self.dnd_start <= self.dnd_end {
+            self.dnd_start <= time && time < self.dnd_end
+        } else {
+            time >= self.dnd_start || time < self.dnd_end
+        }
+    }
+}
+
+#[derive(Debug, QueryableByName, Clone, PartialEq, Eq)]
+/// ConversationSummary is a struct that represents a chat of a user with a friend or a group, with its newest message and the number of unread messages
+pub struct ConversationSummary {
+    #[diesel(sql_type = Bool)]
+    pub is_group: bool,
+    #[diesel(sql_type = Int4)]
+    pub conversation_id: i32,
+    /// The name of the group or the first name of the friend
+    #[diesel(sql_type = Varchar)]
+    pub name: String,
+    /// The last name of the friend, none for groups
+    #[diesel(sql_type = Nullable<Varchar>)]
+    pub last_name: Option<String>,
+    #[diesel(sql_type = Nullable<Int4>)]
+    pub sender_id: Option<i32>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub message: Option<String>,
+    #[diesel(sql_type = Nullable<Timestamptz>)]
+    pub send_date: Option<DateTime<Utc>>,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    pub nonce: Option<String>,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    pub sender_public_key: Option<String>,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    pub receiver_public_key: Option<String>,
+    #[diesel(sql_type = Nullable<Int4>)]
+    pub key_epoch: Option<i32>,
+    #[diesel(sql_type = Int8)]
+    pub unread_count: i64,
+    /// Set if the newest message could not be decrypted on this device
+    #[diesel(sql_type = Bool)]
+    pub undecryptable: bool,
+}
+
+impl ConversationSummary {
+    /// Returns the friend of a chat with a friend
+    pub fn user(&self) -> Option<User> {
+        (!self.is_group).then(|| User {
+            id: self.conversation_id,
+            first_name: self.name.clone(),
+            last_name: self.last_name.clone().unwrap_or_default(),
+        })
+    }
+
+    /// Returns the group of a group chat
+    pub fn group(&self) -> Option<Group> {
+        self.is_group.then(|| Group {
+            id: self.conversation_id,
+            name: self.name.clone(),
+        })
+    }
+
+    /// Returns the name that is shown for the chat
+    pub fn display_name(&self) -> String {
+        match &self.last_name {
+            Some(last_name) => format!("{} {}", self.name, last_name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Returns the text of the newest message that is shown, none if the chat has no messages
+    pub fn display_text(&self) -> Option<String> {
+        self.message
+            .as_deref()
+            .map(|message| display_text(message, self.undecryptable))
+    }
+}
+
+/// Returns the text of a message that is shown, a placeholder if it could not be decrypted
+fn display_text(message: &str, undecryptable: bool) -> String {
+    if undecryptable {
+        t("message-undecryptable")
+    } else {
+        message.to_string()
+    }
+}
+
+#[derive(Debug, Insertable, Queryable, AsChangeset, Clone, PartialEq, Eq)]
+#[diesel(table_name = user_preferences)]
+/// UserPreferences is a struct that represents the preferences a user syncs to the account in the database
+pub struct UserPreferences {
+    pub user_id: i32,
+    /// The synced preferences as TOML
+    pub preferences: String,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Queryable, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// UserAccount is a struct that represents the account of a user as seen by an operator
+pub struct UserAccount {
+    pub id: i32,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    /// When the account was disabled, none if the user can log in
+    pub disabled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// GroupOverview is a struct that represents a group and the number of its members
+pub struct GroupOverview {
+    pub id: i32,
+    pub name: String,
+    pub member_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Statistics is a struct that represents the number of records stored in the database
+pub struct Statistics {
+    pub users: i64,
+    pub disabled_users: i64,
+    pub groups: i64,
+    pub direct_messages: i64,
+    pub group_messages: i64,
+    pub active_sessions: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// PurgedMessages is a struct that represents the number of messages deleted by a purge
+pub struct PurgedMessages {
+    pub direct_messages: usize,
+    pub group_messages: usize,
+}
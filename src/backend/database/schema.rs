@@ -1,52 +1,177 @@
 // @generated automatically by Diesel CLI.
 
-use diesel::table;
+diesel::table! {
+    conversation_reads (user_id, is_group, conversation_id) {
+        user_id -> Int4,
+        is_group -> Bool,
+        conversation_id -> Int4,
+        last_read_at -> Timestamptz,
+    }
+}
 
-table! {
+diesel::table! {
     group_messages (sender_id, receiver_id, send_date) {
         sender_id -> Int4,
         receiver_id -> Int4,
         message -> Text,
-        send_date -> Timestamp,
+        send_date -> Timestamptz,
+        key_epoch -> Nullable<Int4>,
+        #[max_length = 24]
+        nonce -> Nullable<Varchar>,
+        #[max_length = 32]
+        client_id -> Nullable<Varchar>,
+        edited_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    group_sender_keys (group_id, key_epoch, sender_id, recipient_id) {
+        group_id -> Int4,
+        key_epoch -> Int4,
+        sender_id -> Int4,
+        recipient_id -> Int4,
+        distributor_id -> Int4,
+        #[max_length = 96]
+        encrypted_key -> Varchar,
+        #[max_length = 24]
+        nonce -> Varchar,
+        created_at -> Timestamp,
+        #[max_length = 64]
+        distributor_public_key -> Nullable<Varchar>,
+        #[max_length = 64]
+        recipient_public_key -> Nullable<Varchar>,
     }
 }
 
-table! {
+diesel::table! {
     groups (id) {
         id -> Int4,
+        #[max_length = 100]
         name -> Varchar,
         creation_date -> Timestamp,
+        key_epoch -> Int4,
     }
 }
 
-table! {
-    user_to_groups (user_id, group_id, accepted_invite) {
+diesel::table! {
+    login_failures (scope, identifier) {
+        #[max_length = 20]
+        scope -> Varchar,
+        #[max_length = 255]
+        identifier -> Varchar,
+        failed_attempts -> Int4,
+        last_failed_at -> Timestamptz,
+        locked_until -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    notification_settings (user_id) {
         user_id -> Int4,
-        group_id -> Int4,
-        accepted_invite -> Bool,
+        dnd_enabled -> Bool,
+        dnd_start -> Time,
+        dnd_end -> Time,
+        muted_user_ids -> Array<Int4>,
+        muted_group_ids -> Array<Int4>,
+    }
+}
+
+diesel::table! {
+    password_history (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 255]
+        password_hash -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    password_resets (token_hash) {
+        #[max_length = 64]
+        token_hash -> Varchar,
+        user_id -> Int4,
+        expires_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    pending_verifications (token_hash) {
+        #[max_length = 64]
+        token_hash -> Varchar,
+        user_id -> Int4,
+        #[max_length = 100]
+        email -> Varchar,
+        expires_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    recovery_codes (user_id, code_hash) {
+        user_id -> Int4,
+        #[max_length = 255]
+        code_hash -> Varchar,
     }
 }
 
-table! {
+diesel::table! {
+    sessions (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        #[max_length = 255]
+        device_name -> Varchar,
+        created_at -> Timestamptz,
+        last_seen_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    user_keys (user_id) {
+        user_id -> Int4,
+        #[max_length = 64]
+        public_key -> Varchar,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
     user_messages (sender_id, receiver_id, send_date) {
         sender_id -> Int4,
         receiver_id -> Int4,
         message -> Text,
-        send_date -> Timestamp,
+        send_date -> Timestamptz,
+        #[max_length = 24]
+        nonce -> Nullable<Varchar>,
+        #[max_length = 32]
+        client_id -> Nullable<Varchar>,
+        edited_at -> Nullable<Timestamptz>,
+        #[max_length = 64]
+        sender_public_key -> Nullable<Varchar>,
+        #[max_length = 64]
+        receiver_public_key -> Nullable<Varchar>,
     }
 }
 
-table! {
-    users (id) {
-        id -> Int4,
-        first_name -> Varchar,
-        last_name -> Varchar,
-        email -> Varchar,
-        password -> Varchar,
+diesel::table! {
+    user_preferences (user_id) {
+        user_id -> Int4,
+        preferences -> Text,
+        updated_at -> Timestamp,
     }
 }
 
-table! {
+diesel::table! {
+    user_to_groups (user_id, group_id) {
+        user_id -> Int4,
+        group_id -> Int4,
+        accepted_invite -> Bool,
+    }
+}
+
+diesel::table! {
     user_to_user_friends (user_one_id, user_two_id) {
         user_one_id -> Int4,
         user_two_id -> Int4,
@@ -54,3 +179,57 @@ table! {
         accepted_user_two -> Bool,
     }
 }
+
+diesel::table! {
+    users (id) {
+        id -> Int4,
+        #[max_length = 50]
+        first_name -> Varchar,
+        #[max_length = 50]
+        last_name -> Varchar,
+        #[max_length = 100]
+        email -> Varchar,
+        #[max_length = 255]
+        password -> Varchar,
+        sessions_revoked_at -> Nullable<Timestamptz>,
+        #[max_length = 64]
+        totp_secret -> Nullable<Varchar>,
+        totp_enabled -> Bool,
+        disabled_at -> Nullable<Timestamptz>,
+        totp_last_step -> Nullable<Int8>,
+    }
+}
+diesel::joinable!(conversation_reads -> users (user_id));
+diesel::joinable!(group_messages -> groups (receiver_id));
+diesel::joinable!(group_messages -> users (sender_id));
+diesel::joinable!(group_sender_keys -> groups (group_id));
+diesel::joinable!(notification_settings -> users (user_id));
+diesel::joinable!(password_history -> users (user_id));
+diesel::joinable!(password_resets -> users (user_id));
+diesel::joinable!(pending_verifications -> users (user_id));
+diesel::joinable!(recovery_codes -> users (user_id));
+diesel::joinable!(sessions -> users (user_id));
+diesel::joinable!(user_keys -> users (user_id));
+diesel::joinable!(user_preferences -> users (user_id));
+diesel::joinable!(user_to_groups -> groups (group_id));
+diesel::joinable!(user_to_groups -> users (user_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    conversation_reads,
+    group_messages,
+    group_sender_keys,
+    groups,
+    login_failures,
+    notification_settings,
+    password_history,
+    password_resets,
+    pending_verifications,
+    recovery_codes,
+    sessions,
+    user_keys,
+    user_messages,
+    user_preferences,
+    user_to_groups,
+    user_to_user_friends,
+    users,
+);
@@ -0,0 +1,103 @@
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::{
+    group_messages, groups, sessions, user_messages, user_to_groups, users,
+};
+use crate::backend::entities::group_ops::find_all_groups;
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+
+/// Find all groups with the number of their members, ordered by id. Returns the groups or an error message
+pub fn find_group_overviews() -> Result<Vec<GroupOverview>, String> {
+    let groups = find_all_groups()?;
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let member_counts = user_to_groups::table
+        .group_by(user_to_groups::group_id)
+        .select((user_to_groups::group_id, count_star()))
+        .load::<(i32, i64)>(&mut connection)
+        .map_err(|err| format!("Error counting group members: {}", err))?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+    Ok(groups
+        .into_iter()
+        .map(|group| GroupOverview {
+            member_count: member_counts.get(&group.id).copied().unwrap_or_default() as usize,
+            id: group.id,
+            name: group.name,
+        })
+        .collect())
+}
+
+/// Count the users, groups, messages and sessions in the database. Returns the statistics or an error message
+pub fn find_statistics() -> Result<Statistics, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let count_error = |err: diesel::result::Error| format!("Error counting records: {}", err);
+
+    Ok(Statistics {
+        users: users::table
+            .count()
+            .get_result(&mut connection)
+            .map_err(count_error)?,
+        disabled_users: users::table
+            .filter(users::disabled_at.is_not_null())
+            .count()
+            .get_result(&mut connection)
+            .map_err(count_error)?,
+        groups: groups::table
+            .count()
+            .get_result(&mut connection)
+            .map_err(count_error)?,
+        direct_messages: user_messages::table
+            .count()
+            .get_result(&mut connection)
+            .map_err(count_error)?,
+        group_messages: group_messages::table
+            .count()
+            .get_result(&mut connection)
+            .map_err(count_error)?,
+        // Sessions started before all sessions of their user were revoked, e.g. by a password reset, are not active anymore
+        active_sessions: sessions::table
+            .inner_join(users::table)
+            .filter(sessions::revoked_at.is_null())
+            .filter(
+                users::sessions_revoked_at
+                    .is_null()
+                    .or(users::sessions_revoked_at.le(sessions::created_at.nullable())),
+            )
+            .count()
+            .get_result(&mut connection)
+            .map_err(count_error)?,
+    })
+}
+
+/// Delete all direct and group messages sent before the given time. Returns the number of deleted messages or an error message
+pub fn purge_messages_before(before: DateTime<Utc>) -> Result<PurgedMessages, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    connection
+        .transaction::<_, diesel::result::Error, _>(|connection| {
+            let direct_messages =
+                diesel::delete(user_messages::table.filter(user_messages::send_date.lt(before)))
+                    .execute(connection)?;
+            let group_messages =
+                diesel::delete(group_messages::table.filter(group_messages::send_date.lt(before)))
+                    .execute(connection)?;
+
+            Ok(PurgedMessages {
+                direct_messages,
+                group_messages,
+            })
+        })
+        .map_err(|err| format!("Failed to purge messages: {}", err))
+}
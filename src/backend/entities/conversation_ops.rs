@@ -0,0 +1,86 @@
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::conversation_reads::{self};
+
+use diesel::dsl::now;
+use diesel::prelude::*;
+use diesel::sql_types::Int4;
+
+/// The chats of a user with the friends and the accepted groups. Each chat has its newest message and the number of messages
+/// of others that were sent after the user last read the chat. Chats with the newest messages come first
+const CONVERSATION_SUMMARIES_QUERY: &str = "
+    SELECT FALSE AS is_group, u.id AS conversation_id, u.first_name AS name, u.last_name AS last_name,
+        last_message.sender_id, last_message.message, last_message.send_date, last_message.nonce,
+        last_message.sender_public_key, last_message.receiver_public_key, NULL::INT AS key_epoch,
+        FALSE AS undecryptable,
+        (SELECT COUNT(*) FROM user_messages m
+            WHERE m.sender_id = u.id AND m.receiver_id = $1
+            AND m.send_date > COALESCE(r.last_read_at, '-infinity')) AS unread_count
+    FROM user_to_user_friends f
+    JOIN users u ON u.id = CASE WHEN f.user_one_id = $1 THEN f.user_two_id ELSE f.user_one_id END
+    LEFT JOIN LATERAL (
+        SELECT m.sender_id, m.message, m.send_date, m.nonce, m.sender_public_key, m.receiver_public_key
+        FROM user_messages m
+        WHERE (m.sender_id = $1 AND m.receiver_id = u.id) OR (m.sender_id = u.id AND m.receiver_id = $1)
+        ORDER BY m.send_date DESC LIMIT 1
+    ) last_message ON TRUE
+    LEFT JOIN conversation_reads r ON r.user_id = $1 AND NOT r.is_group AND r.conversation_id = u.id
+    WHERE (f.user_one_id = $1 OR f.user_two_id = $1) AND f.accepted_user_one AND f.accepted_user_two
+    UNION ALL
+    SELECT TRUE, g.id, g.name, NULL,
+        last_message.sender_id, last_message.message, last_message.send_date, last_message.nonce,
+        NULL::VARCHAR, NULL::VARCHAR, last_message.key_epoch, FALSE,
+        (SELECT COUNT(*) FROM group_messages m
+            WHERE m.receiver_id = g.id AND m.sender_id <> $1
+            AND m.send_date > COALESCE(r.last_read_at, '-infinity'))
+    FROM user_to_groups ug
+    JOIN groups g ON g.id = ug.group_id
+    LEFT JOIN LATERAL (
+        SELECT m.sender_id, m.message, m.send_date, m.nonce, m.key_epoch FROM group_messages m
+        WHERE m.receiver_id = g.id
+        ORDER BY m.send_date DESC LIMIT 1
+    ) last_message ON TRUE
+    LEFT JOIN conversation_reads r ON r.user_id = $1 AND r.is_group AND r.conversation_id = g.id
+    WHERE ug.user_id = $1 AND ug.accepted_invite
+    ORDER BY send_date DESC NULLS LAST, name";
+
+/// Find the chats of a user with the friends and the groups of the user, sorted by their newest message.
+/// The messages are returned as they are stored. Returns the conversation summaries or an error message
+pub fn find_conversation_summaries(user_id: i32) -> Result<Vec<ConversationSummary>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    diesel::sql_query(CONVERSATION_SUMMARIES_QUERY)
+        .bind::<Int4, _>(user_id)
+        .load::<ConversationSummary>(&mut connection)
+        .map_err(|err| format!("Error loading conversations: {}", err))
+}
+
+/// Mark a chat of a user with another user or a group as read up to now. Returns nothing or an error message
+pub fn mark_conversation_read(
+    user_id: i32,
+    is_group: bool,
+    conversation_id: i32,
+) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    diesel::insert_into(conversation_reads::table)
+        .values((
+            conversation_reads::user_id.eq(user_id),
+            conversation_reads::is_group.eq(is_group),
+            conversation_reads::conversation_id.eq(conversation_id),
+            conversation_reads::last_read_at.eq(now),
+        ))
+        .on_conflict((
+            conversation_reads::user_id,
+            conversation_reads::is_group,
+            conversation_reads::conversation_id,
+        ))
+        .do_update()
+        .set(conversation_reads::last_read_at.eq(now))
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to mark conversation as read: {}", err))?;
+
+    Ok(())
+}
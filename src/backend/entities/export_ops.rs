@@ -0,0 +1,484 @@
+use crate::backend::crypto::{
+    direct_message::load_direct_messages, group_message::load_group_messages, key_store::KeyStore,
+};
+use crate::backend::entities::{
+    group_ops::find_groups_by_ids, user_group_ops::find_all_user_groups_of_group,
+    user_ops::find_users_by_ids,
+};
+use crate::i18n::{self, t, t_args};
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Format of the times in the HTML and plain text exports, in the time zone of the user interface
+const EXPORT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Format of the first and last day of the exported time span
+const EXPORT_DAY_FORMAT: &str = "%Y-%m-%d";
+
+/// A loaded message before it is exported: sender ID, send date, edit date and text
+type LoadedMessage = (i32, DateTime<Utc>, Option<DateTime<Utc>>, String);
+
+/// The file formats a chat can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ExportFormat {
+    Json,
+    Html,
+    Text,
+}
+
+/// Whether an export contains a chat between two users or the chat of a group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatKind {
+    Direct,
+    Group,
+}
+
+/// The time span of the exported messages. Open ends include all messages before or after
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportRange {
+    pub from: Option<DateTime<Utc>>,
+    /// The end of the span, messages sent at this time are not included
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// A user that took part in an exported chat
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportedParticipant {
+    pub id: i32,
+    pub name: String,
+}
+
+/// A decrypted message of an exported chat
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportedMessage {
+    pub sender_id: i32,
+    pub sender_name: String,
+    pub sent_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub message: String,
+}
+
+/// The messages of a chat with the names of their senders, ready to be written in one of the export formats
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChatExport {
+    pub kind: ChatKind,
+    /// The ID of the other user or of the group
+    pub chat_id: i32,
+    pub chat_name: String,
+    pub exported_by: i32,
+    pub exported_at: DateTime<Utc>,
+    pub from: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub participants: Vec<ExportedParticipant>,
+    pub messages: Vec<ExportedMessage>,
+}
+
+/// Implementation of the export format
+impl ExportFormat {
+    /// All formats in the order they are offered
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Json, ExportFormat::Html, ExportFormat::Text];
+
+    /// Returns the file extension of the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Html => "html",
+            ExportFormat::Text => "txt",
+        }
+    }
+}
+
+/// Display implementation for the export format, used by the format selection
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExportFormat::Json => write!(f, "JSON"),
+            ExportFormat::Html => write!(f, "HTML"),
+            ExportFormat::Text => write!(f, "{}", t("export-format-text")),
+        }
+    }
+}
+
+/// Implementation of the export range
+impl ExportRange {
+    /// Build the range from the first and the last day to export in the time zone of the user interface, both days included.
+    /// Returns the range or an error message if the last day is before the first day
+    pub fn from_dates(from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<Self, String> {
+        if let (Some(from), Some(to)) = (from, to) {
+            if to < from {
+                return Err(t("export-invalid-range"));
+            }
+        }
+
+        let time_zone = i18n::time_zone();
+        let start_of =
+            |date: NaiveDate| i18n::utc_time(date.and_time(Default::default()), time_zone);
+
+        Ok(ExportRange {
+            from: from.map(start_of),
+            until: to.map(|to| start_of(to + Duration::days(1))),
+        })
+    }
+
+    /// Check if a message sent at the given time is in the range. Returns true if it is, false otherwise
+    pub fn contains(&self, date: DateTime<Utc>) -> bool {
+        self.from.is_none_or(|from| date >= from) && self.until.is_none_or(|until| date < until)
+    }
+}
+
+/// Export the chat between a user and another user. The messages are loaded with all messages between the users
+/// and decrypted with the keys of this device. Returns the export or an error message
+pub fn export_direct_chat(
+    key_store: &KeyStore,
+    user_id: i32,
+    other_user_id: i32,
+    range: ExportRange,
+) -> Result<ChatExport, String> {
+    let names = find_names(vec![user_id, other_user_id])?;
+    let messages = load_direct_messages(key_store, user_id, other_user_id, None)?
+        .into_iter()
+        .map(|message| {
+            (
+                message.sender_id,
+                message.send_date,
+                message.edited_at,
+                message.display_text(),
+            )
+        })
+        .collect();
+
+    Ok(build_export(
+        ChatKind::Direct,
+        other_user_id,
+        name_of(&names, other_user_id),
+        user_id,
+        range,
+        vec![user_id, other_user_id],
+        &names,
+        messages,
+    ))
+}
+
+/// Export the chat of a group for a user. The messages are loaded with all messages of the group and decrypted with
+/// the sender keys the user received, messages the user cannot read are exported with a placeholder.
+/// Returns the export or an error message
+pub fn export_group_chat(
+    key_store: &KeyStore,
+    user_id: i32,
+    group_id: i32,
+    range: ExportRange,
+) -> Result<ChatExport, String> {
+    let group_name = find_groups_by_ids(vec![group_id])?
+        .into_iter()
+        .next()
+        .map(|group| group.name)
+        .ok_or_else(|| format!("Group with id {} not found", group_id))?;
+
+    let group_messages = load_group_messages(key_store, user_id, group_id, None)?;
+
+    // Members that left the group are still named as senders of their messages
+    let member_ids: Vec<i32> = find_all_user_groups_of_group(group_id)?
+        .into_iter()
+        .filter(|user_group| user_group.accepted_invite)
+        .map(|user_group| user_group.user_id)
+        .collect();
+    let user_ids: BTreeSet<i32> = member_ids
+        .iter()
+        .copied()
+        .chain(group_messages.iter().map(|message| message.sender_id))
+        .collect();
+    let names = find_names(user_ids.into_iter().collect())?;
+
+    let messages = group_messages
+        .into_iter()
+        .map(|message| {
+            (
+                message.sender_id,
+                message.send_date,
+                message.edited_at,
+                message.display_text(),
+            )
+        })
+        .collect();
+
+    Ok(build_export(
+        ChatKind::Group,
+        group_id,
+        group_name,
+        user_id,
+        range,
+        member_ids,
+        &names,
+        messages,
+    ))
+}
+
+/// Implementation of the chat export
+impl ChatExport {
+    /// Returns the export in the given format or an error message
+    pub fn render(&self, format: ExportFormat) -> Result<String, String> {
+        match format {
+            ExportFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|err| format!("Failed to serialize chat export: {}", err)),
+            ExportFormat::Html => Ok(self.to_html()),
+            ExportFormat::Text => Ok(self.to_text()),
+        }
+    }
+
+    /// Returns the title of the export in the language of the user interface
+    pub fn title(&self) -> String {
+        let key = match self.kind {
+            ChatKind::Direct => "export-title-direct",
+            ChatKind::Group => "export-title-group",
+        };
+        t_args(key, &[("name", self.chat_name.clone())])
+    }
+
+    /// Returns the lines below the title: when the chat was exported and which messages it contains
+    fn header_lines(&self) -> Vec<String> {
+        let mut lines = vec![t_args(
+            "export-exported-at",
+            &[("date", format_time(self.exported_at))],
+        )];
+
+        if self.from.is_some() || self.until.is_some() {
+            // The end of the range is exclusive, so the last day is the day before it
+            let to = self
+                .until
+                .map(|until| format_day(until - Duration::minutes(1)))
+                .unwrap_or_else(|| "…".to_string());
+            let from = self.from.map(format_day).unwrap_or_else(|| "…".to_string());
+            lines.push(t_args("export-range", &[("from", from), ("to", to)]));
+        }
+        if self.messages.is_empty() {
+            lines.push(t("export-no-messages"));
+        }
+
+        lines
+    }
+
+    /// Returns the sender and time of a message, e.g. "[2024-05-01 12:30] John Doe"
+    fn message_heading(message: &ExportedMessage) -> String {
+        let edited = match message.edited_at {
+            Some(_) => format!(" ({})", t("export-edited")),
+            None => String::new(),
+        };
+        format!(
+            "[{}] {}{}",
+            format_time(message.sent_at),
+            message.sender_name,
+            edited
+        )
+    }
+
+    /// Returns the export as plain text. Lines of a message after the first are indented
+    fn to_text(&self) -> String {
+        let mut text = format!("{}\n{}\n", self.title(), self.header_lines().join("\n"));
+
+        for message in &self.messages {
+            text.push('\n');
+            text.push_str(&format!(
+                "{}: {}",
+                Self::message_heading(message),
+                message.message.replace('\n', "\n    ")
+            ));
+        }
+        text.push('\n');
+
+        text
+    }
+
+    /// Returns the export as an HTML document that can be opened without any other file
+    fn to_html(&self) -> String {
+        let header = self
+            .header_lines()
+            .iter()
+            .map(|line| format!("<p class=\"info\">{}</p>", escape_html(line)))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let messages = self
+            .messages
+            .iter()
+            .map(|message| {
+                let own = if message.sender_id == self.exported_by {
+                    " own"
+                } else {
+                    ""
+                };
+                format!(
+                    "<div class=\"message{}\">\n<div class=\"heading\">{}</div>\n<div class=\"text\">{}</div>\n</div>",
+                    own,
+                    escape_html(&Self::message_heading(message)),
+                    escape_html(&message.message).replace('\n', "<br>")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            "<!DOCTYPE html>
+<html lang=\"{}\">
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 50em; margin: 2em auto; padding: 0 1em; color: #1e1e1e; background: #ffffff; }}
+.info {{ color: #555555; margin: 0.2em 0; }}
+.message {{ margin: 1em 0; padding: 0.5em 0.8em; border-radius: 0.5em; background: #ececec; }}
+.message.own {{ margin-left: 4em; background: #dceaff; }}
+.heading {{ font-size: 0.85em; color: #555555; margin-bottom: 0.3em; }}
+.text {{ white-space: pre-wrap; overflow-wrap: anywhere; }}
+</style>
+</head>
+<body>
+<h1>{}</h1>
+{}
+{}
+</body>
+</html>
+",
+            i18n::language().code(),
+            escape_html(&self.title()),
+            escape_html(&self.title()),
+            header,
+            messages
+        )
+    }
+}
+
+/// Write an export in the given format to a file. Missing directories are created.
+/// Returns nothing or an error message
+pub fn save_chat_export(
+    export: &ChatExport,
+    format: ExportFormat,
+    path: &Path,
+) -> Result<(), String> {
+    let content = export.render(format)?;
+
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Failed to create directory {}: {}", parent.display(), err))?;
+    }
+
+    fs::write(path, content).map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+/// Returns the suggested path of an export: a file named after the chat and the current date in the download directory
+/// of the user, or in the home directory if there is none
+pub fn default_export_path(chat_name: &str, format: ExportFormat) -> PathBuf {
+    let name: String = chat_name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    dirs::download_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_default()
+        .join(format!(
+            "chat-{}-{}.{}",
+            name,
+            Local::now().format("%Y-%m-%d"),
+            format.extension()
+        ))
+}
+
+/// Build the export from the loaded messages. Only the messages in the range are exported, ordered by their send date
+#[allow(clippy::too_many_arguments)]
+fn build_export(
+    kind: ChatKind,
+    chat_id: i32,
+    chat_name: String,
+    exported_by: i32,
+    range: ExportRange,
+    participant_ids: Vec<i32>,
+    names: &HashMap<i32, String>,
+    messages: Vec<LoadedMessage>,
+) -> ChatExport {
+    let mut messages: Vec<ExportedMessage> = messages
+        .into_iter()
+        .filter(|(_, sent_at, _, _)| range.contains(*sent_at))
+        .map(|(sender_id, sent_at, edited_at, message)| ExportedMessage {
+            sender_id,
+            sender_name: name_of(names, sender_id),
+            sent_at,
+            edited_at,
+            message,
+        })
+        .collect();
+    messages.sort_by_key(|message| message.sent_at);
+
+    ChatExport {
+        kind,
+        chat_id,
+        chat_name,
+        exported_by,
+        exported_at: Utc::now(),
+        from: range.from,
+        until: range.until,
+        participants: participant_ids
+            .into_iter()
+            .map(|id| ExportedParticipant {
+                id,
+                name: name_of(names, id),
+            })
+            .collect(),
+        messages,
+    }
+}
+
+/// Find the full names of users. Returns the names by user ID or an error message
+fn find_names(user_ids: Vec<i32>) -> Result<HashMap<i32, String>, String> {
+    Ok(find_users_by_ids(user_ids)?
+        .into_iter()
+        .map(|user| (user.id, format!("{} {}", user.first_name, user.last_name)))
+        .collect())
+}
+
+/// Returns the name of a user, or a placeholder if the user no longer exists
+fn name_of(names: &HashMap<i32, String>, user_id: i32) -> String {
+    names
+        .get(&user_id)
+        .cloned()
+        .unwrap_or_else(|| t("export-unknown-user"))
+}
+
+/// Returns a point in time as it is shown in the exports, in the time zone of the user interface
+fn format_time(date: DateTime<Utc>) -> String {
+    i18n::local_time(date, i18n::time_zone())
+        .format(EXPORT_TIME_FORMAT)
+        .to_string()
+}
+
+/// Returns the day of a point in time as it is shown in the exports, in the time zone of the user interface
+fn format_day(date: DateTime<Utc>) -> String {
+    i18n::local_time(date, i18n::time_zone())
+        .format(EXPORT_DAY_FORMAT)
+        .to_string()
+}
+
+/// Escape the characters that have a meaning in HTML
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -2,36 +2,51 @@ use crate::backend::database::db::establish_connection;
 use crate::backend::database::models::*;
 use crate::backend::database::schema::group_messages::{self};
 
+use chrono::{DateTime, Utc};
+use diesel::dsl::now;
 use diesel::prelude::*;
 
-/// Create a new group message. Returns the group message or an error message
+/// Create a new group message. Encrypted messages are stored with the key epoch and the nonce they were encrypted with.
+/// A message with a client ID is only stored once, sending it again by the same sender returns the stored message.
+/// Returns the group message or an error message
 pub fn create_group_message(
     sender_id: i32,
     receiver_id: i32,
     message: &str,
+    key_epoch: Option<i32>,
+    nonce: Option<&str>,
+    client_id: Option<&str>,
 ) -> Result<GroupMessage, String> {
     let new_group_message = NewGroupMessage {
         sender_id,
         receiver_id,
         message,
+        key_epoch,
+        nonce,
+        client_id,
     };
 
     let mut connection =
         establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
 
-    diesel::insert_into(group_messages::table)
+    let inserted_message = diesel::insert_into(group_messages::table)
         .values(&new_group_message)
-        .execute(&mut connection)
+        .on_conflict(group_messages::client_id)
+        .do_nothing()
+        .returning(GroupMessage::as_select())
+        .get_result::<GroupMessage>(&mut connection)
+        .optional()
         .map_err(|err| format!("Failed to insert group message: {}", err))?;
 
-    let group_message_summary = GroupMessage {
-        sender_id: new_group_message.sender_id,
-        receiver_id: new_group_message.receiver_id,
-        message: new_group_message.message.to_owned(),
-        send_date: chrono::Local::now().naive_local(),
-    };
-
-    Ok(group_message_summary)
+    match inserted_message {
+        Some(group_message) => Ok(group_message),
+        None => group_messages::table
+            .filter(group_messages::client_id.eq(client_id))
+            .filter(group_messages::sender_id.eq(sender_id))
+            .select(GroupMessage::as_select())
+            .first::<GroupMessage>(&mut connection)
+            .map_err(|err| format!("Failed to find sent group message: {}", err)),
+    }
 }
 
 /// Find all group messages. Returns a vector of group messages or an error message
@@ -41,12 +56,70 @@ pub fn find_all_messages_of_group(group_id: i32) -> Result<Vec<GroupMessage>, St
 
     let messages = group_messages::table
         .filter(group_messages::receiver_id.eq(group_id))
+        .select(GroupMessage::as_select())
         .load::<GroupMessage>(&mut connection)
         .map_err(|err| format!("Failed to load group messages: {}", err))?;
 
     Ok(messages)
 }
 
+/// Find the messages of a group that were sent or edited at or after the given date. Returns a vector of group messages or an error message
+pub fn find_messages_of_group_since(
+    group_id: i32,
+    since: DateTime<Utc>,
+) -> Result<Vec<GroupMessage>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let messages = group_messages::table
+        .filter(group_messages::receiver_id.eq(group_id))
+        .filter(
+            group_messages::send_date
+                .ge(since)
+                .or(group_messages::edited_at.ge(since)),
+        )
+        .select(GroupMessage::as_select())
+        .load::<GroupMessage>(&mut connection)
+        .map_err(|err| format!("Failed to load group messages: {}", err))?;
+
+    Ok(messages)
+}
+
+/// Replace the text of a message the sender sent to a group. Encrypted messages are stored with the key epoch and the nonce
+/// they were encrypted with now. Returns the time of the edit or an error message
+pub fn update_group_message(
+    sender_id: i32,
+    group_id: i32,
+    send_date: DateTime<Utc>,
+    message: &str,
+    key_epoch: Option<i32>,
+    nonce: Option<&str>,
+) -> Result<DateTime<Utc>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let edited_at = diesel::update(
+        group_messages::table
+            .filter(group_messages::sender_id.eq(sender_id))
+            .filter(group_messages::receiver_id.eq(group_id))
+            .filter(group_messages::send_date.eq(send_date)),
+    )
+    .set((
+        group_messages::message.eq(message),
+        group_messages::key_epoch.eq(key_epoch),
+        group_messages::nonce.eq(nonce),
+        group_messages::edited_at.eq(now),
+    ))
+    .returning(group_messages::edited_at)
+    .get_result::<Option<DateTime<Utc>>>(&mut connection)
+    .optional()
+    .map_err(|err| format!("Failed to update group message: {}", err))?;
+
+    edited_at
+        .flatten()
+        .ok_or_else(|| "The message could not be found".to_string())
+}
+
 /// Delete all group messages. Returns the number of deleted messages or an error message
 pub fn delete_group_messages(sender_id: i32, receiver_id: i32) -> Result<usize, String> {
     let mut connection =
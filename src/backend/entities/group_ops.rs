@@ -46,6 +46,18 @@ pub fn find_groups_by_ids(group_ids: Vec<i32>) -> Result<Vec<Group>, String> {
     Ok(result_groups)
 }
 
+/// Find all groups, ordered by id. Returns the groups or an error message
+pub fn find_all_groups() -> Result<Vec<Group>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    groups::table
+        .select((groups::id, groups::name))
+        .order(groups::id)
+        .load::<Group>(&mut connection)
+        .map_err(|err| format!("Error loading groups: {}", err))
+}
+
 /// Delete a group. Returns nothing or an error message
 pub fn delete_group(group_id: i32) -> Result<(), String> {
     use crate::backend::database::schema::groups::dsl::*;
@@ -60,3 +72,34 @@ pub fn delete_group(group_id: i32) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Find the current key epoch of a group. Returns the key epoch or an error message
+pub fn find_group_key_epoch(group_id: i32) -> Result<i32, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    groups::table
+        .find(group_id)
+        .select(groups::key_epoch)
+        .first::<i32>(&mut connection)
+        .map_err(|err| format!("Failed to find key epoch of group {}: {}", group_id, err))
+}
+
+/// Start a new key epoch of a group, e.g. because the own sender key of the current epoch cannot be read anymore.
+/// Returns the new key epoch or an error message
+pub fn start_new_key_epoch(group_id: i32) -> Result<i32, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    rotate_group_key(&mut connection, group_id)
+        .map_err(|err| format!("Failed to rotate key of group {}: {}", group_id, err))
+}
+
+/// Start a new key epoch of a group, so every member has to distribute a new sender key before sending again.
+/// Runs on the given connection, so it can be part of the transaction that changes the members. Returns the new key epoch
+pub fn rotate_group_key(connection: &mut PgConnection, group_id: i32) -> QueryResult<i32> {
+    diesel::update(groups::table.find(group_id))
+        .set(groups::key_epoch.eq(groups::key_epoch + 1))
+        .returning(groups::key_epoch)
+        .get_result::<i32>(connection)
+}
@@ -0,0 +1,70 @@
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::group_sender_keys::{self};
+
+use diesel::prelude::*;
+
+/// Store encrypted sender keys for their recipients. Keys a recipient already has are kept.
+/// Returns the number of stored keys or an error message
+pub fn distribute_sender_keys(sender_keys: &[GroupSenderKey]) -> Result<usize, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    diesel::insert_into(group_sender_keys::table)
+        .values(sender_keys)
+        .on_conflict_do_nothing()
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to distribute sender keys: {}", err))
+}
+
+/// Find the sender key of a member in a key epoch of a group, encrypted for a recipient.
+/// Returns the sender key, none if the recipient has not received it, or an error message
+pub fn find_sender_key(
+    group_id: i32,
+    key_epoch: i32,
+    sender_id: i32,
+    recipient_id: i32,
+) -> Result<Option<GroupSenderKey>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    group_sender_keys::table
+        .find((group_id, key_epoch, sender_id, recipient_id))
+        .first::<GroupSenderKey>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error querying sender key: {}", err))
+}
+
+/// Find all sender keys of a group that were encrypted for a recipient. Returns the sender keys or an error message
+pub fn find_sender_keys_of_recipient(
+    group_id: i32,
+    recipient_id: i32,
+) -> Result<Vec<GroupSenderKey>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    group_sender_keys::table
+        .filter(group_sender_keys::group_id.eq(group_id))
+        .filter(group_sender_keys::recipient_id.eq(recipient_id))
+        .load::<GroupSenderKey>(&mut connection)
+        .map_err(|err| format!("Error loading sender keys: {}", err))
+}
+
+/// Find the IDs of all users that received the sender key of a member in a key epoch of a group.
+/// Returns the IDs of the recipients or an error message
+pub fn find_recipients_of_sender_key(
+    group_id: i32,
+    key_epoch: i32,
+    sender_id: i32,
+) -> Result<Vec<i32>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    group_sender_keys::table
+        .filter(group_sender_keys::group_id.eq(group_id))
+        .filter(group_sender_keys::key_epoch.eq(key_epoch))
+        .filter(group_sender_keys::sender_id.eq(sender_id))
+        .select(group_sender_keys::recipient_id)
+        .load::<i32>(&mut connection)
+        .map_err(|err| format!("Error loading recipients of sender key: {}", err))
+}
@@ -0,0 +1,191 @@
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::login_failures::{self};
+
+use chrono::{DateTime, Duration, Utc};
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::{Nullable, Text};
+
+/// Scope of the failed login attempts of an account, identified by the entered email
+pub const ACCOUNT_SCOPE: &str = "account";
+
+/// Scope of the failed login attempts of a source, identified by the address of the client
+pub const SOURCE_SCOPE: &str = "source";
+
+/// Number of failed attempts of an account after which every further attempt is delayed
+const ACCOUNT_BACKOFF_THRESHOLD: i32 = 3;
+
+/// Number of failed attempts after which an account is locked
+const ACCOUNT_LOCK_THRESHOLD: i32 = 10;
+
+/// Number of failed attempts of a source after which every further attempt is delayed
+const SOURCE_BACKOFF_THRESHOLD: i32 = 10;
+
+/// Number of failed attempts after which a source is locked
+const SOURCE_LOCK_THRESHOLD: i32 = 50;
+
+/// Longest delay between two login attempts once the backoff has started
+const MAX_BACKOFF_SECONDS: i64 = 300;
+
+/// How long an account or a source is locked
+const LOCK_DURATION_MINUTES: i64 = 15;
+
+/// Failed attempts are forgotten once the last one is older than this
+const FAILURE_MEMORY_HOURS: i64 = 24;
+
+/// Source of clients that connect to the database over a Unix socket and have no address
+const LOCAL_SOURCE: &str = "local";
+
+/// Returns the source identifier of this client. The address is the one the database server sees for the connection,
+/// so it cannot be chosen by the client, and all accounts tried from one address share a counter. Returns the source or an error message
+pub fn client_source() -> Result<String, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let address = diesel::select(sql::<Nullable<Text>>("host(inet_client_addr())"))
+        .get_result::<Option<String>>(&mut connection)
+        .map_err(|err| format!("Error querying client address: {}", err))?;
+
+    Ok(address.unwrap_or_else(|| LOCAL_SOURCE.to_string()))
+}
+
+/// Find the failed login attempts of an account or a source. Returns the failed attempts, none if there are none, or an error message
+pub fn find_login_failure(scope: &str, identifier: &str) -> Result<Option<LoginFailure>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    login_failures::table
+        .find((scope, identifier))
+        .first::<LoginFailure>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error querying login failures: {}", err))
+}
+
+/// Check if a login attempt for an email from a source is allowed. Unknown emails are tracked the same way as existing ones,
+/// so the result does not reveal which emails are registered. Returns none if the attempt is allowed,
+/// the number of seconds to wait otherwise, or an error message
+pub fn login_retry_after(email: &str, source: &str) -> Result<Option<i64>, String> {
+    let now = Utc::now();
+
+    let account_wait = find_login_failure(ACCOUNT_SCOPE, email)?
+        .and_then(|failure| seconds_until(blocked_until(&failure, ACCOUNT_BACKOFF_THRESHOLD), now));
+    let source_wait = find_login_failure(SOURCE_SCOPE, source)?
+        .and_then(|failure| seconds_until(blocked_until(&failure, SOURCE_BACKOFF_THRESHOLD), now));
+
+    Ok(account_wait.max(source_wait))
+}
+
+/// Record a failed login attempt for an email from a source. The account or the source is locked
+/// once too many attempts have failed. Returns nothing or an error message
+pub fn record_failed_login(email: &str, source: &str) -> Result<(), String> {
+    record_failure(ACCOUNT_SCOPE, email, ACCOUNT_LOCK_THRESHOLD)?;
+    record_failure(SOURCE_SCOPE, source, SOURCE_LOCK_THRESHOLD)
+}
+
+/// Reset the failed login attempts of an email after a successful login. The attempts of the source are kept,
+/// so logging in to one account does not hide the attempts on other accounts. Returns nothing or an error message
+pub fn reset_failed_logins(email: &str) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    diesel::delete(login_failures::table.find((ACCOUNT_SCOPE, email)))
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to reset login failures: {}", err))?;
+
+    Ok(())
+}
+
+/// Unlock an account that was locked after too many failed login attempts. Meant for administrators. Returns nothing or an error message
+pub fn unlock_account(email: &str) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    diesel::delete(login_failures::table.find((ACCOUNT_SCOPE, email)))
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to unlock account: {}", err))?;
+
+    Ok(())
+}
+
+/// Find all accounts that are currently locked. Meant for administrators. Returns the failed attempts of the accounts or an error message
+pub fn find_locked_accounts() -> Result<Vec<LoginFailure>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    login_failures::table
+        .filter(login_failures::scope.eq(ACCOUNT_SCOPE))
+        .filter(login_failures::locked_until.gt(Utc::now()))
+        .load::<LoginFailure>(&mut connection)
+        .map_err(|err| format!("Error loading locked accounts: {}", err))
+}
+
+/// Increment the failed attempts of an account or a source and lock it if the threshold is reached
+fn record_failure(scope: &str, identifier: &str, lock_threshold: i32) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+    let now = Utc::now();
+
+    // Sources are not reset by a successful login, so old attempts are forgotten to start counting again
+    diesel::delete(
+        login_failures::table
+            .find((scope, identifier))
+            .filter(login_failures::last_failed_at.lt(now - Duration::hours(FAILURE_MEMORY_HOURS))),
+    )
+    .execute(&mut connection)
+    .map_err(|err| format!("Failed to forget old login failures: {}", err))?;
+
+    let failure = diesel::insert_into(login_failures::table)
+        .values(&LoginFailure {
+            scope: scope.to_string(),
+            identifier: identifier.to_string(),
+            failed_attempts: 1,
+            last_failed_at: now,
+            locked_until: None,
+        })
+        .on_conflict((login_failures::scope, login_failures::identifier))
+        .do_update()
+        .set((
+            login_failures::failed_attempts.eq(login_failures::failed_attempts + 1),
+            login_failures::last_failed_at.eq(now),
+        ))
+        .get_result::<LoginFailure>(&mut connection)
+        .map_err(|err| format!("Failed to record login failure: {}", err))?;
+
+    if failure.failed_attempts >= lock_threshold {
+        diesel::update(login_failures::table.find((scope, identifier)))
+            .set(
+                login_failures::locked_until
+                    .eq(Some(now + Duration::minutes(LOCK_DURATION_MINUTES))),
+            )
+            .execute(&mut connection)
+            .map_err(|err| format!("Failed to lock {}: {}", scope, err))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the time until which further attempts are blocked. The delay doubles with every failed attempt above the threshold
+fn blocked_until(failure: &LoginFailure, backoff_threshold: i32) -> Option<DateTime<Utc>> {
+    let backoff_until = if failure.failed_attempts >= backoff_threshold {
+        let exponent = (failure.failed_attempts - backoff_threshold).min(16) as u32;
+        let delay = 2_i64.pow(exponent).min(MAX_BACKOFF_SECONDS);
+        Some(failure.last_failed_at + Duration::seconds(delay))
+    } else {
+        None
+    };
+
+    backoff_until.max(failure.locked_until)
+}
+
+/// Returns the number of whole seconds from now until a time, none if the time has passed
+fn seconds_until(until: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Option<i64> {
+    let until = until?;
+    if until <= now {
+        return None;
+    }
+
+    // Round up, so a wait of less than a second is not shown as zero seconds
+    let milliseconds = (until - now).num_milliseconds();
+    Some((milliseconds + 999) / 1000)
+}
@@ -1,6 +1,19 @@
+pub mod admin_ops;
+pub mod conversation_ops;
+pub mod export_ops;
 pub mod group_message_ops;
 pub mod group_ops;
+pub mod group_sender_key_ops;
+pub mod login_throttle_ops;
+pub mod notification_settings_ops;
+pub mod password_history_ops;
+pub mod password_reset_ops;
+pub mod pending_verification_ops;
+pub mod session_ops;
+pub mod two_factor_ops;
 pub mod user_friend_ops;
 pub mod user_group_ops;
+pub mod user_key_ops;
 pub mod user_message_ops;
 pub mod user_ops;
+pub mod user_preferences_ops;
@@ -0,0 +1,36 @@
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::notification_settings::{self};
+
+use diesel::prelude::*;
+
+/// Find the notification settings of a user. A user that has not changed them gets the default settings.
+/// Returns the notification settings or an error message
+pub fn find_notification_settings(user_id: i32) -> Result<NotificationSettings, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let settings = notification_settings::table
+        .find(user_id)
+        .first::<NotificationSettings>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error querying notification settings: {}", err))?;
+
+    Ok(settings.unwrap_or_else(|| NotificationSettings::new(user_id)))
+}
+
+/// Store the notification settings of a user. Existing settings are replaced. Returns nothing or an error message
+pub fn save_notification_settings(settings: &NotificationSettings) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    diesel::insert_into(notification_settings::table)
+        .values(settings)
+        .on_conflict(notification_settings::user_id)
+        .do_update()
+        .set(settings)
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to save notification settings: {}", err))?;
+
+    Ok(())
+}
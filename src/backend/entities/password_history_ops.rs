@@ -0,0 +1,89 @@
+use crate::backend::crypto::password::verify_password;
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::password_history::{self};
+use crate::backend::database::schema::users;
+use crate::config::config;
+use crate::i18n::t_args;
+
+use diesel::prelude::*;
+
+/// Check that a new password is neither the current password of a user nor one of the previous passwords
+/// that the password policy remembers. Returns nothing or an error message
+pub fn check_password_not_reused(user_id: i32, new_password: &str) -> Result<(), String> {
+    let history_size = config().password_policy.history_size;
+    if history_size == 0 {
+        return Ok(());
+    }
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let current_hash = users::table
+        .find(user_id)
+        .select(users::password)
+        .first::<String>(&mut connection)
+        .map_err(|err| format!("Error finding user: {}", err))?;
+
+    // The current password counts as one of the remembered passwords
+    let previous_hashes = password_history::table
+        .filter(password_history::user_id.eq(user_id))
+        .order((
+            password_history::created_at.desc(),
+            password_history::id.desc(),
+        ))
+        .select(password_history::password_hash)
+        .limit(history_size as i64 - 1)
+        .load::<String>(&mut connection)
+        .map_err(|err| format!("Error loading password history: {}", err))?;
+
+    for hash in std::iter::once(current_hash).chain(previous_hashes) {
+        if verify_password(new_password, &hash)? {
+            return Err(t_args(
+                "error-password-reused",
+                &[("count", history_size.to_string())],
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Remember the hash of a password that is replaced and forget the hashes that the password policy no longer needs.
+/// Runs on the given connection, so it can be part of the transaction that replaces the password
+pub fn record_replaced_password(
+    connection: &mut PgConnection,
+    user_id: i32,
+    replaced_hash: &str,
+) -> QueryResult<()> {
+    // The new password is the current one, so one less previous password has to be remembered
+    let keep = config().password_policy.history_size.saturating_sub(1);
+
+    if keep > 0 {
+        diesel::insert_into(password_history::table)
+            .values(NewPasswordHistory {
+                user_id,
+                password_hash: replaced_hash,
+            })
+            .execute(connection)?;
+    }
+
+    let kept_ids = password_history::table
+        .filter(password_history::user_id.eq(user_id))
+        .order((
+            password_history::created_at.desc(),
+            password_history::id.desc(),
+        ))
+        .select(password_history::id)
+        .limit(keep as i64)
+        .load::<i32>(connection)?;
+
+    diesel::delete(
+        password_history::table
+            .filter(password_history::user_id.eq(user_id))
+            .filter(password_history::id.ne_all(kept_ids)),
+    )
+    .execute(connection)?;
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use crate::backend::crypto::password::hash_password;
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::password_resets::{self};
+use crate::backend::database::schema::users;
+use crate::backend::entities::password_history_ops::{
+    check_password_not_reused, record_replaced_password,
+};
+use crate::backend::entities::session_ops::{generate_token, hash_token};
+use crate::backend::entities::user_ops::{find_user_by_email, find_user_with_password_by_id};
+use crate::backend::mail::mailer::Mailer;
+use crate::config::config;
+use crate::i18n::t;
+
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use log::info;
+
+/// How long a password reset token can be redeemed after it was issued
+const PASSWORD_RESET_TOKEN_LIFETIME_MINUTES: i64 = 60;
+
+/// Length of a generated password reset token
+const PASSWORD_RESET_TOKEN_LENGTH: usize = 32;
+
+/// Create a new password reset for a user. Previous password resets of the user are replaced.
+/// Only the hash of the token is stored. Returns the password reset and the token or an error message
+pub fn create_password_reset(user_id: i32) -> Result<(PasswordReset, String), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    diesel::delete(password_resets::table.filter(password_resets::user_id.eq(user_id)))
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to delete old password resets: {}", err))?;
+
+    let token = generate_token(PASSWORD_RESET_TOKEN_LENGTH);
+    let password_reset = PasswordReset {
+        token_hash: hash_token(&token),
+        user_id,
+        expires_at: Utc::now() + Duration::minutes(PASSWORD_RESET_TOKEN_LIFETIME_MINUTES),
+    };
+
+    diesel::insert_into(password_resets::table)
+        .values(&password_reset)
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to insert password reset: {}", err))?;
+
+    Ok((password_reset, token))
+}
+
+/// Request a password reset for an email and send the token to it. If no user with the email exists,
+/// nothing is sent, so the result does not reveal which emails are registered. Returns nothing or an error message
+pub fn request_password_reset(email: &str, mailer: &dyn Mailer) -> Result<(), String> {
+    let user = match find_user_by_email(email) {
+        Ok(user) => user,
+        Err(err) => {
+            info!("Password reset requested for unknown email: {}", err);
+            return Ok(());
+        }
+    };
+
+    let (_, token) = create_password_reset(user.id)?;
+
+    let body = format!(
+        "Please use the following code to reset your password:\n\n{}\n\nThe code is valid for {} minutes and can only be used once. If you did not request a password reset, you can ignore this email.",
+        token, PASSWORD_RESET_TOKEN_LIFETIME_MINUTES
+    );
+
+    mailer.send(email, "Reset your password", &body)
+}
+
+/// Find all password resets of a user. Returns a vector of password resets or an error message
+pub fn find_password_resets_of_user(user_id: i32) -> Result<Vec<PasswordReset>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let results = password_resets::table
+        .filter(password_resets::user_id.eq(user_id))
+        .load::<PasswordReset>(&mut connection)
+        .map_err(|err| format!("Error loading password resets: {}", err))?;
+
+    Ok(results)
+}
+
+/// Reset the password of a user with a password reset token. The token can only be used once, even if it is
+/// submitted twice at the same time. All existing sessions of the user are revoked. Returns nothing or an error message
+pub fn reset_password(token: &str, new_password: &str) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let password_reset = password_resets::table
+        .find(hash_token(token))
+        .first::<PasswordReset>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error querying password reset: {}", err))?
+        .ok_or_else(|| t("error-invalid-reset-code"))?;
+
+    if password_reset.expires_at < Utc::now() {
+        return Err(t("error-reset-code-expired"));
+    }
+
+    config().password_policy.check(new_password)?;
+
+    let existing_user = find_user_with_password_by_id(password_reset.user_id)?;
+    check_password_not_reused(password_reset.user_id, new_password)?;
+
+    let hashed_password = hash_password(new_password)?;
+
+    connection
+        .transaction::<_, diesel::result::Error, _>(|connection| {
+            // Only the submission that deletes the token may reset the password, any other one finds no row
+            let user_id = diesel::delete(
+                password_resets::table
+                    .filter(password_resets::token_hash.eq(&password_reset.token_hash))
+                    .filter(password_resets::expires_at.gt(Utc::now())),
+            )
+            .returning(password_resets::user_id)
+            .get_result::<i32>(connection)?;
+
+            diesel::update(users::table.find(user_id))
+                .set((
+                    users::password.eq(hashed_password),
+                    users::sessions_revoked_at.eq(Some(Utc::now())),
+                ))
+                .execute(connection)?;
+
+            record_replaced_password(connection, user_id, &existing_user.password)?;
+
+            diesel::delete(password_resets::table.filter(password_resets::user_id.eq(user_id)))
+                .execute(connection)?;
+
+            Ok(())
+        })
+        .map_err(|err| match err {
+            diesel::result::Error::NotFound => t("error-invalid-reset-code"),
+            err => format!("Failed to reset password: {}", err),
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::pending_verifications::{self};
+use crate::backend::database::schema::users;
+use crate::backend::entities::session_ops::{generate_token, hash_token};
+use crate::backend::mail::mailer::Mailer;
+use crate::i18n::{t, t_args};
+
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+
+/// How long a verification token can be redeemed after it was issued
+const VERIFICATION_TOKEN_LIFETIME_HOURS: i64 = 24;
+
+/// Length of a generated verification token
+const VERIFICATION_TOKEN_LENGTH: usize = 32;
+
+/// How long to wait before another verification code can be sent to the same email
+const VERIFICATION_RESEND_COOLDOWN_SECONDS: i64 = 60;
+
+/// Create a new pending verification for an email. Previous verifications of the same email are replaced.
+/// Only the hash of the token is stored. Returns the pending verification and the token or an error message
+pub fn create_pending_verification(
+    user_id: i32,
+    email: &str,
+) -> Result<(PendingVerification, String), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    diesel::delete(
+        pending_verifications::table
+            .filter(pending_verifications::user_id.eq(user_id))
+            .filter(pending_verifications::email.eq(email)),
+    )
+    .execute(&mut connection)
+    .map_err(|err| format!("Failed to delete old verifications: {}", err))?;
+
+    let token = generate_token(VERIFICATION_TOKEN_LENGTH);
+    let pending_verification = PendingVerification {
+        token_hash: hash_token(&token),
+        user_id,
+        email: email.to_string(),
+        expires_at: Utc::now() + Duration::hours(VERIFICATION_TOKEN_LIFETIME_HOURS),
+    };
+
+    diesel::insert_into(pending_verifications::table)
+        .values(&pending_verification)
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to insert pending verification: {}", err))?;
+
+    Ok((pending_verification, token))
+}
+
+/// Find how long to wait until another verification code can be sent to an email. A code can be sent once per cooldown,
+/// so nobody can flood an address with mails. Returns the remaining seconds, none if a code can be sent, or an error message
+pub fn find_verification_cooldown(email: &str) -> Result<Option<i64>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let last_expires_at = pending_verifications::table
+        .filter(pending_verifications::email.eq(email))
+        .select(pending_verifications::expires_at)
+        .order(pending_verifications::expires_at.desc())
+        .first::<DateTime<Utc>>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error querying pending verifications: {}", err))?;
+
+    Ok(last_expires_at.and_then(|expires_at| {
+        let sent_at = expires_at - Duration::hours(VERIFICATION_TOKEN_LIFETIME_HOURS);
+        let remaining = (sent_at + Duration::seconds(VERIFICATION_RESEND_COOLDOWN_SECONDS)
+            - Utc::now())
+        .num_seconds();
+        (remaining > 0).then_some(remaining)
+    }))
+}
+
+/// Create a new pending verification and send the token to the email. No code is sent during the cooldown of the email.
+/// Returns nothing or an error message
+pub fn request_email_verification(
+    user_id: i32,
+    email: &str,
+    mailer: &dyn Mailer,
+) -> Result<(), String> {
+    if let Some(seconds) = find_verification_cooldown(email)? {
+        return Err(t_args(
+            "error-verification-code-cooldown",
+            &[("seconds", seconds.to_string())],
+        ));
+    }
+
+    let (_, token) = create_pending_verification(user_id, email)?;
+
+    let body = format!(
+        "Please use the following code to verify your email address:\n\n{}\n\nThe code is valid for {} hours.",
+        token, VERIFICATION_TOKEN_LIFETIME_HOURS
+    );
+
+    mailer.send(email, "Verify your email address", &body)
+}
+
+/// Find all pending verifications of a user. Returns a vector of pending verifications or an error message
+pub fn find_pending_verifications_of_user(
+    user_id: i32,
+) -> Result<Vec<PendingVerification>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let results = pending_verifications::table
+        .filter(pending_verifications::user_id.eq(user_id))
+        .load::<PendingVerification>(&mut connection)
+        .map_err(|err| format!("Error loading pending verifications: {}", err))?;
+
+    Ok(results)
+}
+
+/// Check if the email of a user still has to be verified. Returns true if a verification is pending, false otherwise
+pub fn is_email_unverified(user_id: i32, email: &str) -> Result<bool, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let count: i64 = pending_verifications::table
+        .filter(pending_verifications::user_id.eq(user_id))
+        .filter(pending_verifications::email.eq(email))
+        .count()
+        .get_result(&mut connection)
+        .map_err(|err| format!("Error querying pending verifications: {}", err))?;
+
+    Ok(count > 0)
+}
+
+/// Redeem a verification token of a user. The verified email becomes the email of the user
+/// and all pending verifications of the user are removed. Returns the verified email or an error message
+pub fn redeem_email_verification(user_id: i32, token: &str) -> Result<String, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let pending_verification = pending_verifications::table
+        .filter(pending_verifications::token_hash.eq(hash_token(token)))
+        .filter(pending_verifications::user_id.eq(user_id))
+        .first::<PendingVerification>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error querying pending verification: {}", err))?
+        .ok_or_else(|| t("error-invalid-verification-code"))?;
+
+    if pending_verification.expires_at < Utc::now() {
+        return Err(t("error-verification-code-expired"));
+    }
+
+    connection
+        .transaction::<_, diesel::result::Error, _>(|connection| {
+            diesel::update(users::table.find(user_id))
+                .set(users::email.eq(&pending_verification.email))
+                .execute(connection)?;
+
+            diesel::delete(
+                pending_verifications::table.filter(pending_verifications::user_id.eq(user_id)),
+            )
+            .execute(connection)?;
+
+            Ok(())
+        })
+        .map_err(|err| format!("Failed to verify email: {}", err))?;
+
+    Ok(pending_verification.email)
+}
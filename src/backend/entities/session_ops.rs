@@ -0,0 +1,173 @@
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::sessions::{self};
+use crate::backend::database::schema::users;
+use crate::backend::entities::user_ops::find_sessions_revoked_at;
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use std::env;
+
+/// Length of a generated session token
+const SESSION_TOKEN_LENGTH: usize = 48;
+
+/// Returns the name of this device, shown in the list of sessions. The host name is used
+pub fn device_name() -> String {
+    env::var("HOSTNAME")
+        .or_else(|_| env::var("COMPUTERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Create a new session of a user on a device. Only the hash of the token is stored.
+/// Returns the session and the token in plain text, which has to be kept by the client, or an error message
+pub fn create_session(user_id: i32, device_name: &str) -> Result<(Session, String), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let token = generate_token(SESSION_TOKEN_LENGTH);
+    let token_hash = hash_token(&token);
+    let now = Utc::now();
+
+    let session = diesel::insert_into(sessions::table)
+        .values(&NewSession {
+            user_id,
+            token_hash: &token_hash,
+            device_name,
+            created_at: now,
+            last_seen_at: now,
+        })
+        .get_result::<Session>(&mut connection)
+        .map_err(|err| format!("Failed to insert session: {}", err))?;
+
+    Ok((session, token))
+}
+
+/// Find the user and the session of a session token. The last seen time of the session is updated.
+/// Returns the user and the session, none if the token is unknown or the session is no longer active, or an error message
+pub fn find_user_by_session_token(token: &str) -> Result<Option<(User, Session)>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let Some(session) = sessions::table
+        .filter(sessions::token_hash.eq(hash_token(token)))
+        .first::<Session>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error querying session: {}", err))?
+    else {
+        return Ok(None);
+    };
+
+    if !is_active(&session, find_sessions_revoked_at(session.user_id)?) {
+        return Ok(None);
+    }
+
+    let user = users::table
+        .find(session.user_id)
+        .select((users::id, users::first_name, users::last_name))
+        .first::<User>(&mut connection)
+        .map_err(|err| format!("Error querying user: {}", err))?;
+
+    let session = diesel::update(sessions::table.find(session.id))
+        .set(sessions::last_seen_at.eq(Utc::now()))
+        .get_result::<Session>(&mut connection)
+        .map_err(|err| format!("Failed to update session: {}", err))?;
+
+    Ok(Some((user, session)))
+}
+
+/// Find all active sessions of a user, the most recently used first. Returns a vector of sessions or an error message
+pub fn find_active_sessions_of_user(user_id: i32) -> Result<Vec<Session>, String> {
+    let sessions_revoked_at = find_sessions_revoked_at(user_id)?;
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let results = sessions::table
+        .filter(sessions::user_id.eq(user_id))
+        .filter(sessions::revoked_at.is_null())
+        .order(sessions::last_seen_at.desc())
+        .load::<Session>(&mut connection)
+        .map_err(|err| format!("Error loading sessions: {}", err))?;
+
+    Ok(results
+        .into_iter()
+        .filter(|session| is_active(session, sessions_revoked_at))
+        .collect())
+}
+
+/// Check if a session is still active. A session is inactive once it was revoked on its own
+/// or all sessions of the user were revoked, e.g. by a password reset. Returns true if the session is active, false otherwise
+pub fn is_session_active(session_id: i32) -> Result<bool, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let Some(session) = sessions::table
+        .find(session_id)
+        .first::<Session>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error querying session: {}", err))?
+    else {
+        return Ok(false);
+    };
+
+    Ok(is_active(
+        &session,
+        find_sessions_revoked_at(session.user_id)?,
+    ))
+}
+
+/// Revoke a session of a user. Returns nothing or an error message
+pub fn revoke_session(user_id: i32, session_id: i32) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let updated = diesel::update(
+        sessions::table
+            .filter(sessions::id.eq(session_id))
+            .filter(sessions::user_id.eq(user_id)),
+    )
+    .set(sessions::revoked_at.eq(Some(Utc::now())))
+    .execute(&mut connection)
+    .map_err(|err| format!("Failed to revoke session: {}", err))?;
+
+    if updated == 0 {
+        return Err(format!("Session with id {} not found", session_id));
+    }
+
+    Ok(())
+}
+
+/// Revoke the session of a session token, e.g. when the user logs out. Returns nothing or an error message
+pub fn revoke_session_by_token(token: &str) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    diesel::update(sessions::table.filter(sessions::token_hash.eq(hash_token(token))))
+        .set(sessions::revoked_at.eq(Some(Utc::now())))
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to revoke session: {}", err))?;
+
+    Ok(())
+}
+
+/// Check if a session is neither revoked on its own nor started before all sessions of the user were revoked
+fn is_active(session: &Session, sessions_revoked_at: Option<DateTime<Utc>>) -> bool {
+    session.revoked_at.is_none()
+        && sessions_revoked_at.is_none_or(|revoked_at| revoked_at <= session.created_at)
+}
+
+/// Hash a session, verification or password reset token, so a leaked database does not contain usable tokens
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Generate a random alphanumeric session, verification or password reset token of the given length
+pub fn generate_token(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,298 @@
+use crate::backend::crypto::password::{hash_password, verify_password};
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::recovery_codes::{self};
+use crate::backend::database::schema::users;
+use crate::backend::entities::user_ops::find_user_with_password_by_id;
+use crate::i18n::t;
+
+use diesel::prelude::*;
+use rand::{distributions::Alphanumeric, Rng};
+use std::time::{SystemTime, UNIX_EPOCH};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+/// Issuer shown in authenticator apps
+const TOTP_ISSUER: &str = "SecSE24 Group08";
+
+/// Number of recovery codes generated when two-factor authentication is enabled
+const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Length of a generated recovery code
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+/// Number of time steps before and after the current one in which a code is accepted, for clocks that are slightly off
+const TOTP_SKEW_STEPS: u64 = 1;
+
+/// TotpEnrollment is a struct that represents a started but not yet confirmed two-factor enrolment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+/// Start the two-factor enrolment of a user. A new secret is stored for the user, but two-factor authentication
+/// stays disabled until the enrolment is confirmed with a valid code. Returns the enrolment or an error message.
+///
+/// The secret is stored in plain text in `users.totp_secret`: every client connects to the database directly, so a key to
+/// encrypt it would have to be shipped with every client and would not protect it. Access to the database has to be restricted instead.
+pub fn start_totp_enrollment(user_id: i32, email: &str) -> Result<TotpEnrollment, String> {
+    if is_totp_enabled(user_id)? {
+        return Err(t("error-two-factor-already-enabled"));
+    }
+
+    let secret = Secret::generate_secret().to_encoded().to_string();
+    let totp = build_totp(&secret, email)?;
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    diesel::update(users::table.find(user_id))
+        .set(users::totp_secret.eq(Some(&secret)))
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to store two-factor secret: {}", err))?;
+
+    Ok(TotpEnrollment {
+        secret,
+        otpauth_uri: totp.get_url(),
+    })
+}
+
+/// Confirm the two-factor enrolment of a user with a code of the authenticator app. Two-factor authentication is enabled
+/// and new recovery codes are generated. Returns the recovery codes in plain text or an error message
+pub fn confirm_totp_enrollment(user_id: i32, code: &str) -> Result<Vec<String>, String> {
+    let (secret, enabled) = find_totp_settings(user_id)?;
+    let secret = secret.ok_or_else(|| t("error-two-factor-not-started"))?;
+
+    if enabled {
+        return Err(t("error-two-factor-already-enabled"));
+    }
+
+    if !accept_totp_code(user_id, &secret, code)? {
+        return Err(t("error-invalid-two-factor-code"));
+    }
+
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_recovery_code())
+        .collect();
+
+    let new_recovery_codes = codes
+        .iter()
+        .map(|code| {
+            Ok(RecoveryCode {
+                user_id,
+                code_hash: hash_password(code)?,
+            })
+        })
+        .collect::<Result<Vec<RecoveryCode>, String>>()?;
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    connection
+        .transaction::<_, diesel::result::Error, _>(|connection| {
+            diesel::delete(recovery_codes::table.filter(recovery_codes::user_id.eq(user_id)))
+                .execute(connection)?;
+
+            diesel::insert_into(recovery_codes::table)
+                .values(&new_recovery_codes)
+                .execute(connection)?;
+
+            diesel::update(users::table.find(user_id))
+                .set(users::totp_enabled.eq(true))
+                .execute(connection)?;
+
+            Ok(())
+        })
+        .map_err(|err| format!("Failed to enable two-factor authentication: {}", err))?;
+
+    Ok(codes)
+}
+
+/// Check if two-factor authentication is enabled for a user. Returns true if it is enabled, false otherwise
+pub fn is_totp_enabled(user_id: i32) -> Result<bool, String> {
+    let (_, enabled) = find_totp_settings(user_id)?;
+    Ok(enabled)
+}
+
+/// Verify the second factor of a user. The code can either be a code of the authenticator app
+/// or a recovery code, which is removed once it has been used. Returns nothing or an error message
+pub fn verify_second_factor(user_id: i32, code: &str) -> Result<(), String> {
+    let (secret, enabled) = find_totp_settings(user_id)?;
+
+    let secret = match (secret, enabled) {
+        (Some(secret), true) => secret,
+        _ => return Err(t("error-two-factor-not-enabled")),
+    };
+
+    let code = code.trim();
+
+    if accept_totp_code(user_id, &secret, code)? {
+        return Ok(());
+    }
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let stored_codes = recovery_codes::table
+        .filter(recovery_codes::user_id.eq(user_id))
+        .load::<RecoveryCode>(&mut connection)
+        .map_err(|err| format!("Error loading recovery codes: {}", err))?;
+
+    for stored_code in stored_codes {
+        if verify_password(code, &stored_code.code_hash).unwrap_or(false) {
+            diesel::delete(recovery_codes::table.find((user_id, &stored_code.code_hash)))
+                .execute(&mut connection)
+                .map_err(|err| format!("Failed to delete recovery code: {}", err))?;
+
+            return Ok(());
+        }
+    }
+
+    Err(t("error-invalid-two-factor-code"))
+}
+
+/// Find the number of unused recovery codes of a user. Returns the number or an error message
+pub fn count_recovery_codes(user_id: i32) -> Result<i64, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let count: i64 = recovery_codes::table
+        .filter(recovery_codes::user_id.eq(user_id))
+        .count()
+        .get_result(&mut connection)
+        .map_err(|err| format!("Error querying recovery codes: {}", err))?;
+
+    Ok(count)
+}
+
+/// Disable two-factor authentication of a user. Requires the current password and a valid
+/// code of the authenticator app or a recovery code. Returns nothing or an error message
+pub fn disable_totp(user_id: i32, password: &str, code: &str) -> Result<(), String> {
+    let existing_user = find_user_with_password_by_id(user_id)
+        .map_err(|err| format!("Error finding user: {}", err))?;
+
+    if !verify_password(password, &existing_user.password)? {
+        return Err(t("error-password-mismatch"));
+    }
+
+    verify_second_factor(user_id, code)?;
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    connection
+        .transaction::<_, diesel::result::Error, _>(|connection| {
+            diesel::delete(recovery_codes::table.filter(recovery_codes::user_id.eq(user_id)))
+                .execute(connection)?;
+
+            diesel::update(users::table.find(user_id))
+                .set((
+                    users::totp_secret.eq(None::<String>),
+                    users::totp_enabled.eq(false),
+                    users::totp_last_step.eq(None::<i64>),
+                ))
+                .execute(connection)?;
+
+            Ok(())
+        })
+        .map_err(|err| format!("Failed to disable two-factor authentication: {}", err))?;
+
+    Ok(())
+}
+
+/// Generate the current code for a base32 encoded secret. Returns the code or an error message
+pub fn generate_current_code(secret: &str) -> Result<String, String> {
+    generate_code_at(secret, unix_time()?)
+}
+
+/// Generate the code for a base32 encoded secret at a time in seconds since the Unix epoch. Returns the code or an error message
+pub fn generate_code_at(secret: &str, time: u64) -> Result<String, String> {
+    Ok(build_totp(secret, "")?.generate(time))
+}
+
+/// Find the stored secret and whether two-factor authentication is enabled for a user
+fn find_totp_settings(user_id: i32) -> Result<(Option<String>, bool), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    users::table
+        .find(user_id)
+        .select((users::totp_secret, users::totp_enabled))
+        .first::<(Option<String>, bool)>(&mut connection)
+        .map_err(|err| format!("Error querying user: {}", err))
+}
+
+/// Accept a code of the authenticator app of a user. A code is only accepted once: its time step has to be later than the
+/// step of the last accepted code, so a captured code cannot be replayed while it is still valid.
+/// Returns true if the code was accepted, false otherwise, or an error message
+fn accept_totp_code(user_id: i32, secret: &str, code: &str) -> Result<bool, String> {
+    let Some(step) = find_totp_step(secret, code)? else {
+        return Ok(false);
+    };
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    // The step is only stored if it is later, so two logins with the same code cannot both succeed
+    let updated = diesel::update(
+        users::table.find(user_id).filter(
+            users::totp_last_step
+                .is_null()
+                .or(users::totp_last_step.lt(step)),
+        ),
+    )
+    .set(users::totp_last_step.eq(Some(step)))
+    .execute(&mut connection)
+    .map_err(|err| format!("Failed to store two-factor code: {}", err))?;
+
+    Ok(updated == 1)
+}
+
+/// Find the time step of a code of the authenticator app, the current step or one next to it.
+/// Returns the step, none if the code is invalid, or an error message
+fn find_totp_step(secret: &str, code: &str) -> Result<Option<i64>, String> {
+    let totp = build_totp(secret, "")?;
+    let current_step = unix_time()? / totp.step;
+
+    Ok(
+        (current_step.saturating_sub(TOTP_SKEW_STEPS)..=current_step + TOTP_SKEW_STEPS)
+            .find(|step| totp.check(code.trim(), step * totp.step))
+            .map(|step| step as i64),
+    )
+}
+
+/// Returns the current time in seconds since the Unix epoch or an error message
+fn unix_time() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .map_err(|err| format!("Invalid system time: {}", err))
+}
+
+/// Build the RFC 6238 TOTP of a base32 encoded secret with six digits and a period of 30 seconds.
+/// Codes are checked for a single time step, the steps next to it are checked by `find_totp_step`
+fn build_totp(secret: &str, account_name: &str) -> Result<TOTP, String> {
+    let secret_bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|err| format!("Invalid two-factor secret: {:?}", err))?;
+
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        0,
+        30,
+        secret_bytes,
+        Some(TOTP_ISSUER.to_string()),
+        account_name.replace(':', ""),
+    )
+    .map_err(|err| format!("Failed to create two-factor secret: {}", err))
+}
+
+/// Generate a random alphanumeric recovery code
+fn generate_recovery_code() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(RECOVERY_CODE_LENGTH)
+        .map(char::from)
+        .collect()
+}
@@ -1,10 +1,11 @@
 use crate::backend::database::db::establish_connection;
 use crate::backend::database::models::*;
 use crate::backend::database::schema::user_to_groups;
+use crate::backend::entities::group_ops::rotate_group_key;
 
 use diesel::prelude::*;
 
-/// Create a new user group. Returns the user group or an error message
+/// Create a new user group and rotate the key of the group. Returns the user group or an error message
 pub fn create_user_group(
     user_id: i32,
     group_id: i32,
@@ -19,9 +20,15 @@ pub fn create_user_group(
     let mut connection =
         establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
 
-    diesel::insert_into(user_to_groups::table)
-        .values(&user_group)
-        .execute(&mut connection)
+    // The new member must not read messages of the current key epoch
+    connection
+        .transaction::<_, diesel::result::Error, _>(|connection| {
+            diesel::insert_into(user_to_groups::table)
+                .values(&user_group)
+                .execute(connection)?;
+
+            rotate_group_key(connection, group_id)
+        })
         .map_err(|err| format!("Failed to insert new user group: {}", err))?;
 
     let user_group = UserToGroup {
@@ -33,7 +40,7 @@ pub fn create_user_group(
     Ok(user_group)
 }
 
-/// Update a user group. Returns the user group or an error message
+/// Update a user group and rotate the key of the group. Returns the user group or an error message
 pub fn update_user_group(
     user_id: i32,
     group_id: i32,
@@ -42,14 +49,19 @@ pub fn update_user_group(
     let mut connection =
         establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
 
-    diesel::update(
-        user_to_groups::table
-            .filter(user_to_groups::user_id.eq(user_id))
-            .filter(user_to_groups::group_id.eq(group_id)),
-    )
-    .set(user_to_groups::accepted_invite.eq(accepted_invite))
-    .execute(&mut connection)
-    .map_err(|err| format!("Failed to update user group: {}", err))?;
+    connection
+        .transaction::<_, diesel::result::Error, _>(|connection| {
+            diesel::update(
+                user_to_groups::table
+                    .filter(user_to_groups::user_id.eq(user_id))
+                    .filter(user_to_groups::group_id.eq(group_id)),
+            )
+            .set(user_to_groups::accepted_invite.eq(accepted_invite))
+            .execute(connection)?;
+
+            rotate_group_key(connection, group_id)
+        })
+        .map_err(|err| format!("Failed to update user group: {}", err))?;
 
     let user_group = UserToGroup {
         user_id,
@@ -96,18 +108,26 @@ pub fn find_all_user_groups_of_group(group_id: i32) -> Result<Vec<UserToGroup>,
     Ok(results)
 }
 
-/// Delete a user group. Returns the number of user groups deleted or an error message
+/// Delete a user group and rotate the key of the group, so the removed user cannot read new messages.
+/// Returns the number of user groups deleted or an error message
 pub fn delete_user_group(user_id: i32, group_id: i32) -> Result<usize, String> {
     let mut connection =
         establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
 
-    let num_deleted = diesel::delete(
-        user_to_groups::table
-            .filter(user_to_groups::user_id.eq(user_id))
-            .filter(user_to_groups::group_id.eq(group_id)),
-    )
-    .execute(&mut connection)
-    .map_err(|err| format!("Failed to delete user group: {}", err))?;
-
-    Ok(num_deleted)
+    connection
+        .transaction::<_, diesel::result::Error, _>(|connection| {
+            let num_deleted = diesel::delete(
+                user_to_groups::table
+                    .filter(user_to_groups::user_id.eq(user_id))
+                    .filter(user_to_groups::group_id.eq(group_id)),
+            )
+            .execute(connection)?;
+
+            if num_deleted > 0 {
+                rotate_group_key(connection, group_id)?;
+            }
+
+            Ok(num_deleted)
+        })
+        .map_err(|err| format!("Failed to delete user group: {}", err))
 }
@@ -0,0 +1,44 @@
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::user_keys::{self};
+
+use chrono::Local;
+use diesel::prelude::*;
+
+/// Publish the public identity key of a user. An existing key is replaced, e.g. when the user logs in on a new device.
+/// Returns nothing or an error message
+pub fn publish_public_key(user_id: i32, public_key: &str) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+    let now = Local::now().naive_local();
+
+    diesel::insert_into(user_keys::table)
+        .values(&UserKey {
+            user_id,
+            public_key: public_key.to_string(),
+            updated_at: now,
+        })
+        .on_conflict(user_keys::user_id)
+        .do_update()
+        .set((
+            user_keys::public_key.eq(public_key),
+            user_keys::updated_at.eq(now),
+        ))
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to publish public key: {}", err))?;
+
+    Ok(())
+}
+
+/// Find the published public identity key of a user. Returns the key, none if the user has not published a key, or an error message
+pub fn find_public_key(user_id: i32) -> Result<Option<String>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    user_keys::table
+        .find(user_id)
+        .select(user_keys::public_key)
+        .first::<String>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error querying public key: {}", err))
+}
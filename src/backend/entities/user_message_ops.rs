@@ -2,36 +2,52 @@ use crate::backend::database::db::establish_connection;
 use crate::backend::database::models::*;
 use crate::backend::database::schema::user_messages::{self};
 
+use chrono::{DateTime, Utc};
+use diesel::dsl::now;
 use diesel::prelude::*;
 
-/// Create a new user message. Returns the user message summary or an error message
+/// Create a new user message. Encrypted messages are stored with their nonce and the public keys of both users,
+/// the message then holds the ciphertext.
+/// A message with a client ID is only stored once, sending it again by the same sender returns the stored message.
+/// Returns the user message summary or an error message
 pub fn create_user_message(
     sender_id: i32,
     receiver_id: i32,
     message: &str,
+    encryption: Option<&MessageEncryption>,
+    client_id: Option<&str>,
 ) -> Result<UserMessage, String> {
     let new_user_message = NewUserMessage {
         sender_id,
         receiver_id,
         message,
+        nonce: encryption.map(|encryption| encryption.nonce),
+        client_id,
+        sender_public_key: encryption.map(|encryption| encryption.sender_public_key),
+        receiver_public_key: encryption.map(|encryption| encryption.receiver_public_key),
     };
 
     let mut connection =
         establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
 
-    diesel::insert_into(user_messages::table)
+    let inserted_message = diesel::insert_into(user_messages::table)
         .values(&new_user_message)
-        .execute(&mut connection)
+        .on_conflict(user_messages::client_id)
+        .do_nothing()
+        .returning(UserMessage::as_select())
+        .get_result::<UserMessage>(&mut connection)
+        .optional()
         .map_err(|err| format!("Failed to insert new user message: {}", err))?;
 
-    let group_message_summary = UserMessage {
-        sender_id: new_user_message.sender_id,
-        receiver_id: new_user_message.receiver_id,
-        message: new_user_message.message.to_string(),
-        send_date: chrono::Local::now().naive_local(),
-    };
-
-    Ok(group_message_summary)
+    match inserted_message {
+        Some(user_message) => Ok(user_message),
+        None => user_messages::table
+            .filter(user_messages::client_id.eq(client_id))
+            .filter(user_messages::sender_id.eq(sender_id))
+            .select(UserMessage::as_select())
+            .first::<UserMessage>(&mut connection)
+            .map_err(|err| format!("Failed to find sent user message: {}", err)),
+    }
 }
 
 /// Find all messages between two users. Returns a list of user messages or an error message
@@ -51,18 +67,124 @@ pub fn find_all_messages_between_users(
                 .eq(user2_id)
                 .and(user_messages::receiver_id.eq(user1_id))),
         )
-        .select((
-            user_messages::sender_id,
-            user_messages::receiver_id,
-            user_messages::message,
-            user_messages::send_date,
-        ))
+        .select(UserMessage::as_select())
         .load::<UserMessage>(&mut connection)
         .map_err(|err| format!("Error loading messages: {}", err))?;
 
     Ok(results)
 }
 
+/// Find the messages between two users that were sent or edited at or after the given date. Returns a list of user messages or an error message
+pub fn find_messages_between_users_since(
+    user1_id: i32,
+    user2_id: i32,
+    since: DateTime<Utc>,
+) -> Result<Vec<UserMessage>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let results = user_messages::table
+        .filter(
+            (user_messages::sender_id
+                .eq(user1_id)
+                .and(user_messages::receiver_id.eq(user2_id)))
+            .or(user_messages::sender_id
+                .eq(user2_id)
+                .and(user_messages::receiver_id.eq(user1_id))),
+        )
+        .filter(
+            user_messages::send_date
+                .ge(since)
+                .or(user_messages::edited_at.ge(since)),
+        )
+        .select(UserMessage::as_select())
+        .load::<UserMessage>(&mut connection)
+        .map_err(|err| format!("Error loading messages: {}", err))?;
+
+    Ok(results)
+}
+
+/// Find the messages other users sent to a user after the given date, oldest first. Returns a list of user messages or an error message
+pub fn find_messages_to_user_since(
+    user_id: i32,
+    since: DateTime<Utc>,
+) -> Result<Vec<UserMessage>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let results = user_messages::table
+        .filter(user_messages::receiver_id.eq(user_id))
+        .filter(user_messages::sender_id.ne(user_id))
+        .filter(user_messages::send_date.gt(since))
+        .order(user_messages::send_date.asc())
+        .select(UserMessage::as_select())
+        .load::<UserMessage>(&mut connection)
+        .map_err(|err| format!("Error loading messages: {}", err))?;
+
+    Ok(results)
+}
+
+/// Find the newest message between two users. Returns the user message, none if they have not written yet, or an error message
+pub fn find_last_message_between_users(
+    user1_id: i32,
+    user2_id: i32,
+) -> Result<Option<UserMessage>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    user_messages::table
+        .filter(
+            (user_messages::sender_id
+                .eq(user1_id)
+                .and(user_messages::receiver_id.eq(user2_id)))
+            .or(user_messages::sender_id
+                .eq(user2_id)
+                .and(user_messages::receiver_id.eq(user1_id))),
+        )
+        .order(user_messages::send_date.desc())
+        .select(UserMessage::as_select())
+        .first::<UserMessage>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error loading messages: {}", err))
+}
+
+/// Replace the text of a message the sender sent. Encrypted messages are stored with their new nonce and public keys.
+/// Returns the time of the edit or an error message
+pub fn update_user_message(
+    sender_id: i32,
+    receiver_id: i32,
+    send_date: DateTime<Utc>,
+    message: &str,
+    encryption: Option<&MessageEncryption>,
+) -> Result<DateTime<Utc>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let edited_at = diesel::update(
+        user_messages::table
+            .filter(user_messages::sender_id.eq(sender_id))
+            .filter(user_messages::receiver_id.eq(receiver_id))
+            .filter(user_messages::send_date.eq(send_date)),
+    )
+    .set((
+        user_messages::message.eq(message),
+        user_messages::nonce.eq(encryption.map(|encryption| encryption.nonce)),
+        user_messages::sender_public_key
+            .eq(encryption.map(|encryption| encryption.sender_public_key)),
+        user_messages::receiver_public_key
+            .eq(encryption.map(|encryption| encryption.receiver_public_key)),
+        user_messages::edited_at.eq(now),
+    ))
+    .returning(user_messages::edited_at)
+    .get_result::<Option<DateTime<Utc>>>(&mut connection)
+    .optional()
+    .map_err(|err| format!("Failed to update user message: {}", err))?;
+
+    edited_at
+        .flatten()
+        .ok_or_else(|| "The message could not be found".to_string())
+}
+
 /// Delete a user message. Returns nothing or an error message
 pub fn delete_user_message(other_sender_id: i32, other_receiver_id: i32) -> Result<(), String> {
     let mut connection =
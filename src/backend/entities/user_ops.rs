@@ -1,10 +1,33 @@
-use crate::backend::database::db::{establish_connection, is_strong_password};
+use crate::backend::crypto::password::{hash_password, needs_rehash, verify_password};
+use crate::backend::database::db::establish_connection;
 use crate::backend::database::models::*;
 use crate::backend::database::schema::users::{self};
-
-use bcrypt::{hash, verify, DEFAULT_COST};
+use crate::backend::entities::password_history_ops::{
+    check_password_not_reused, record_replaced_password,
+};
+use crate::backend::entities::pending_verification_ops::request_email_verification;
+use crate::backend::mail::mailer::Mailer;
+use crate::config::config;
+use crate::i18n::t;
+
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 
+/// The columns of a user account
+const ACCOUNT_COLUMNS: (
+    users::id,
+    users::first_name,
+    users::last_name,
+    users::email,
+    users::disabled_at,
+) = (
+    users::id,
+    users::first_name,
+    users::last_name,
+    users::email,
+    users::disabled_at,
+);
+
 /// Create a new user. Returns the user summary or an error message
 pub fn create_user(
     first_name: &str,
@@ -21,13 +44,10 @@ pub fn create_user(
 
     // Check if the email already exists
     if user_email_exists(user.email)? {
-        return Err("Email address already in use".to_string());
+        return Err(t("error-email-in-use"));
     }
 
-    let hashed_password = match hash(user.password, DEFAULT_COST) {
-        Ok(h) => h,
-        Err(err) => return Err(format!("Failed to hash password: {}", err)),
-    };
+    let hashed_password = hash_password(user.password)?;
 
     // Establish a new connection each time
     let mut connection =
@@ -53,57 +73,81 @@ pub fn create_user(
     })
 }
 
-/// Update a user's password. Returns nothing or an error message
+/// Update a user's password. The new password has to follow the password policy and must not be one of the
+/// previous passwords of the user. Returns nothing or an error message
 pub fn update_password(user_id: i32, old_password: &str, new_password: &str) -> Result<(), String> {
-    let mut connection =
-        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
-    use crate::backend::database::schema::users::dsl::*;
-
     // Retrieve the user from the database
     let existing_user = find_user_with_password_by_id(user_id)
         .map_err(|err| format!("Error finding user: {}", err))?;
 
-    if !is_strong_password(new_password) {
-        return Err("Password must be at least 8 characters long and contain at least one uppercase letter, one lowercase letter, one digit, and one special character".to_string());
-    }
+    config().password_policy.check(new_password)?;
 
     // Verify old password
-    if !verify(old_password, &existing_user.password)
-        .map_err(|err| format!("Failed to verify old password: {}", err))?
-    {
-        return Err("Old password does not match.".to_string());
+    if !verify_password(old_password, &existing_user.password)? {
+        return Err(t("error-old-password-mismatch"));
     }
 
+    check_password_not_reused(user_id, new_password)?;
+
     // Hash the new password
-    let hashed_password = hash(new_password, DEFAULT_COST)
-        .map_err(|err| format!("Failed to hash password: {}", err))?;
+    let hashed_password = hash_password(new_password)?;
 
-    // Update the user's password
-    diesel::update(users.find(user_id))
-        .set(password.eq(hashed_password))
-        .execute(&mut connection)
-        .map_err(|err| format!("Unable to update user deletion status: {}", err))?;
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    // Update the user's password and remember the old one
+    connection
+        .transaction::<_, diesel::result::Error, _>(|connection| {
+            diesel::update(users::table.find(user_id))
+                .set(users::password.eq(hashed_password))
+                .execute(connection)?;
+
+            record_replaced_password(connection, user_id, &existing_user.password)
+        })
+        .map_err(|err| format!("Unable to update password: {}", err))?;
 
     Ok(())
 }
 
-/// Update a user's email. Returns nothing or an error message
-pub fn update_email(user_id: i32, new_email: &str) -> Result<(), String> {
+/// Replace the stored hash of a user's password after a successful login, if it is a bcrypt hash or was created
+/// with other Argon2id parameters than the configured ones. The password itself does not change.
+/// Returns true if the hash was replaced, false if it is up to date, or an error message
+pub fn rehash_password_if_needed(
+    user_id: i32,
+    password: &str,
+    stored_hash: &str,
+) -> Result<bool, String> {
+    if !needs_rehash(stored_hash) {
+        return Ok(false);
+    }
+
+    let hashed_password = hash_password(password)?;
+
     let mut connection =
         establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
-    use crate::backend::database::schema::users::dsl::*;
 
+    // Only replace the hash that was verified, in case the password was changed in the meantime
+    diesel::update(
+        users::table
+            .find(user_id)
+            .filter(users::password.eq(stored_hash)),
+    )
+    .set(users::password.eq(hashed_password))
+    .execute(&mut connection)
+    .map_err(|err| format!("Unable to update password hash: {}", err))?;
+
+    Ok(true)
+}
+
+/// Request a change of a user's email. The email is only changed after the verification code that is sent
+/// to the new email has been redeemed. Returns nothing or an error message
+pub fn update_email(user_id: i32, new_email: &str, mailer: &dyn Mailer) -> Result<(), String> {
     // Check if the new email already exists
     if user_email_exists(new_email)? {
-        return Err("Email address already in use".to_string());
+        return Err(t("error-email-in-use"));
     }
 
-    diesel::update(users.find(user_id))
-        .set(email.eq(new_email))
-        .execute(&mut connection)
-        .map_err(|err| format!("Unable to update user deletion status: {}", err))?;
-
-    Ok(())
+    request_email_verification(user_id, new_email, mailer)
 }
 
 /// Find all users. Returns a vector of users or an error message
@@ -119,6 +163,50 @@ pub fn find_all_user() -> Result<Vec<User>, String> {
     Ok(results)
 }
 
+/// Find the accounts of all users, ordered by id. Returns a vector of accounts or an error message
+pub fn find_all_user_accounts() -> Result<Vec<UserAccount>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let results = users::table
+        .select(ACCOUNT_COLUMNS)
+        .order(users::id)
+        .load::<UserAccount>(&mut connection)
+        .map_err(|err| format!("Error loading users: {}", err))?;
+
+    Ok(results)
+}
+
+/// Find the accounts of all users whose first name, last name or email contains the query, ignoring case.
+/// Returns a vector of accounts ordered by id or an error message
+pub fn search_user_accounts(query: &str) -> Result<Vec<UserAccount>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    // Wildcards in the query are matched literally
+    let pattern = format!(
+        "%{}%",
+        query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+
+    let results = users::table
+        .filter(
+            users::first_name
+                .ilike(&pattern)
+                .or(users::last_name.ilike(&pattern))
+                .or(users::email.ilike(&pattern)),
+        )
+        .select(ACCOUNT_COLUMNS)
+        .order(users::id)
+        .load::<UserAccount>(&mut connection)
+        .map_err(|err| format!("Error searching users: {}", err))?;
+
+    Ok(results)
+}
+
 /// Find a user by id with password. Returns the user with password or an error message
 pub fn find_user_with_password_by_id(user_id: i32) -> Result<UserPassword, String> {
     let mut connection =
@@ -179,6 +267,22 @@ pub fn find_user_by_email(email_param: &str) -> Result<User, String> {
     }
 }
 
+/// Find the email of a user by id. Returns the email or an error message
+pub fn find_user_email_by_id(user_id: i32) -> Result<String, String> {
+    use crate::backend::database::schema::users::dsl::*;
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let email_value = users
+        .find(user_id)
+        .select(email)
+        .first::<String>(&mut connection)
+        .map_err(|err| format!("Error querying user: {}", err))?;
+
+    Ok(email_value)
+}
+
 /// Check if a user with the given email exists. Returns true if the email exists, false otherwise
 pub fn user_email_exists(email_param: &str) -> Result<bool, String> {
     use crate::backend::database::schema::users::dsl::*;
@@ -200,6 +304,94 @@ pub fn user_email_exists(email_param: &str) -> Result<bool, String> {
     }
 }
 
+/// Find the time at which all sessions of a user were revoked. Returns the time, none if the sessions were never revoked, or an error message
+pub fn find_sessions_revoked_at(user_id: i32) -> Result<Option<DateTime<Utc>>, String> {
+    use crate::backend::database::schema::users::dsl::*;
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let revoked_at = users
+        .find(user_id)
+        .select(sessions_revoked_at)
+        .first::<Option<DateTime<Utc>>>(&mut connection)
+        .map_err(|err| format!("Error querying user: {}", err))?;
+
+    Ok(revoked_at)
+}
+
+/// Set a new password for a user without the old password, e.g. when an operator resets it.
+/// All existing sessions of the user are revoked. Returns nothing or an error message
+pub fn set_password(user_id: i32, new_password: &str) -> Result<(), String> {
+    config().password_policy.check(new_password)?;
+
+    let existing_user = find_user_with_password_by_id(user_id)?;
+    check_password_not_reused(user_id, new_password)?;
+
+    let hashed_password = hash_password(new_password)?;
+
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    connection
+        .transaction::<_, diesel::result::Error, _>(|connection| {
+            diesel::update(users::table.find(user_id))
+                .set((
+                    users::password.eq(hashed_password),
+                    users::sessions_revoked_at.eq(Some(Utc::now())),
+                ))
+                .execute(connection)?;
+
+            record_replaced_password(connection, user_id, &existing_user.password)
+        })
+        .map_err(|err| format!("Unable to update password: {}", err))?;
+
+    Ok(())
+}
+
+/// Disable or enable the account of a user. A disabled user cannot log in and all existing sessions are revoked.
+/// Returns nothing or an error message
+pub fn set_user_disabled(user_id: i32, disabled: bool) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let now = Utc::now();
+    let target = users::table.find(user_id);
+    let updated = if disabled {
+        diesel::update(target)
+            .set((
+                users::disabled_at.eq(Some(now)),
+                users::sessions_revoked_at.eq(Some(now)),
+            ))
+            .execute(&mut connection)
+    } else {
+        diesel::update(target)
+            .set(users::disabled_at.eq(None::<DateTime<Utc>>))
+            .execute(&mut connection)
+    }
+    .map_err(|err| format!("Unable to update user {}: {}", user_id, err))?;
+
+    if updated == 0 {
+        return Err(format!("User with id {} not found", user_id));
+    }
+
+    Ok(())
+}
+
+/// Check if the account of a user is disabled. Returns true if the account is disabled, false otherwise
+pub fn is_user_disabled(user_id: i32) -> Result<bool, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let disabled_at = users::table
+        .find(user_id)
+        .select(users::disabled_at)
+        .first::<Option<DateTime<Utc>>>(&mut connection)
+        .map_err(|err| format!("Error querying user: {}", err))?;
+
+    Ok(disabled_at.is_some())
+}
+
 /// Delete a user. Returns nothing or an error message
 pub fn delete_user(user_id: i32) -> Result<(), String> {
     use crate::backend::database::schema::users::dsl::*;
@@ -0,0 +1,42 @@
+use crate::backend::database::db::establish_connection;
+use crate::backend::database::models::*;
+use crate::backend::database::schema::user_preferences::{self};
+
+use chrono::Local;
+use diesel::prelude::*;
+
+/// Find the preferences a user has synced to the account.
+/// Returns the preferences as TOML, none if the user has not synced them, or an error message
+pub fn find_user_preferences(user_id: i32) -> Result<Option<String>, String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    user_preferences::table
+        .find(user_id)
+        .select(user_preferences::preferences)
+        .first::<String>(&mut connection)
+        .optional()
+        .map_err(|err| format!("Error querying user preferences: {}", err))
+}
+
+/// Store the synced preferences of a user as TOML. Existing preferences are replaced. Returns nothing or an error message
+pub fn save_user_preferences(user_id: i32, preferences: &str) -> Result<(), String> {
+    let mut connection =
+        establish_connection().map_err(|err| format!("Failed to establish connection: {}", err))?;
+
+    let user_preferences = UserPreferences {
+        user_id,
+        preferences: preferences.to_string(),
+        updated_at: Local::now().naive_local(),
+    };
+
+    diesel::insert_into(user_preferences::table)
+        .values(&user_preferences)
+        .on_conflict(user_preferences::user_id)
+        .do_update()
+        .set(&user_preferences)
+        .execute(&mut connection)
+        .map_err(|err| format!("Failed to save user preferences: {}", err))?;
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use super::mailer::Mailer;
+
+/// Mailer that appends every email to a file. Meant for local testing without a mail server
+#[derive(Debug, Clone)]
+pub struct FileMailer {
+    path: PathBuf,
+}
+
+/// Implementation of the file mailer
+impl FileMailer {
+    /// Create a new file mailer that writes to the given path
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileMailer { path: path.into() }
+    }
+}
+
+impl Mailer for FileMailer {
+    /// Append the email to the file. The file is created if it does not exist
+    fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), String> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|err| format!("Failed to open mail file: {}", err))?;
+
+        file.write_all(format_mail(to, subject, body).as_bytes())
+            .map_err(|err| format!("Failed to write mail file: {}", err))?;
+
+        Ok(())
+    }
+}
+
+/// Mailer that prints every email to the standard output. Meant for local testing without a mail server
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutMailer;
+
+impl Mailer for StdoutMailer {
+    /// Print the email to the standard output
+    fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), String> {
+        print!("{}", format_mail(to, subject, body));
+
+        Ok(())
+    }
+}
+
+/// Format an email as plain text
+fn format_mail(to: &str, subject: &str, body: &str) -> String {
+    format!("To: {}\nSubject: {}\n\n{}\n\n", to, subject, body)
+}
@@ -0,0 +1,49 @@
+use dotenv::dotenv;
+use std::env;
+
+use super::{
+    file_mailer::{FileMailer, StdoutMailer},
+    smtp_mailer::SmtpMailer,
+};
+
+/// A transport that is able to deliver an email to a single recipient
+pub trait Mailer {
+    /// Send an email. Returns nothing or an error message
+    fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), String>;
+}
+
+/// Create the mailer that is configured in the environment. Returns the mailer or an error message.
+/// The transport is selected with `MAIL_TRANSPORT`:
+/// - `smtp`: Sends the email over SMTP, configured with `SMTP_HOST`, `SMTP_USERNAME`, `SMTP_PASSWORD` and `MAIL_FROM`
+/// - `file`: Appends the email to the file in `MAIL_FILE_PATH` (default `mail.log`)
+/// - `stdout` or unset: Prints the email to the standard output
+pub fn create_mailer() -> Result<Box<dyn Mailer>, String> {
+    dotenv().ok();
+
+    let transport = env::var("MAIL_TRANSPORT").unwrap_or_else(|_| "stdout".to_string());
+
+    match transport.to_lowercase().as_str() {
+        "smtp" => {
+            let host = read_env_var("SMTP_HOST")?;
+            let username = read_env_var("SMTP_USERNAME")?;
+            let password = read_env_var("SMTP_PASSWORD")?;
+            let from = read_env_var("MAIL_FROM")?;
+
+            Ok(Box::new(SmtpMailer::new(
+                &host, &username, &password, &from,
+            )?))
+        }
+        "file" => {
+            let path = env::var("MAIL_FILE_PATH").unwrap_or_else(|_| "mail.log".to_string());
+
+            Ok(Box::new(FileMailer::new(path)))
+        }
+        "stdout" => Ok(Box::new(StdoutMailer)),
+        other => Err(format!("Unknown mail transport: {}", other)),
+    }
+}
+
+/// Read a required environment variable. Returns the value or an error message
+fn read_env_var(name: &str) -> Result<String, String> {
+    env::var(name).map_err(|_| format!("Environment variable {} is not set", name))
+}
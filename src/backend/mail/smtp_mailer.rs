@@ -0,0 +1,52 @@
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, Message, SmtpTransport,
+    Transport,
+};
+
+use super::mailer::Mailer;
+
+/// Mailer that delivers emails through an SMTP relay
+#[derive(Clone)]
+pub struct SmtpMailer {
+    transport: SmtpTransport,
+    from: Mailbox,
+}
+
+/// Implementation of the SMTP mailer
+impl SmtpMailer {
+    /// Create a new SMTP mailer. Returns the mailer or an error message
+    pub fn new(host: &str, username: &str, password: &str, from: &str) -> Result<Self, String> {
+        let from = from
+            .parse::<Mailbox>()
+            .map_err(|err| format!("Invalid sender address: {}", err))?;
+
+        let transport = SmtpTransport::relay(host)
+            .map_err(|err| format!("Failed to create SMTP transport: {}", err))?
+            .credentials(Credentials::new(username.to_string(), password.to_string()))
+            .build();
+
+        Ok(SmtpMailer { transport, from })
+    }
+}
+
+impl Mailer for SmtpMailer {
+    /// Send the email through the SMTP relay
+    fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), String> {
+        let to = to
+            .parse::<Mailbox>()
+            .map_err(|err| format!("Invalid recipient address: {}", err))?;
+
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(subject)
+            .body(body.to_string())
+            .map_err(|err| format!("Failed to build email: {}", err))?;
+
+        self.transport
+            .send(&email)
+            .map_err(|err| format!("Failed to send email: {}", err))?;
+
+        Ok(())
+    }
+}
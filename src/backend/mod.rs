@@ -1,2 +1,4 @@
+pub mod crypto;
 pub mod database;
 pub mod entities;
+pub mod mail;
@@ -0,0 +1,49 @@
+use std::env;
+use std::io;
+use std::process::ExitCode;
+
+use secse24_group08::{
+    admin::{
+        cli::{help, parse_args},
+        commands::{format_error, run},
+    },
+    config::{set_config, Config},
+};
+
+/// Entry point of the admin tool for operators. Run `chatadmin help` for the commands
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (config, remaining) = match Config::load(&args) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::from(2);
+        }
+    };
+
+    env_logger::Builder::new()
+        .parse_filters(&config.log_level)
+        .init();
+    set_config(config);
+
+    let admin_args = match parse_args(&remaining) {
+        Ok(admin_args) => admin_args,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, help());
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&admin_args, &mut io::stdin().lock()) {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{}", output);
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", format_error(&err, admin_args.json));
+            ExitCode::FAILURE
+        }
+    }
+}
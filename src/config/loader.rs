@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use dotenv::dotenv;
+use toml::Value;
+
+use super::Config;
+
+/// Name of the directory in the configuration directory of the user
+const APP_DIRECTORY: &str = "secse24_group08";
+
+/// A setting that can be overridden by environment variables and a command-line flag
+struct Setting {
+    /// The key in the config file, e.g. "database.url"
+    key: &'static str,
+    /// The environment variables, the first one that is set is used
+    env: &'static [&'static str],
+    flag: &'static str,
+    description: &'static str,
+}
+
+/// The settings in the order they are listed in the usage
+const SETTINGS: &[Setting] = &[
+    Setting {
+        key: "database.url",
+        env: &["DATABASE_URL"],
+        flag: "--database-url",
+        description: "URL of the PostgreSQL database",
+    },
+    Setting {
+        key: "database.pool_size",
+        env: &["DATABASE_POOL_SIZE"],
+        flag: "--pool-size",
+        description: "Largest number of open database connections",
+    },
+    Setting {
+        key: "database.connect_timeout_secs",
+        env: &["DATABASE_CONNECT_TIMEOUT_SECS"],
+        flag: "--connect-timeout",
+        description: "Seconds to wait for a database connection",
+    },
+    Setting {
+        key: "polling.home_secs",
+        env: &["POLL_HOME_SECS"],
+        flag: "--poll-home-secs",
+        description: "Seconds between refreshes of the home page",
+    },
+    Setting {
+        key: "polling.chat_secs",
+        env: &["POLL_CHAT_SECS"],
+        flag: "--poll-chat-secs",
+        description: "Seconds between refreshes of open chats",
+    },
+    Setting {
+        key: "security.argon2_memory_kib",
+        env: &["ARGON2_MEMORY_KIB"],
+        flag: "--argon2-memory-kib",
+        description: "Memory of Argon2id password hashes in KiB",
+    },
+    Setting {
+        key: "security.argon2_iterations",
+        env: &["ARGON2_ITERATIONS"],
+        flag: "--argon2-iterations",
+        description: "Iterations of Argon2id password hashes",
+    },
+    Setting {
+        key: "security.argon2_parallelism",
+        env: &["ARGON2_PARALLELISM"],
+        flag: "--argon2-parallelism",
+        description: "Lanes of Argon2id password hashes",
+    },
+    Setting {
+        key: "password_policy.min_length",
+        env: &["PASSWORD_MIN_LENGTH"],
+        flag: "--password-min-length",
+        description: "Minimum length of new passwords in characters",
+    },
+    Setting {
+        key: "password_policy.require_uppercase",
+        env: &["PASSWORD_REQUIRE_UPPERCASE"],
+        flag: "--password-require-uppercase",
+        description: "Require an uppercase letter (true or false)",
+    },
+    Setting {
+        key: "password_policy.require_lowercase",
+        env: &["PASSWORD_REQUIRE_LOWERCASE"],
+        flag: "--password-require-lowercase",
+        description: "Require a lowercase letter (true or false)",
+    },
+    Setting {
+        key: "password_policy.require_digit",
+        env: &["PASSWORD_REQUIRE_DIGIT"],
+        flag: "--password-require-digit",
+        description: "Require a digit (true or false)",
+    },
+    Setting {
+        key: "password_policy.require_special",
+        env: &["PASSWORD_REQUIRE_SPECIAL"],
+        flag: "--password-require-special",
+        description: "Require a special character (true or false)",
+    },
+    Setting {
+        key: "password_policy.reject_common",
+        env: &["PASSWORD_REJECT_COMMON"],
+        flag: "--password-reject-common",
+        description: "Reject common and breached passwords (true or false)",
+    },
+    Setting {
+        key: "password_policy.history_size",
+        env: &["PASSWORD_HISTORY_SIZE"],
+        flag: "--password-history-size",
+        description: "Number of previous passwords that cannot be reused",
+    },
+    Setting {
+        key: "notifications.transport",
+        env: &["NOTIFICATION_TRANSPORT"],
+        flag: "--notification-transport",
+        description: "How notifications are shown, desktop or stdout",
+    },
+    Setting {
+        key: "log_level",
+        env: &["LOG_LEVEL", "RUST_LOG"],
+        flag: "--log-level",
+        description: "Log level or filter, e.g. info",
+    },
+];
+
+/// The flag that selects the config file
+const CONFIG_FLAG: &str = "--config";
+
+/// The environment variable that selects the config file
+const CONFIG_ENV: &str = "CONFIG_PATH";
+
+/// Implementation of the loading of the configuration
+impl Config {
+    /// Load the configuration from the config file, the environment (including a `.env` file) and the command-line flags,
+    /// each layer overriding the previous one. The config file is selected with `--config` or CONFIG_PATH, otherwise
+    /// `config.toml` in the configuration directory of the user is used if it exists.
+    /// Returns the configuration and the arguments that are not config flags, or an error message that lists every invalid value
+    pub fn load(args: &[String]) -> Result<(Config, Vec<String>), String> {
+        dotenv().ok();
+
+        let (flags, remaining) = split_flags(args)?;
+        let explicit_path = flags
+            .get(CONFIG_FLAG)
+            .cloned()
+            .or_else(|| env::var(CONFIG_ENV).ok());
+
+        let file = match explicit_path {
+            Some(path) => Some((
+                path.clone(),
+                fs::read_to_string(&path)
+                    .map_err(|err| format!("Failed to read config file {}: {}", path, err))?,
+            )),
+            None => {
+                let path = default_config_path();
+                fs::read_to_string(&path)
+                    .ok()
+                    .map(|content| (path.display().to_string(), content))
+            }
+        };
+
+        let config = load_layers(
+            file.as_ref()
+                .map(|(path, content)| (path.as_str(), content.as_str())),
+            &|name| env::var(name).ok(),
+            &flags,
+        )?;
+
+        Ok((config, remaining))
+    }
+}
+
+/// Build the configuration from the layers. The file is given as its path and content, the environment as a lookup
+/// and the flags by name. Returns the configuration or an error message that lists every invalid value with its source
+pub fn load_layers(
+    file: Option<(&str, &str)>,
+    env: &dyn Fn(&str) -> Option<String>,
+    flags: &HashMap<String, String>,
+) -> Result<Config, String> {
+    let defaults =
+        Value::try_from(Config::default()).map_err(|err| format!("Invalid defaults: {}", err))?;
+    let mut merged = defaults.clone();
+    // The source of every value that was not taken from the defaults
+    let mut sources: HashMap<&str, String> = HashMap::new();
+    let mut errors = Vec::new();
+
+    if let Some((path, content)) = file {
+        let source = format!("config file {}", path);
+        let value: Value = toml::from_str(content).map_err(|err| format!("{}: {}", source, err))?;
+        merge(&mut merged, value);
+        for setting in SETTINGS {
+            if lookup(&merged, setting.key) != lookup(&defaults, setting.key) {
+                sources.insert(setting.key, source.clone());
+            }
+        }
+    }
+
+    for setting in SETTINGS {
+        let from_env = setting.env.iter().find_map(|name| {
+            env(name).map(|value| (format!("environment variable {}", name), value))
+        });
+        let from_flag = flags
+            .get(setting.flag)
+            .map(|value| (format!("flag {}", setting.flag), value.clone()));
+
+        // The flag overrides the environment
+        for (source, raw) in from_env.into_iter().chain(from_flag) {
+            match parse_like(lookup(&defaults, setting.key), &raw) {
+                Ok(value) => {
+                    set(&mut merged, setting.key, value);
+                    sources.insert(setting.key, source);
+                }
+                Err(expected) => errors.push(format!(
+                    "{} ({}): \"{}\" is not {}",
+                    setting.key, source, raw, expected
+                )),
+            }
+        }
+    }
+
+    let config = match merged.try_into::<Config>() {
+        Ok(config) => Some(config),
+        Err(err) => {
+            errors.push(format!(
+                "{}: {}",
+                file.map(|(path, _)| format!("config file {}", path))
+                    .unwrap_or_else(|| "configuration".to_string()),
+                err
+            ));
+            None
+        }
+    };
+
+    if let Some(config) = &config {
+        errors.extend(
+            config
+                .validate()
+                .into_iter()
+                .map(|(key, message)| match sources.get(key) {
+                    Some(source) => format!("{} ({}): {}", key, source, message),
+                    None => format!("{}: {}", key, message),
+                }),
+        );
+    }
+
+    match config {
+        Some(config) if errors.is_empty() => Ok(config),
+        _ => Err(format!("Invalid configuration:\n{}", errors.join("\n"))),
+    }
+}
+
+/// Returns the config flags with their description, so the binaries can list them in their usage
+pub fn usage() -> String {
+    let mut lines = vec![format!(
+        "  {:<38}{}",
+        format!("{} <path>", CONFIG_FLAG),
+        "Config file (default: config.toml in the configuration directory)"
+    )];
+    lines.extend(SETTINGS.iter().map(|setting| {
+        format!(
+            "  {:<38}{} [{}]",
+            format!("{} <value>", setting.flag),
+            setting.description,
+            setting.env.join(", ")
+        )
+    }));
+    lines.join("\n")
+}
+
+/// Split the arguments into config flags, given as `--flag value` or `--flag=value`, and the other arguments.
+/// Returns both or an error message
+fn split_flags(args: &[String]) -> Result<(HashMap<String, String>, Vec<String>), String> {
+    let is_config_flag =
+        |name: &str| name == CONFIG_FLAG || SETTINGS.iter().any(|setting| setting.flag == name);
+
+    let mut flags = HashMap::new();
+    let mut remaining = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.split_once('=') {
+            Some((name, value)) if is_config_flag(name) => {
+                flags.insert(name.to_string(), value.to_string());
+            }
+            _ if is_config_flag(arg) => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("Missing value after {}", arg))?;
+                flags.insert(arg.clone(), value.clone());
+            }
+            _ => remaining.push(arg.clone()),
+        }
+    }
+
+    Ok((flags, remaining))
+}
+
+/// Returns the path of the config file in the configuration directory of the user
+fn default_config_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(env::temp_dir)
+        .join(APP_DIRECTORY)
+        .join("config.toml")
+}
+
+/// Parse a value of an environment variable or flag with the type of the default value.
+/// Returns the value or a description of the expected type
+fn parse_like(default: Option<&Value>, raw: &str) -> Result<Value, &'static str> {
+    match default {
+        Some(Value::Integer(_)) => raw
+            .trim()
+            .parse::<i64>()
+            .map(Value::Integer)
+            .map_err(|_| "a whole number"),
+        Some(Value::Boolean(_)) => match raw.trim().to_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok(Value::Boolean(true)),
+            "false" | "no" | "0" => Ok(Value::Boolean(false)),
+            _ => Err("true or false"),
+        },
+        _ => Ok(Value::String(raw.to_string())),
+    }
+}
+
+/// Returns the value at a dotted key like "database.url"
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(value, |value, part| value.as_table()?.get(part))
+}
+
+/// Set the value at a dotted key like "database.url". Missing tables are created
+fn set(value: &mut Value, key: &str, new_value: Value) {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().unwrap_or_default();
+
+    let mut table = value;
+    for part in parts {
+        let Some(entries) = table.as_table_mut() else {
+            return;
+        };
+        table = entries
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Table(Default::default()));
+    }
+    if let Some(entries) = table.as_table_mut() {
+        entries.insert(last.to_string(), new_value);
+    }
+}
+
+/// Merge the values of the overlay into the base. Tables are merged, all other values are replaced
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Table(base), Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
@@ -0,0 +1,357 @@
+pub mod loader;
+
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use log::{error, LevelFilter};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{t, t_args};
+
+lazy_static! {
+    /// The configuration of the application. It is loaded from the config file and the environment on first use,
+    /// the binaries replace it with the configuration that includes their command-line flags
+    static ref CONFIG: RwLock<Config> = RwLock::new(Config::load(&[]).map(|(config, _)| config).unwrap_or_else(|err| {
+        error!("Invalid configuration, the defaults are used: {}", err);
+        Config::default()
+    }));
+
+    /// The bundled list of common and breached passwords, in lowercase
+    static ref COMMON_PASSWORDS: HashSet<String> = include_str!("common_passwords.txt")
+        .lines()
+        .map(|line| line.trim().to_lowercase())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+}
+
+/// Smallest and largest number of pooled database connections. Some operations hold a connection while they
+/// query another one, so the pool needs at least two connections
+const MIN_POOL_SIZE: u32 = 2;
+const MAX_POOL_SIZE: u32 = 100;
+
+/// Largest number of previous passwords that are remembered per user
+const MAX_PASSWORD_HISTORY: usize = 24;
+
+/// The transports that can show notifications
+const NOTIFICATION_TRANSPORTS: &[&str] = &["desktop", "stdout"];
+
+/// The configuration of the application. Values missing in the config file keep their defaults
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The log level or filter, e.g. "info" or "secse24_group08=debug"
+    pub log_level: String,
+    pub database: DatabaseConfig,
+    pub polling: PollingConfig,
+    pub security: SecurityConfig,
+    pub password_policy: PasswordPolicy,
+    pub notifications: NotificationConfig,
+}
+
+/// The connection to the database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    /// The URL of the PostgreSQL database, empty if none is configured
+    pub url: String,
+    /// The largest number of connections that are kept open
+    pub pool_size: u32,
+    /// How long to wait for a connection before the database is considered unavailable
+    pub connect_timeout_secs: u64,
+}
+
+/// How often the application polls the database for new data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PollingConfig {
+    /// Interval in which the lists of the home page are refreshed
+    pub home_secs: u64,
+    /// Interval in which the open chats are refreshed
+    pub chat_secs: u64,
+}
+
+/// The Argon2id parameters for hashing passwords and codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecurityConfig {
+    /// Memory used per hash in KiB
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
+}
+
+/// The rules a new password has to follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PasswordPolicy {
+    /// Minimum length in characters
+    pub min_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_special: bool,
+    /// Reject passwords from the bundled list of common and breached passwords
+    pub reject_common: bool,
+    /// Number of previous passwords, including the current one, that cannot be used again. 0 allows any reuse
+    pub history_size: usize,
+}
+
+/// How notifications are shown to the user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationConfig {
+    /// The transport, "desktop" for desktop notifications or "stdout" to print them to the standard output
+    pub transport: String,
+}
+
+/// Default implementation for the configuration
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            log_level: "error".to_string(),
+            database: DatabaseConfig::default(),
+            polling: PollingConfig::default(),
+            security: SecurityConfig::default(),
+            password_policy: PasswordPolicy::default(),
+            notifications: NotificationConfig::default(),
+        }
+    }
+}
+
+/// Default implementation for the database configuration
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
+            url: String::new(),
+            pool_size: 10,
+            connect_timeout_secs: 5,
+        }
+    }
+}
+
+/// Default implementation for the polling configuration
+impl Default for PollingConfig {
+    fn default() -> Self {
+        PollingConfig {
+            home_secs: 10,
+            chat_secs: 5,
+        }
+    }
+}
+
+/// Default implementation for the security configuration
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        SecurityConfig {
+            argon2_memory_kib: argon2::Params::DEFAULT_M_COST,
+            argon2_iterations: argon2::Params::DEFAULT_T_COST,
+            argon2_parallelism: argon2::Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// Default implementation for the password policy
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: 8,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_special: true,
+            reject_common: true,
+            history_size: 5,
+        }
+    }
+}
+
+/// Default implementation for the notification configuration
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        NotificationConfig {
+            transport: "desktop".to_string(),
+        }
+    }
+}
+
+/// Implementation of the configuration
+impl Config {
+    /// Check that all values can be used. Returns every invalid value with the key it was set with
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+
+        if let Some(invalid) = self
+            .log_level
+            .split(',')
+            .map(|directive| directive.rsplit('=').next().unwrap_or_default().trim())
+            .find(|level| LevelFilter::from_str(level).is_err())
+        {
+            errors.push((
+                "log_level",
+                format!(
+                    "\"{}\" is not a log level, use off, error, warn, info, debug or trace",
+                    invalid
+                ),
+            ));
+        }
+
+        let url = &self.database.url;
+        if !url.is_empty() && !url.starts_with("postgres://") && !url.starts_with("postgresql://") {
+            errors.push((
+                "database.url",
+                "must start with postgres:// or postgresql://".to_string(),
+            ));
+        }
+        if !(MIN_POOL_SIZE..=MAX_POOL_SIZE).contains(&self.database.pool_size) {
+            errors.push((
+                "database.pool_size",
+                format!(
+                    "{} is not between {} and {}",
+                    self.database.pool_size, MIN_POOL_SIZE, MAX_POOL_SIZE
+                ),
+            ));
+        }
+        if self.database.connect_timeout_secs == 0 {
+            errors.push((
+                "database.connect_timeout_secs",
+                "must be at least 1".to_string(),
+            ));
+        }
+        if self.polling.home_secs == 0 {
+            errors.push(("polling.home_secs", "must be at least 1".to_string()));
+        }
+        if self.polling.chat_secs == 0 {
+            errors.push(("polling.chat_secs", "must be at least 1".to_string()));
+        }
+        if self.security.argon2_iterations < argon2::Params::MIN_T_COST {
+            errors.push((
+                "security.argon2_iterations",
+                format!("must be at least {}", argon2::Params::MIN_T_COST),
+            ));
+        }
+        if !(argon2::Params::MIN_P_COST..=argon2::Params::MAX_P_COST)
+            .contains(&self.security.argon2_parallelism)
+        {
+            errors.push((
+                "security.argon2_parallelism",
+                format!(
+                    "{} is not between {} and {}",
+                    self.security.argon2_parallelism,
+                    argon2::Params::MIN_P_COST,
+                    argon2::Params::MAX_P_COST
+                ),
+            ));
+        }
+        // Argon2 needs at least 8 KiB of memory per lane
+        let min_memory = argon2::Params::MIN_M_COST.max(8 * self.security.argon2_parallelism);
+        if self.security.argon2_memory_kib < min_memory {
+            errors.push((
+                "security.argon2_memory_kib",
+                format!("must be at least {}", min_memory),
+            ));
+        }
+        if self.password_policy.min_length == 0 {
+            errors.push((
+                "password_policy.min_length",
+                "must be at least 1".to_string(),
+            ));
+        }
+        if self.password_policy.history_size > MAX_PASSWORD_HISTORY {
+            errors.push((
+                "password_policy.history_size",
+                format!("must be at most {}", MAX_PASSWORD_HISTORY),
+            ));
+        }
+        if !NOTIFICATION_TRANSPORTS.contains(&self.notifications.transport.to_lowercase().as_str())
+        {
+            errors.push((
+                "notifications.transport",
+                format!(
+                    "\"{}\" is not a notification transport, use {}",
+                    self.notifications.transport,
+                    NOTIFICATION_TRANSPORTS.join(" or ")
+                ),
+            ));
+        }
+
+        errors
+    }
+}
+
+/// Implementation of the password policy
+impl PasswordPolicy {
+    /// Check if a password follows the policy. The reuse of previous passwords is checked when the password is stored.
+    /// Returns nothing or the error message to display
+    pub fn check(&self, password: &str) -> Result<(), String> {
+        let follows_rules = password.chars().count() >= self.min_length
+            && (!self.require_lowercase || password.chars().any(|c| c.is_lowercase()))
+            && (!self.require_uppercase || password.chars().any(|c| c.is_uppercase()))
+            && (!self.require_digit || password.chars().any(|c| c.is_ascii_digit()))
+            && (!self.require_special || password.chars().any(|c| !c.is_alphanumeric()));
+
+        if !follows_rules {
+            return Err(self.describe());
+        }
+        if self.reject_common && is_common_password(password) {
+            return Err(t("password-common"));
+        }
+
+        Ok(())
+    }
+
+    /// Check if a password follows the policy. Returns true if it does, false otherwise
+    pub fn is_satisfied_by(&self, password: &str) -> bool {
+        self.check(password).is_ok()
+    }
+
+    /// Returns the rules of the policy in the language of the user interface
+    pub fn describe(&self) -> String {
+        let length = self.min_length.to_string();
+        let classes: Vec<String> = [
+            (self.require_uppercase, "password-policy-uppercase"),
+            (self.require_lowercase, "password-policy-lowercase"),
+            (self.require_digit, "password-policy-digit"),
+            (self.require_special, "password-policy-special"),
+        ]
+        .into_iter()
+        .filter(|(required, _)| *required)
+        .map(|(_, key)| t(key))
+        .collect();
+
+        let classes = match classes.as_slice() {
+            [] => return t_args("password-policy-length", &[("length", length)]),
+            [class] => class.clone(),
+            [first, second] => t_args(
+                "list-two",
+                &[("first", first.clone()), ("second", second.clone())],
+            ),
+            [rest @ .., last] => t_args(
+                "list-many",
+                &[("rest", rest.join(", ")), ("last", last.clone())],
+            ),
+        };
+
+        t_args(
+            "password-policy",
+            &[("length", length), ("classes", classes)],
+        )
+    }
+}
+
+/// Check if a password is in the bundled list of common and breached passwords, ignoring the case.
+/// Returns true if it is, false otherwise
+pub fn is_common_password(password: &str) -> bool {
+    COMMON_PASSWORDS.contains(&password.to_lowercase())
+}
+
+/// Returns the configuration of the application
+pub fn config() -> Config {
+    CONFIG.read().expect("Failed to read configuration").clone()
+}
+
+/// Replace the configuration of the application, e.g. with the configuration that includes the command-line flags
+pub fn set_config(config: Config) {
+    *CONFIG.write().expect("Failed to write configuration") = config;
+}
@@ -1,20 +1,62 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use std::time::Duration;
 
-use iced::{executor, time, Application, Command, Element, Subscription};
+use chrono::Local;
+use iced::{
+    event, executor,
+    keyboard::{self, key::Named, Key, Modifiers},
+    time,
+    widget::{self, column, container, row, text_input},
+    window, Application, Command, Element, Event, Length, Subscription,
+};
+use log::error;
 
-use crate::backend::database::models::User;
+use crate::backend::{
+    database::{
+        db::is_database_available,
+        models::{ConversationSummary, Group, Session, User},
+    },
+    entities::{
+        session_ops::{
+            create_session, device_name, find_user_by_session_token, is_session_active,
+            revoke_session_by_token,
+        },
+        user_ops::find_user_email_by_id,
+        user_preferences_ops::{find_user_preferences, save_user_preferences},
+    },
+};
+use crate::config::config;
+use crate::i18n::{self, t, Language};
 
 use super::{
-    group_chat::{self, GroupChat},
+    chat_layout::{ChatId, ChatLayout, ChatLayoutMessage, OpenChat},
+    drafts::Drafts,
+    group_chat,
     home::{self, Home},
+    local_cache::{CachedData, CachedGroupChat, CachedUserChat, LocalCache, UnlockedCache},
     login::{self, Login},
+    notification::{
+        activity::{self, load_activity, ActivitySnapshot, ActivityWatcher},
+        memory_notifier::StdoutNotifier,
+        notifier::{create_notifier, Notification, Notifier},
+    },
+    outbox::{deliver, Outbox, OutboxMessage, OutboxRecipient, SentMessage},
+    preferences::{Preferences, PreferencesStore, SyncedPreferences},
+    quick_switcher::{QuickSwitcher, QuickSwitcherMessage},
     register::{self, Register},
+    reset_password::{self, ResetPassword},
+    session_store::SessionStore,
+    shortcuts::Shortcut,
     tabs_home::{
+        chat_tab::ChatTabMessage,
         group_tab::GroupTabMessage,
         setting_tab::{self, SettingsTabMessage},
         user_tab::UserTabMessage,
     },
-    user_chat::{self, UserChat},
+    task::run_blocking,
+    theme::{self, detect_system_dark_mode},
+    user_chat,
 };
 
 /// Define the pages that the application can have
@@ -23,21 +65,55 @@ pub enum Page {
     Home,
     Login,
     Register,
-    UserChat,
-    GroupChat,
+    ResetPassword,
+    Chats,
+}
+
+/// The stores of this device the application is started with
+#[derive(Debug, Clone, Default)]
+pub struct AppFlags {
+    pub session_store: SessionStore,
+    pub preferences_store: PreferencesStore,
 }
 
 /// Define the application struct
 #[derive(Clone, Debug)]
 pub struct App {
     current_user: Option<User>,
+    session: Option<Session>,
+    session_token: Option<String>,
+    session_store: SessionStore,
     current_page: Page,
     app_theme: setting_tab::AppTheme,
+    // Preferences of this device and the preferences that were last written to the preferences file
+    preferences: Preferences,
+    saved_preferences: Preferences,
+    preferences_store: PreferencesStore,
+    // The custom theme that is edited in the settings tab, shown until it is saved
+    theme_preview: Option<iced::Theme>,
+    // Set if the system prefers a dark theme, used by the system theme
+    system_dark_mode: bool,
     login: Login,
     home: Home,
     register: Register,
-    user_chat: UserChat,
-    group_chat: GroupChat,
+    reset_password: ResetPassword,
+    // The open chats with their state
+    chat_layout: ChatLayout,
+    // Local cache of the current user
+    local_cache: LocalCache,
+    cache: Option<UnlockedCache>,
+    cached_data: Option<Arc<CachedData>>,
+    // Set while the database cannot be reached
+    offline: bool,
+    // Messages of the current user that have not been sent yet
+    outbox: Outbox,
+    // Messages the current user started to write but has not sent yet
+    drafts: Drafts,
+    // Desktop notifications about new activity of the current user
+    notifier: Arc<dyn Notifier>,
+    activity_watcher: ActivityWatcher,
+    // Set while the quick switcher is open
+    quick_switcher: Option<QuickSwitcher>,
 }
 
 /// Define the messages that the application can have
@@ -47,8 +123,36 @@ pub enum Message {
     LoginMessage(login::LoginMessage),
     HomeMessage(home::HomeMessage),
     RegisterMessage(register::RegisterMessage),
-    UserChatMessage(user_chat::UserChatMessage),
-    GroupChatMessage(group_chat::GroupChatMessage),
+    ResetPasswordMessage(reset_password::ResetPasswordMessage),
+    ChatLayoutMessage(ChatLayoutMessage),
+    // Messages of an open chat with the ID of the other user or the group
+    UserChatMessage(i32, user_chat::UserChatMessage),
+    GroupChatMessage(i32, group_chat::GroupChatMessage),
+    QuickSwitcherMessage(QuickSwitcherMessage),
+    // Keyboard
+    KeyPressed(Key, Modifiers),
+    // Sessions
+    SessionStarted(User, bool, Result<(Session, String), String>),
+    SessionRestored(String, Result<Option<(User, Session)>, String>),
+    SessionChecked(i32, Result<bool, String>),
+    SessionEnded(Result<(), String>),
+    // Local cache
+    CacheUnlocked(i32, Result<(UnlockedCache, Option<CachedData>), String>),
+    CacheSaved(Result<(), String>),
+    ConnectionChecked(bool),
+    // Outbox
+    OutboxSent(OutboxMessage, Result<SentMessage, String>),
+    // Notifications
+    ActivityChecked(i32, Result<ActivitySnapshot, String>),
+    NotificationShown(Result<(), String>),
+    // Preferences
+    PreferencesLoaded(i32, Result<Option<String>, String>),
+    PreferencesSynced(Result<(), String>),
+    SystemThemeDetected(Option<bool>),
+    // Window
+    WindowResized(u32, u32),
+    WindowMoved(i32, i32),
+    CloseRequested,
 }
 
 /// Implementation of the application
@@ -63,19 +167,20 @@ impl App {
         self.register = Register::default();
     }
 
+    /// Clear the reset password page
+    fn clear_reset_password(&mut self) {
+        self.reset_password = ResetPassword::default();
+    }
+
     /// Clear the home page
     fn clear_home(&mut self) {
         self.home = Home::default();
     }
 
-    /// Clear the user chat page
-    fn clear_user_chat(&mut self) {
-        self.user_chat = UserChat::default();
-    }
-
-    /// Clear the group chat page
-    fn clear_group_chat(&mut self) {
-        self.group_chat = GroupChat::default();
+    /// Close all chats
+    fn clear_chats(&mut self) {
+        self.chat_layout = ChatLayout::default();
+        self.quick_switcher = None;
     }
 
     /// Handle the switch page message
@@ -83,221 +188,1777 @@ impl App {
         self.current_page = page;
     }
 
+    /// Start a session for the user in the background. The session token is only kept on this device if remember me is set
+    fn start_session(&mut self, user: User, remember_me: bool) -> Command<Message> {
+        let user_id = user.id;
+
+        Command::perform(
+            run_blocking(move || create_session(user_id, &device_name())),
+            move |result| Message::SessionStarted(user.clone(), remember_me, result),
+        )
+    }
+
+    /// Handle the started session and show the home page
+    fn handle_session_started(
+        &mut self,
+        user: User,
+        remember_me: bool,
+        result: Result<(Session, String), String>,
+    ) -> Command<Message> {
+        match result {
+            Ok((session, token)) => {
+                if remember_me {
+                    if let Err(err) = self.session_store.save(&token) {
+                        error!("Error storing session: {}", err);
+                    }
+                }
+                self.session = Some(session);
+                self.session_token = Some(token);
+            }
+            Err(err) => {
+                error!("Error creating session: {}", err);
+            }
+        }
+
+        let user_id = user.id;
+        // The password is only needed to unlock the cache and dropped afterwards
+        let password = self.login.take_password();
+        Command::batch([
+            self.show_home(user),
+            self.unlock_cache(user_id, password, remember_me),
+        ])
+    }
+
+    /// Unlock the local cache of the user with the password entered on the login page in the background.
+    /// The password is dropped once the cache is unlocked. For a remembered session the key of the cache is stored
+    /// with the session, so the cache can be unlocked when the session is restored
+    fn unlock_cache(
+        &mut self,
+        user_id: i32,
+        password: String,
+        remember_me: bool,
+    ) -> Command<Message> {
+        if password.is_empty() {
+            return Command::none();
+        }
+
+        let local_cache = self.local_cache.clone();
+        let session_store = self.session_store.clone();
+        let email = self.login.get_email().to_lowercase();
+
+        Command::perform(
+            run_blocking(move || {
+                let cache = local_cache.unlock(&email, &password)?;
+                if remember_me {
+                    if let Err(err) = session_store.save_cache_key(&cache.export_key()) {
+                        error!("Error storing cache key: {}", err);
+                    }
+                }
+                Ok(load_unlocked_cache(cache))
+            }),
+            move |result| Message::CacheUnlocked(user_id, result),
+        )
+    }
+
+    /// Unlock the local cache of a restored session in the background with the key that was stored with the session
+    fn unlock_remembered_cache(&mut self, user_id: i32) -> Command<Message> {
+        let Some(cache_key) = self.session_store.load_cache_key() else {
+            return Command::none();
+        };
+
+        let local_cache = self.local_cache.clone();
+
+        Command::perform(
+            run_blocking(move || {
+                let email = find_user_email_by_id(user_id)?;
+                let cache = local_cache.unlock_with_key(&email, &cache_key)?;
+                Ok(load_unlocked_cache(cache))
+            }),
+            move |result| Message::CacheUnlocked(user_id, result),
+        )
+    }
+
+    /// Handle the unlocked cache of the user and show the cached data in the tabs that are still loading
+    fn handle_cache_unlocked(
+        &mut self,
+        user_id: i32,
+        result: Result<(UnlockedCache, Option<CachedData>), String>,
+    ) -> Command<Message> {
+        let Some(user) = self.current_user.clone().filter(|user| user.id == user_id) else {
+            return Command::none();
+        };
+
+        match result {
+            Ok((cache, data)) => {
+                let data = data
+                    .filter(|data| data.user.id == user_id)
+                    .unwrap_or_else(|| CachedData::new(user));
+                self.home.show_cached(&data);
+                self.outbox.merge(data.outbox.clone());
+                self.drafts.merge(data.drafts.clone());
+                self.refresh_chat_outboxes();
+                self.show_drafts();
+                self.cache = Some(cache);
+                self.cached_data = Some(Arc::new(data));
+                self.cache_outbox()
+            }
+            Err(err) => {
+                error!("Error unlocking cache: {}", err);
+                Command::none()
+            }
+        }
+    }
+
+    /// Show the home page with the data cached on this device after a login while the database cannot be reached
+    fn handle_offline_login(&mut self, cache: UnlockedCache, data: CachedData) -> Command<Message> {
+        let user = data.user.clone();
+        self.outbox.merge(data.outbox.clone());
+        self.drafts.merge(data.drafts.clone());
+        self.cache = Some(cache);
+        self.cached_data = Some(Arc::new(data));
+        self.offline = true;
+        self.show_home(user)
+    }
+
+    /// Update the cached data and save a snapshot of it in the background if the update returns that it has changed.
+    /// The data is only copied if an earlier snapshot is still being saved
+    fn update_cache(&mut self, update: impl FnOnce(&mut CachedData) -> bool) -> Command<Message> {
+        let (Some(cache), Some(data)) = (self.cache.as_ref(), self.cached_data.as_mut()) else {
+            return Command::none();
+        };
+
+        if !update(Arc::make_mut(data)) {
+            return Command::none();
+        }
+
+        let cache = cache.clone();
+        let version = cache.next_version();
+        let data = Arc::clone(data);
+        Command::perform(
+            run_blocking(move || cache.save_snapshot(version, &data)),
+            Message::CacheSaved,
+        )
+    }
+
+    /// Cache the messages of an open user chat
+    fn cache_user_chat(&mut self, other_user_id: i32) -> Command<Message> {
+        let Some(user_chat) = self.chat_layout.user_chat(ChatId::User(other_user_id)) else {
+            return Command::none();
+        };
+        let Some(fingerprints) = user_chat.get_fingerprints().cloned() else {
+            return Command::none();
+        };
+
+        let chat = CachedUserChat {
+            messages: user_chat.get_messages().clone(),
+            fingerprints,
+        };
+
+        self.update_cache(|data| insert_if_changed(&mut data.user_chats, other_user_id, chat))
+    }
+
+    /// Cache the messages of an open group chat
+    fn cache_group_chat(&mut self, group_id: i32) -> Command<Message> {
+        let Some(group_chat) = self.chat_layout.group_chat(ChatId::Group(group_id)) else {
+            return Command::none();
+        };
+
+        let chat = CachedGroupChat {
+            messages: group_chat.get_messages().clone(),
+            users: group_chat.get_users_of_group().clone(),
+        };
+
+        self.update_cache(|data| insert_if_changed(&mut data.group_chats, group_id, chat))
+    }
+
+    /// Store the outbox in the cache, so queued messages are not lost when the app is closed
+    fn cache_outbox(&mut self) -> Command<Message> {
+        let outbox = self.outbox.clone();
+        self.update_cache(|data| replace_if_changed(&mut data.outbox, outbox))
+    }
+
+    /// Drop the cache, the outbox and the drafts of the current user from memory. The cache file stays on this device
+    fn clear_cache(&mut self) {
+        self.cache = None;
+        self.cached_data = None;
+        self.offline = false;
+        self.outbox = Outbox::default();
+        self.drafts = Drafts::default();
+    }
+
+    /// Remember the text that has been written in an open chat as its draft, so it is restored when the chat is opened again
+    fn track_draft(&mut self, id: ChatId) {
+        let Some(chat) = self.chat_layout.chats().iter().find(|chat| chat.id() == id) else {
+            return;
+        };
+
+        self.drafts.set(id, chat.input_value());
+        self.home.get_chat_tab().set_drafts(self.drafts.clone());
+    }
+
+    /// Show the drafts in the open chats that have no text yet and in the chats tab
+    fn show_drafts(&mut self) {
+        for chat in self.chat_layout.chats_mut() {
+            if let (false, Some(draft)) = (chat.has_draft(), self.drafts.get(chat.id())) {
+                chat.set_input_value(draft.to_string());
+            }
+        }
+        self.home.get_chat_tab().set_drafts(self.drafts.clone());
+    }
+
+    /// Store the drafts in the cache, so they are restored after a restart
+    fn cache_drafts(&mut self) -> Command<Message> {
+        let drafts = self.drafts.clone();
+        self.update_cache(|data| replace_if_changed(&mut data.drafts, drafts))
+    }
+
+    /// Store the preferences if they changed. The synced preferences are uploaded in the background if the user syncs them
+    fn save_preferences(&mut self) -> Command<Message> {
+        if self.preferences == self.saved_preferences {
+            return Command::none();
+        }
+
+        if let Err(err) = self.preferences_store.save(&self.preferences) {
+            error!("Error saving preferences: {}", err);
+        }
+        let upload = self.preferences.sync
+            && (!self.saved_preferences.sync
+                || self.preferences.synced() != self.saved_preferences.synced());
+        self.saved_preferences = self.preferences.clone();
+
+        if upload {
+            self.upload_preferences()
+        } else {
+            Command::none()
+        }
+    }
+
+    /// Upload the synced preferences of the current user in the background. Nothing is uploaded while offline
+    fn upload_preferences(&self) -> Command<Message> {
+        let Some(user_id) = self.current_user.as_ref().map(|user| user.id) else {
+            return Command::none();
+        };
+        if self.offline {
+            return Command::none();
+        }
+
+        let preferences = match self.preferences.synced().to_toml() {
+            Ok(preferences) => preferences,
+            Err(err) => {
+                error!("Error syncing preferences: {}", err);
+                return Command::none();
+            }
+        };
+
+        Command::perform(
+            run_blocking(move || save_user_preferences(user_id, &preferences)),
+            Message::PreferencesSynced,
+        )
+    }
+
+    /// Load the preferences the current user synced to the account in the background
+    fn load_synced_preferences(&self) -> Command<Message> {
+        let Some(user_id) = self.current_user.as_ref().map(|user| user.id) else {
+            return Command::none();
+        };
+        if !self.preferences.sync || self.offline {
+            return Command::none();
+        }
+
+        Command::perform(
+            run_blocking(move || find_user_preferences(user_id)),
+            move |result| Message::PreferencesLoaded(user_id, result),
+        )
+    }
+
+    /// Use the preferences synced from another device. Preferences that have never been synced are uploaded
+    fn handle_preferences_loaded(
+        &mut self,
+        user_id: i32,
+        result: Result<Option<String>, String>,
+    ) -> Command<Message> {
+        if self.current_user.as_ref().map(|user| user.id) != Some(user_id) {
+            return Command::none();
+        }
+
+        let content = match result {
+            Ok(Some(content)) => content,
+            Ok(None) => return self.upload_preferences(),
+            Err(err) => {
+                error!("Error loading synced preferences: {}", err);
+                return Command::none();
+            }
+        };
+
+        match SyncedPreferences::from_toml(&content) {
+            Ok(synced) => {
+                self.preferences.apply_synced(synced);
+                self.app_theme = self.preferences.theme;
+                self.apply_locale();
+                // The preferences on this device are the same as on the account now, so nothing is uploaded
+                if let Err(err) = self.preferences_store.save(&self.preferences) {
+                    error!("Error saving preferences: {}", err);
+                }
+                self.saved_preferences = self.preferences.clone();
+                Command::batch([self.select_last_tab(), self.detect_system_theme()])
+            }
+            Err(err) => {
+                error!("Error loading synced preferences: {}", err);
+                Command::none()
+            }
+        }
+    }
+
+    /// Show the user interface in the language and the time zone of the preferences, or in those of the system if none are chosen
+    fn apply_locale(&self) {
+        i18n::set_language(self.preferences.language.unwrap_or_else(Language::detect));
+        i18n::set_time_zone(
+            self.preferences
+                .time_zone
+                .as_deref()
+                .and_then(|time_zone| i18n::parse_time_zone(time_zone).ok()),
+        );
+    }
+
+    /// Detect in the background if the system prefers a dark theme. Nothing is detected unless the system theme is used
+    fn detect_system_theme(&self) -> Command<Message> {
+        if self.app_theme != setting_tab::AppTheme::System {
+            return Command::none();
+        }
+
+        Command::perform(
+            run_blocking(|| Ok(detect_system_dark_mode())),
+            |result: Result<Option<bool>, String>| {
+                Message::SystemThemeDetected(result.ok().flatten())
+            },
+        )
+    }
+
+    /// Show the tab of the home page that was open last
+    fn select_last_tab(&mut self) -> Command<Message> {
+        if self.home.get_active_tab() == self.preferences.last_tab {
+            return Command::none();
+        }
+
+        self.handle_home_message(home::HomeMessage::TabSelected(
+            self.preferences.last_tab.clone(),
+        ))
+    }
+
+    /// Handle a window event. The size and position are stored with the preferences on the next tick or when the window is closed
+    fn handle_window_event(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::WindowResized(width, height) => {
+                self.preferences.window.width = width as f32;
+                self.preferences.window.height = height as f32;
+                Command::none()
+            }
+            Message::WindowMoved(x, y) => {
+                self.preferences.window.x = Some(x);
+                self.preferences.window.y = Some(y);
+                Command::none()
+            }
+            Message::CloseRequested => {
+                Command::batch([self.save_preferences(), window::close(window::Id::MAIN)])
+            }
+            _ => Command::none(),
+        }
+    }
+
+    /// Show the queued messages of the open chats
+    fn refresh_chat_outboxes(&mut self) {
+        let Some(user_id) = self.current_user.as_ref().map(|user| user.id) else {
+            return;
+        };
+
+        for chat in self.chat_layout.chats_mut() {
+            let outbox = self.outbox.of_chat(user_id, chat.id().recipient());
+            match chat {
+                OpenChat::User(user_chat) => user_chat.set_outbox(outbox),
+                OpenChat::Group(group_chat) => group_chat.set_outbox(outbox),
+            }
+        }
+    }
+
+    /// Send the queued messages that are due in the background. Nothing is sent while offline
+    fn send_outbox(&mut self) -> Command<Message> {
+        if self.offline || self.outbox.is_empty() {
+            return Command::none();
+        }
+
+        let commands: Vec<Command<Message>> = self
+            .outbox
+            .start_sending_due(Local::now().naive_local())
+            .into_iter()
+            .map(|message| {
+                Command::perform(
+                    run_blocking({
+                        let message = message.clone();
+                        move || deliver(&message)
+                    }),
+                    move |result| Message::OutboxSent(message.clone(), result),
+                )
+            })
+            .collect();
+
+        self.refresh_chat_outboxes();
+        Command::batch(commands)
+    }
+
+    /// Handle a message that was sent from the outbox. A sent message is shown in its chat if the chat is open,
+    /// a message that could not be sent is retried with a growing delay
+    fn handle_outbox_sent(
+        &mut self,
+        message: OutboxMessage,
+        result: Result<SentMessage, String>,
+    ) -> Command<Message> {
+        let mut commands = Vec::new();
+
+        match result {
+            Ok(sent_message) => {
+                self.outbox.remove(&message.client_id);
+                let sent = user_chat::UserChatMessage::MessageSent(
+                    message.client_id.clone(),
+                    Ok(sent_message.clone()),
+                );
+
+                match message.recipient {
+                    OutboxRecipient::User(other_user_id) => {
+                        commands.push(self.update_user_chat(other_user_id, sent));
+                        commands.push(self.cache_user_chat(other_user_id));
+                    }
+                    OutboxRecipient::Group(group_id) => {
+                        commands.push(self.update_group_chat(
+                            group_id,
+                            group_chat::GroupChatMessage::MessageSent(
+                                message.client_id.clone(),
+                                Ok(sent_message),
+                            ),
+                        ));
+                        commands.push(self.cache_group_chat(group_id));
+                    }
+                }
+            }
+            Err(err) => {
+                error!("Error sending queued message: {}", err);
+                self.outbox
+                    .record_failure(&message.client_id, Local::now().naive_local());
+            }
+        }
+
+        self.refresh_chat_outboxes();
+        commands.push(self.cache_outbox());
+        Command::batch(commands)
+    }
+
+    /// Add a message that was queued in a chat to the outbox. While online the chat sends it at once
+    fn track_queued_message(&mut self, message: &OutboxMessage) {
+        self.outbox.push(message.clone());
+        if !self.offline {
+            self.outbox.start_sending(&message.client_id);
+        }
+    }
+
+    /// Retry a message that the user retries in a chat. While online the chat sends it at once
+    fn track_retried_message(&mut self, client_id: &str) {
+        self.outbox.retry(client_id, Local::now().naive_local());
+        if !self.offline {
+            self.outbox.start_sending(client_id);
+        }
+    }
+
+    /// Update the outbox with the result of a message that was sent by a chat
+    fn track_sent_message(&mut self, client_id: &str, result: &Result<SentMessage, String>) {
+        match result {
+            Ok(_) => self.outbox.remove(client_id),
+            Err(_) => self
+                .outbox
+                .record_failure(client_id, Local::now().naive_local()),
+        }
+    }
+
+    /// Check in the background if something happened for the current user the user should be notified about
+    fn check_activity(&mut self) -> Command<Message> {
+        let Some(user) = self.current_user.clone() else {
+            return Command::none();
+        };
+
+        if self.offline || !self.activity_watcher.start_check() {
+            return Command::none();
+        }
+
+        let since = self.activity_watcher.since();
+        let user_id = user.id;
+
+        Command::perform(
+            run_blocking(move || load_activity(&user, since)),
+            move |result| Message::ActivityChecked(user_id, result),
+        )
+    }
+
+    /// Handle the checked activity. New activity is shown as desktop notifications, unless the user muted it or
+    /// does not want to be disturbed
+    fn handle_activity_checked(
+        &mut self,
+        user_id: i32,
+        result: Result<ActivitySnapshot, String>,
+    ) -> Command<Message> {
+        if self.current_user.as_ref().map(|user| user.id) != Some(user_id) {
+            return Command::none();
+        }
+
+        let snapshot = match result {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                error!("Error checking activity: {}", err);
+                self.activity_watcher.cancel_check();
+                return Command::none();
+            }
+        };
+
+        let activities = self.activity_watcher.update(&snapshot);
+        let notifications = activity::notifications(
+            &activities,
+            &snapshot.settings,
+            &self.preferences.notifications,
+            Local::now().naive_local().time(),
+        );
+
+        Command::batch(
+            notifications
+                .into_iter()
+                .map(|notification| self.notify(notification)),
+        )
+    }
+
+    /// Show a notification in the background
+    fn notify(&self, notification: Notification) -> Command<Message> {
+        let notifier = self.notifier.clone();
+
+        Command::perform(
+            run_blocking(move || notifier.notify(&notification)),
+            Message::NotificationShown,
+        )
+    }
+
+    /// Check in the background if the database can be reached
+    fn check_connection(&self) -> Command<Message> {
+        Command::perform(
+            run_blocking(|| Ok(is_database_available())),
+            |result: Result<bool, String>| Message::ConnectionChecked(result.unwrap_or(false)),
+        )
+    }
+
+    /// Switch between the online and the offline mode. A user that logged in while offline has no session,
+    /// so the user has to log in again once the database can be reached
+    fn handle_connection_checked(&mut self, available: bool) -> Command<Message> {
+        if self.current_user.is_none() || available != self.offline {
+            return Command::none();
+        }
+
+        if available && self.session.is_none() {
+            return self.logout(&t("app-back-online"));
+        }
+
+        self.offline = !available;
+        self.home.set_offline(self.offline);
+        for chat in self.chat_layout.chats_mut() {
+            match chat {
+                OpenChat::User(user_chat) => user_chat.set_offline(self.offline),
+                OpenChat::Group(group_chat) => group_chat.set_offline(self.offline),
+            }
+        }
+        self.send_outbox()
+    }
+
+    /// Restore the session stored on this device in the background, so the user does not have to log in again after a restart
+    fn restore_session(&mut self) -> Command<Message> {
+        let Some(token) = self.session_store.load() else {
+            return Command::none();
+        };
+
+        Command::perform(
+            run_blocking({
+                let token = token.clone();
+                move || find_user_by_session_token(&token)
+            }),
+            move |result| Message::SessionRestored(token.clone(), result),
+        )
+    }
+
+    /// Handle the restored session. The stored token is removed if the session is no longer active
+    fn handle_session_restored(
+        &mut self,
+        token: String,
+        result: Result<Option<(User, Session)>, String>,
+    ) -> Command<Message> {
+        match result {
+            Ok(Some((user, session))) => {
+                self.session = Some(session);
+                self.session_token = Some(token);
+                let user_id = user.id;
+                return Command::batch([
+                    self.show_home(user),
+                    self.unlock_remembered_cache(user_id),
+                ]);
+            }
+            Ok(None) => {
+                if let Err(err) = self.session_store.clear() {
+                    error!("Error removing session: {}", err);
+                }
+            }
+            Err(err) => {
+                error!("Error restoring session: {}", err);
+            }
+        }
+
+        Command::none()
+    }
+
+    /// Set the current user and show the home page. Cached data is shown at once, the data is only loaded while online
+    fn show_home(&mut self, user: User) -> Command<Message> {
+        self.clear_home();
+        self.clear_chats();
+        self.activity_watcher = ActivityWatcher::default();
+        self.current_user = Some(user);
+        self.home
+            .set_current_user(self.current_user.as_ref().unwrap().clone());
+        self.home.set_offline(self.offline);
+        self.handle_switch_page(Page::Home);
+
+        if let Some(data) = self.cached_data.as_ref() {
+            self.home.show_cached(data);
+        }
+
+        if self.offline {
+            return Command::none();
+        }
+
+        // Selecting the tab that was open last loads its data
+        let load_command = if self.home.get_active_tab() == self.preferences.last_tab {
+            self.home
+                .update(home::HomeMessage::Tick)
+                .map(Message::HomeMessage)
+        } else {
+            self.select_last_tab()
+        };
+
+        Command::batch([load_command, self.load_synced_preferences()])
+    }
+
+    /// Revoke the session of the current user in the background and remove it from this device
+    fn end_session(&mut self) -> Command<Message> {
+        if let Err(err) = self.session_store.clear() {
+            error!("Error removing session: {}", err);
+        }
+        self.session = None;
+
+        let Some(token) = self.session_token.take() else {
+            return Command::none();
+        };
+
+        Command::perform(
+            run_blocking(move || revoke_session_by_token(&token)),
+            Message::SessionEnded,
+        )
+    }
+
+    /// Check in the background if the session of the current user has been revoked, e.g. on another device or because the password was reset
+    fn check_session(&self) -> Command<Message> {
+        let (Some(_), Some(session)) = (self.current_user.as_ref(), self.session.as_ref()) else {
+            return Command::none();
+        };
+
+        let session_id = session.id;
+
+        Command::perform(
+            run_blocking(move || is_session_active(session_id)),
+            move |result| Message::SessionChecked(session_id, result),
+        )
+    }
+
+    /// Log out the current user if the checked session is still the current one and has been revoked
+    fn handle_session_checked(
+        &mut self,
+        session_id: i32,
+        result: Result<bool, String>,
+    ) -> Command<Message> {
+        if self.session.as_ref().map(|session| session.id) != Some(session_id) {
+            return Command::none();
+        }
+
+        match result {
+            Ok(true) => Command::none(),
+            Ok(false) => self.logout(&t("app-session-ended")),
+            Err(err) => {
+                error!("Error checking session: {}", err);
+                Command::none()
+            }
+        }
+    }
+
+    /// Log out the current user and show the login page with the given error
+    fn logout(&mut self, error: &str) -> Command<Message> {
+        let command = Command::batch([
+            self.cache_drafts(),
+            self.save_preferences(),
+            self.end_session(),
+        ]);
+        self.current_user = None;
+        self.clear_cache();
+        self.clear_login();
+        self.clear_home();
+        self.clear_chats();
+        self.login.set_error(error.to_string());
+        self.handle_switch_page(Page::Login);
+        command
+    }
+
     /// Handle the login message
-    fn handle_login_message(&mut self, login_message: login::LoginMessage) {
+    fn handle_login_message(&mut self, login_message: login::LoginMessage) -> Command<Message> {
         match login_message {
             login::LoginMessage::NavigateToRegister => {
                 self.clear_register();
-                self.handle_switch_page(Page::Register)
-            }
-            login::LoginMessage::SubmitLogin => {
-                self.login.update(login_message);
-                if self.login.get_found_user().id != -1 {
-                    self.clear_home();
-                    self.current_user = Some(self.login.get_found_user().clone());
-                    self.home
-                        .set_current_user(self.current_user.as_ref().unwrap().clone());
-                    self.home.update(home::HomeMessage::Tick);
-                    self.handle_switch_page(Page::Home)
+                self.handle_switch_page(Page::Register);
+                Command::none()
+            }
+            login::LoginMessage::LoginSuccess(user) => {
+                self.start_session(user, self.login.get_remember_me())
+            }
+            login::LoginMessage::OfflineLoginSuccess(cache, data) => {
+                // The cache is already unlocked, so the password is not needed anymore
+                self.login.take_password();
+                self.handle_offline_login(cache, *data)
+            }
+            login::LoginMessage::ResetRequestFinished(_) => {
+                let command = self.login.update(login_message);
+                if self.login.get_reset_requested() {
+                    self.clear_reset_password();
+                    self.reset_password
+                        .set_email(self.login.get_email().clone());
+                    self.reset_password.set_info(t("reset-code-sent"));
+                    self.handle_switch_page(Page::ResetPassword)
                 }
+                command.map(Message::LoginMessage)
             }
-            _ => {
-                self.login.update(login_message);
+            _ => self.login.update(login_message).map(Message::LoginMessage),
+        }
+    }
+
+    /// Open the chat with a user beside the other open chats and focus it. The cached messages are shown at once
+    /// when the chat is opened, the messages are loaded unless the app is offline
+    fn open_user_chat(&mut self, user: User) -> Command<Message> {
+        let cached_chat = self
+            .cached_data
+            .as_ref()
+            .and_then(|data| data.user_chats.get(&user.id))
+            .cloned();
+
+        let (user_chat, opened) = self
+            .chat_layout
+            .open_user_chat(self.current_user.as_ref().unwrap().clone(), user);
+        if let (true, Some(chat)) = (opened, cached_chat) {
+            user_chat.show_cached(chat.messages, chat.fingerprints);
+        }
+        user_chat.set_offline(self.offline);
+        self.refresh_chat_outboxes();
+        self.show_drafts();
+        self.show_chats()
+    }
+
+    /// Open the chat of a group beside the other open chats and focus it. The cached messages are shown at once
+    /// when the chat is opened, the messages are loaded unless the app is offline
+    fn open_group_chat(&mut self, group: Group) -> Command<Message> {
+        let cached_chat = self
+            .cached_data
+            .as_ref()
+            .and_then(|data| data.group_chats.get(&group.id))
+            .cloned();
+
+        let (group_chat, opened) = self
+            .chat_layout
+            .open_group_chat(self.current_user.as_ref().unwrap().clone(), group);
+        if let (true, Some(chat)) = (opened, cached_chat) {
+            group_chat.show_cached(chat.messages, chat.users);
+        }
+        group_chat.set_offline(self.offline);
+        self.refresh_chat_outboxes();
+        self.show_drafts();
+        self.show_chats()
+    }
+
+    /// Open the chat with the friend or the group of a conversation
+    fn open_conversation(&mut self, conversation: ConversationSummary) -> Command<Message> {
+        match (conversation.user(), conversation.group()) {
+            (Some(user), _) => self.open_user_chat(user),
+            (_, Some(group)) => self.open_group_chat(group),
+            _ => Command::none(),
+        }
+    }
+
+    /// Show the open chats. The shown chats scroll back to where they were and load their new messages unless the app is offline
+    fn show_chats(&mut self) -> Command<Message> {
+        if self.chat_layout.is_empty() {
+            self.handle_switch_page(Page::Home);
+            return Command::none();
+        }
+
+        self.handle_switch_page(Page::Chats);
+
+        let mut commands = Vec::new();
+        for id in self.chat_layout.visible() {
+            commands.push(self.restore_scroll(id));
+            if !self.offline {
+                commands.push(self.tick_chat(id));
+            }
+        }
+
+        Command::batch(commands)
+    }
+
+    /// Load the new messages of an open chat
+    fn tick_chat(&mut self, id: ChatId) -> Command<Message> {
+        match id {
+            ChatId::User(other_user_id) => {
+                self.update_user_chat(other_user_id, user_chat::UserChatMessage::Tick)
+            }
+            ChatId::Group(group_id) => {
+                self.update_group_chat(group_id, group_chat::GroupChatMessage::Tick)
+            }
+        }
+    }
+
+    /// Scroll an open chat back to where it was when it was shown the last time
+    fn restore_scroll(&self, id: ChatId) -> Command<Message> {
+        match id {
+            ChatId::User(other_user_id) => self
+                .chat_layout
+                .user_chat(id)
+                .map(|chat| {
+                    chat.restore_scroll()
+                        .map(move |message| Message::UserChatMessage(other_user_id, message))
+                })
+                .unwrap_or_else(Command::none),
+            ChatId::Group(group_id) => self
+                .chat_layout
+                .group_chat(id)
+                .map(|chat| {
+                    chat.restore_scroll()
+                        .map(move |message| Message::GroupChatMessage(group_id, message))
+                })
+                .unwrap_or_else(Command::none),
+        }
+    }
+
+    /// Update an open user chat. Messages of a chat that has been closed are dropped
+    fn update_user_chat(
+        &mut self,
+        other_user_id: i32,
+        message: user_chat::UserChatMessage,
+    ) -> Command<Message> {
+        match self.chat_layout.user_chat_mut(ChatId::User(other_user_id)) {
+            Some(chat) => chat
+                .update(message)
+                .map(move |message| Message::UserChatMessage(other_user_id, message)),
+            None => Command::none(),
+        }
+    }
+
+    /// Update an open group chat. Messages of a chat that has been closed are dropped
+    fn update_group_chat(
+        &mut self,
+        group_id: i32,
+        message: group_chat::GroupChatMessage,
+    ) -> Command<Message> {
+        match self.chat_layout.group_chat_mut(ChatId::Group(group_id)) {
+            Some(chat) => chat
+                .update(message)
+                .map(move |message| Message::GroupChatMessage(group_id, message)),
+            None => Command::none(),
+        }
+    }
+
+    /// Handle the chat layout message. The chats stay open when the home page is shown
+    fn handle_chat_layout_message(
+        &mut self,
+        chat_layout_message: ChatLayoutMessage,
+    ) -> Command<Message> {
+        if self.current_user.is_none() {
+            return Command::none();
+        }
+
+        match chat_layout_message {
+            ChatLayoutMessage::Focus(id) => {
+                self.chat_layout.focus(id);
+                self.show_chats()
+            }
+            ChatLayoutMessage::OpenBeside(id) => {
+                self.chat_layout.open_beside(id);
+                self.show_chats()
             }
+            ChatLayoutMessage::CloseSplit => {
+                self.chat_layout.close_split();
+                self.show_chats()
+            }
+            ChatLayoutMessage::Close(id) => {
+                self.chat_layout.close(id);
+                if self.current_page == Page::Chats {
+                    return self.show_chats();
+                }
+                Command::none()
+            }
+            ChatLayoutMessage::ShowHome => {
+                self.handle_switch_page(Page::Home);
+                Command::none()
+            }
+            ChatLayoutMessage::Tick if self.offline => self.check_connection(),
+            ChatLayoutMessage::Tick => {
+                let mut commands = vec![
+                    self.check_connection(),
+                    self.check_session(),
+                    self.send_outbox(),
+                    self.check_activity(),
+                    self.save_preferences(),
+                    self.detect_system_theme(),
+                ];
+                for id in self.chat_layout.visible() {
+                    commands.push(self.tick_chat(id));
+                }
+                Command::batch(commands)
+            }
+        }
+    }
+
+    /// Handle the selected tab of the home page. The settings tab shows the theme and the preferences of this device
+    fn handle_tab_selected(&mut self, tab_id: home::TabId) -> Command<Message> {
+        self.theme_preview = None;
+        match tab_id {
+            home::TabId::Settings => {
+                self.home.get_settings_tab().set_app_theme(self.app_theme);
+                self.home
+                    .get_settings_tab()
+                    .set_preferences(&self.preferences);
+                self.home
+                    .get_settings_tab()
+                    .set_current_session_id(self.session.as_ref().map(|session| session.id));
+                self.home
+                    .update(home::HomeMessage::TabSelected(tab_id))
+                    .map(Message::HomeMessage)
+            }
+            _ => self
+                .home
+                .update(home::HomeMessage::TabSelected(tab_id))
+                .map(Message::HomeMessage),
         }
     }
 
     /// Handle the home message. Home message can be from the home page, user tab, group tab, or settings tab
-    fn handle_home_message(&mut self, home_message: home::HomeMessage) {
+    fn handle_home_message(&mut self, home_message: home::HomeMessage) -> Command<Message> {
+        // Results that arrive after the user logged out are dropped
+        if self.current_user.is_none() {
+            return Command::none();
+        }
+
         match home_message {
             home::HomeMessage::NavigateToLogin => {
+                let command = Command::batch([
+                    self.cache_drafts(),
+                    self.save_preferences(),
+                    self.end_session(),
+                ]);
+                self.current_user = None;
+                self.clear_chats();
+                self.clear_cache();
                 self.clear_login();
-                self.handle_switch_page(Page::Login)
+                self.handle_switch_page(Page::Login);
+                command
+            }
+            home::HomeMessage::Tick if self.offline => self.check_connection(),
+            home::HomeMessage::Tick => Command::batch([
+                self.check_connection(),
+                self.check_session(),
+                self.send_outbox(),
+                self.check_activity(),
+                self.cache_drafts(),
+                self.save_preferences(),
+                self.detect_system_theme(),
+                self.home.update(home_message).map(Message::HomeMessage),
+            ]),
+            home::HomeMessage::TabSelected(tab_id) => {
+                // The tab is stored with the preferences on the next tick
+                self.preferences.last_tab = tab_id.clone();
+                self.handle_tab_selected(tab_id)
+            }
+            home::HomeMessage::ChatTab(ChatTabMessage::OpenConversation(conversation)) => {
+                self.open_conversation(conversation)
             }
-            home::HomeMessage::TabSelected(tab_id) => match tab_id {
-                home::TabId::Settings => {
-                    self.home.get_settings_tab().set_app_theme(self.app_theme);
-                    self.home.update(home::HomeMessage::TabSelected(tab_id));
-                }
-                _ => {
-                    self.home.update(home::HomeMessage::TabSelected(tab_id));
-                }
-            },
             home::HomeMessage::UserTab(user_tag_message) => match user_tag_message {
-                UserTabMessage::ChatWithUser(user) => {
-                    self.user_chat
-                        .set_properties(self.current_user.as_ref().unwrap().clone(), user);
-                    self.user_chat.update(user_chat::UserChatMessage::Tick);
-                    self.handle_switch_page(Page::UserChat)
-                }
-                _ => {
-                    self.home.get_user_tab().update(user_tag_message);
+                UserTabMessage::ChatWithUser(user) => self.open_user_chat(user),
+                // Only the latest data is cached, stale data is dropped by the tab
+                UserTabMessage::FriendsLoaded(generation, users, Ok(friend_relations))
+                    if self.home.get_user_tab().get_load().is_current(generation) =>
+                {
+                    let cache_command = self.update_cache(|data| {
+                        let users_changed = replace_if_changed(&mut data.users, users.clone());
+                        let relations_changed = replace_if_changed(
+                            &mut data.friend_relations,
+                            friend_relations.clone(),
+                        );
+                        users_changed || relations_changed
+                    });
+                    let command = self
+                        .home
+                        .update(home::HomeMessage::UserTab(UserTabMessage::FriendsLoaded(
+                            generation,
+                            users,
+                            Ok(friend_relations),
+                        )))
+                        .map(Message::HomeMessage);
+                    Command::batch([command, cache_command])
                 }
+                _ => self
+                    .home
+                    .update(home::HomeMessage::UserTab(user_tag_message))
+                    .map(Message::HomeMessage),
             },
 
             home::HomeMessage::GroupTab(group_tab_message) => match group_tab_message {
-                GroupTabMessage::ChatWithGroup(group) => {
-                    self.group_chat
-                        .set_properties(self.current_user.as_ref().unwrap().clone(), group);
-                    self.group_chat.update(group_chat::GroupChatMessage::Tick);
-                    self.handle_switch_page(Page::GroupChat);
-                }
-                _ => {
-                    self.home.get_group_tab().update(group_tab_message);
+                GroupTabMessage::ChatWithGroup(group) => self.open_group_chat(group),
+                GroupTabMessage::DataLoaded(generation, users, Ok(group_tab_data))
+                    if self.home.get_group_tab().get_load().is_current(generation) =>
+                {
+                    let cache_command = self.update_cache(|data| {
+                        let users_changed = replace_if_changed(&mut data.users, users.clone());
+                        let groups_changed =
+                            replace_if_changed(&mut data.group_tab, group_tab_data.clone());
+                        users_changed || groups_changed
+                    });
+                    let command = self
+                        .home
+                        .update(home::HomeMessage::GroupTab(GroupTabMessage::DataLoaded(
+                            generation,
+                            users,
+                            Ok(group_tab_data),
+                        )))
+                        .map(Message::HomeMessage);
+                    Command::batch([command, cache_command])
                 }
+                _ => self
+                    .home
+                    .update(home::HomeMessage::GroupTab(group_tab_message))
+                    .map(Message::HomeMessage),
             },
 
             home::HomeMessage::SettingsTab(settings_tab_message) => match settings_tab_message {
-                SettingsTabMessage::DeleteAccount => {
-                    self.home.get_settings_tab().update(settings_tab_message);
+                SettingsTabMessage::AccountDeleted(_) => {
+                    let command = self
+                        .home
+                        .update(home::HomeMessage::SettingsTab(settings_tab_message))
+                        .map(Message::HomeMessage);
                     if self.home.get_settings_tab().get_account_deleted() {
+                        let command = self.end_session();
                         self.current_user = None;
+                        if let Some(cache) = self.cache.as_ref() {
+                            if let Err(err) = cache.remove() {
+                                error!("Error removing cache: {}", err);
+                            }
+                        }
+                        self.clear_cache();
                         self.clear_login();
                         self.clear_home();
-                        self.handle_switch_page(Page::Login)
+                        self.clear_chats();
+                        self.handle_switch_page(Page::Login);
+                        return command;
                     }
+                    command
+                }
+                SettingsTabMessage::ChangeTheme(_)
+                | SettingsTabMessage::ThemeNameInputChanged(_)
+                | SettingsTabMessage::ThemeColorInputChanged(..)
+                | SettingsTabMessage::SaveCustomTheme
+                | SettingsTabMessage::SelectCustomTheme(_)
+                | SettingsTabMessage::EditCustomTheme(_)
+                | SettingsTabMessage::DeleteCustomTheme(_)
+                | SettingsTabMessage::ImportTheme => {
+                    let command = self
+                        .home
+                        .update(home::HomeMessage::SettingsTab(settings_tab_message))
+                        .map(Message::HomeMessage);
+                    let settings_tab = self.home.get_settings_tab();
+                    self.app_theme = settings_tab.get_app_theme();
+                    self.theme_preview = settings_tab.get_theme_preview();
+                    self.preferences.theme = self.app_theme;
+                    self.preferences.custom_theme = settings_tab.get_custom_theme().to_string();
+                    self.preferences.custom_themes = settings_tab.get_custom_themes().clone();
+                    Command::batch([command, self.save_preferences(), self.detect_system_theme()])
+                }
+                SettingsTabMessage::NotificationsEnabledToggled(_)
+                | SettingsTabMessage::NotificationPreviewToggled(_)
+                | SettingsTabMessage::SyncPreferencesToggled(_)
+                | SettingsTabMessage::ChangeFontScale(_) => {
+                    let command = self
+                        .home
+                        .update(home::HomeMessage::SettingsTab(settings_tab_message))
+                        .map(Message::HomeMessage);
+                    let settings_tab = self.home.get_settings_tab();
+                    self.preferences.notifications = settings_tab.get_notification_preferences();
+                    self.preferences.sync = settings_tab.get_sync_preferences();
+                    self.preferences.font_scale = settings_tab.get_font_scale();
+                    Command::batch([command, self.save_preferences()])
                 }
-                SettingsTabMessage::ChangeTheme(_) => {
-                    self.home.get_settings_tab().update(settings_tab_message);
-                    self.app_theme = self.home.get_settings_tab().get_app_theme();
+                SettingsTabMessage::SaveShortcuts | SettingsTabMessage::ResetShortcuts => {
+                    let command = self
+                        .home
+                        .update(home::HomeMessage::SettingsTab(settings_tab_message))
+                        .map(Message::HomeMessage);
+                    self.preferences.shortcuts =
+                        self.home.get_settings_tab().get_shortcuts().clone();
+                    Command::batch([command, self.save_preferences()])
                 }
-                _ => self.home.get_settings_tab().update(settings_tab_message),
+                SettingsTabMessage::ChangeLanguage(_) | SettingsTabMessage::SaveTimeZone => {
+                    let command = self
+                        .home
+                        .update(home::HomeMessage::SettingsTab(settings_tab_message))
+                        .map(Message::HomeMessage);
+                    let settings_tab = self.home.get_settings_tab();
+                    self.preferences.language = settings_tab.get_language();
+                    self.preferences.time_zone = settings_tab.get_time_zone().map(str::to_string);
+                    self.apply_locale();
+                    Command::batch([command, self.save_preferences()])
+                }
+                _ => self
+                    .home
+                    .update(home::HomeMessage::SettingsTab(settings_tab_message))
+                    .map(Message::HomeMessage),
             },
-            _ => {
-                self.home.update(home_message);
-            }
+            _ => self.home.update(home_message).map(Message::HomeMessage),
         }
     }
 
     /// Handle the register message
-    fn handle_register_message(&mut self, register_message: register::RegisterMessage) {
+    fn handle_register_message(
+        &mut self,
+        register_message: register::RegisterMessage,
+    ) -> Command<Message> {
         match register_message {
             register::RegisterMessage::NavigateToLogin => {
                 self.clear_login();
-                self.handle_switch_page(Page::Login)
+                self.handle_switch_page(Page::Login);
+                Command::none()
             }
-            _ => {
-                self.register.update(register_message);
+            _ => self
+                .register
+                .update(register_message)
+                .map(Message::RegisterMessage),
+        }
+    }
+
+    /// Handle the reset password message
+    fn handle_reset_password_message(
+        &mut self,
+        reset_password_message: reset_password::ResetPasswordMessage,
+    ) -> Command<Message> {
+        match reset_password_message {
+            reset_password::ResetPasswordMessage::NavigateToLogin => {
+                self.clear_login();
+                self.handle_switch_page(Page::Login);
+                Command::none()
             }
+            _ => self
+                .reset_password
+                .update(reset_password_message)
+                .map(Message::ResetPasswordMessage),
         }
     }
 
-    /// Handle the user chat message
-    fn handle_user_chat_message(&mut self, user_chat_message: user_chat::UserChatMessage) {
+    /// Handle the message of an open user chat
+    fn handle_user_chat_message(
+        &mut self,
+        other_user_id: i32,
+        user_chat_message: user_chat::UserChatMessage,
+    ) -> Command<Message> {
+        // Results that arrive after the user logged out are dropped
+        if self.current_user.is_none() {
+            return Command::none();
+        }
+
         match user_chat_message {
             user_chat::UserChatMessage::Back => {
-                self.clear_user_chat();
-                self.handle_switch_page(Page::Home)
+                self.handle_switch_page(Page::Home);
+                self.cache_drafts()
+            }
+            user_chat::UserChatMessage::InputChanged(_)
+            | user_chat::UserChatMessage::SendMessage => {
+                let command = self.update_user_chat(other_user_id, user_chat_message);
+                self.track_draft(ChatId::User(other_user_id));
+                command
             }
-            _ => {
-                self.user_chat.update(user_chat_message);
+            user_chat::UserChatMessage::MessagesLoaded(_, _, _) => {
+                let command = self.update_user_chat(other_user_id, user_chat_message);
+                Command::batch([command, self.cache_user_chat(other_user_id)])
             }
+            user_chat::UserChatMessage::MessageQueued(message) => {
+                self.track_queued_message(&message);
+                let command = self.update_user_chat(
+                    other_user_id,
+                    user_chat::UserChatMessage::MessageQueued(message),
+                );
+                Command::batch([command, self.cache_outbox()])
+            }
+            user_chat::UserChatMessage::RetryMessage(client_id) => {
+                self.track_retried_message(&client_id);
+                let command = self.update_user_chat(
+                    other_user_id,
+                    user_chat::UserChatMessage::RetryMessage(client_id),
+                );
+                Command::batch([command, self.cache_outbox()])
+            }
+            user_chat::UserChatMessage::MessageSent(client_id, result) => {
+                self.track_sent_message(&client_id, &result);
+                let command = self.update_user_chat(
+                    other_user_id,
+                    user_chat::UserChatMessage::MessageSent(client_id, result),
+                );
+                Command::batch([
+                    command,
+                    self.cache_user_chat(other_user_id),
+                    self.cache_outbox(),
+                ])
+            }
+            _ => self.update_user_chat(other_user_id, user_chat_message),
         }
     }
 
-    /// Handle the group chat message
-    fn handle_group_chat_message(&mut self, group_chat_message: group_chat::GroupChatMessage) {
+    /// Handle the message of an open group chat
+    fn handle_group_chat_message(
+        &mut self,
+        group_id: i32,
+        group_chat_message: group_chat::GroupChatMessage,
+    ) -> Command<Message> {
+        // Results that arrive after the user logged out are dropped
+        if self.current_user.is_none() {
+            return Command::none();
+        }
+
         match group_chat_message {
             group_chat::GroupChatMessage::Back => {
-                self.clear_group_chat();
-                self.handle_switch_page(Page::Home)
+                self.handle_switch_page(Page::Home);
+                self.cache_drafts()
+            }
+            group_chat::GroupChatMessage::InputChanged(_)
+            | group_chat::GroupChatMessage::SendMessage => {
+                let command = self.update_group_chat(group_id, group_chat_message);
+                self.track_draft(ChatId::Group(group_id));
+                command
+            }
+            group_chat::GroupChatMessage::MessagesLoaded(_, _, _) => {
+                let command = self.update_group_chat(group_id, group_chat_message);
+                Command::batch([command, self.cache_group_chat(group_id)])
             }
-            _ => {
-                self.group_chat.update(group_chat_message);
+            group_chat::GroupChatMessage::MessageQueued(message) => {
+                self.track_queued_message(&message);
+                let command = self.update_group_chat(
+                    group_id,
+                    group_chat::GroupChatMessage::MessageQueued(message),
+                );
+                Command::batch([command, self.cache_outbox()])
             }
+            group_chat::GroupChatMessage::RetryMessage(client_id) => {
+                self.track_retried_message(&client_id);
+                let command = self.update_group_chat(
+                    group_id,
+                    group_chat::GroupChatMessage::RetryMessage(client_id),
+                );
+                Command::batch([command, self.cache_outbox()])
+            }
+            group_chat::GroupChatMessage::MessageSent(client_id, result) => {
+                self.track_sent_message(&client_id, &result);
+                let command = self.update_group_chat(
+                    group_id,
+                    group_chat::GroupChatMessage::MessageSent(client_id, result),
+                );
+                Command::batch([
+                    command,
+                    self.cache_group_chat(group_id),
+                    self.cache_outbox(),
+                ])
+            }
+            _ => self.update_group_chat(group_id, group_chat_message),
         }
     }
-}
 
-impl Application for App {
-    type Message = Message;
-    type Executor = executor::Default;
-    type Theme = iced::Theme;
-    type Flags = ();
+    /// Open the quick switcher with the chats shown in the chat tab and load all chats of the current user.
+    /// If it is open already, it is closed
+    fn toggle_quick_switcher(&mut self) -> Command<Message> {
+        if self.quick_switcher.take().is_some() {
+            return Command::none();
+        }
 
-    /// Initialize the application
-    fn new(_flags: Self::Flags) -> (Self, Command<Self::Message>) {
-        let app = Self {
-            current_user: None,
-            current_page: Page::Login,
-            app_theme: setting_tab::AppTheme::Moonfly,
-            login: Login::default(),
-            home: Home::default(),
-            register: Register::default(),
-            user_chat: UserChat::default(),
-            group_chat: GroupChat::default(),
+        let conversations = self.home.get_chat_tab().get_conversations().to_vec();
+        self.quick_switcher = Some(QuickSwitcher::new(conversations));
+
+        let load_command = match (self.offline, self.current_user.as_ref()) {
+            (false, Some(user)) => QuickSwitcher::load(user.id).map(Message::QuickSwitcherMessage),
+            _ => Command::none(),
         };
-        (app, Command::none())
+        Command::batch([load_command, text_input::focus(QuickSwitcher::input_id())])
     }
 
-    // Set the title of the application
-    fn title(&self) -> String {
-        "SecSE24 Group08".to_string()
+    /// Handle the quick switcher message. Opening a chat closes the quick switcher
+    fn handle_quick_switcher_message(
+        &mut self,
+        quick_switcher_message: QuickSwitcherMessage,
+    ) -> Command<Message> {
+        let Some(quick_switcher) = self.quick_switcher.as_mut() else {
+            return Command::none();
+        };
+
+        match quick_switcher_message {
+            QuickSwitcherMessage::OpenSelected => match quick_switcher.selected() {
+                Some(conversation) => {
+                    self.quick_switcher = None;
+                    self.open_conversation(conversation)
+                }
+                None => Command::none(),
+            },
+            QuickSwitcherMessage::Open(conversation) => {
+                self.quick_switcher = None;
+                self.open_conversation(conversation)
+            }
+            QuickSwitcherMessage::Close => {
+                self.quick_switcher = None;
+                Command::none()
+            }
+            _ => quick_switcher
+                .update(quick_switcher_message)
+                .map(Message::QuickSwitcherMessage),
+        }
     }
 
-    /// Update the application based on the message
-    fn update(&mut self, message: Message) -> Command<Self::Message> {
+    /// Handle a key press. Tab moves the focus to the next input on every page, Shift+Tab to the previous one.
+    /// While the quick switcher is open, the arrow keys select a chat. Shortcuts only work on the home page and in the chats
+    fn handle_key_pressed(&mut self, key: Key, modifiers: Modifiers) -> Command<Message> {
+        if key == Key::Named(Named::Tab) && !modifiers.command() && !modifiers.alt() {
+            return if modifiers.shift() {
+                widget::focus_previous()
+            } else {
+                widget::focus_next()
+            };
+        }
+
+        if self.current_user.is_none() || !matches!(self.current_page, Page::Home | Page::Chats) {
+            return Command::none();
+        }
+
+        let shortcut = self.preferences.shortcuts.find(&key, modifiers);
+
+        if self.quick_switcher.is_some() {
+            let message = match (&key, shortcut) {
+                (_, Some(Shortcut::QuickSwitcher | Shortcut::Back)) => QuickSwitcherMessage::Close,
+                (Key::Named(Named::ArrowDown), _) => QuickSwitcherMessage::SelectNext,
+                (Key::Named(Named::ArrowUp), _) => QuickSwitcherMessage::SelectPrevious,
+                _ => return Command::none(),
+            };
+            return self.handle_quick_switcher_message(message);
+        }
+
+        match shortcut {
+            Some(shortcut) => self.handle_shortcut(shortcut),
+            None => Command::none(),
+        }
+    }
+
+    /// Handle a keyboard shortcut. Back closes the search and cancels an edit before it leaves the chat
+    fn handle_shortcut(&mut self, shortcut: Shortcut) -> Command<Message> {
+        match shortcut {
+            Shortcut::QuickSwitcher => self.toggle_quick_switcher(),
+            Shortcut::ChatsTab => self.show_tab(home::TabId::Chats),
+            Shortcut::FriendsTab => self.show_tab(home::TabId::User),
+            Shortcut::GroupsTab => self.show_tab(home::TabId::Group),
+            Shortcut::Back | Shortcut::EditLastMessage | Shortcut::Search => {
+                match (self.current_page == Page::Chats)
+                    .then(|| self.chat_layout.focused())
+                    .flatten()
+                {
+                    Some(id) => self.handle_chat_shortcut(id, shortcut),
+                    None => Command::none(),
+                }
+            }
+        }
+    }
+
+    /// Show a tab of the home page, also if the chats are shown
+    fn show_tab(&mut self, tab_id: home::TabId) -> Command<Message> {
+        let command = if self.current_page == Page::Chats {
+            self.handle_switch_page(Page::Home);
+            self.cache_drafts()
+        } else {
+            Command::none()
+        };
+
+        Command::batch([
+            command,
+            self.handle_home_message(home::HomeMessage::TabSelected(tab_id)),
+        ])
+    }
+
+    /// Handle a shortcut in the focused chat. The last message is only edited if nothing has been typed yet
+    fn handle_chat_shortcut(&mut self, id: ChatId, shortcut: Shortcut) -> Command<Message> {
+        match id {
+            ChatId::User(other_user_id) => {
+                let Some(chat) = self.chat_layout.user_chat(id) else {
+                    return Command::none();
+                };
+                let message = match shortcut {
+                    Shortcut::Back if chat.is_exporting() => {
+                        user_chat::UserChatMessage::ExportClosed
+                    }
+                    Shortcut::Back if chat.is_searching() => {
+                        user_chat::UserChatMessage::SearchClosed
+                    }
+                    Shortcut::Back if chat.is_editing() => {
+                        user_chat::UserChatMessage::EditCancelled
+                    }
+                    Shortcut::Back => user_chat::UserChatMessage::Back,
+                    Shortcut::EditLastMessage
+                        if chat.get_input_value().is_empty() && !chat.is_editing() =>
+                    {
+                        user_chat::UserChatMessage::EditLastMessage
+                    }
+                    Shortcut::Search => user_chat::UserChatMessage::SearchOpened,
+                    _ => return Command::none(),
+                };
+                self.handle_user_chat_message(other_user_id, message)
+            }
+            ChatId::Group(group_id) => {
+                let Some(chat) = self.chat_layout.group_chat(id) else {
+                    return Command::none();
+                };
+                let message = match shortcut {
+                    Shortcut::Back if chat.is_exporting() => {
+                        group_chat::GroupChatMessage::ExportClosed
+                    }
+                    Shortcut::Back if chat.is_searching() => {
+                        group_chat::GroupChatMessage::SearchClosed
+                    }
+                    Shortcut::Back if chat.is_editing() => {
+                        group_chat::GroupChatMessage::EditCancelled
+                    }
+                    Shortcut::Back => group_chat::GroupChatMessage::Back,
+                    Shortcut::EditLastMessage
+                        if chat.get_input_value().is_empty() && !chat.is_editing() =>
+                    {
+                        group_chat::GroupChatMessage::EditLastMessage
+                    }
+                    Shortcut::Search => group_chat::GroupChatMessage::SearchOpened,
+                    _ => return Command::none(),
+                };
+                self.handle_group_chat_message(group_id, message)
+            }
+        }
+    }
+
+    /// Handle a message of the application
+    fn handle_message(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::SwitchPage(page) => {
                 self.handle_switch_page(page);
                 Command::none()
             }
-            Message::LoginMessage(login_message) => {
-                self.handle_login_message(login_message);
+            Message::LoginMessage(login_message) => self.handle_login_message(login_message),
+            Message::HomeMessage(home_message) => self.handle_home_message(home_message),
+            Message::RegisterMessage(register_message) => {
+                self.handle_register_message(register_message)
+            }
+            Message::ResetPasswordMessage(reset_password_message) => {
+                self.handle_reset_password_message(reset_password_message)
+            }
+            Message::ChatLayoutMessage(chat_layout_message) => {
+                // The drafts are stored regularly while chats are shown and when a chat is closed or left,
+                // not on every change of the shown chats
+                let store_drafts = matches!(
+                    chat_layout_message,
+                    ChatLayoutMessage::Tick
+                        | ChatLayoutMessage::Close(_)
+                        | ChatLayoutMessage::ShowHome
+                );
+                let command = self.handle_chat_layout_message(chat_layout_message);
+                if store_drafts {
+                    Command::batch([command, self.cache_drafts()])
+                } else {
+                    command
+                }
+            }
+            Message::UserChatMessage(other_user_id, user_chat_message) => {
+                self.handle_user_chat_message(other_user_id, user_chat_message)
+            }
+            Message::GroupChatMessage(group_id, group_chat_message) => {
+                self.handle_group_chat_message(group_id, group_chat_message)
+            }
+            Message::QuickSwitcherMessage(quick_switcher_message) => {
+                self.handle_quick_switcher_message(quick_switcher_message)
+            }
+            Message::KeyPressed(key, modifiers) => self.handle_key_pressed(key, modifiers),
+            Message::SessionStarted(user, remember_me, result) => {
+                self.handle_session_started(user, remember_me, result)
+            }
+            Message::SessionRestored(token, result) => self.handle_session_restored(token, result),
+            Message::SessionChecked(session_id, result) => {
+                self.handle_session_checked(session_id, result)
+            }
+            Message::SessionEnded(result) => {
+                if let Err(err) = result {
+                    error!("Error revoking session: {}", err);
+                }
                 Command::none()
             }
-            Message::HomeMessage(home_message) => {
-                self.handle_home_message(home_message);
+            Message::CacheUnlocked(user_id, result) => self.handle_cache_unlocked(user_id, result),
+            Message::CacheSaved(result) => {
+                if let Err(err) = result {
+                    error!("Error saving cache: {}", err);
+                }
                 Command::none()
             }
-            Message::RegisterMessage(register_message) => {
-                self.handle_register_message(register_message);
+            Message::ConnectionChecked(available) => self.handle_connection_checked(available),
+            Message::OutboxSent(message, result) => self.handle_outbox_sent(message, result),
+            Message::ActivityChecked(user_id, result) => {
+                self.handle_activity_checked(user_id, result)
+            }
+            Message::NotificationShown(result) => {
+                if let Err(err) = result {
+                    error!("Error showing notification: {}", err);
+                }
                 Command::none()
             }
-            Message::UserChatMessage(user_chat_message) => {
-                self.handle_user_chat_message(user_chat_message);
+            Message::PreferencesLoaded(user_id, result) => {
+                self.handle_preferences_loaded(user_id, result)
+            }
+            Message::PreferencesSynced(result) => {
+                if let Err(err) = result {
+                    error!("Error syncing preferences: {}", err);
+                }
                 Command::none()
             }
-            Message::GroupChatMessage(group_chat_message) => {
-                self.handle_group_chat_message(group_chat_message);
+            Message::SystemThemeDetected(dark_mode) => {
+                if let Some(dark_mode) = dark_mode {
+                    self.system_dark_mode = dark_mode;
+                }
                 Command::none()
             }
+            Message::WindowResized(..) | Message::WindowMoved(..) | Message::CloseRequested => {
+                self.handle_window_event(message)
+            }
         }
     }
 
-    /// View the application based on the current page
+    /// Returns the view of the open chats: the sidebar, the focused chat and the chat beside it
+    fn view_chats(&self) -> Element<'_, Message> {
+        let mut panes = row![self.chat_layout.sidebar().map(Message::ChatLayoutMessage)];
+
+        for id in self.chat_layout.visible() {
+            let pane: Element<Message> = match id {
+                ChatId::User(other_user_id) => match self.chat_layout.user_chat(id) {
+                    Some(chat) => chat
+                        .view()
+                        .map(move |message| Message::UserChatMessage(other_user_id, message)),
+                    None => column![].into(),
+                },
+                ChatId::Group(group_id) => match self.chat_layout.group_chat(id) {
+                    Some(chat) => chat
+                        .view()
+                        .map(move |message| Message::GroupChatMessage(group_id, message)),
+                    None => column![].into(),
+                },
+            };
+            panes = panes.push(container(pane).width(Length::Fill));
+        }
+
+        panes.into()
+    }
+}
+
+impl Application for App {
+    type Message = Message;
+    type Executor = executor::Default;
+    type Theme = iced::Theme;
+    type Flags = AppFlags;
+
+    /// Initialize the application with the preferences of this device. A session stored on this device is restored
+    fn new(flags: Self::Flags) -> (Self, Command<Self::Message>) {
+        let preferences = flags.preferences_store.load();
+        let mut app = Self {
+            current_user: None,
+            session: None,
+            session_token: None,
+            session_store: flags.session_store,
+            current_page: Page::Login,
+            app_theme: preferences.theme,
+            saved_preferences: preferences.clone(),
+            preferences,
+            preferences_store: flags.preferences_store,
+            theme_preview: None,
+            system_dark_mode: true,
+            login: Login::default(),
+            home: Home::default(),
+            register: Register::default(),
+            reset_password: ResetPassword::default(),
+            chat_layout: ChatLayout::default(),
+            local_cache: LocalCache::default(),
+            cache: None,
+            cached_data: None,
+            offline: false,
+            outbox: Outbox::default(),
+            drafts: Drafts::default(),
+            notifier: create_notifier().unwrap_or_else(|err| {
+                error!("Error creating notifier: {}", err);
+                Arc::new(StdoutNotifier)
+            }),
+            activity_watcher: ActivityWatcher::default(),
+            quick_switcher: None,
+        };
+        app.apply_locale();
+        theme::set_status_colors(&app.theme());
+        let command = Command::batch([app.restore_session(), app.detect_system_theme()]);
+        (app, command)
+    }
+
+    // Set the title of the application
+    fn title(&self) -> String {
+        "SecSE24 Group08".to_string()
+    }
+
+    /// Update the application based on the message. The colors of error and success messages follow the shown theme
+    fn update(&mut self, message: Message) -> Command<Self::Message> {
+        let command = self.handle_message(message);
+        theme::set_status_colors(&self.theme());
+        command
+    }
+
+    /// View the application based on the current page. The quick switcher is shown above the page
     fn view(&self) -> Element<Message> {
-        match self.current_page {
+        let page = match self.current_page {
             Page::Login => self.login.view().map(Message::LoginMessage),
+            Page::Home if !self.chat_layout.is_empty() => row![
+                self.chat_layout.sidebar().map(Message::ChatLayoutMessage),
+                self.home.view().map(Message::HomeMessage)
+            ]
+            .into(),
             Page::Home => self.home.view().map(Message::HomeMessage),
             Page::Register => self.register.view().map(Message::RegisterMessage),
-            Page::UserChat => self.user_chat.view().map(Message::UserChatMessage),
-            Page::GroupChat => self.group_chat.view().map(Message::GroupChatMessage),
+            Page::ResetPassword => self
+                .reset_password
+                .view()
+                .map(Message::ResetPasswordMessage),
+            Page::Chats => self.view_chats(),
+        };
+
+        match self.quick_switcher.as_ref() {
+            Some(quick_switcher) => column![
+                quick_switcher.view().map(Message::QuickSwitcherMessage),
+                page
+            ]
+            .into(),
+            None => page,
         }
     }
 
-    /// Set the theme of the application
+    /// Set the theme of the application. A custom theme that is edited is shown while the settings are open
     fn theme(&self) -> iced::Theme {
+        if let Some(theme_preview) = self.theme_preview.as_ref() {
+            if self.current_page == Page::Home {
+                return theme_preview.clone();
+            }
+        }
+
         match self.app_theme {
             setting_tab::AppTheme::Moonfly => iced::Theme::Moonfly,
             setting_tab::AppTheme::Oxocarbon => iced::Theme::Oxocarbon,
             setting_tab::AppTheme::Dracula => iced::Theme::Dracula,
             setting_tab::AppTheme::KanagawaWave => iced::Theme::KanagawaWave,
             setting_tab::AppTheme::Light => iced::Theme::Light,
+            setting_tab::AppTheme::HighContrast => theme::high_contrast_theme(),
+            setting_tab::AppTheme::System if self.system_dark_mode => iced::Theme::Dark,
+            setting_tab::AppTheme::System => iced::Theme::Light,
+            // A custom theme that no longer exists falls back to the default theme
+            setting_tab::AppTheme::Custom => self
+                .preferences
+                .selected_custom_theme()
+                .and_then(|theme| theme.to_theme().ok())
+                .unwrap_or(iced::Theme::Moonfly),
         }
     }
 
-    /// Set the subscription of the application. The subscription is used to update the application every few seconds based on the current page
+    /// Scale the application with the font scale of the preferences
+    fn scale_factor(&self) -> f64 {
+        self.preferences.font_scale
+    }
+
+    /// Set the subscription of the application. The subscription is used to update the application in the configured polling interval of the current page
     fn subscription(&self) -> iced::Subscription<Message> {
-        match self.current_page {
-            Page::Home => time::every(Duration::from_secs(10))
+        let polling = config().polling;
+        let page_subscription = match self.current_page {
+            Page::Home => time::every(Duration::from_secs(polling.home_secs))
                 .map(|_| Message::HomeMessage(home::HomeMessage::Tick)),
-            Page::UserChat => time::every(Duration::from_secs(5))
-                .map(|_| Message::UserChatMessage(user_chat::UserChatMessage::Tick)),
-            Page::GroupChat => time::every(Duration::from_secs(5))
-                .map(|_| Message::GroupChatMessage(group_chat::GroupChatMessage::Tick)),
+            Page::Chats => time::every(Duration::from_secs(polling.chat_secs))
+                .map(|_| Message::ChatLayoutMessage(ChatLayoutMessage::Tick)),
             _ => Subscription::none(),
+        };
+
+        Subscription::batch([
+            page_subscription,
+            event::listen_with(window_event),
+            event::listen_with(keyboard_event),
+        ])
+    }
+}
+
+/// Replace a value of the cached data. Returns true if it has changed, false otherwise
+fn replace_if_changed<T: PartialEq>(cached: &mut T, value: T) -> bool {
+    if *cached == value {
+        return false;
+    }
+    *cached = value;
+    true
+}
+
+/// Insert a value into a map of the cached data. Returns true if it has changed, false otherwise
+fn insert_if_changed<V: PartialEq>(cached: &mut BTreeMap<i32, V>, key: i32, value: V) -> bool {
+    if cached.get(&key) == Some(&value) {
+        return false;
+    }
+    cached.insert(key, value);
+    true
+}
+
+/// Load the data of an unlocked cache. A cache that was encrypted with an old password is replaced.
+/// Returns the cache and its data, none if nothing is cached
+fn load_unlocked_cache(cache: UnlockedCache) -> (UnlockedCache, Option<CachedData>) {
+    let data = cache.load().unwrap_or_else(|err| {
+        error!("Error loading cache: {}", err);
+        None
+    });
+    (cache, data)
+}
+
+/// Returns the message of a window event whose result is stored with the preferences
+fn window_event(event: Event, _status: event::Status) -> Option<Message> {
+    match event {
+        Event::Window(_, window::Event::Resized { width, height }) => {
+            Some(Message::WindowResized(width, height))
+        }
+        Event::Window(_, window::Event::Moved { x, y }) => Some(Message::WindowMoved(x, y)),
+        Event::Window(_, window::Event::CloseRequested) => Some(Message::CloseRequested),
+        _ => None,
+    }
+}
+
+/// Returns the message of a key press. Keys captured by an input are handled too, so the shortcuts work while typing
+fn keyboard_event(event: Event, _status: event::Status) -> Option<Message> {
+    match event {
+        Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+            Some(Message::KeyPressed(key, modifiers))
         }
+        _ => None,
     }
 }
 
@@ -318,14 +1979,75 @@ impl App {
         self.home.clone()
     }
 
+    /// Get the reset password page
+    pub fn get_reset_password(&self) -> ResetPassword {
+        self.reset_password.clone()
+    }
+
+    /// Get the login page
+    pub fn get_login(&self) -> Login {
+        self.login.clone()
+    }
+
     /// Get the app theme
     pub fn get_app_theme(&self) -> setting_tab::AppTheme {
         self.app_theme
     }
+
+    /// Get if the app is in offline mode
+    pub fn get_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Get the cached data of the current user
+    pub fn get_cached_data(&self) -> Option<&CachedData> {
+        self.cached_data.as_deref()
+    }
+
+    /// Get the messages of the current user that have not been sent yet
+    pub fn get_outbox(&self) -> &Outbox {
+        &self.outbox
+    }
+
+    /// Get the preferences of this device
+    pub fn get_preferences(&self) -> &Preferences {
+        &self.preferences
+    }
+
+    /// Get the drafts of the current user
+    pub fn get_drafts(&self) -> &Drafts {
+        &self.drafts
+    }
+
+    /// Get if the local cache of the current user is unlocked
+    pub fn is_cache_unlocked(&self) -> bool {
+        self.cache.is_some()
+    }
+
+    /// Get the open chats
+    pub fn get_chat_layout(&self) -> &ChatLayout {
+        &self.chat_layout
+    }
+
+    /// Get the quick switcher if it is open
+    pub fn get_quick_switcher(&self) -> Option<&QuickSwitcher> {
+        self.quick_switcher.as_ref()
+    }
 }
 
 /// Setter methods for testing
 impl App {
+    /// Set the session of the current user
+    pub fn set_session(&mut self, session: Session, token: String) {
+        self.session = Some(session);
+        self.session_token = Some(token);
+    }
+
+    /// Set the notifier that shows the notifications
+    pub fn set_notifier(&mut self, notifier: Arc<dyn Notifier>) {
+        self.notifier = notifier;
+    }
+
     /// Set the current user
     pub fn set_current_user(&mut self, user: User) {
         self.current_user = Some(user.clone());
@@ -0,0 +1,289 @@
+use crate::backend::{
+    crypto::key_store::KeyStore,
+    entities::export_ops::{
+        default_export_path, export_direct_chat, export_group_chat, save_chat_export, ExportFormat,
+        ExportRange,
+    },
+};
+use crate::i18n::{t, t_args};
+
+use chrono::NaiveDate;
+use iced::{
+    alignment::Horizontal,
+    widget::{button, column, pick_list, row, text, text_input},
+    Command, Element, Length,
+};
+use log::error;
+use std::path::PathBuf;
+
+use super::task::run_blocking;
+use super::theme::{error_color, input_style, success_color};
+
+/// Format of the first and last day of the export in the inputs
+const DATE_INPUT_FORMAT: &str = "%Y-%m-%d";
+
+/// The chat that is exported and the user that exports it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatExportTarget {
+    Direct { user_id: i32, other_user_id: i32 },
+    Group { user_id: i32, group_id: i32 },
+}
+
+/// Represents the state of the form to export a chat, shown in the user chat and the group chat
+#[derive(Debug, Clone)]
+pub struct ChatExportForm {
+    target: ChatExportTarget,
+    format: ExportFormat,
+    from_value: String,
+    to_value: String,
+    path_value: String,
+    info: String,
+    error: String,
+    // Set while the chat is written to the file
+    exporting: bool,
+}
+
+/// Represents the messages that can be sent to the export form
+#[derive(Debug, Clone)]
+pub enum ChatExportMessage {
+    FormatSelected(ExportFormat),
+    FromChanged(String),
+    ToChanged(String),
+    PathChanged(String),
+    Export,
+    Exported(Result<String, String>),
+}
+
+/// Implementation of the export form
+impl ChatExportForm {
+    /// Creates the form for a chat. The file is suggested in the download directory, named after the chat
+    pub fn new(target: ChatExportTarget, chat_name: &str) -> Self {
+        let format = ExportFormat::Json;
+
+        ChatExportForm {
+            target,
+            format,
+            from_value: String::new(),
+            to_value: String::new(),
+            path_value: default_export_path(chat_name, format).display().to_string(),
+            info: String::new(),
+            error: String::new(),
+            exporting: false,
+        }
+    }
+
+    /// Returns the time span of the export from the inputs, empty inputs leave the span open.
+    /// Returns the range or an error message if a date is invalid
+    pub fn range(&self) -> Result<ExportRange, String> {
+        ExportRange::from_dates(parse_date(&self.from_value)?, parse_date(&self.to_value)?)
+    }
+
+    /// Changes the format and the extension of the file to the one of the format
+    fn select_format(&mut self, format: ExportFormat) {
+        let path = PathBuf::from(self.path_value.trim());
+        let has_known_extension = path.extension().is_some_and(|extension| {
+            ExportFormat::ALL
+                .iter()
+                .any(|format| extension == format.extension())
+        });
+
+        if has_known_extension || path.extension().is_none() {
+            self.path_value = path
+                .with_extension(format.extension())
+                .display()
+                .to_string();
+        }
+        self.format = format;
+    }
+
+    /// Exports the chat in the background. If the dates or the path are invalid, it returns
+    fn export(&mut self) -> Command<ChatExportMessage> {
+        self.info = String::new();
+        self.error = String::new();
+
+        let range = match self.range() {
+            Ok(range) => range,
+            Err(e) => {
+                self.error = e;
+                return Command::none();
+            }
+        };
+
+        let path = self.path_value.trim().to_string();
+        if path.is_empty() {
+            self.error = t("export-path-empty");
+            return Command::none();
+        }
+
+        self.exporting = true;
+        let target = self.target;
+        let format = self.format;
+
+        Command::perform(
+            run_blocking(move || {
+                let key_store = KeyStore::default();
+                let export = match target {
+                    ChatExportTarget::Direct {
+                        user_id,
+                        other_user_id,
+                    } => export_direct_chat(&key_store, user_id, other_user_id, range)?,
+                    ChatExportTarget::Group { user_id, group_id } => {
+                        export_group_chat(&key_store, user_id, group_id, range)?
+                    }
+                };
+                save_chat_export(&export, format, path.as_ref())?;
+                Ok(path)
+            }),
+            ChatExportMessage::Exported,
+        )
+    }
+
+    /// Handles the result of the export
+    fn handle_exported(&mut self, result: Result<String, String>) {
+        self.exporting = false;
+
+        match result {
+            Ok(path) => {
+                self.info = t_args("export-saved", &[("path", path)]);
+            }
+            Err(e) => {
+                error!("Error exporting chat: {:?}", e);
+                self.error = t_args("export-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Updates the export form based on the message
+    pub fn update(&mut self, message: ChatExportMessage) -> Command<ChatExportMessage> {
+        match message {
+            ChatExportMessage::FormatSelected(format) => {
+                self.select_format(format);
+            }
+            ChatExportMessage::FromChanged(value) => {
+                self.from_value = value;
+            }
+            ChatExportMessage::ToChanged(value) => {
+                self.to_value = value;
+            }
+            ChatExportMessage::PathChanged(value) => {
+                self.path_value = value;
+            }
+            ChatExportMessage::Export => {
+                if !self.exporting {
+                    return self.export();
+                }
+            }
+            ChatExportMessage::Exported(result) => {
+                self.handle_exported(result);
+            }
+        }
+
+        Command::none()
+    }
+
+    /// Returns the view of the export form
+    pub fn view(&self) -> Element<'_, ChatExportMessage> {
+        let button_width = 100;
+        let padding = 10;
+        let spacing = 20;
+
+        let format_pick_list = pick_list(
+            &ExportFormat::ALL[..],
+            Some(self.format),
+            ChatExportMessage::FormatSelected,
+        )
+        .width(150)
+        .padding(padding);
+
+        let from_input = text_input(&t("export-from"), &self.from_value)
+            .style(input_style())
+            .width(150)
+            .padding(padding)
+            .on_input(ChatExportMessage::FromChanged);
+
+        let to_input = text_input(&t("export-to"), &self.to_value)
+            .style(input_style())
+            .width(150)
+            .padding(padding)
+            .on_input(ChatExportMessage::ToChanged);
+
+        let path_input = text_input(&t("export-path"), &self.path_value)
+            .style(input_style())
+            .width(Length::Fill)
+            .padding(padding)
+            .on_input(ChatExportMessage::PathChanged)
+            .on_submit(ChatExportMessage::Export);
+
+        let export_button = button(text(t("export-save")).horizontal_alignment(Horizontal::Center))
+            .width(button_width)
+            .padding(padding)
+            .on_press_maybe((!self.exporting).then_some(ChatExportMessage::Export));
+
+        let options_row = row!(format_pick_list, from_input, to_input)
+            .spacing(spacing)
+            .padding(padding);
+
+        let path_row = row!(path_input, export_button)
+            .spacing(spacing)
+            .padding(padding);
+
+        let status: Element<ChatExportMessage> = if self.exporting {
+            text(t("export-running")).size(15).into()
+        } else if !self.error.is_empty() {
+            text(&self.error).size(15).style(error_color()).into()
+        } else if !self.info.is_empty() {
+            text(&self.info).size(15).style(success_color()).into()
+        } else {
+            column![].into()
+        };
+
+        column![options_row, path_row, status]
+            .padding(padding)
+            .into()
+    }
+}
+
+/// Parses a day entered in an input as YYYY-MM-DD. Returns none for an empty input or an error message
+fn parse_date(value: &str) -> Result<Option<NaiveDate>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    NaiveDate::parse_from_str(value, DATE_INPUT_FORMAT)
+        .map(Some)
+        .map_err(|_| t_args("export-invalid-date", &[("date", value.to_string())]))
+}
+
+/// Getter methods for testing
+impl ChatExportForm {
+    /// Gets the target of the export
+    pub fn get_target(&self) -> ChatExportTarget {
+        self.target
+    }
+
+    /// Gets the selected format
+    pub fn get_format(&self) -> ExportFormat {
+        self.format
+    }
+
+    /// Gets the path value
+    pub fn get_path_value(&self) -> &String {
+        &self.path_value
+    }
+
+    /// Gets the info message
+    pub fn get_info(&self) -> &String {
+        &self.info
+    }
+
+    /// Gets the error message
+    pub fn get_error(&self) -> &String {
+        &self.error
+    }
+
+    /// Gets if the chat is exported at the moment
+    pub fn is_exporting(&self) -> bool {
+        self.exporting
+    }
+}
@@ -0,0 +1,328 @@
+use iced::{
+    alignment::Horizontal,
+    theme,
+    widget::{button, column, container, row, text, tooltip, tooltip::Position, Column},
+    Alignment, Element, Length,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::database::models::{Group, User};
+use crate::i18n::t;
+
+use super::group_chat::GroupChat;
+use super::outbox::OutboxRecipient;
+use super::user_chat::UserChat;
+
+/// Identifies an open chat: the chat with another user or the chat of a group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChatId {
+    User(i32),
+    Group(i32),
+}
+
+/// A chat that is open in the layout. It keeps its state, e.g. the draft and the scroll position, while another chat is shown
+#[derive(Debug, Clone)]
+pub enum OpenChat {
+    User(UserChat),
+    Group(GroupChat),
+}
+
+/// Represents the open chats: a sidebar with all open chats, the focused chat and optionally a second chat beside it
+#[derive(Debug, Clone, Default)]
+pub struct ChatLayout {
+    chats: Vec<OpenChat>,
+    focused: Option<ChatId>,
+    split: Option<ChatId>,
+}
+
+/// Represents the messages that can be sent to the chat layout
+#[derive(Debug, Clone)]
+pub enum ChatLayoutMessage {
+    Focus(ChatId),
+    OpenBeside(ChatId),
+    CloseSplit,
+    Close(ChatId),
+    ShowHome,
+    Tick,
+}
+
+/// Implementation of the open chat
+impl OpenChat {
+    /// Returns the ID of the chat
+    pub fn id(&self) -> ChatId {
+        match self {
+            OpenChat::User(chat) => ChatId::User(chat.get_other_user().id),
+            OpenChat::Group(chat) => ChatId::Group(chat.get_current_group().id),
+        }
+    }
+
+    /// Returns the text that has been written in the chat but not sent yet
+    pub fn input_value(&self) -> &str {
+        match self {
+            OpenChat::User(chat) => chat.get_input_value(),
+            OpenChat::Group(chat) => chat.get_input_value(),
+        }
+    }
+
+    /// Sets the text of the input, e.g. to restore the draft of the chat
+    pub fn set_input_value(&mut self, input_value: String) {
+        match self {
+            OpenChat::User(chat) => chat.set_input_value(input_value),
+            OpenChat::Group(chat) => chat.set_input_value(input_value),
+        }
+    }
+
+    /// Returns if the chat has a draft
+    pub fn has_draft(&self) -> bool {
+        !self.input_value().trim().is_empty()
+    }
+
+    /// Returns the name that is shown in the sidebar
+    pub fn name(&self) -> String {
+        match self {
+            OpenChat::User(chat) => format!(
+                "{} {}",
+                chat.get_other_user().first_name,
+                chat.get_other_user().last_name
+            ),
+            OpenChat::Group(chat) => chat.get_current_group().name.clone(),
+        }
+    }
+}
+
+/// Implementation of the chat id
+impl ChatId {
+    /// Returns the recipient of the messages that are sent in the chat
+    pub fn recipient(&self) -> OutboxRecipient {
+        match *self {
+            ChatId::User(user_id) => OutboxRecipient::User(user_id),
+            ChatId::Group(group_id) => OutboxRecipient::Group(group_id),
+        }
+    }
+}
+
+/// Implementation of the chat layout
+impl ChatLayout {
+    /// Opens the chat with a user and focuses it. A chat that is already open keeps its state.
+    /// Returns the chat and if it has just been opened
+    pub fn open_user_chat(&mut self, current_user: User, user: User) -> (&mut UserChat, bool) {
+        let id = ChatId::User(user.id);
+        let opened = !self.contains(id);
+        if opened {
+            let mut chat = UserChat::default();
+            chat.set_properties(current_user, user);
+            self.chats.push(OpenChat::User(chat));
+        }
+        self.focus(id);
+
+        (self.user_chat_mut(id).unwrap(), opened)
+    }
+
+    /// Opens the chat of a group and focuses it. A chat that is already open keeps its state.
+    /// Returns the chat and if it has just been opened
+    pub fn open_group_chat(&mut self, current_user: User, group: Group) -> (&mut GroupChat, bool) {
+        let id = ChatId::Group(group.id);
+        let opened = !self.contains(id);
+        if opened {
+            let mut chat = GroupChat::default();
+            chat.set_properties(current_user, group);
+            self.chats.push(OpenChat::Group(chat));
+        }
+        self.focus(id);
+
+        (self.group_chat_mut(id).unwrap(), opened)
+    }
+
+    /// Returns if a chat is open
+    pub fn contains(&self, id: ChatId) -> bool {
+        self.chats.iter().any(|chat| chat.id() == id)
+    }
+
+    /// Returns if no chat is open
+    pub fn is_empty(&self) -> bool {
+        self.chats.is_empty()
+    }
+
+    /// Returns the open chats in the order they were opened
+    pub fn chats(&self) -> &[OpenChat] {
+        &self.chats
+    }
+
+    /// Returns the open chats to change them, e.g. to set the offline mode
+    pub fn chats_mut(&mut self) -> impl Iterator<Item = &mut OpenChat> {
+        self.chats.iter_mut()
+    }
+
+    /// Returns the focused chat
+    pub fn focused(&self) -> Option<ChatId> {
+        self.focused
+    }
+
+    /// Returns the chat that is shown beside the focused chat
+    pub fn split(&self) -> Option<ChatId> {
+        self.split
+    }
+
+    /// Returns the chats that are shown, the focused one first
+    pub fn visible(&self) -> Vec<ChatId> {
+        self.focused.into_iter().chain(self.split).collect()
+    }
+
+    /// Focuses an open chat. A chat that is shown beside the focused chat swaps places with it
+    pub fn focus(&mut self, id: ChatId) {
+        if !self.contains(id) || self.focused == Some(id) {
+            return;
+        }
+
+        if self.split == Some(id) {
+            self.split = self.focused;
+        }
+        self.focused = Some(id);
+    }
+
+    /// Shows an open chat beside the focused chat. Without a focused chat the chat is focused
+    pub fn open_beside(&mut self, id: ChatId) {
+        if !self.contains(id) || self.focused == Some(id) {
+            return;
+        }
+
+        if self.focused.is_none() {
+            self.focused = Some(id);
+        } else {
+            self.split = Some(id);
+        }
+    }
+
+    /// Stops showing a second chat. The chat stays open in the sidebar
+    pub fn close_split(&mut self) {
+        self.split = None;
+    }
+
+    /// Closes a chat and drops its state. If it was focused, the chat beside it or the last opened chat is focused
+    pub fn close(&mut self, id: ChatId) {
+        self.chats.retain(|chat| chat.id() != id);
+
+        if self.split == Some(id) {
+            self.split = None;
+        }
+        if self.focused == Some(id) {
+            self.focused = self
+                .split
+                .take()
+                .or_else(|| self.chats.last().map(OpenChat::id));
+        }
+    }
+
+    /// Returns an open chat with a user
+    pub fn user_chat(&self, id: ChatId) -> Option<&UserChat> {
+        self.chats.iter().find_map(|chat| match chat {
+            OpenChat::User(user_chat) if chat.id() == id => Some(user_chat),
+            _ => None,
+        })
+    }
+
+    /// Returns an open chat with a user to change it
+    pub fn user_chat_mut(&mut self, id: ChatId) -> Option<&mut UserChat> {
+        self.chats.iter_mut().find_map(|chat| {
+            let chat_id = chat.id();
+            match chat {
+                OpenChat::User(user_chat) if chat_id == id => Some(user_chat),
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns an open group chat
+    pub fn group_chat(&self, id: ChatId) -> Option<&GroupChat> {
+        self.chats.iter().find_map(|chat| match chat {
+            OpenChat::Group(group_chat) if chat.id() == id => Some(group_chat),
+            _ => None,
+        })
+    }
+
+    /// Returns an open group chat to change it
+    pub fn group_chat_mut(&mut self, id: ChatId) -> Option<&mut GroupChat> {
+        self.chats.iter_mut().find_map(|chat| {
+            let chat_id = chat.id();
+            match chat {
+                OpenChat::Group(group_chat) if chat_id == id => Some(group_chat),
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns the view of the sidebar with the open chats
+    pub fn sidebar(&self) -> Element<'_, ChatLayoutMessage> {
+        let sidebar_width = 220;
+        let padding = 10;
+        let spacing = 10;
+
+        let home_button = button(text(t("chats-home")).horizontal_alignment(Horizontal::Center))
+            .width(Length::Fill)
+            .padding(padding)
+            .on_press(ChatLayoutMessage::ShowHome);
+
+        let mut chats_column: Column<ChatLayoutMessage> =
+            column![home_button, text(t("chats-open"))].spacing(spacing);
+
+        for chat in &self.chats {
+            let id = chat.id();
+            let marker = if self.focused == Some(id) || self.split == Some(id) {
+                "▶ "
+            } else {
+                ""
+            };
+
+            let draft_label = if chat.has_draft() { " ✎" } else { "" };
+            let chat_button = button(text(format!("{}{}{}", marker, chat.name(), draft_label)))
+                .width(Length::Fill)
+                .on_press(ChatLayoutMessage::Focus(id));
+
+            // The buttons only show a symbol, so their names are shown when the mouse is over them
+            let split_button = if self.split == Some(id) {
+                labeled(
+                    button(text("◫").horizontal_alignment(Horizontal::Center))
+                        .on_press(ChatLayoutMessage::CloseSplit),
+                    t("chats-close-split"),
+                )
+            } else {
+                labeled(
+                    button(text("◫").horizontal_alignment(Horizontal::Center)).on_press_maybe(
+                        (self.focused != Some(id)).then_some(ChatLayoutMessage::OpenBeside(id)),
+                    ),
+                    t("chats-open-beside"),
+                )
+            };
+
+            let close_button = labeled(
+                button(text("✕").horizontal_alignment(Horizontal::Center))
+                    .on_press(ChatLayoutMessage::Close(id)),
+                t("chats-close"),
+            );
+
+            chats_column = chats_column.push(
+                row![chat_button, split_button, close_button]
+                    .spacing(5)
+                    .align_items(Alignment::Center),
+            );
+        }
+
+        chats_column.width(sidebar_width).padding(padding).into()
+    }
+}
+
+/// Shows the name of a button that only has a symbol when the mouse is over it
+fn labeled<'a>(
+    content: impl Into<Element<'a, ChatLayoutMessage>>,
+    label: String,
+) -> Element<'a, ChatLayoutMessage> {
+    tooltip(
+        content,
+        container(text(label))
+            .padding(5)
+            .style(theme::Container::Box),
+        Position::Bottom,
+    )
+    .into()
+}
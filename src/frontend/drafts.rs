@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use super::chat_layout::ChatId;
+
+/// A message the user started to write in a chat but has not sent yet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Draft {
+    pub chat: ChatId,
+    pub text: String,
+}
+
+/// The drafts of the current user, at most one per chat
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Drafts {
+    drafts: Vec<Draft>,
+}
+
+/// Implementation of the drafts
+impl Drafts {
+    /// Returns the draft of a chat, none if there is none
+    pub fn get(&self, chat: ChatId) -> Option<&str> {
+        self.drafts
+            .iter()
+            .find(|draft| draft.chat == chat)
+            .map(|draft| draft.text.as_str())
+    }
+
+    /// Returns if a chat has a draft
+    pub fn contains(&self, chat: ChatId) -> bool {
+        self.get(chat).is_some()
+    }
+
+    /// Returns the drafts in the order they were started
+    pub fn drafts(&self) -> &[Draft] {
+        &self.drafts
+    }
+
+    /// Set the draft of a chat. An empty or blank text removes the draft
+    pub fn set(&mut self, chat: ChatId, text: &str) {
+        if text.trim().is_empty() {
+            self.drafts.retain(|draft| draft.chat != chat);
+            return;
+        }
+
+        match self.drafts.iter_mut().find(|draft| draft.chat == chat) {
+            Some(draft) => draft.text = text.to_string(),
+            None => self.drafts.push(Draft {
+                chat,
+                text: text.to_string(),
+            }),
+        }
+    }
+
+    /// Add the drafts that are not known yet, e.g. the drafts loaded from the cache
+    pub fn merge(&mut self, other: Drafts) {
+        for draft in other.drafts {
+            if !self.contains(draft.chat) {
+                self.drafts.push(draft);
+            }
+        }
+    }
+}
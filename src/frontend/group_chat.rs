@@ -1,22 +1,35 @@
 use crate::backend::{
+    crypto::{
+        group_message::{edit_group_message, load_group_messages, share_group_history},
+        key_store::KeyStore,
+    },
     database::{
         db::format_send_date,
         models::{Group, GroupMessage, User},
     },
-    entities::{
-        group_message_ops::{create_group_message, find_all_messages_of_group},
-        user_ops::find_users_by_ids,
-    },
+    entities::{conversation_ops::mark_conversation_read, user_ops::find_users_by_ids},
 };
+use crate::i18n::{t, t_args};
 
+use chrono::{DateTime, Local, Utc};
 use iced::{
     alignment::Horizontal,
-    widget::{button, column, horizontal_space, row, text, text_input, Scrollable},
-    Element, Length,
+    widget::{
+        button, column, horizontal_space, row,
+        scrollable::{self, RelativeOffset},
+        text, text_input, Scrollable,
+    },
+    Command, Element, Length,
 };
 
 use log::error;
 
+use super::chat_export::{ChatExportForm, ChatExportMessage, ChatExportTarget};
+use super::local_cache::{merge_messages, sync_start};
+use super::outbox::{deliver, Outbox, OutboxMessage, OutboxRecipient, OutboxStatus, SentMessage};
+use super::task::{run_blocking, LoadTracker};
+use super::theme::{error_color, input_style, success_color};
+
 /// Represents the state of the group chat
 #[derive(Debug, Clone)]
 pub struct GroupChat {
@@ -25,15 +38,51 @@ pub struct GroupChat {
     messages: Vec<GroupMessage>,
     users_of_group: Vec<User>,
     input_value: String,
+    error: String,
+    info: String,
+    // Set until the messages have been loaded for the first time
+    loading: bool,
+    // Load of the messages that is started on every tick
+    load: LoadTracker,
+    // Messages of this group that have not been sent yet
+    outbox: Outbox,
+    // Set while the history is being shared
+    sharing: bool,
+    // Set while the database cannot be reached and the cached messages are shown
+    offline: bool,
+    // Relative vertical scroll position of the messages, restored when the chat is shown again
+    scroll_offset: f32,
+    // Send date of the own message that is edited in the input
+    editing: Option<DateTime<Utc>>,
+    // Set while the search is open, only the messages containing it are shown
+    search_value: Option<String>,
+    // Set while the form to export the chat is open
+    export_form: Option<ChatExportForm>,
 }
 
 /// Represents the messages that can be sent to the group chat
 #[derive(Debug, Clone)]
 pub enum GroupChatMessage {
     SendMessage,
+    MessageQueued(OutboxMessage),
+    MessageSent(String, Result<SentMessage, String>),
+    RetryMessage(String),
     InputChanged(String),
     Back,
+    Scrolled(f32),
+    EditLastMessage,
+    EditCancelled,
+    MessageEdited(Result<GroupMessage, String>),
+    SearchOpened,
+    SearchChanged(String),
+    SearchClosed,
+    ExportOpened,
+    ExportClosed,
+    Export(ChatExportMessage),
     Tick,
+    MessagesLoaded(i32, u64, Result<(Vec<GroupMessage>, Vec<User>), String>),
+    ShareHistory,
+    HistoryShared(Result<usize, String>),
 }
 
 /// Default implementation for the group chat
@@ -52,6 +101,17 @@ impl Default for GroupChat {
             users_of_group: vec![],
             messages: vec![],
             input_value: String::new(),
+            error: String::new(),
+            info: String::new(),
+            loading: true,
+            load: LoadTracker::default(),
+            outbox: Outbox::default(),
+            sharing: false,
+            offline: false,
+            scroll_offset: 1.0,
+            editing: None,
+            search_value: None,
+            export_form: None,
         }
     }
 }
@@ -60,62 +120,241 @@ impl Default for GroupChat {
 impl GroupChat {
     /// Sets the properties of the group chat
     pub fn set_properties(&mut self, current_user: User, current_group: Group) {
+        if current_group.id != self.current_group.id {
+            self.load.invalidate();
+        }
         self.current_user = current_user;
         self.current_group = current_group;
     }
 
-    /// Clears the data of the group chat
-    fn clear_data(&mut self) {
-        self.users_of_group = vec![];
-        self.messages = vec![];
+    /// Shows the cached messages of the group and their senders until they are synced
+    pub fn show_cached(&mut self, messages: Vec<GroupMessage>, users_of_group: Vec<User>) {
+        self.messages = messages;
+        self.users_of_group = users_of_group;
+        self.loading = false;
+    }
+
+    /// Returns the ID of the scrollable with the messages. Every chat has its own, so the chats that are shown side by side
+    /// keep their scroll positions
+    pub fn scroll_id(&self) -> scrollable::Id {
+        scrollable::Id::new(format!("group_chat_{}", self.current_group.id))
+    }
+
+    /// Scrolls the messages to the position they had when the chat was shown the last time
+    pub fn restore_scroll(&self) -> Command<GroupChatMessage> {
+        scrollable::snap_to(
+            self.scroll_id(),
+            RelativeOffset {
+                x: 0.0,
+                y: self.scroll_offset,
+            },
+        )
+    }
+
+    /// Returns the ID of the message input, so it can be focused by a shortcut
+    pub fn input_id(&self) -> text_input::Id {
+        text_input::Id::new(format!("group_chat_input_{}", self.current_group.id))
+    }
+
+    /// Returns the ID of the search input
+    pub fn search_id(&self) -> text_input::Id {
+        text_input::Id::new(format!("group_chat_search_{}", self.current_group.id))
+    }
+
+    /// Returns if a message is edited
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// Returns if the search is open
+    pub fn is_searching(&self) -> bool {
+        self.search_value.is_some()
     }
 
-    /// Sends a message to the group. If the message is empty, it does nothing
-    fn send_group_message(&mut self) {
+    /// Returns if the form to export the chat is open
+    pub fn is_exporting(&self) -> bool {
+        self.export_form.is_some()
+    }
+
+    /// Sets the text of the input, e.g. to restore the draft of the chat
+    pub fn set_input_value(&mut self, input_value: String) {
+        self.input_value = input_value;
+    }
+
+    /// Sets the offline mode, in which the cached messages can only be read and new messages are queued
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// Sets the messages of this group that have not been sent yet
+    pub fn set_outbox(&mut self, outbox: Outbox) {
+        self.outbox = outbox;
+    }
+
+    /// Queues a message to the group in the outbox, so it is not lost if it cannot be sent. If the message is empty, it does nothing.
+    /// While a message is edited, the edited message is sent instead
+    fn send_group_message(&mut self) -> Command<GroupChatMessage> {
         if self.input_value.is_empty() {
-            return;
+            return Command::none();
         }
 
-        let result = create_group_message(
+        if let Some(send_date) = self.editing.take() {
+            return self.send_edited_message(send_date);
+        }
+
+        let message = OutboxMessage::new(
             self.current_user.id,
-            self.current_group.id,
-            self.input_value.as_str(),
+            OutboxRecipient::Group(self.current_group.id),
+            &std::mem::take(&mut self.input_value),
         );
+        self.outbox.push(message.clone());
+
+        Command::perform(async move { message }, GroupChatMessage::MessageQueued)
+    }
+
+    /// Encrypts and sends a queued message in the background. While offline the message stays queued
+    fn send_from_outbox(&mut self, client_id: &str) -> Command<GroupChatMessage> {
+        if self.offline {
+            return Command::none();
+        }
+
+        let Some(message) = self.outbox.start_sending(client_id) else {
+            return Command::none();
+        };
+
+        Command::perform(run_blocking(move || deliver(&message)), {
+            let client_id = client_id.to_string();
+            move |result| GroupChatMessage::MessageSent(client_id.clone(), result)
+        })
+    }
 
+    /// Handles the result of sending a message. The sent message is moved from the outbox to the messages,
+    /// a message that could not be sent stays in the outbox and is retried later
+    fn handle_message_sent(&mut self, client_id: &str, result: Result<SentMessage, String>) {
         match result {
-            Ok(message) => {
-                self.input_value = String::new();
-                self.messages.push(message);
+            Ok(sent_message) => {
+                self.error = String::new();
+                self.outbox.remove(client_id);
+                if let SentMessage::Group(message) = sent_message {
+                    merge_messages(&mut self.messages, vec![message]);
+                }
             }
             Err(e) => {
                 error!("Error sending message: {}", e);
+                self.error = t_args("chat-send-failed", &[("error", e)]);
+                self.outbox
+                    .record_failure(client_id, Local::now().naive_local());
             }
         }
     }
 
-    /// Handles the tick event. Fetches all messages of the group and the users of the group
-    fn handle_tick(&mut self) {
-        self.clear_data();
+    /// Puts the last own message into the input to edit it. Edits are not queued, so it is not possible while offline
+    fn edit_last_message(&mut self) -> Command<GroupChatMessage> {
+        if self.offline {
+            return Command::none();
+        }
 
-        let result = find_all_messages_of_group(self.current_group.id);
+        let Some(message) = self
+            .messages
+            .iter()
+            .rev()
+            .find(|message| message.sender_id == self.current_user.id)
+        else {
+            return Command::none();
+        };
+
+        self.editing = Some(message.send_date);
+        self.input_value = message.message.clone();
+        text_input::focus(self.input_id())
+    }
 
+    /// Encrypts the edited message for the current members and replaces the stored message in the background
+    fn send_edited_message(&mut self, send_date: DateTime<Utc>) -> Command<GroupChatMessage> {
+        let user_id = self.current_user.id;
+        let group_id = self.current_group.id;
+        let message = std::mem::take(&mut self.input_value);
+
+        Command::perform(
+            run_blocking(move || {
+                edit_group_message(&KeyStore::default(), user_id, group_id, send_date, &message)
+            }),
+            GroupChatMessage::MessageEdited,
+        )
+    }
+
+    /// Handles the result of editing a message. The edited message replaces the old one
+    fn handle_message_edited(&mut self, result: Result<GroupMessage, String>) {
         match result {
-            Ok(messages) => {
-                self.messages = messages;
-                let user_ids: Vec<i32> = self
-                    .messages
-                    .iter()
-                    .map(|message| message.sender_id)
-                    .collect();
+            Ok(message) => {
+                self.error = String::new();
+                // A load that started before the edit would bring back the old text
+                self.load.invalidate();
+                merge_messages(&mut self.messages, vec![message]);
+            }
+            Err(e) => {
+                error!("Error editing message: {}", e);
+                self.error = t_args("chat-edit-failed", &[("error", e)]);
+            }
+        }
+    }
 
-                let result = find_users_by_ids(user_ids);
+    /// Returns if a message is shown with the current search
+    fn matches_search(&self, message: &str) -> bool {
+        match &self.search_value {
+            Some(search_value) => message
+                .to_lowercase()
+                .contains(&search_value.trim().to_lowercase()),
+            None => true,
+        }
+    }
 
-                match result {
-                    Ok(users) => {
-                        self.users_of_group = users;
-                    }
-                    Err(e) => {
-                        error!("Error fetching users: {}", e);
+    /// Sends a queued message again at once
+    fn retry_message(&mut self, client_id: &str) -> Command<GroupChatMessage> {
+        self.outbox.retry(client_id, Local::now().naive_local());
+        self.send_from_outbox(client_id)
+    }
+
+    /// Handles the tick event. Fetches and decrypts the messages of the group since the last sync and their senders in the background.
+    /// The tick is skipped while the messages are still being fetched
+    fn handle_tick(&mut self) -> Command<GroupChatMessage> {
+        let user_id = self.current_user.id;
+        let group_id = self.current_group.id;
+        let since = sync_start(&self.messages);
+        let Some(generation) = self.load.start() else {
+            return Command::none();
+        };
+
+        Command::perform(
+            run_blocking(move || load_group_chat(user_id, group_id, since)),
+            move |result| GroupChatMessage::MessagesLoaded(group_id, generation, result),
+        )
+    }
+
+    /// Handles the loaded messages and users and merges them into the known ones. Data of a group that is no longer open
+    /// and data loaded before a newer load or an edit is ignored. If loading failed, the known messages are kept
+    fn handle_messages_loaded(
+        &mut self,
+        group_id: i32,
+        generation: u64,
+        result: Result<(Vec<GroupMessage>, Vec<User>), String>,
+    ) {
+        if group_id != self.current_group.id || !self.load.finish(generation) {
+            return;
+        }
+
+        self.loading = false;
+
+        match result {
+            Ok((messages, users)) => {
+                merge_messages(&mut self.messages, messages);
+                for user in users {
+                    match self
+                        .users_of_group
+                        .iter_mut()
+                        .find(|known| known.id == user.id)
+                    {
+                        Some(known) => *known = user,
+                        None => self.users_of_group.push(user),
                     }
                 }
             }
@@ -125,20 +364,121 @@ impl GroupChat {
         }
     }
 
+    /// Shares the keys of the messages the current user can read with the members that cannot read them, e.g. new members.
+    /// If the history is already being shared, it does nothing
+    fn share_history(&mut self) -> Command<GroupChatMessage> {
+        if self.sharing {
+            return Command::none();
+        }
+
+        let user_id = self.current_user.id;
+        let group_id = self.current_group.id;
+
+        self.sharing = true;
+        Command::perform(
+            run_blocking(move || share_group_history(&KeyStore::default(), user_id, group_id)),
+            GroupChatMessage::HistoryShared,
+        )
+    }
+
+    /// Handles the result of sharing the history
+    fn handle_history_shared(&mut self, result: Result<usize, String>) {
+        self.sharing = false;
+
+        match result {
+            Ok(0) => {
+                self.error = String::new();
+                self.info = t("group-chat-history-already-shared");
+            }
+            Ok(_) => {
+                self.error = String::new();
+                self.info = t("group-chat-history-shared");
+            }
+            Err(e) => {
+                error!("Error sharing history: {}", e);
+                self.info = String::new();
+                self.error = t_args("group-chat-share-failed", &[("error", e)]);
+            }
+        }
+    }
+
     /// Updates the group chat based on the message.
-    pub fn update(&mut self, message: GroupChatMessage) {
+    pub fn update(&mut self, message: GroupChatMessage) -> Command<GroupChatMessage> {
         match message {
             GroupChatMessage::SendMessage => {
-                self.send_group_message();
+                return self.send_group_message();
+            }
+            GroupChatMessage::MessageQueued(message) => {
+                return self.send_from_outbox(&message.client_id);
+            }
+            GroupChatMessage::MessageSent(client_id, result) => {
+                self.handle_message_sent(&client_id, result);
+            }
+            GroupChatMessage::RetryMessage(client_id) => {
+                return self.retry_message(&client_id);
             }
             GroupChatMessage::InputChanged(value) => {
                 self.input_value = value;
             }
             GroupChatMessage::Back => {}
+            GroupChatMessage::Scrolled(offset) => {
+                // The offset is not a number if all messages fit
+                if offset.is_finite() {
+                    self.scroll_offset = offset;
+                }
+            }
+            GroupChatMessage::EditLastMessage => {
+                return self.edit_last_message();
+            }
+            GroupChatMessage::EditCancelled => {
+                self.editing = None;
+                self.input_value = String::new();
+            }
+            GroupChatMessage::MessageEdited(result) => {
+                self.handle_message_edited(result);
+            }
+            GroupChatMessage::SearchOpened => {
+                self.search_value.get_or_insert_with(String::new);
+                return text_input::focus(self.search_id());
+            }
+            GroupChatMessage::SearchChanged(value) => {
+                self.search_value = Some(value);
+            }
+            GroupChatMessage::SearchClosed => {
+                self.search_value = None;
+            }
+            GroupChatMessage::ExportOpened => {
+                let target = ChatExportTarget::Group {
+                    user_id: self.current_user.id,
+                    group_id: self.current_group.id,
+                };
+                let chat_name = self.current_group.name.clone();
+                self.export_form
+                    .get_or_insert_with(|| ChatExportForm::new(target, &chat_name));
+            }
+            GroupChatMessage::ExportClosed => {
+                self.export_form = None;
+            }
+            GroupChatMessage::Export(message) => {
+                if let Some(export_form) = &mut self.export_form {
+                    return export_form.update(message).map(GroupChatMessage::Export);
+                }
+            }
             GroupChatMessage::Tick => {
-                self.handle_tick();
+                return self.handle_tick();
+            }
+            GroupChatMessage::MessagesLoaded(group_id, generation, result) => {
+                self.handle_messages_loaded(group_id, generation, result);
+            }
+            GroupChatMessage::ShareHistory => {
+                return self.share_history();
+            }
+            GroupChatMessage::HistoryShared(result) => {
+                self.handle_history_shared(result);
             }
         }
+
+        Command::none()
     }
 
     /// Returns the view of the group chat
@@ -150,34 +490,97 @@ impl GroupChat {
 
         let group_name = text(format!("[{}]:", self.current_group.name)).size(text_size);
 
-        let back_button = button(text("Back").horizontal_alignment(Horizontal::Center))
+        let back_button = button(text(t("back")).horizontal_alignment(Horizontal::Center))
             .width(button_width)
             .padding(padding)
             .on_press(GroupChatMessage::Back);
 
-        let top_row = row!(group_name, horizontal_space(), back_button)
-            .spacing(spacing)
-            .padding(padding);
+        let share_label = if self.sharing {
+            t("group-chat-sharing")
+        } else {
+            t("group-chat-share-history")
+        };
 
-        let other_users = text("[Users]:").size(text_size);
+        let share_button = button(text(share_label).horizontal_alignment(Horizontal::Center))
+            .padding(padding)
+            .on_press_maybe(
+                (!self.sharing && !self.offline).then_some(GroupChatMessage::ShareHistory),
+            );
+
+        let export_button = match self.export_form {
+            Some(_) => {
+                button(text(t("chat-export-close")).horizontal_alignment(Horizontal::Center))
+                    .on_press(GroupChatMessage::ExportClosed)
+            }
+            None => button(text(t("chat-export")).horizontal_alignment(Horizontal::Center))
+                .on_press(GroupChatMessage::ExportOpened),
+        }
+        .padding(padding);
+
+        let top_row = row!(
+            group_name,
+            horizontal_space(),
+            share_button,
+            export_button,
+            back_button
+        )
+        .spacing(spacing)
+        .padding(padding);
 
-        let own_name = text("[You]:").size(text_size);
+        let export_row: Element<GroupChatMessage> = match &self.export_form {
+            Some(export_form) => export_form.view().map(GroupChatMessage::Export),
+            None => column![].into(),
+        };
+
+        let other_users = text(t("group-chat-users")).size(text_size);
+
+        let own_name = text(t("chat-you")).size(text_size);
 
         let name_row = row!(other_users, horizontal_space(), own_name)
             .spacing(spacing)
             .padding(padding);
 
+        let search_row: Element<GroupChatMessage> = match &self.search_value {
+            Some(search_value) => row!(
+                text_input(&t("chat-search"), search_value)
+                    .style(input_style())
+                    .id(self.search_id())
+                    .width(Length::Fill)
+                    .padding(padding)
+                    .on_input(GroupChatMessage::SearchChanged),
+                button(text(t("chat-search-close")).horizontal_alignment(Horizontal::Center))
+                    .width(button_width)
+                    .padding(padding)
+                    .on_press(GroupChatMessage::SearchClosed)
+            )
+            .spacing(spacing)
+            .padding(padding)
+            .into(),
+            None => column![].into(),
+        };
+
         let mut message_column = column![].spacing(spacing).padding(padding);
 
         for message in &self.messages {
+            if !self.matches_search(&message.message) {
+                continue;
+            }
+
+            let send_date = match message.edited_at {
+                Some(_) => t_args(
+                    "chat-edited",
+                    &[("date", format_send_date(message.send_date))],
+                ),
+                None => format_send_date(message.send_date),
+            };
+
             if message.sender_id == self.current_user.id {
-                let message_date =
-                    text(format!("{}:", format_send_date(message.send_date))).size(text_size);
+                let message_date = text(format!("{}:", send_date)).size(text_size);
 
                 let date_row = row!(horizontal_space(), message_date);
                 message_column = message_column.push(date_row);
 
-                let message_text = text(message.message.as_str()).size(text_size);
+                let message_text = text(message.display_text()).size(text_size);
 
                 let message_row = row!(horizontal_space(), message_text);
                 message_column = message_column.push(message_row);
@@ -190,40 +593,94 @@ impl GroupChat {
 
                 let message_date = text(format!(
                     "{} {}, {}:",
-                    user.first_name,
-                    user.last_name,
-                    format_send_date(message.send_date)
+                    user.first_name, user.last_name, send_date
                 ))
                 .size(text_size);
 
                 message_column = message_column.push(message_date);
 
-                let message_text = text(message.message.as_str()).size(text_size);
+                let message_text = text(message.display_text()).size(text_size);
 
                 message_column = message_column.push(message_text);
             }
         }
 
+        // Messages that have not been sent yet are shown below the sent ones
+        for message in self.outbox.messages() {
+            let status: Element<GroupChatMessage> = match message.status {
+                OutboxStatus::Pending if message.sending => {
+                    text(t("chat-sending")).size(text_size).into()
+                }
+                OutboxStatus::Pending => text(t("chat-pending")).size(text_size).into(),
+                OutboxStatus::Failed => row!(
+                    text(t("chat-failed")).size(text_size),
+                    button(text(t("chat-retry")).horizontal_alignment(Horizontal::Center))
+                        .on_press(GroupChatMessage::RetryMessage(message.client_id.clone()))
+                )
+                .spacing(spacing)
+                .into(),
+            };
+            message_column = message_column.push(row!(horizontal_space(), status));
+
+            let message_text = text(message.message.as_str()).size(text_size);
+            message_column = message_column.push(row!(horizontal_space(), message_text));
+        }
+
+        if self.loading {
+            message_column = message_column.push(text(t("chat-loading")).size(text_size));
+        }
+
         let message_scrollable = Scrollable::new(message_column)
+            .id(self.scroll_id())
+            .on_scroll(|viewport| GroupChatMessage::Scrolled(viewport.relative_offset().y))
             .width(Length::Fill)
             .height(Length::Fill);
 
-        let input_field = text_input("Type your message...", &self.input_value)
+        let input_field = text_input(&t("chat-input"), &self.input_value)
+            .style(input_style())
+            .id(self.input_id())
             .width(Length::Fill)
             .padding(padding)
             .on_submit(GroupChatMessage::SendMessage)
             .on_input(GroupChatMessage::InputChanged);
 
-        let send_button = button(text("Send").horizontal_alignment(Horizontal::Center))
+        let send_button = button(text(t("chat-send")).horizontal_alignment(Horizontal::Center))
             .width(button_width)
             .padding(padding)
             .on_press(GroupChatMessage::SendMessage);
 
-        let bottom_row = row!(input_field, send_button)
-            .spacing(spacing)
-            .padding(padding);
-
-        let content = column![top_row, name_row, message_scrollable, bottom_row];
+        let bottom_row = if self.editing.is_some() {
+            let cancel_button =
+                button(text(t("chat-edit-cancel")).horizontal_alignment(Horizontal::Center))
+                    .width(button_width)
+                    .padding(padding)
+                    .on_press(GroupChatMessage::EditCancelled);
+            row!(input_field, send_button, cancel_button)
+        } else {
+            row!(input_field, send_button)
+        }
+        .spacing(spacing)
+        .padding(padding);
+
+        let status_message: Element<GroupChatMessage> = if self.offline {
+            text(t("chat-offline")).size(15).style(error_color()).into()
+        } else if !self.error.is_empty() {
+            text(&self.error).size(15).style(error_color()).into()
+        } else if !self.info.is_empty() {
+            text(&self.info).size(15).style(success_color()).into()
+        } else {
+            column![].into()
+        };
+
+        let content = column![
+            top_row,
+            export_row,
+            search_row,
+            name_row,
+            message_scrollable,
+            status_message,
+            bottom_row
+        ];
 
         content.into()
     }
@@ -231,6 +688,11 @@ impl GroupChat {
 
 /// Getter methods for testing
 impl GroupChat {
+    /// Getter for the load of the messages
+    pub fn get_load(&self) -> &LoadTracker {
+        &self.load
+    }
+
     /// Getter for the current user
     pub fn get_current_user(&self) -> &User {
         &self.current_user
@@ -255,6 +717,49 @@ impl GroupChat {
     pub fn get_input_value(&self) -> &String {
         &self.input_value
     }
+
+    /// Getter for the error message
+    pub fn get_error(&self) -> &String {
+        &self.error
+    }
+
+    /// Getter for the info message
+    pub fn get_info(&self) -> &String {
+        &self.info
+    }
+
+    /// Getter for the messages that have not been sent yet
+    pub fn get_outbox(&self) -> &Outbox {
+        &self.outbox
+    }
+
+    /// Getter for the relative scroll position of the messages
+    pub fn get_scroll_offset(&self) -> f32 {
+        self.scroll_offset
+    }
+
+    /// Getter for the send date of the edited message
+    pub fn get_editing(&self) -> Option<DateTime<Utc>> {
+        self.editing
+    }
+
+    /// Getter for the search value
+    pub fn get_search_value(&self) -> Option<&str> {
+        self.search_value.as_deref()
+    }
+
+    /// Getter for the form to export the chat
+    pub fn get_export_form(&self) -> Option<&ChatExportForm> {
+        self.export_form.as_ref()
+    }
+
+    /// Getter for the messages that are shown with the current search
+    pub fn get_visible_messages(&self) -> Vec<&GroupMessage> {
+        self.messages
+            .iter()
+            .filter(|message| self.matches_search(&message.message))
+            .collect()
+    }
 }
 
 /// Setters methods for testing
@@ -269,3 +774,23 @@ impl GroupChat {
         self.users_of_group.push(user);
     }
 }
+
+/// Fetches and decrypts the messages of a group for a user, all of them or only those sent at or after the given date,
+/// and the users that sent them. The group chat is marked as read. Returns the messages and the users or an error message
+fn load_group_chat(
+    user_id: i32,
+    group_id: i32,
+    since: Option<DateTime<Utc>>,
+) -> Result<(Vec<GroupMessage>, Vec<User>), String> {
+    mark_conversation_read(user_id, true, group_id)?;
+    let messages = load_group_messages(&KeyStore::default(), user_id, group_id, since)?;
+    let user_ids: Vec<i32> = messages.iter().map(|message| message.sender_id).collect();
+
+    match find_users_by_ids(user_ids) {
+        Ok(users) => Ok((messages, users)),
+        Err(e) => {
+            error!("Error fetching users: {}", e);
+            Ok((messages, vec![]))
+        }
+    }
+}
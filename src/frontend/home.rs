@@ -1,17 +1,24 @@
 use iced::{
     alignment::Horizontal,
     widget::{button, column, horizontal_space, row, text},
-    Element,
+    Command, Element,
 };
 use iced_aw::{widgets::Tabs, TabLabel};
+use log::error;
+use serde::{Deserialize, Serialize};
 
 use crate::backend::{database::models::User, entities::user_ops::find_all_user};
+use crate::i18n::{t, t_args};
 
+use super::local_cache::CachedData;
 use super::tabs_home::{
+    chat_tab::{ChatTab, ChatTabMessage},
     group_tab::{GroupTab, GroupTabMessage},
     setting_tab::{SettingTab, SettingsTabMessage},
     user_tab::{UserTab, UserTabMessage},
 };
+use super::task::run_blocking;
+use super::theme::error_color;
 
 /// Represents the state of the home page
 #[derive(Debug, Clone)]
@@ -19,9 +26,12 @@ pub struct Home {
     current_user: Option<User>,
     // Sub views
     active_tab: TabId,
+    chat_tab: ChatTab,
     user_tab: UserTab,
     group_tab: GroupTab,
     settings_tab: SettingTab,
+    // Set while the database cannot be reached and the cached data is shown
+    offline: bool,
 }
 
 /// Represents the messages that can be sent to the home page
@@ -31,16 +41,19 @@ pub enum HomeMessage {
     NavigateToLogin,
     // Tabs
     TabSelected(TabId),
+    ChatTab(ChatTabMessage),
     UserTab(UserTabMessage),
     GroupTab(GroupTabMessage),
     SettingsTab(SettingsTabMessage),
     // Load data
     Tick,
+    UsersLoaded(Result<Vec<User>, String>),
 }
 
 /// Represents the tabs of the home page
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum TabId {
+    Chats,
     User,
     Group,
     Settings,
@@ -52,9 +65,11 @@ impl Default for Home {
         Home {
             current_user: None,
             active_tab: TabId::User,
+            chat_tab: ChatTab::default(),
             user_tab: UserTab::default(),
             group_tab: GroupTab::default(),
             settings_tab: SettingTab::default(),
+            offline: false,
         }
     }
 }
@@ -76,6 +91,11 @@ impl Home {
         self.active_tab.clone()
     }
 
+    /// Getter method for the chat tab
+    pub fn get_chat_tab(&mut self) -> &mut ChatTab {
+        &mut self.chat_tab
+    }
+
     /// Getter method for the user tab
     pub fn get_user_tab(&mut self) -> &mut UserTab {
         &mut self.user_tab
@@ -91,36 +111,98 @@ impl Home {
         &mut self.settings_tab
     }
 
+    /// Setter method for the offline mode
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// Shows the cached data in the tabs that have not loaded their data yet, so they do not wait for the database
+    pub fn show_cached(&mut self, data: &CachedData) {
+        let current_user = self.current_user.as_ref().unwrap().clone();
+
+        if self.user_tab.get_loading() {
+            self.user_tab.set_current_user(current_user.clone());
+            self.user_tab
+                .show_cached(data.users.clone(), data.friend_relations.clone());
+        }
+
+        if self.group_tab.get_loading() {
+            self.group_tab.set_current_user(current_user);
+            self.group_tab
+                .show_cached(data.users.clone(), data.group_tab.clone());
+        }
+    }
+
     /// Clears the settings tab
     fn clear_settings_tab(&mut self) {
         self.settings_tab = SettingTab::default();
     }
 
-    /// Loads the data of the home page. It filters the users that are not allowed to be shown, such as the current user and test users
-    fn load_data(&mut self) -> Vec<User> {
-        let users = find_all_user();
+    /// Loads the data of the home page in the background
+    fn load_data(&mut self) -> Command<HomeMessage> {
+        Command::perform(run_blocking(find_all_user), HomeMessage::UsersLoaded)
+    }
 
-        match users {
+    /// Handles the loaded users. It filters the users that are not allowed to be shown, such as the current user and test users,
+    /// and passes them to the active tab
+    fn handle_users_loaded(&mut self, users: Result<Vec<User>, String>) -> Command<HomeMessage> {
+        // The shown data is kept if the users could not be loaded
+        let users = match users {
             Ok(mut users) => {
                 users.retain(|user| user.id != self.current_user.as_ref().unwrap().id);
                 users
             }
-            Err(_) => Vec::new(),
+            Err(e) => {
+                error!("Error loading users: {}", e);
+                return Command::none();
+            }
+        };
+
+        match self.active_tab {
+            TabId::Chats => {
+                self.chat_tab
+                    .set_current_user(self.current_user.as_ref().unwrap().clone());
+                self.chat_tab
+                    .update(ChatTabMessage::Tick)
+                    .map(HomeMessage::ChatTab)
+            }
+            TabId::User => {
+                self.user_tab
+                    .set_current_user(self.current_user.as_ref().unwrap().clone());
+                self.user_tab
+                    .update(UserTabMessage::Tick(users))
+                    .map(HomeMessage::UserTab)
+            }
+            TabId::Group => {
+                self.group_tab
+                    .set_current_user(self.current_user.as_ref().unwrap().clone());
+                self.group_tab
+                    .update(GroupTabMessage::Tick(users))
+                    .map(HomeMessage::GroupTab)
+            }
+            _ => Command::none(),
         }
     }
 
     /// Sets the properties of the home page
-    pub fn update(&mut self, message: HomeMessage) {
+    pub fn update(&mut self, message: HomeMessage) -> Command<HomeMessage> {
         match message {
             // Changeing page
-            HomeMessage::NavigateToLogin => {}
+            HomeMessage::NavigateToLogin => Command::none(),
             HomeMessage::TabSelected(tab_id) => match tab_id {
+                TabId::Chats => {
+                    self.clear_settings_tab();
+                    self.chat_tab
+                        .set_current_user(self.current_user.as_ref().unwrap().clone());
+                    self.active_tab = tab_id;
+                    self.update(HomeMessage::Tick)
+                }
                 TabId::User => {
                     self.clear_settings_tab();
                     self.user_tab
                         .set_current_user(self.current_user.as_ref().unwrap().clone());
                     self.active_tab = tab_id;
-                    self.update(HomeMessage::Tick);
+                    self.update(HomeMessage::Tick)
                 }
 
                 TabId::Group => {
@@ -128,41 +210,36 @@ impl Home {
                     self.group_tab
                         .set_current_user(self.current_user.as_ref().unwrap().clone());
                     self.active_tab = tab_id;
-                    self.update(HomeMessage::Tick);
+                    self.update(HomeMessage::Tick)
                 }
                 TabId::Settings => {
                     self.settings_tab
                         .set_current_user(self.current_user.as_ref().unwrap().clone());
-                    self.active_tab = tab_id
+                    self.active_tab = tab_id;
+                    Command::batch([
+                        self.settings_tab.load_two_factor_status(),
+                        self.settings_tab.load_sessions(),
+                        self.settings_tab.load_notification_settings(),
+                    ])
+                    .map(HomeMessage::SettingsTab)
                 }
             },
+            HomeMessage::ChatTab(message) => {
+                self.chat_tab.update(message).map(HomeMessage::ChatTab)
+            }
             HomeMessage::UserTab(message) => {
-                self.user_tab.update(message);
+                self.user_tab.update(message).map(HomeMessage::UserTab)
             }
             HomeMessage::GroupTab(message) => {
-                self.group_tab.update(message);
-            }
-            HomeMessage::SettingsTab(message) => {
-                self.settings_tab.update(message);
+                self.group_tab.update(message).map(HomeMessage::GroupTab)
             }
+            HomeMessage::SettingsTab(message) => self
+                .settings_tab
+                .update(message)
+                .map(HomeMessage::SettingsTab),
             // Load data
-            HomeMessage::Tick => {
-                let users = self.load_data();
-
-                match self.active_tab {
-                    TabId::User => {
-                        self.user_tab
-                            .set_current_user(self.current_user.as_ref().unwrap().clone());
-                        self.user_tab.update(UserTabMessage::Tick(users.clone()));
-                    }
-                    TabId::Group => {
-                        self.group_tab
-                            .set_current_user(self.current_user.as_ref().unwrap().clone());
-                        self.group_tab.update(GroupTabMessage::Tick(users.clone()));
-                    }
-                    _ => {}
-                }
-            }
+            HomeMessage::Tick => self.load_data(),
+            HomeMessage::UsersLoaded(users) => self.handle_users_loaded(users),
         }
     }
 
@@ -173,14 +250,22 @@ impl Home {
         let spacing = 20;
 
         // Top row
-        let welcome_message = text(format!(
-            "Welcome {} {}!",
-            self.current_user.as_ref().unwrap().first_name,
-            self.current_user.as_ref().unwrap().last_name
+        let welcome_message = text(t_args(
+            "home-welcome",
+            &[
+                (
+                    "first_name",
+                    self.current_user.as_ref().unwrap().first_name.clone(),
+                ),
+                (
+                    "last_name",
+                    self.current_user.as_ref().unwrap().last_name.clone(),
+                ),
+            ],
         ))
         .size(30);
 
-        let logout_button = button(text("Logout").horizontal_alignment(Horizontal::Center))
+        let logout_button = button(text(t("home-logout")).horizontal_alignment(Horizontal::Center))
             .width(button_width)
             .padding(padding)
             .on_press(HomeMessage::NavigateToLogin);
@@ -190,24 +275,35 @@ impl Home {
             .padding(padding);
 
         let tabs = Tabs::new(HomeMessage::TabSelected)
+            .push(
+                TabId::Chats,
+                TabLabel::IconText('💬', t("home-tab-chats")),
+                self.chat_tab.view().map(HomeMessage::ChatTab),
+            )
             .push(
                 TabId::User,
-                TabLabel::IconText('👤', "User".to_string()),
+                TabLabel::IconText('👤', t("home-tab-user")),
                 self.user_tab.view().map(HomeMessage::UserTab),
             )
             .push(
                 TabId::Group,
-                TabLabel::IconText('👥', "Group".to_string()),
+                TabLabel::IconText('👥', t("home-tab-group")),
                 self.group_tab.view().map(HomeMessage::GroupTab),
             )
             .push(
                 TabId::Settings,
-                TabLabel::IconText('⛭', "Settings".to_string()),
+                TabLabel::IconText('⛭', t("home-tab-settings")),
                 self.settings_tab.view().map(HomeMessage::SettingsTab),
             )
             .set_active_tab(&self.active_tab);
 
-        let content = column![top_row, tabs].spacing(spacing);
+        let offline_message: Element<HomeMessage> = if self.offline {
+            text(t("home-offline")).size(15).style(error_color()).into()
+        } else {
+            column![].into()
+        };
+
+        let content = column![top_row, offline_message, tabs].spacing(spacing);
 
         content.into()
     }
@@ -219,4 +315,9 @@ impl Home {
     pub fn active_tab(&self) -> TabId {
         self.active_tab.clone()
     }
+
+    /// Returns if the home page is in offline mode
+    pub fn get_offline(&self) -> bool {
+        self.offline
+    }
 }
@@ -0,0 +1,414 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+use aes_gcm::{aead::KeyInit, Aes256Gcm};
+use argon2::Argon2;
+use chrono::{DateTime, Duration, Utc};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::backend::{
+    crypto::{cipher, direct_message::ConversationFingerprints},
+    database::models::{GroupMessage, User, UserMessage, UserToUserFriend},
+};
+
+use super::drafts::Drafts;
+use super::outbox::Outbox;
+use super::tabs_home::group_tab::GroupTabData;
+
+/// Name of the directory in the configuration directory of the user
+const APP_DIRECTORY: &str = "secse24_group08";
+
+/// Version of the cache file format. Version 2 stores the send dates of messages in UTC, older caches are loaded again
+const CACHE_VERSION: u32 = 2;
+
+/// Length of the salt of the key derivation in bytes
+const SALT_LENGTH: usize = 16;
+
+/// Messages are synced again from a little before the newest cached message, so messages that were stored
+/// with an earlier date while the last sync ran are not missed
+const SYNC_OVERLAP_SECONDS: i64 = 60;
+
+/// Cache files are saved one at a time, so concurrent saves do not write the same temporary file
+static SAVE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Stores the data the user has seen on this device, so the app can show it immediately and while the database
+/// cannot be reached. Every account has its own file, encrypted with a key derived from the password of the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalCache {
+    directory: PathBuf,
+}
+
+/// A cache file of an account that has been unlocked with the password of the user
+#[derive(Clone)]
+pub struct UnlockedCache {
+    path: PathBuf,
+    salt: [u8; SALT_LENGTH],
+    key: [u8; 32],
+    versions: Arc<SnapshotVersions>,
+}
+
+/// The versions of the snapshots of the cached data, shared by all clones of an unlocked cache
+#[derive(Default)]
+struct SnapshotVersions {
+    /// The version of the newest snapshot that was taken
+    taken: AtomicU64,
+    /// The version of the snapshot that was stored last
+    saved: AtomicU64,
+}
+
+/// The data of an account that is stored in the cache
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedData {
+    pub user: User,
+    pub users: Vec<User>,
+    pub friend_relations: Vec<UserToUserFriend>,
+    pub group_tab: GroupTabData,
+    pub user_chats: BTreeMap<i32, CachedUserChat>,
+    pub group_chats: BTreeMap<i32, CachedGroupChat>,
+    #[serde(default)]
+    pub outbox: Outbox,
+    #[serde(default)]
+    pub drafts: Drafts,
+}
+
+/// The cached messages of a chat with another user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedUserChat {
+    pub messages: Vec<UserMessage>,
+    pub fingerprints: ConversationFingerprints,
+}
+
+/// The cached messages of a group chat and the users that sent them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedGroupChat {
+    pub messages: Vec<GroupMessage>,
+    pub users: Vec<User>,
+}
+
+/// The content of a cache file. Only the salt is stored in plain text
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Default implementation for the LocalCache struct
+impl Default for LocalCache {
+    /// The directory is read from LOCAL_CACHE_PATH, otherwise the configuration directory of the user is used
+    fn default() -> Self {
+        let directory = env::var("LOCAL_CACHE_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                dirs::config_dir()
+                    .unwrap_or_else(env::temp_dir)
+                    .join(APP_DIRECTORY)
+                    .join("cache")
+            });
+
+        LocalCache::new(directory)
+    }
+}
+
+/// Implementation of the local cache
+impl LocalCache {
+    /// Create a new local cache that stores its files in the given directory
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        LocalCache {
+            directory: directory.into(),
+        }
+    }
+
+    /// Derive the key of the cache of an account from the password. The salt of an existing cache file is reused,
+    /// otherwise a new one is created. Deriving the key does not check the password, loading the data does.
+    /// Returns the unlocked cache or an error message
+    pub fn unlock(&self, email: &str, password: &str) -> Result<UnlockedCache, String> {
+        let path = self.cache_path(email);
+
+        let salt = match read_cache_file(&path)? {
+            Some(file) => decode_salt(&file.salt)?,
+            None => {
+                let mut salt = [0u8; SALT_LENGTH];
+                OsRng.fill_bytes(&mut salt);
+                salt
+            }
+        };
+
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(password.as_bytes(), &salt, &mut key)
+            .map_err(|err| format!("Failed to derive cache key: {}", err))?;
+
+        Ok(UnlockedCache {
+            path,
+            salt,
+            key,
+            versions: Arc::default(),
+        })
+    }
+
+    /// Unlock the cache of an account with a key that was exported from an unlocked cache, e.g. for a remembered session
+    /// that has no password. Returns the unlocked cache or an error message if the key is invalid
+    pub fn unlock_with_key(
+        &self,
+        email: &str,
+        exported_key: &str,
+    ) -> Result<UnlockedCache, String> {
+        let (salt, key) = exported_key
+            .trim()
+            .split_once(':')
+            .ok_or("Invalid cache key")?;
+        let key = hex::decode(key)
+            .ok()
+            .and_then(|key| key.try_into().ok())
+            .ok_or("Invalid cache key")?;
+
+        Ok(UnlockedCache {
+            path: self.cache_path(email),
+            salt: decode_salt(salt)?,
+            key,
+            versions: Arc::default(),
+        })
+    }
+
+    /// Returns the path of the cache file of an account. The file name does not reveal the email
+    fn cache_path(&self, email: &str) -> PathBuf {
+        let hash = hex::encode(Sha256::digest(email.trim().to_lowercase().as_bytes()));
+        self.directory.join(format!("{}.cache", hash))
+    }
+}
+
+/// Implementation of the unlocked cache
+impl UnlockedCache {
+    /// Load the cached data. Returns the data, none if nothing has been cached yet, or an error message
+    /// if the cache was encrypted with another password or has been changed
+    pub fn load(&self) -> Result<Option<CachedData>, String> {
+        let Some(file) = read_cache_file(&self.path)? else {
+            return Ok(None);
+        };
+
+        if file.version != CACHE_VERSION {
+            return Ok(None);
+        }
+
+        let data = cipher::open(
+            &self.cipher(),
+            &associated_data(file.version),
+            &file.ciphertext,
+            &file.nonce,
+        )
+        .map_err(|_| "The cache could not be decrypted".to_string())?;
+
+        serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|err| format!("Invalid cache: {}", err))
+    }
+
+    /// Encrypt and store the data. The file is replaced at once, so a crash cannot leave a partly written cache.
+    /// Returns nothing or an error message
+    pub fn save(&self, data: &CachedData) -> Result<(), String> {
+        self.save_snapshot(self.next_version(), data)
+    }
+
+    /// Returns the version of a new snapshot of the data, taken before the snapshot is saved in the background
+    pub fn next_version(&self) -> u64 {
+        self.versions.taken.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Encrypt and store a snapshot of the data with its version. A snapshot that is older than the one stored last is
+    /// skipped, so saves that finish out of order cannot replace newer data. Returns nothing or an error message
+    pub fn save_snapshot(&self, version: u64, data: &CachedData) -> Result<(), String> {
+        if version <= self.versions.saved.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let plaintext = serde_json::to_vec(data)
+            .map_err(|err| format!("Failed to serialize cache: {}", err))?;
+        let encrypted = cipher::seal(&self.cipher(), &associated_data(CACHE_VERSION), &plaintext)?;
+
+        let content = serde_json::to_string(&CacheFile {
+            version: CACHE_VERSION,
+            salt: hex::encode(self.salt),
+            nonce: encrypted.nonce,
+            ciphertext: encrypted.ciphertext,
+        })
+        .map_err(|err| format!("Failed to serialize cache: {}", err))?;
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create cache directory: {}", err))?;
+        }
+
+        let _guard = SAVE_LOCK
+            .lock()
+            .map_err(|_| "Failed to lock cache file".to_string())?;
+
+        // A newer snapshot may have been stored while this one was encrypted
+        if version <= self.versions.saved.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let temporary_path = self.path.with_extension("tmp");
+        let mut options = OpenOptions::new();
+        options.create(true).write(true).truncate(true);
+
+        // Only the current user may read the cache
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options
+            .open(&temporary_path)
+            .map_err(|err| format!("Failed to open cache file: {}", err))?;
+        file.write_all(content.as_bytes())
+            .map_err(|err| format!("Failed to write cache file: {}", err))?;
+
+        fs::rename(&temporary_path, &self.path)
+            .map_err(|err| format!("Failed to write cache file: {}", err))?;
+        self.versions.saved.store(version, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Returns the salt and the key of the cache, so it can be unlocked again without the password
+    pub fn export_key(&self) -> String {
+        format!("{}:{}", hex::encode(self.salt), hex::encode(self.key))
+    }
+
+    /// Remove the cache file, e.g. when the account is deleted. Returns nothing or an error message
+    pub fn remove(&self) -> Result<(), String> {
+        match fs::remove_file(&self.path) {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(format!("Failed to remove cache file: {}", err)),
+        }
+    }
+
+    /// Returns the cipher of the cache key
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(&self.key.into())
+    }
+}
+
+/// The key of the cache is not printed
+impl fmt::Debug for UnlockedCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UnlockedCache")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Implementation of the cached data
+impl CachedData {
+    /// Create empty cached data of a user
+    pub fn new(user: User) -> Self {
+        CachedData {
+            user,
+            users: Vec::new(),
+            friend_relations: Vec::new(),
+            group_tab: GroupTabData::default(),
+            user_chats: BTreeMap::new(),
+            group_chats: BTreeMap::new(),
+            outbox: Outbox::default(),
+            drafts: Drafts::default(),
+        }
+    }
+}
+
+/// A message of a chat that is cached and synced in the background
+pub trait SyncedMessage: Clone {
+    /// Returns the sender, the receiver and the send date, which identify the message
+    fn key(&self) -> (i32, i32, DateTime<Utc>);
+
+    /// Returns true if the message could not be decrypted
+    fn is_undecryptable(&self) -> bool;
+}
+
+/// Implementation of the synced message for user messages
+impl SyncedMessage for UserMessage {
+    fn key(&self) -> (i32, i32, DateTime<Utc>) {
+        (self.sender_id, self.receiver_id, self.send_date)
+    }
+
+    fn is_undecryptable(&self) -> bool {
+        self.undecryptable
+    }
+}
+
+/// Implementation of the synced message for group messages
+impl SyncedMessage for GroupMessage {
+    fn key(&self) -> (i32, i32, DateTime<Utc>) {
+        (self.sender_id, self.receiver_id, self.send_date)
+    }
+
+    fn is_undecryptable(&self) -> bool {
+        self.undecryptable
+    }
+}
+
+/// Returns the date from which the messages of a chat have to be synced: a little before the first message that could
+/// not be decrypted, because its key may have been shared since, or else before the newest message. Returns none if all
+/// messages have to be loaded
+pub fn sync_start<M: SyncedMessage>(messages: &[M]) -> Option<DateTime<Utc>> {
+    let send_date = |message: &M| message.key().2;
+
+    let start = messages
+        .iter()
+        .filter(|message| message.is_undecryptable())
+        .map(send_date)
+        .min()
+        .or_else(|| messages.iter().map(send_date).max())?;
+
+    Some(start - Duration::seconds(SYNC_OVERLAP_SECONDS))
+}
+
+/// Merge synced messages into the messages of a chat. Known messages are replaced, the messages stay sorted by their send date
+pub fn merge_messages<M: SyncedMessage>(messages: &mut Vec<M>, synced_messages: Vec<M>) {
+    for synced_message in synced_messages {
+        match messages
+            .iter_mut()
+            .find(|message| message.key() == synced_message.key())
+        {
+            Some(message) => *message = synced_message,
+            None => messages.push(synced_message),
+        }
+    }
+
+    messages.sort_by_key(|message| message.key().2);
+}
+
+/// Read a cache file. Returns the file, none if it does not exist or cannot be read as a cache file, or an error message
+fn read_cache_file(path: &PathBuf) -> Result<Option<CacheFile>, String> {
+    match fs::read(path) {
+        Ok(content) => Ok(serde_json::from_slice(&content).ok()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(format!("Failed to read cache file: {}", err)),
+    }
+}
+
+/// Decode the hex encoded salt of a cache file
+fn decode_salt(salt: &str) -> Result<[u8; SALT_LENGTH], String> {
+    hex::decode(salt)
+        .map_err(|err| format!("Invalid cache salt: {}", err))?
+        .try_into()
+        .map_err(|_| "Invalid cache salt length".to_string())
+}
+
+/// Returns the data that is authenticated together with the cache
+fn associated_data(version: u32) -> String {
+    format!("secse24_group08 cache v{}", version)
+}
@@ -1,22 +1,56 @@
 use crate::backend::{
-    database::models::User,
-    entities::user_ops::{find_user_by_email, find_user_with_password_by_id},
+    crypto::{key_store::KeyStore, password::verify_password},
+    database::{db::is_database_available, models::User},
+    entities::{
+        login_throttle_ops::{
+            client_source, login_retry_after, record_failed_login, reset_failed_logins,
+        },
+        password_reset_ops::request_password_reset,
+        pending_verification_ops::{
+            find_verification_cooldown, is_email_unverified, redeem_email_verification,
+            request_email_verification,
+        },
+        two_factor_ops::{is_totp_enabled, verify_second_factor},
+        user_ops::{
+            find_user_by_email, find_user_email_by_id, find_user_with_password_by_id,
+            is_user_disabled, rehash_password_if_needed,
+        },
+    },
+    mail::mailer::create_mailer,
 };
-use bcrypt::verify;
+use crate::i18n::{t, t_args};
 use iced::{
     alignment::Horizontal,
-    widget::{button, column, row, text, text_input, Container},
-    Alignment, Color, Element, Length,
+    widget::{button, checkbox, column, row, text, text_input, Container},
+    Alignment, Command, Element, Length,
 };
 use log::error;
 
+use super::local_cache::{CachedData, LocalCache, UnlockedCache};
+use super::task::run_blocking;
+use super::theme::{error_color, input_style, success_color};
+
 /// Represents the state of the login page
 #[derive(Debug, Clone)]
 pub struct Login {
     email: String,
     password: String,
+    remember_me: bool,
     error: String,
+    info: String,
     found_user: User,
+    // Email verification
+    verification_code: String,
+    unverified_user: Option<User>,
+    // Password reset
+    reset_requested: bool,
+    // Two-factor authentication
+    two_factor_code: String,
+    two_factor_user: Option<User>,
+    // Brute-force protection, none to use the address of the connection
+    source: Option<String>,
+    // Set while a backend call is in flight
+    loading: bool,
 }
 
 /// Represents the messages that can be sent to the login page
@@ -24,9 +58,36 @@ pub struct Login {
 pub enum LoginMessage {
     EmailChanged(String),
     PasswordChanged(String),
+    RememberMeToggled(bool),
     SubmitLogin,
-    LoginSuccess,
+    LoginFinished(Result<LoginOutcome, String>),
+    LoginSuccess(User),
+    OfflineLoginSuccess(UnlockedCache, Box<CachedData>),
     NavigateToRegister,
+    // Email verification
+    VerificationCodeChanged(String),
+    SubmitVerificationCode,
+    ResendVerificationCode,
+    VerificationCodeSent(Result<Option<i64>, String>),
+    // Password reset
+    ForgotPassword,
+    ResetRequestFinished(Result<(), String>),
+    // Two-factor authentication
+    TwoFactorCodeChanged(String),
+    SubmitTwoFactorCode,
+}
+
+/// Represents the result of a login attempt whose password was correct
+#[derive(Debug, Clone)]
+pub enum LoginOutcome {
+    /// The user is logged in
+    Success(User),
+    /// The email of the user still has to be verified
+    Unverified(User),
+    /// The user has to enter the code of the authenticator app or a recovery code
+    TwoFactorRequired(User),
+    /// The database cannot be reached, the user can read the data cached on this device
+    Offline(UnlockedCache, Box<CachedData>),
 }
 
 /// Default implementation for the Login struct
@@ -34,13 +95,22 @@ impl Default for Login {
     fn default() -> Self {
         Login {
             error: String::new(),
+            info: String::new(),
             email: String::new(),
             password: String::new(),
+            remember_me: false,
             found_user: User {
                 id: -1,
                 first_name: String::new(),
                 last_name: String::new(),
             },
+            verification_code: String::new(),
+            unverified_user: None,
+            reset_requested: false,
+            two_factor_code: String::new(),
+            two_factor_user: None,
+            source: None,
+            loading: false,
         }
     }
 }
@@ -52,64 +122,236 @@ impl Login {
         &self.found_user
     }
 
+    /// Getter method for the remember me checkbox
+    pub fn get_remember_me(&self) -> bool {
+        self.remember_me
+    }
+
+    /// Getter method for the password reset requested flag
+    pub fn get_reset_requested(&self) -> bool {
+        self.reset_requested
+    }
+
     /// Setter method for the found user
     pub fn set_found_user(&mut self, user: User) {
         self.found_user = user;
     }
 
-    /// Handles the login process. It checks if the email and password fields are empty,
-    /// finds the user by email, finds the user's password, verifies the password, and sets the found user if the login is successful.
-    /// If the login fails, it displays an error message.
-    fn handle_login(&mut self) {
+    /// Setter method for the error message
+    pub fn set_error(&mut self, error: String) {
+        self.error = error;
+    }
+
+    /// Takes the entered password out of the login page, so it is not kept in memory after the login
+    pub fn take_password(&mut self) -> String {
+        std::mem::take(&mut self.password)
+    }
+
+    /// Handles the login submission. It checks if the email and password fields are empty
+    /// and starts the authentication in the background
+    fn handle_login(&mut self) -> Command<LoginMessage> {
+        self.info = String::new();
+        self.two_factor_user = None;
+
         // Check if the email and password fields are empty
         if self.email.is_empty() || self.password.is_empty() {
-            self.error = "Please fill in both email and password fields.".to_string();
-            return;
+            self.error = t("login-fill-in-fields");
+            return Command::none();
         }
 
-        // Find the user by email
-        let found_user = find_user_by_email(&self.email.to_lowercase());
-        match found_user {
-            Ok(user) => {
-                // Find the user's password
-                let user_id = user.id;
-                let found_password = find_user_with_password_by_id(user_id);
-                match found_password {
-                    Ok(password_data) => {
-                        let stored_password = password_data.password;
-
-                        // Verify the password
-                        match verify(&self.password, &stored_password) {
-                            Ok(verified) => {
-                                if verified {
-                                    // Set the found user
-                                    // Need to return the user to the main app
-                                    self.found_user = user;
-                                    return;
-                                } else {
-                                    error!("Password verification failed");
-                                }
-                            }
-                            Err(err) => {
-                                error!("Error verifying password: {:?}", err);
-                            }
-                        }
-                    }
-                    Err(err) => {
-                        error!("Error finding password: {:?}", err);
-                    }
+        let email = self.email.to_lowercase();
+        let password = self.password.clone();
+        let source = self.source.clone();
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || authenticate(&email, &password, source.as_deref())),
+            LoginMessage::LoginFinished,
+        )
+    }
+
+    /// Handles the result of a login attempt. If the login is successful, the found user is set
+    /// and the login success is reported to the main app. Otherwise, it displays an error message.
+    fn handle_login_finished(
+        &mut self,
+        result: Result<LoginOutcome, String>,
+    ) -> Command<LoginMessage> {
+        self.loading = false;
+
+        match result {
+            Ok(LoginOutcome::Success(user)) => {
+                // Set the found user
+                // Need to return the user to the main app
+                self.error = String::new();
+                self.info = String::new();
+                self.verification_code = String::new();
+                self.unverified_user = None;
+                self.two_factor_code = String::new();
+                self.two_factor_user = None;
+                self.found_user = user.clone();
+                return Command::perform(async move { user }, LoginMessage::LoginSuccess);
+            }
+            Ok(LoginOutcome::Offline(cache, data)) => {
+                self.error = String::new();
+                self.info = String::new();
+                self.found_user = data.user.clone();
+                return Command::perform(async move { (cache, data) }, |(cache, data)| {
+                    LoginMessage::OfflineLoginSuccess(cache, data)
+                });
+            }
+            Ok(LoginOutcome::Unverified(user)) => {
+                self.error = t("login-verify-email");
+                self.unverified_user = Some(user);
+            }
+            Ok(LoginOutcome::TwoFactorRequired(user)) => {
+                self.error = String::new();
+                self.info = t("login-enter-two-factor-code");
+                self.two_factor_user = Some(user);
+            }
+            Err(error) => {
+                self.error = error;
+                self.info = String::new();
+            }
+        }
+
+        Command::none()
+    }
+
+    /// Verifies the second factor of the user whose password was already checked in the background
+    fn submit_two_factor_code(&mut self) -> Command<LoginMessage> {
+        let Some(user) = self.two_factor_user.clone() else {
+            return Command::none();
+        };
+
+        if self.two_factor_code.trim().is_empty() {
+            self.error = t("login-two-factor-code-empty");
+            self.info = String::new();
+            return Command::none();
+        }
+
+        let email = self.email.to_lowercase();
+        let code = self.two_factor_code.clone();
+        let source = self.source.clone();
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || {
+                authenticate_second_factor(&email, source.as_deref(), user, &code)
+            }),
+            LoginMessage::LoginFinished,
+        )
+    }
+
+    /// Redeems the verification code of the unverified user in the background. If successful, the login is submitted again
+    fn submit_verification_code(&mut self) -> Command<LoginMessage> {
+        let Some(user) = self.unverified_user.as_ref() else {
+            return Command::none();
+        };
+
+        if self.verification_code.is_empty() {
+            self.error = t("login-verification-code-empty");
+            return Command::none();
+        }
+
+        let user_id = user.id;
+        let verification_code = self.verification_code.trim().to_string();
+        let email = self.email.to_lowercase();
+        let password = self.password.clone();
+        let source = self.source.clone();
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || {
+                redeem_email_verification(user_id, &verification_code)?;
+                authenticate(&email, &password, source.as_deref())
+            }),
+            LoginMessage::LoginFinished,
+        )
+    }
+
+    /// Sends a new verification code to the stored email of the unverified user in the background.
+    /// The entered email is not used, so a code cannot be sent to another address. During the cooldown of the email
+    /// no code is sent and the remaining seconds are returned
+    fn resend_verification_code(&mut self) -> Command<LoginMessage> {
+        let Some(user) = self.unverified_user.as_ref() else {
+            return Command::none();
+        };
+
+        let user_id = user.id;
+
+        Command::perform(
+            run_blocking(move || {
+                let email = find_user_email_by_id(user_id)?;
+                if let Some(seconds) = find_verification_cooldown(&email)? {
+                    return Ok(Some(seconds));
                 }
+                let mailer = create_mailer()?;
+                request_email_verification(user_id, &email, &*mailer)?;
+                Ok(None)
+            }),
+            LoginMessage::VerificationCodeSent,
+        )
+    }
+
+    /// Handles the result of sending a new verification code
+    fn handle_verification_code_sent(&mut self, result: Result<Option<i64>, String>) {
+        match result {
+            Ok(None) => {
+                self.error = String::new();
+                self.info = t("login-verification-code-resent");
+            }
+            Ok(Some(seconds)) => {
+                self.error = t_args(
+                    "error-verification-code-cooldown",
+                    &[("seconds", seconds.to_string())],
+                );
+                self.info = String::new();
+            }
+            Err(err) => {
+                error!("Error sending verification email: {:?}", err);
+                self.error = t("login-verification-email-failed");
+                self.info = String::new();
+            }
+        }
+    }
+
+    /// Requests a password reset for the entered email in the background. The result does not reveal if an account exists for the email
+    fn forgot_password(&mut self) -> Command<LoginMessage> {
+        self.reset_requested = false;
+
+        if self.email.is_empty() {
+            self.error = t("login-reset-email-empty");
+            self.info = String::new();
+            return Command::none();
+        }
+
+        let email = self.email.to_lowercase();
+
+        Command::perform(
+            run_blocking(move || {
+                create_mailer().and_then(|mailer| request_password_reset(&email, &*mailer))
+            }),
+            LoginMessage::ResetRequestFinished,
+        )
+    }
+
+    /// Handles the result of a password reset request
+    fn handle_reset_request_finished(&mut self, result: Result<(), String>) {
+        match result {
+            Ok(_) => {
+                self.error = String::new();
+                self.reset_requested = true;
             }
             Err(err) => {
-                error!("Error finding user: {:?}", err);
+                error!("Error sending password reset email: {:?}", err);
+                self.error = t("reset-email-failed");
+                self.info = String::new();
             }
         }
-        // If the login failed, display an error message
-        self.error = "Login failed. Either the email or password was incorrect.".to_string();
     }
 
     /// Updates the login page based on the message received
-    pub fn update(&mut self, message: LoginMessage) {
+    pub fn update(&mut self, message: LoginMessage) -> Command<LoginMessage> {
         match message {
             // Update the email field
             LoginMessage::EmailChanged(email) => {
@@ -119,15 +361,63 @@ impl Login {
             LoginMessage::PasswordChanged(password) => {
                 self.password = password;
             }
-            // Handle the login submission
-            LoginMessage::SubmitLogin => {
-                self.handle_login();
+            // Update the remember me checkbox
+            LoginMessage::RememberMeToggled(remember_me) => {
+                self.remember_me = remember_me;
+            }
+            // Handle the login submission, unless a login is already in flight
+            LoginMessage::SubmitLogin if !self.loading => {
+                return self.handle_login();
             }
+            // Handle the result of the login
+            LoginMessage::LoginFinished(result) => {
+                return self.handle_login_finished(result);
+            }
+            // Is handled in the main app
+            LoginMessage::LoginSuccess(_) => {}
             // Is handled in the main app
-            LoginMessage::LoginSuccess => {}
+            LoginMessage::OfflineLoginSuccess(_, _) => {}
             // Is handled in the main app
             LoginMessage::NavigateToRegister => {}
+            // Update the verification code field
+            LoginMessage::VerificationCodeChanged(verification_code) => {
+                self.verification_code = verification_code;
+            }
+            // Handle the verification code submission
+            LoginMessage::SubmitVerificationCode if !self.loading => {
+                return self.submit_verification_code();
+            }
+            // Send a new verification code
+            LoginMessage::ResendVerificationCode => {
+                return self.resend_verification_code();
+            }
+            // Handle the result of sending a new verification code
+            LoginMessage::VerificationCodeSent(result) => {
+                self.handle_verification_code_sent(result);
+            }
+            // Request a password reset
+            LoginMessage::ForgotPassword => {
+                return self.forgot_password();
+            }
+            // Handle the result of the password reset request
+            LoginMessage::ResetRequestFinished(result) => {
+                self.handle_reset_request_finished(result);
+            }
+            // Update the two-factor code field
+            LoginMessage::TwoFactorCodeChanged(two_factor_code) => {
+                self.two_factor_code = two_factor_code;
+            }
+            // Handle the two-factor code submission
+            LoginMessage::SubmitTwoFactorCode if !self.loading => {
+                return self.submit_two_factor_code();
+            }
+            // Submissions while a login is in flight are ignored
+            LoginMessage::SubmitLogin
+            | LoginMessage::SubmitVerificationCode
+            | LoginMessage::SubmitTwoFactorCode => {}
         }
+
+        Command::none()
     }
 
     /// Returns the view of the login page
@@ -137,51 +427,142 @@ impl Login {
         let padding = 10;
         let spacing: u16 = 20;
 
-        let login = text("Login:").size(30);
+        let login = text(t("login-title")).size(30);
 
-        let email_input = text_input("Email:", self.email.as_str())
+        let email_input = text_input(&t("login-email"), self.email.as_str())
+            .style(input_style())
             .width(input_width)
             .padding(padding)
             .on_input(LoginMessage::EmailChanged);
 
-        let password_input = text_input("Password:", self.password.as_str())
+        let password_input = text_input(&t("login-password"), self.password.as_str())
+            .style(input_style())
             .width(input_width)
             .padding(padding)
             .secure(true)
             .on_input(LoginMessage::PasswordChanged)
             .on_submit(LoginMessage::SubmitLogin);
 
-        let login_button = button(text("Login").horizontal_alignment(Horizontal::Center))
-            .width(button_width)
-            .padding(padding)
-            .on_press(LoginMessage::SubmitLogin);
+        // The buttons are disabled while a login is in flight
+        let submit_login = (!self.loading).then_some(LoginMessage::SubmitLogin);
 
-        let register_button = button(text("Register").horizontal_alignment(Horizontal::Center))
+        let login_button = button(text(t("login-button")).horizontal_alignment(Horizontal::Center))
             .width(button_width)
             .padding(padding)
-            .on_press(LoginMessage::NavigateToRegister);
+            .on_press_maybe(submit_login);
+
+        let register_button =
+            button(text(t("register-button")).horizontal_alignment(Horizontal::Center))
+                .width(button_width)
+                .padding(padding)
+                .on_press(LoginMessage::NavigateToRegister);
 
         let error_message: Element<LoginMessage> = if !self.error.is_empty() {
-            text(&self.error)
+            text(&self.error).size(15).style(error_color()).into()
+        } else {
+            text("").size(15).into()
+        };
+
+        let info_message: Element<LoginMessage> = if self.loading {
+            text(t("login-loading"))
                 .size(15)
-                .style(Color::from_rgb(1.0, 0.0, 0.0)) // Red color
+                .style(success_color())
                 .into()
+        } else if !self.info.is_empty() {
+            text(&self.info).size(15).style(success_color()).into()
         } else {
             text("").size(15).into()
         };
 
-        let login_content = column![login, email_input, password_input]
+        let remember_me_checkbox = checkbox(t("login-remember-me"), self.remember_me)
+            .on_toggle(LoginMessage::RememberMeToggled);
+
+        let login_content = column![login, email_input, password_input, remember_me_checkbox]
             .padding(padding)
             .spacing(spacing);
 
-        let button_row = row![login_button, register_button]
+        let forgot_password_button =
+            button(text(t("login-forgot-password")).horizontal_alignment(Horizontal::Center))
+                .width(button_width + 40)
+                .padding(padding)
+                .on_press(LoginMessage::ForgotPassword);
+
+        let button_row = row![login_button, register_button, forgot_password_button]
             .padding(padding)
             .spacing(spacing);
 
-        let content = column![login_content, button_row, error_message]
-            .spacing(20)
+        let verification_content: Element<LoginMessage> = if self.unverified_user.is_some() {
+            let verification_code_input = text_input(
+                &t("login-verification-code"),
+                self.verification_code.as_str(),
+            )
+            .style(input_style())
+            .width(input_width)
+            .padding(padding)
+            .on_input(LoginMessage::VerificationCodeChanged)
+            .on_submit(LoginMessage::SubmitVerificationCode);
+
+            let verify_button =
+                button(text(t("login-verify")).horizontal_alignment(Horizontal::Center))
+                    .width(button_width)
+                    .padding(padding)
+                    .on_press_maybe(
+                        (!self.loading).then_some(LoginMessage::SubmitVerificationCode),
+                    );
+
+            let resend_button =
+                button(text(t("resend-code")).horizontal_alignment(Horizontal::Center))
+                    .width(button_width)
+                    .padding(padding)
+                    .on_press(LoginMessage::ResendVerificationCode);
+
+            column![
+                verification_code_input,
+                row![verify_button, resend_button].spacing(spacing)
+            ]
             .padding(padding)
-            .align_items(Alignment::Center);
+            .spacing(spacing)
+            .align_items(Alignment::Center)
+            .into()
+        } else {
+            column![].into()
+        };
+
+        let two_factor_content: Element<LoginMessage> = if self.two_factor_user.is_some() {
+            let two_factor_code_input =
+                text_input(&t("login-two-factor-code"), self.two_factor_code.as_str())
+                    .style(input_style())
+                    .width(input_width)
+                    .padding(padding)
+                    .on_input(LoginMessage::TwoFactorCodeChanged)
+                    .on_submit(LoginMessage::SubmitTwoFactorCode);
+
+            let confirm_button =
+                button(text(t("login-confirm")).horizontal_alignment(Horizontal::Center))
+                    .width(button_width)
+                    .padding(padding)
+                    .on_press_maybe((!self.loading).then_some(LoginMessage::SubmitTwoFactorCode));
+
+            column![two_factor_code_input, confirm_button]
+                .padding(padding)
+                .spacing(spacing)
+                .align_items(Alignment::Center)
+                .into()
+        } else {
+            column![].into()
+        };
+
+        let content = column![
+            login_content,
+            button_row,
+            verification_content,
+            two_factor_content,
+            error_message,
+            info_message
+        ]
+        .spacing(20)
+        .padding(padding)
+        .align_items(Alignment::Center);
 
         let content = Container::new(content)
             .width(Length::Fill)
@@ -193,6 +574,14 @@ impl Login {
     }
 }
 
+/// Setter methods for testing
+impl Login {
+    /// Setter method for the source of the login attempts
+    pub fn set_source(&mut self, source: String) {
+        self.source = Some(source);
+    }
+}
+
 /// Getter methods for testing
 impl Login {
     /// Getter method for the email field
@@ -209,4 +598,189 @@ impl Login {
     pub fn get_error(&self) -> &String {
         &self.error
     }
+
+    /// Getter method for the info message
+    pub fn get_info(&self) -> &String {
+        &self.info
+    }
+
+    /// Getter method for the user that still has to verify the email
+    pub fn get_unverified_user(&self) -> Option<&User> {
+        self.unverified_user.as_ref()
+    }
+
+    /// Getter method for the user that still has to enter the second factor
+    pub fn get_two_factor_user(&self) -> Option<&User> {
+        self.two_factor_user.as_ref()
+    }
+
+    /// Getter method for the loading state
+    pub fn get_loading(&self) -> bool {
+        self.loading
+    }
+}
+
+/// Authenticates a user with email and password. It checks that the login is not throttled after failed attempts,
+/// finds the user by email, finds the user's password, verifies the password (replacing an outdated hash), checks that the email is verified and
+/// whether the second factor is required. Failed attempts are recorded. If the database cannot be reached, the password
+/// unlocks the data cached on this device instead. Returns the outcome or the error message to display
+fn authenticate(email: &str, password: &str, source: Option<&str>) -> Result<LoginOutcome, String> {
+    if !is_database_available() {
+        return offline_login(email, password);
+    }
+    let source = &resolve_source(source)?;
+
+    // Block the attempt if too many attempts have failed
+    check_login_allowed(email, source)?;
+
+    // Find the user by email
+    let found_user = find_user_by_email(email);
+    match found_user {
+        Ok(user) => {
+            // Find the user's password
+            let found_password = find_user_with_password_by_id(user.id);
+            match found_password {
+                Ok(password_data) => {
+                    // Verify the password
+                    match verify_password(password, &password_data.password) {
+                        Ok(true) => {
+                            // Old bcrypt hashes are replaced with Argon2id hashes while the password is known
+                            if let Err(err) = rehash_password_if_needed(
+                                user.id,
+                                password,
+                                &password_data.password,
+                            ) {
+                                error!("Error rehashing password: {:?}", err);
+                            }
+                            return authenticated_outcome(email, user);
+                        }
+                        Ok(false) => {
+                            error!("Password verification failed");
+                        }
+                        Err(err) => {
+                            error!("Error verifying password: {:?}", err);
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!("Error finding password: {:?}", err);
+                }
+            }
+        }
+        Err(err) => {
+            error!("Error finding user: {:?}", err);
+        }
+    }
+
+    // If the login failed, return an error message
+    if let Err(err) = record_failed_login(email, source) {
+        error!("Error recording failed login: {:?}", err);
+    }
+    Err(t("login-failed-credentials"))
+}
+
+/// Unlocks the data cached on this device with the password, so the user can read it while the database cannot be reached.
+/// Returns the offline outcome or the error message to display
+fn offline_login(email: &str, password: &str) -> Result<LoginOutcome, String> {
+    let cache = LocalCache::default()
+        .unlock(email, password)
+        .map_err(|err| {
+            error!("Error unlocking cache: {:?}", err);
+            t("login-failed")
+        })?;
+
+    match cache.load() {
+        Ok(Some(data)) => Ok(LoginOutcome::Offline(cache, Box::new(data))),
+        Ok(None) => Err(t("login-offline-no-data")),
+        Err(err) => {
+            error!("Error loading cache: {:?}", err);
+            Err(t("login-failed-credentials"))
+        }
+    }
+}
+
+/// Decides the outcome for a user whose password was correct. A disabled account cannot log in, the login is blocked until the email is verified,
+/// the second factor is asked for if two-factor authentication is enabled
+fn authenticated_outcome(email: &str, user: User) -> Result<LoginOutcome, String> {
+    let login_failed = |err: String| {
+        error!("Error completing login: {:?}", err);
+        t("login-failed")
+    };
+
+    if is_user_disabled(user.id).map_err(login_failed)? {
+        return Err(t("login-account-disabled"));
+    }
+
+    if is_email_unverified(user.id, email).map_err(login_failed)? {
+        return Ok(LoginOutcome::Unverified(user));
+    }
+
+    if is_totp_enabled(user.id).map_err(login_failed)? {
+        return Ok(LoginOutcome::TwoFactorRequired(user));
+    }
+
+    complete_login(email, user)
+}
+
+/// Verifies the second factor of a user whose password was already checked. Failed attempts are recorded.
+/// Returns the outcome or the error message to display
+fn authenticate_second_factor(
+    email: &str,
+    source: Option<&str>,
+    user: User,
+    code: &str,
+) -> Result<LoginOutcome, String> {
+    let source = &resolve_source(source)?;
+    check_login_allowed(email, source)?;
+
+    match verify_second_factor(user.id, code) {
+        Ok(_) => complete_login(email, user),
+        Err(err) => {
+            error!("Error verifying second factor: {:?}", err);
+            if let Err(err) = record_failed_login(email, source) {
+                error!("Error recording failed login: {:?}", err);
+            }
+            Err(t("login-invalid-two-factor-code"))
+        }
+    }
+}
+
+/// Returns the source of the login attempts, the address of the connection unless a source was set, or the error message to display
+fn resolve_source(source: Option<&str>) -> Result<String, String> {
+    match source {
+        Some(source) => Ok(source.to_string()),
+        None => client_source().map_err(|err| {
+            error!("Error finding the source of the login: {:?}", err);
+            t("login-failed")
+        }),
+    }
+}
+
+/// Checks if a login attempt for the email is allowed. If too many attempts have failed, an error message
+/// with the time to wait is returned. The message is the same for existing and unknown emails
+fn check_login_allowed(email: &str, source: &str) -> Result<(), String> {
+    match login_retry_after(email, source) {
+        Ok(None) => Ok(()),
+        Ok(Some(seconds)) => Err(t_args(
+            "login-too-many-attempts",
+            &[("seconds", seconds.to_string())],
+        )),
+        Err(err) => {
+            error!("Error checking failed logins: {:?}", err);
+            Err(t("login-failed"))
+        }
+    }
+}
+
+/// Resets the failed login attempts of the account after a successful login
+fn complete_login(email: &str, user: User) -> Result<LoginOutcome, String> {
+    if let Err(err) = reset_failed_logins(email) {
+        error!("Error resetting failed logins: {:?}", err);
+    }
+
+    if let Err(err) = KeyStore::default().load_or_create_identity(user.id) {
+        error!("Error loading identity key: {:?}", err);
+    }
+
+    Ok(LoginOutcome::Success(user))
 }
@@ -1,7 +1,20 @@
 pub mod app;
+pub mod chat_export;
+pub mod chat_layout;
+pub mod drafts;
 pub mod group_chat;
 pub mod home;
+pub mod local_cache;
 pub mod login;
+pub mod notification;
+pub mod outbox;
+pub mod preferences;
+pub mod quick_switcher;
 pub mod register;
+pub mod reset_password;
+pub mod session_store;
+pub mod shortcuts;
 pub mod tabs_home;
+pub mod task;
+pub mod theme;
 pub mod user_chat;
@@ -0,0 +1,361 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveTime, Utc};
+
+use crate::backend::{
+    crypto::{
+        direct_message::load_received_direct_messages, group_message::load_group_messages,
+        key_store::KeyStore,
+    },
+    database::models::{Group, NotificationSettings, User},
+    entities::{
+        group_message_ops::find_messages_of_group_since,
+        notification_settings_ops::find_notification_settings, user_ops::find_users_by_ids,
+    },
+};
+use crate::frontend::{
+    preferences::NotificationPreferences,
+    tabs_home::{group_tab::load_group_tab_data, user_tab::find_friend_request_ids},
+};
+use crate::i18n::{t, t_args};
+
+use super::notifier::Notification;
+
+/// Number of characters of a message that are shown in a notification
+const PREVIEW_LENGTH: usize = 100;
+
+/// Something that happened for the current user that the user may want to be notified about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Activity {
+    DirectMessage {
+        sender_id: i32,
+        sender_name: String,
+        message: String,
+    },
+    GroupMessage {
+        group_id: i32,
+        group_name: String,
+        sender_name: String,
+        message: String,
+        mention: bool,
+    },
+    FriendRequest {
+        user_id: i32,
+        name: String,
+    },
+    GroupInvite {
+        group_id: i32,
+        name: String,
+    },
+}
+
+/// The activity of the current user as it was loaded from the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivitySnapshot {
+    /// When the check started
+    pub checked_at: DateTime<Utc>,
+    /// The messages other users sent since the last check
+    pub messages: Vec<Activity>,
+    /// The send date of the newest loaded message
+    pub newest_message: Option<DateTime<Utc>>,
+    /// The users whose friend request has not been answered yet
+    pub friend_requests: Vec<User>,
+    /// The groups whose invite has not been answered yet
+    pub group_invites: Vec<Group>,
+    pub settings: NotificationSettings,
+}
+
+/// Compares the activity of the current user with the last check, so only new activity is notified
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActivityWatcher {
+    since: Option<DateTime<Utc>>,
+    friend_requests: HashSet<i32>,
+    group_invites: HashSet<i32>,
+    // Set while a check is running, so the same messages are not loaded twice
+    checking: bool,
+}
+
+/// Implementation of the activity
+impl Activity {
+    /// Returns if the user wants to be notified about the activity. Nothing is notified during do not disturb,
+    /// muted conversations are only notified when the user is mentioned
+    pub fn is_notified(&self, settings: &NotificationSettings, time: NaiveTime) -> bool {
+        if settings.is_do_not_disturb(time) {
+            return false;
+        }
+
+        match self {
+            Activity::DirectMessage { sender_id, .. } => {
+                !settings.muted_user_ids.contains(sender_id)
+            }
+            Activity::GroupMessage {
+                group_id, mention, ..
+            } => *mention || !settings.muted_group_ids.contains(group_id),
+            Activity::FriendRequest { .. } | Activity::GroupInvite { .. } => true,
+        }
+    }
+
+    /// Returns the notification that is shown for the activity
+    pub fn notification(&self) -> Notification {
+        let (summary, body) = match self {
+            Activity::DirectMessage {
+                sender_name,
+                message,
+                ..
+            } => (
+                t_args(
+                    "notification-direct-message",
+                    &[("name", sender_name.clone())],
+                ),
+                preview(message),
+            ),
+            Activity::GroupMessage {
+                group_name,
+                sender_name,
+                message,
+                mention: true,
+                ..
+            } => (
+                t_args(
+                    "notification-mention",
+                    &[("name", sender_name.clone()), ("group", group_name.clone())],
+                ),
+                preview(message),
+            ),
+            Activity::GroupMessage {
+                group_name,
+                sender_name,
+                message,
+                ..
+            } => (
+                t_args(
+                    "notification-group-message",
+                    &[("group", group_name.clone())],
+                ),
+                format!("{}: {}", sender_name, preview(message)),
+            ),
+            Activity::FriendRequest { name, .. } => (
+                t("notification-friend-request"),
+                t_args(
+                    "notification-friend-request-body",
+                    &[("name", name.clone())],
+                ),
+            ),
+            Activity::GroupInvite { name, .. } => (
+                t("notification-group-invite"),
+                t_args("notification-group-invite-body", &[("group", name.clone())]),
+            ),
+        };
+
+        Notification { summary, body }
+    }
+
+    /// Returns the notification that is shown for the activity without the text of the message
+    pub fn notification_without_preview(&self) -> Notification {
+        let mut notification = self.notification();
+        if let Activity::DirectMessage { .. } | Activity::GroupMessage { .. } = self {
+            notification.body = t("notification-hidden-preview");
+        }
+        notification
+    }
+}
+
+/// Implementation of the activity watcher
+impl ActivityWatcher {
+    /// Returns the date after which messages are new, none before the first check
+    pub fn since(&self) -> Option<DateTime<Utc>> {
+        self.since
+    }
+
+    /// Start a check. Returns false if a check is already running
+    pub fn start_check(&mut self) -> bool {
+        !std::mem::replace(&mut self.checking, true)
+    }
+
+    /// End a check that failed, the next check loads the same messages again
+    pub fn cancel_check(&mut self) {
+        self.checking = false;
+    }
+
+    /// Compare a snapshot with the last check. The first check only remembers the current state, so the user is not
+    /// notified about everything that happened before. Returns the new activity
+    pub fn update(&mut self, snapshot: &ActivitySnapshot) -> Vec<Activity> {
+        let first_check = self.since.is_none();
+        let mut activities = Vec::new();
+        self.checking = false;
+
+        if !first_check {
+            activities.extend(snapshot.messages.iter().cloned());
+        }
+
+        for user in &snapshot.friend_requests {
+            if !first_check && !self.friend_requests.contains(&user.id) {
+                activities.push(Activity::FriendRequest {
+                    user_id: user.id,
+                    name: format!("{} {}", user.first_name, user.last_name),
+                });
+            }
+        }
+
+        for group in &snapshot.group_invites {
+            if !first_check && !self.group_invites.contains(&group.id) {
+                activities.push(Activity::GroupInvite {
+                    group_id: group.id,
+                    name: group.name.clone(),
+                });
+            }
+        }
+
+        self.since = match (self.since, snapshot.newest_message) {
+            (None, _) => Some(snapshot.checked_at),
+            (Some(since), Some(newest_message)) => Some(since.max(newest_message)),
+            (since, None) => since,
+        };
+        self.friend_requests = snapshot
+            .friend_requests
+            .iter()
+            .map(|user| user.id)
+            .collect();
+        self.group_invites = snapshot
+            .group_invites
+            .iter()
+            .map(|group| group.id)
+            .collect();
+
+        activities
+    }
+}
+
+/// Returns the notifications of the activity the user wants to be notified about at the given time.
+/// No notifications are shown if the user turned them off on this device
+pub fn notifications(
+    activities: &[Activity],
+    settings: &NotificationSettings,
+    preferences: &NotificationPreferences,
+    time: NaiveTime,
+) -> Vec<Notification> {
+    if !preferences.enabled {
+        return Vec::new();
+    }
+
+    activities
+        .iter()
+        .filter(|activity| activity.is_notified(settings, time))
+        .map(|activity| {
+            if preferences.show_preview {
+                activity.notification()
+            } else {
+                activity.notification_without_preview()
+            }
+        })
+        .collect()
+}
+
+/// Returns if a message mentions a user with @ and the first name of the user
+pub fn is_mentioned(message: &str, user: &User) -> bool {
+    message
+        .to_lowercase()
+        .contains(&format!("@{}", user.first_name.to_lowercase()))
+}
+
+/// Load the activity of a user: the messages other users sent after the given date, the open friend requests and group invites,
+/// and the notification settings. No messages are loaded before the first check. Returns the snapshot or an error message
+pub fn load_activity(
+    user: &User,
+    since: Option<DateTime<Utc>>,
+) -> Result<ActivitySnapshot, String> {
+    let checked_at = Utc::now();
+    let group_tab_data = load_group_tab_data(user.id)?;
+    let friend_request_ids = find_friend_request_ids(user.id, group_tab_data.friend_relations());
+
+    let mut direct_messages = Vec::new();
+    let mut group_messages = Vec::new();
+    if let Some(since) = since {
+        let key_store = KeyStore::default();
+        direct_messages = load_received_direct_messages(&key_store, user.id, since)?;
+
+        for group in group_tab_data.groups_of_user() {
+            let has_new_messages = find_messages_of_group_since(group.id, since)?
+                .iter()
+                .any(|message| message.sender_id != user.id && message.send_date > since);
+
+            if has_new_messages {
+                group_messages.extend(
+                    load_group_messages(&key_store, user.id, group.id, Some(since))?
+                        .into_iter()
+                        .filter(|message| message.sender_id != user.id && message.send_date > since)
+                        .map(|message| (group.clone(), message)),
+                );
+            }
+        }
+    }
+
+    // Names of everyone that sent a message or a friend request
+    let mut user_ids: Vec<i32> = direct_messages
+        .iter()
+        .map(|message| message.sender_id)
+        .chain(group_messages.iter().map(|(_, message)| message.sender_id))
+        .chain(friend_request_ids.iter().copied())
+        .collect();
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    let users: HashMap<i32, User> = find_users_by_ids(user_ids)?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect();
+    let name_of = |user_id: i32| {
+        users
+            .get(&user_id)
+            .map(|user| format!("{} {}", user.first_name, user.last_name))
+            .unwrap_or_else(|| "Unknown user".to_string())
+    };
+
+    let newest_message = direct_messages
+        .iter()
+        .map(|message| message.send_date)
+        .chain(group_messages.iter().map(|(_, message)| message.send_date))
+        .max();
+
+    let mut messages: Vec<Activity> = direct_messages
+        .into_iter()
+        .map(|message| Activity::DirectMessage {
+            sender_id: message.sender_id,
+            sender_name: name_of(message.sender_id),
+            message: message.display_text(),
+        })
+        .collect();
+    messages.extend(
+        group_messages
+            .into_iter()
+            .map(|(group, message)| Activity::GroupMessage {
+                group_id: group.id,
+                group_name: group.name,
+                sender_name: name_of(message.sender_id),
+                mention: is_mentioned(&message.message, user),
+                message: message.display_text(),
+            }),
+    );
+
+    Ok(ActivitySnapshot {
+        checked_at,
+        messages,
+        newest_message,
+        friend_requests: friend_request_ids
+            .iter()
+            .filter_map(|user_id| users.get(user_id).cloned())
+            .collect(),
+        group_invites: group_tab_data.invited_groups().to_vec(),
+        settings: find_notification_settings(user.id)?,
+    })
+}
+
+/// Returns the start of a message that is short enough for a notification
+fn preview(message: &str) -> String {
+    if message.chars().count() <= PREVIEW_LENGTH {
+        return message.to_string();
+    }
+
+    let mut preview: String = message.chars().take(PREVIEW_LENGTH).collect();
+    preview.push('…');
+    preview
+}
@@ -0,0 +1,22 @@
+use super::notifier::{Notification, Notifier};
+
+/// Name of the app that is shown with the notifications
+const APP_NAME: &str = "SecSE24 Group08";
+
+/// Notifier that shows desktop notifications. On Linux they are sent to the notification service of the
+/// desktop over D-Bus, as described in the freedesktop notification specification
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    /// Show the notification on the desktop
+    fn notify(&self, notification: &Notification) -> Result<(), String> {
+        notify_rust::Notification::new()
+            .appname(APP_NAME)
+            .summary(&notification.summary)
+            .body(&notification.body)
+            .show()
+            .map(|_| ())
+            .map_err(|err| format!("Failed to show notification: {}", err))
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::{Arc, Mutex};
+
+use super::notifier::{Notification, Notifier};
+
+/// Notifier that keeps every notification in memory instead of showing it. Meant for tests
+#[derive(Debug, Clone, Default)]
+pub struct MemoryNotifier {
+    notifications: Arc<Mutex<Vec<Notification>>>,
+}
+
+/// Implementation of the memory notifier
+impl MemoryNotifier {
+    /// Returns the notifications that have been shown so far
+    pub fn notifications(&self) -> Vec<Notification> {
+        self.notifications
+            .lock()
+            .map(|notifications| notifications.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Notifier for MemoryNotifier {
+    /// Store the notification
+    fn notify(&self, notification: &Notification) -> Result<(), String> {
+        self.notifications
+            .lock()
+            .map_err(|_| "Failed to lock notifications".to_string())?
+            .push(notification.clone());
+
+        Ok(())
+    }
+}
+
+/// Notifier that prints every notification to the standard output. Meant for systems without a notification service
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutNotifier;
+
+impl Notifier for StdoutNotifier {
+    /// Print the notification to the standard output
+    fn notify(&self, notification: &Notification) -> Result<(), String> {
+        println!("{}: {}", notification.summary, notification.body);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+pub mod activity;
+pub mod desktop_notifier;
+pub mod memory_notifier;
+pub mod notifier;
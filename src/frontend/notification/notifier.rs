@@ -0,0 +1,32 @@
+use std::fmt;
+use std::sync::Arc;
+
+use super::{desktop_notifier::DesktopNotifier, memory_notifier::StdoutNotifier};
+use crate::config::config;
+
+/// A notification that is shown to the user outside of the app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+}
+
+/// A transport that is able to show a notification to the user
+pub trait Notifier: fmt::Debug + Send + Sync {
+    /// Show a notification. Returns nothing or an error message
+    fn notify(&self, notification: &Notification) -> Result<(), String>;
+}
+
+/// Create the notifier of the configured transport. Returns the notifier or an error message.
+/// The transport is selected with `notifications.transport` or `NOTIFICATION_TRANSPORT`:
+/// - `desktop`, the default: Shows a desktop notification, on Linux through the freedesktop notification service
+/// - `stdout`: Prints the notification to the standard output
+pub fn create_notifier() -> Result<Arc<dyn Notifier>, String> {
+    let transport = config().notifications.transport;
+
+    match transport.to_lowercase().as_str() {
+        "desktop" => Ok(Arc::new(DesktopNotifier)),
+        "stdout" => Ok(Arc::new(StdoutNotifier)),
+        other => Err(format!("Unknown notification transport: {}", other)),
+    }
+}
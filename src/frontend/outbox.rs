@@ -0,0 +1,225 @@
+use chrono::{Duration, Local, NaiveDateTime};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    crypto::{
+        direct_message::send_direct_message, group_message::send_group_message, key_store::KeyStore,
+    },
+    database::models::{GroupMessage, UserMessage},
+};
+
+/// Delay before the first retry of a message that could not be sent. It doubles with every failed attempt
+const RETRY_DELAY_SECONDS: i64 = 5;
+
+/// Longest delay between two retries
+const MAX_RETRY_DELAY_SECONDS: i64 = 300;
+
+/// After this many failed attempts a message is no longer retried automatically
+const MAX_ATTEMPTS: u32 = 5;
+
+/// The chat a queued message is sent to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutboxRecipient {
+    User(i32),
+    Group(i32),
+}
+
+/// The state of a queued message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutboxStatus {
+    /// The message is sent or retried automatically
+    Pending,
+    /// The message is only sent again when the user retries it
+    Failed,
+}
+
+/// A message that has not been stored on the server yet. The client ID is sent with the message,
+/// so a message is stored only once even if it is sent several times
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    pub client_id: String,
+    pub sender_id: i32,
+    pub recipient: OutboxRecipient,
+    pub message: String,
+    pub created_at: NaiveDateTime,
+    pub attempts: u32,
+    pub next_attempt: NaiveDateTime,
+    pub status: OutboxStatus,
+    // Set while the message is being sent, it is not stored
+    #[serde(skip)]
+    pub sending: bool,
+}
+
+/// The messages of the current user that have not been sent yet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Outbox {
+    messages: Vec<OutboxMessage>,
+}
+
+/// A message that was sent from the outbox
+#[derive(Debug, Clone)]
+pub enum SentMessage {
+    User(UserMessage),
+    Group(GroupMessage),
+}
+
+/// Implementation of the outbox message
+impl OutboxMessage {
+    /// Create a new pending message with a random client ID
+    pub fn new(sender_id: i32, recipient: OutboxRecipient, message: &str) -> Self {
+        let mut client_id = [0u8; 16];
+        OsRng.fill_bytes(&mut client_id);
+        let now = Local::now().naive_local();
+
+        OutboxMessage {
+            client_id: hex::encode(client_id),
+            sender_id,
+            recipient,
+            message: message.to_string(),
+            created_at: now,
+            attempts: 0,
+            next_attempt: now,
+            status: OutboxStatus::Pending,
+            sending: false,
+        }
+    }
+
+    /// Returns if the message should be sent now
+    pub fn is_due(&self, now: NaiveDateTime) -> bool {
+        self.status == OutboxStatus::Pending && !self.sending && self.next_attempt <= now
+    }
+
+    /// Record a failed attempt. The delay until the next attempt doubles with every attempt,
+    /// after too many attempts the message has failed
+    pub fn record_failure(&mut self, now: NaiveDateTime) {
+        self.sending = false;
+        self.attempts += 1;
+
+        if self.attempts >= MAX_ATTEMPTS {
+            self.status = OutboxStatus::Failed;
+            return;
+        }
+
+        let delay = (RETRY_DELAY_SECONDS << (self.attempts - 1)).min(MAX_RETRY_DELAY_SECONDS);
+        self.next_attempt = now + Duration::seconds(delay);
+    }
+
+    /// Send the message again at once, e.g. when the user retries a failed message
+    pub fn retry(&mut self, now: NaiveDateTime) {
+        self.status = OutboxStatus::Pending;
+        self.attempts = 0;
+        self.next_attempt = now;
+    }
+}
+
+/// Implementation of the outbox
+impl Outbox {
+    /// Returns the queued messages in the order they were written
+    pub fn messages(&self) -> &[OutboxMessage] {
+        &self.messages
+    }
+
+    /// Returns if no message is queued
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Returns the queued messages of a user to a chat
+    pub fn of_chat(&self, sender_id: i32, recipient: OutboxRecipient) -> Outbox {
+        Outbox {
+            messages: self
+                .messages
+                .iter()
+                .filter(|message| message.sender_id == sender_id && message.recipient == recipient)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Queue a message. A message that is already queued is not added again
+    pub fn push(&mut self, message: OutboxMessage) {
+        if self.find(&message.client_id).is_none() {
+            self.messages.push(message);
+        }
+    }
+
+    /// Queue the messages of another outbox that are not queued yet
+    pub fn merge(&mut self, other: Outbox) {
+        for message in other.messages {
+            self.push(message);
+        }
+    }
+
+    /// Remove a message that was sent
+    pub fn remove(&mut self, client_id: &str) {
+        self.messages
+            .retain(|message| message.client_id != client_id);
+    }
+
+    /// Mark a message as being sent. Returns the message, none if it is not queued
+    pub fn start_sending(&mut self, client_id: &str) -> Option<OutboxMessage> {
+        let message = self.find(client_id)?;
+        message.sending = true;
+        Some(message.clone())
+    }
+
+    /// Mark all messages that are due as being sent. Returns the messages
+    pub fn start_sending_due(&mut self, now: NaiveDateTime) -> Vec<OutboxMessage> {
+        self.messages
+            .iter_mut()
+            .filter(|message| message.is_due(now))
+            .map(|message| {
+                message.sending = true;
+                message.clone()
+            })
+            .collect()
+    }
+
+    /// Record a failed attempt to send a message
+    pub fn record_failure(&mut self, client_id: &str, now: NaiveDateTime) {
+        if let Some(message) = self.find(client_id) {
+            message.record_failure(now);
+        }
+    }
+
+    /// Send a message again at once
+    pub fn retry(&mut self, client_id: &str, now: NaiveDateTime) {
+        if let Some(message) = self.find(client_id) {
+            message.retry(now);
+        }
+    }
+
+    /// Returns the queued message with the client ID
+    fn find(&mut self, client_id: &str) -> Option<&mut OutboxMessage> {
+        self.messages
+            .iter_mut()
+            .find(|message| message.client_id == client_id)
+    }
+}
+
+/// Encrypt and send a queued message to its chat. Returns the sent message or an error message
+pub fn deliver(message: &OutboxMessage) -> Result<SentMessage, String> {
+    let key_store = KeyStore::default();
+    let client_id = Some(message.client_id.as_str());
+
+    match message.recipient {
+        OutboxRecipient::User(receiver_id) => send_direct_message(
+            &key_store,
+            message.sender_id,
+            receiver_id,
+            &message.message,
+            client_id,
+        )
+        .map(SentMessage::User),
+        OutboxRecipient::Group(group_id) => send_group_message(
+            &key_store,
+            message.sender_id,
+            group_id,
+            &message.message,
+            client_id,
+        )
+        .map(SentMessage::Group),
+    }
+}
@@ -0,0 +1,259 @@
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::home::TabId;
+use super::shortcuts::Shortcuts;
+use super::tabs_home::setting_tab::AppTheme;
+use super::theme::CustomTheme;
+use crate::i18n::Language;
+
+/// Name of the directory in the configuration directory of the user
+const APP_DIRECTORY: &str = "secse24_group08";
+
+/// Smallest and largest font scale
+const MIN_FONT_SCALE: f64 = 0.5;
+const MAX_FONT_SCALE: f64 = 3.0;
+
+/// The preferences of the user on this device. Values missing in the file keep their defaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub theme: AppTheme,
+    /// The name of the custom theme that is used if the theme is custom
+    pub custom_theme: String,
+    /// The language of the user interface. The language of the system is used if none is chosen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    /// The time zone in which times are shown, e.g. "Europe/Berlin". The time zone of the system is used if none is chosen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+    pub font_scale: f64,
+    /// The tab of the home page that is shown after the login
+    pub last_tab: TabId,
+    /// Sync the preferences to the account, so they are used on every device of the user
+    pub sync: bool,
+    pub window: WindowGeometry,
+    pub notifications: NotificationPreferences,
+    /// The key bindings of the keyboard shortcuts
+    pub shortcuts: Shortcuts,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub custom_themes: Vec<CustomTheme>,
+}
+
+/// The size and position of the window. The position is set once the window has been moved
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+}
+
+/// How the user is notified on this device. Do not disturb and muted chats are stored with the account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    pub enabled: bool,
+    /// Show the text of new messages in the notifications
+    pub show_preview: bool,
+}
+
+/// The preferences that are synced to the account. The window geometry belongs to the device and is not synced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncedPreferences {
+    pub theme: AppTheme,
+    pub custom_theme: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    pub font_scale: f64,
+    pub last_tab: TabId,
+    pub notifications: NotificationPreferences,
+    pub shortcuts: Shortcuts,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub custom_themes: Vec<CustomTheme>,
+}
+
+/// Stores the preferences in a TOML file in the configuration directory of the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreferencesStore {
+    path: PathBuf,
+}
+
+/// Default implementation for the preferences
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences {
+            theme: AppTheme::Moonfly,
+            custom_theme: String::new(),
+            language: None,
+            time_zone: None,
+            font_scale: 1.0,
+            last_tab: TabId::User,
+            sync: false,
+            window: WindowGeometry::default(),
+            notifications: NotificationPreferences::default(),
+            shortcuts: Shortcuts::default(),
+            custom_themes: Vec::new(),
+        }
+    }
+}
+
+/// Default implementation for the window geometry
+impl Default for WindowGeometry {
+    fn default() -> Self {
+        WindowGeometry {
+            width: 1600.0,
+            height: 900.0,
+            x: None,
+            y: None,
+        }
+    }
+}
+
+/// Default implementation for the notification preferences
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        NotificationPreferences {
+            enabled: true,
+            show_preview: true,
+        }
+    }
+}
+
+/// Default implementation for the synced preferences
+impl Default for SyncedPreferences {
+    fn default() -> Self {
+        Preferences::default().synced()
+    }
+}
+
+/// Implementation of the preferences
+impl Preferences {
+    /// Read preferences from TOML. Returns the preferences or an error message
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let mut preferences: Preferences =
+            toml::from_str(content).map_err(|err| format!("Invalid preferences: {}", err))?;
+        preferences.font_scale = clamp_font_scale(preferences.font_scale);
+        Ok(preferences)
+    }
+
+    /// Write the preferences as TOML. Returns the TOML or an error message
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|err| format!("Failed to serialize preferences: {}", err))
+    }
+
+    /// Returns the preferences that are synced to the account
+    pub fn synced(&self) -> SyncedPreferences {
+        SyncedPreferences {
+            theme: self.theme,
+            custom_theme: self.custom_theme.clone(),
+            language: self.language,
+            font_scale: self.font_scale,
+            last_tab: self.last_tab.clone(),
+            notifications: self.notifications,
+            shortcuts: self.shortcuts.clone(),
+            custom_themes: self.custom_themes.clone(),
+        }
+    }
+
+    /// Returns the custom theme the user selected
+    pub fn selected_custom_theme(&self) -> Option<&CustomTheme> {
+        self.custom_themes
+            .iter()
+            .find(|theme| theme.name == self.custom_theme)
+    }
+
+    /// Use the preferences that were synced from another device. The window geometry and the sync option are kept
+    pub fn apply_synced(&mut self, synced: SyncedPreferences) {
+        self.theme = synced.theme;
+        self.custom_theme = synced.custom_theme;
+        self.custom_themes = synced.custom_themes;
+        self.language = synced.language;
+        self.font_scale = clamp_font_scale(synced.font_scale);
+        self.last_tab = synced.last_tab;
+        self.notifications = synced.notifications;
+        self.shortcuts = synced.shortcuts;
+    }
+}
+
+/// Implementation of the synced preferences
+impl SyncedPreferences {
+    /// Read synced preferences from TOML. Returns the preferences or an error message
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        toml::from_str(content).map_err(|err| format!("Invalid preferences: {}", err))
+    }
+
+    /// Write the synced preferences as TOML. Returns the TOML or an error message
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|err| format!("Failed to serialize preferences: {}", err))
+    }
+}
+
+/// Default implementation for the PreferencesStore struct
+impl Default for PreferencesStore {
+    /// The path is read from PREFERENCES_PATH, otherwise the configuration directory of the user is used
+    fn default() -> Self {
+        let path = env::var("PREFERENCES_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                dirs::config_dir()
+                    .unwrap_or_else(env::temp_dir)
+                    .join(APP_DIRECTORY)
+                    .join("preferences.toml")
+            });
+
+        PreferencesStore::new(path)
+    }
+}
+
+/// Implementation of the preferences store
+impl PreferencesStore {
+    /// Create a new preferences store that uses the given file
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        PreferencesStore { path: path.into() }
+    }
+
+    /// Load the stored preferences. The defaults are used if no preferences are stored or the file is invalid
+    pub fn load(&self) -> Preferences {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Preferences::from_toml(&content).unwrap_or_else(|err| {
+                error!("Error loading preferences: {}", err);
+                Preferences::default()
+            }),
+            Err(err) if err.kind() == ErrorKind::NotFound => Preferences::default(),
+            Err(err) => {
+                error!("Error reading preferences file: {}", err);
+                Preferences::default()
+            }
+        }
+    }
+
+    /// Store the preferences. Existing preferences are replaced. Returns nothing or an error message
+    pub fn save(&self, preferences: &Preferences) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create preferences directory: {}", err))?;
+        }
+
+        fs::write(&self.path, preferences.to_toml()?)
+            .map_err(|err| format!("Failed to write preferences file: {}", err))
+    }
+}
+
+/// Returns the font scale within the supported range. An invalid scale is reset
+pub fn clamp_font_scale(font_scale: f64) -> f64 {
+    if font_scale.is_finite() {
+        font_scale.clamp(MIN_FONT_SCALE, MAX_FONT_SCALE)
+    } else {
+        1.0
+    }
+}
@@ -0,0 +1,199 @@
+use iced::{
+    alignment::Horizontal,
+    widget::{button, column, row, text, text_input, Column},
+    Alignment, Command, Element, Length,
+};
+use log::error;
+
+use crate::backend::{
+    database::models::ConversationSummary, entities::conversation_ops::find_conversation_summaries,
+};
+use crate::i18n::t;
+
+use super::task::run_blocking;
+use super::theme::{error_color, input_style};
+
+/// Number of chats that are listed at most
+const MAX_RESULTS: usize = 8;
+
+/// Represents the quick switcher, which jumps to a chat with a friend or a group by its name
+#[derive(Debug, Clone)]
+pub struct QuickSwitcher {
+    query: String,
+    // The chats of the current user, the newest chat first
+    conversations: Vec<ConversationSummary>,
+    // Index of the selected chat in the matching chats
+    selected: usize,
+}
+
+/// Represents the messages that can be sent to the quick switcher
+#[derive(Debug, Clone)]
+pub enum QuickSwitcherMessage {
+    QueryChanged(String),
+    ConversationsLoaded(Result<Vec<ConversationSummary>, String>),
+    SelectNext,
+    SelectPrevious,
+    // Opening a chat and closing the switcher are handled by the app
+    OpenSelected,
+    Open(ConversationSummary),
+    Close,
+}
+
+/// Implementation of the quick switcher
+impl QuickSwitcher {
+    /// Creates a quick switcher that lists the given chats until the chats of the user are loaded
+    pub fn new(conversations: Vec<ConversationSummary>) -> Self {
+        QuickSwitcher {
+            query: String::new(),
+            conversations,
+            selected: 0,
+        }
+    }
+
+    /// Returns the ID of the input, so it can be focused when the switcher is opened
+    pub fn input_id() -> text_input::Id {
+        text_input::Id::new("quick_switcher")
+    }
+
+    /// Loads the chats of a user in the background. Only the names are needed, so nothing is decrypted
+    pub fn load(user_id: i32) -> Command<QuickSwitcherMessage> {
+        Command::perform(
+            run_blocking(move || find_conversation_summaries(user_id)),
+            QuickSwitcherMessage::ConversationsLoaded,
+        )
+    }
+
+    /// Returns the chats whose name contains the query, chats whose name starts with it first
+    pub fn matches(&self) -> Vec<&ConversationSummary> {
+        let query = self.query.trim().to_lowercase();
+        let (mut starting, containing): (Vec<_>, Vec<_>) = self
+            .conversations
+            .iter()
+            .filter(|conversation| conversation.display_name().to_lowercase().contains(&query))
+            .partition(|conversation| {
+                conversation
+                    .display_name()
+                    .to_lowercase()
+                    .starts_with(&query)
+            });
+
+        starting.extend(containing);
+        starting.truncate(MAX_RESULTS);
+        starting
+    }
+
+    /// Returns the selected chat, none if no chat matches the query
+    pub fn selected(&self) -> Option<ConversationSummary> {
+        self.matches()
+            .get(self.selected)
+            .map(|&conversation| conversation.clone())
+    }
+
+    /// Updates the quick switcher based on the message
+    pub fn update(&mut self, message: QuickSwitcherMessage) -> Command<QuickSwitcherMessage> {
+        match message {
+            QuickSwitcherMessage::QueryChanged(query) => {
+                self.query = query;
+                self.selected = 0;
+            }
+            QuickSwitcherMessage::ConversationsLoaded(result) => match result {
+                Ok(conversations) => self.conversations = conversations,
+                Err(e) => error!("Error loading chats: {}", e),
+            },
+            QuickSwitcherMessage::SelectNext => {
+                let count = self.matches().len();
+                if count > 0 {
+                    self.selected = (self.selected + 1) % count;
+                }
+            }
+            QuickSwitcherMessage::SelectPrevious => {
+                let count = self.matches().len();
+                if count > 0 {
+                    self.selected = (self.selected + count - 1) % count;
+                }
+            }
+            QuickSwitcherMessage::OpenSelected
+            | QuickSwitcherMessage::Open(_)
+            | QuickSwitcherMessage::Close => {}
+        }
+
+        Command::none()
+    }
+
+    /// Returns the view of the quick switcher
+    pub fn view(&self) -> Element<'_, QuickSwitcherMessage> {
+        let input_width = 400;
+        let padding = 10;
+        let spacing = 10;
+
+        let input = text_input(&t("quick-switcher-input"), &self.query)
+            .style(input_style())
+            .id(Self::input_id())
+            .width(input_width)
+            .padding(padding)
+            .on_input(QuickSwitcherMessage::QueryChanged)
+            .on_submit(QuickSwitcherMessage::OpenSelected);
+
+        let close_button = button(text(t("quick-switcher-close")))
+            .padding(padding)
+            .on_press(QuickSwitcherMessage::Close);
+
+        let matches = self.matches();
+        let results: Element<QuickSwitcherMessage> = if matches.is_empty() {
+            text(t("quick-switcher-no-results"))
+                .style(error_color())
+                .into()
+        } else {
+            matches
+                .into_iter()
+                .enumerate()
+                .fold(
+                    Column::new().spacing(spacing / 2),
+                    |results, (index, conversation)| {
+                        let icon = if conversation.is_group {
+                            '👥'
+                        } else {
+                            '👤'
+                        };
+                        let marker = if index == self.selected { "▶ " } else { "" };
+
+                        results.push(
+                            button(
+                                text(format!(
+                                    "{}{} {}",
+                                    marker,
+                                    icon,
+                                    conversation.display_name()
+                                ))
+                                .horizontal_alignment(Horizontal::Left),
+                            )
+                            .width(input_width)
+                            .padding(padding / 2)
+                            .on_press(QuickSwitcherMessage::Open(conversation.clone())),
+                        )
+                    },
+                )
+                .into()
+        };
+
+        column![
+            row!(input, close_button)
+                .spacing(spacing)
+                .align_items(Alignment::Center),
+            results
+        ]
+        .spacing(spacing)
+        .padding(padding)
+        .width(Length::Fill)
+        .align_items(Alignment::Center)
+        .into()
+    }
+}
+
+/// Getter methods for testing
+impl QuickSwitcher {
+    /// Gets the query
+    pub fn get_query(&self) -> &str {
+        &self.query
+    }
+}
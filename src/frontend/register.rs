@@ -1,13 +1,21 @@
 use iced::{
     alignment::Horizontal,
     widget::{button, column, row, text, text_input, Container},
-    Alignment, Color, Element, Length,
+    Alignment, Command, Element, Length,
 };
+use log::error;
+
+use super::task::run_blocking;
+use super::theme::{error_color, input_style, success_color};
 
 use crate::backend::{
-    database::db::{is_strong_password, is_valid_email},
-    entities::user_ops::create_user,
+    crypto::key_store::KeyStore,
+    database::db::is_valid_email,
+    entities::{pending_verification_ops::request_email_verification, user_ops::create_user},
+    mail::mailer::create_mailer,
 };
+use crate::config::config;
+use crate::i18n::t;
 
 /// Represents the state of the registration page
 #[derive(Default, Debug, Clone)]
@@ -18,6 +26,8 @@ pub struct Register {
     last_name: String,
     error: String,
     info: String,
+    // Set while the registration is in flight
+    loading: bool,
 }
 
 /// Represents the messages that can be sent to the registration page
@@ -28,50 +38,62 @@ pub enum RegisterMessage {
     FirstNameChanged(String),
     LastNameChanged(String),
     SubmitRegister,
+    RegisterFinished(Result<String, String>),
     NavigateToLogin,
 }
 
 /// Implementation of the registration page
 impl Register {
     /// Handles the registration process. It checks if the email, password, first name, and last name fields are empty,
-    /// validates the email and password, and creates a new user. If the registration fails, it displays an error message.
-    fn submit_register(&mut self) {
+    /// validates the email and password and starts the registration in the background.
+    fn submit_register(&mut self) -> Command<RegisterMessage> {
         if self.email.is_empty()
             || self.password.is_empty()
             || self.first_name.is_empty()
             || self.last_name.is_empty()
         {
-            self.error = "Please fill in all fields".to_string();
+            self.error = t("register-fill-in-fields");
             self.info = String::new();
         } else if !is_valid_email(&self.email) {
-            self.error = "Invalid email format".to_string();
+            self.error = t("invalid-email-format");
             self.info = String::new();
-        } else if !is_strong_password(&self.password) {
-            self.error = "Password must be at least 8 characters long and contain at least one uppercase letter, one lowercase letter, one digit, and one special character".to_string();
+        } else if let Err(err) = config().password_policy.check(&self.password) {
+            self.error = err;
             self.info = String::new();
         } else {
-            let result = create_user(
-                &self.first_name,
-                &self.last_name,
-                &self.email.to_lowercase(),
-                &self.password,
+            let first_name = self.first_name.clone();
+            let last_name = self.last_name.clone();
+            let email = self.email.to_lowercase();
+            let password = self.password.clone();
+
+            self.loading = true;
+            return Command::perform(
+                run_blocking(move || register(&first_name, &last_name, &email, &password)),
+                RegisterMessage::RegisterFinished,
             );
+        }
 
-            match result {
-                Ok(_user) => {
-                    self.error = String::new();
-                    self.info = "Account has been registered".to_string();
-                }
-                Err(error) => {
-                    self.error = error;
-                    self.info = String::new()
-                }
+        Command::none()
+    }
+
+    /// Handles the result of the registration. Displays the info message on success, the error message otherwise
+    fn handle_register_finished(&mut self, result: Result<String, String>) {
+        self.loading = false;
+
+        match result {
+            Ok(info) => {
+                self.error = String::new();
+                self.info = info;
+            }
+            Err(error) => {
+                self.error = error;
+                self.info = String::new();
             }
         }
     }
 
     /// Updates the registration page based on the message received
-    pub fn update(&mut self, message: RegisterMessage) {
+    pub fn update(&mut self, message: RegisterMessage) -> Command<RegisterMessage> {
         match message {
             // Handle email change event
             RegisterMessage::EmailChanged(email) => {
@@ -89,12 +111,20 @@ impl Register {
             RegisterMessage::LastNameChanged(last_name) => {
                 self.last_name = last_name;
             }
-            // Handle register submission event
+            // Handle register submission event, unless a registration is already in flight
             RegisterMessage::SubmitRegister => {
-                self.submit_register();
+                if !self.loading {
+                    return self.submit_register();
+                }
+            }
+            // Handle the result of the registration
+            RegisterMessage::RegisterFinished(result) => {
+                self.handle_register_finished(result);
             }
             RegisterMessage::NavigateToLogin => {}
         }
+
+        Command::none()
     }
 
     /// Returns the view of the registration page
@@ -104,36 +134,41 @@ impl Register {
         let padding = 10;
         let spacing = 20;
 
-        let registration = text("Registration:").size(30);
+        let registration = text(t("register-title")).size(30);
 
-        let first_name_input = text_input("First Name", self.first_name.as_str())
+        let first_name_input = text_input(&t("register-first-name"), self.first_name.as_str())
+            .style(input_style())
             .width(input_width)
             .padding(padding)
             .on_input(RegisterMessage::FirstNameChanged);
 
-        let last_name_input = text_input("Last Name", self.last_name.as_str())
+        let last_name_input = text_input(&t("register-last-name"), self.last_name.as_str())
+            .style(input_style())
             .width(input_width)
             .padding(padding)
             .on_input(RegisterMessage::LastNameChanged);
 
-        let email_input = text_input("Email", self.email.as_str())
+        let email_input = text_input(&t("email"), self.email.as_str())
+            .style(input_style())
             .width(input_width)
             .padding(padding)
             .on_input(RegisterMessage::EmailChanged);
 
-        let password_input = text_input("Password", self.password.as_str())
+        let password_input = text_input(&t("password"), self.password.as_str())
+            .style(input_style())
             .width(input_width)
             .padding(padding)
             .secure(true)
             .on_input(RegisterMessage::PasswordChanged)
             .on_submit(RegisterMessage::SubmitRegister);
 
-        let register_button = button(text("Register").horizontal_alignment(Horizontal::Center))
-            .width(button_width)
-            .padding(padding)
-            .on_press(RegisterMessage::SubmitRegister);
+        let register_button =
+            button(text(t("register-button")).horizontal_alignment(Horizontal::Center))
+                .width(button_width)
+                .padding(padding)
+                .on_press_maybe((!self.loading).then_some(RegisterMessage::SubmitRegister));
 
-        let back_button = button(text("Back").horizontal_alignment(Horizontal::Center))
+        let back_button = button(text(t("back")).horizontal_alignment(Horizontal::Center))
             .width(button_width)
             .padding(padding)
             .on_press(RegisterMessage::NavigateToLogin);
@@ -141,17 +176,23 @@ impl Register {
         let error_message: Element<RegisterMessage> = if !self.error.is_empty() {
             text(&self.error)
                 .size(15)
-                .style(Color::from_rgb(1.0, 0.0, 0.0)) // Red color
+                .style(error_color())
                 .horizontal_alignment(Horizontal::Center)
                 .into()
         } else {
             text("").into()
         };
 
-        let info_message: Element<RegisterMessage> = if !self.info.is_empty() {
+        let info_message: Element<RegisterMessage> = if self.loading {
+            text(t("register-loading"))
+                .size(15)
+                .style(success_color())
+                .horizontal_alignment(Horizontal::Center)
+                .into()
+        } else if !self.info.is_empty() {
             text(&self.info)
                 .size(15)
-                .style(Color::from_rgb(0.2, 0.8, 0.2)) //Green color
+                .style(success_color())
                 .horizontal_alignment(Horizontal::Center)
                 .into()
         } else {
@@ -237,3 +278,29 @@ impl Register {
         self.info = info;
     }
 }
+
+/// Creates a new user and sends a verification code to the email. Returns the info message to display or the error message
+fn register(
+    first_name: &str,
+    last_name: &str,
+    email: &str,
+    password: &str,
+) -> Result<String, String> {
+    let user = create_user(first_name, last_name, email, password)?;
+
+    // The identity key is created again on the first login if this fails
+    if let Err(err) = KeyStore::default().create_identity(user.id) {
+        error!("Error creating identity key: {}", err);
+    }
+
+    let result =
+        create_mailer().and_then(|mailer| request_email_verification(user.id, email, &*mailer));
+
+    match result {
+        Ok(_) => Ok(t("register-success")),
+        Err(error) => {
+            error!("Error sending verification email: {}", error);
+            Err(t("register-verification-email-failed"))
+        }
+    }
+}
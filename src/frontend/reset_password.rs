@@ -0,0 +1,288 @@
+use iced::{
+    alignment::Horizontal,
+    widget::{button, column, row, text, text_input, Container},
+    Alignment, Command, Element, Length,
+};
+use log::error;
+
+use super::task::run_blocking;
+use super::theme::{error_color, input_style, success_color};
+
+use crate::backend::{
+    entities::password_reset_ops::{request_password_reset, reset_password},
+    mail::mailer::create_mailer,
+};
+use crate::config::config;
+use crate::i18n::t;
+
+/// Represents the state of the reset password page
+#[derive(Default, Debug, Clone)]
+pub struct ResetPassword {
+    email: String,
+    token: String,
+    new_password: String,
+    error: String,
+    info: String,
+    // Set while the reset is in flight
+    loading: bool,
+}
+
+/// Represents the messages that can be sent to the reset password page
+#[derive(Debug, Clone)]
+pub enum ResetPasswordMessage {
+    EmailChanged(String),
+    TokenChanged(String),
+    NewPasswordChanged(String),
+    ResendResetCode,
+    ResetCodeSent(Result<(), String>),
+    SubmitReset,
+    ResetFinished(Result<(), String>),
+    NavigateToLogin,
+}
+
+/// Implementation of the reset password page
+impl ResetPassword {
+    /// Sets the email the reset code was requested for
+    pub fn set_email(&mut self, email: String) {
+        self.email = email;
+    }
+
+    /// Sets the info message
+    pub fn set_info(&mut self, info: String) {
+        self.info = info;
+    }
+
+    /// Sends a new reset code to the email in the background. The result does not reveal if an account exists for the email
+    fn resend_reset_code(&mut self) -> Command<ResetPasswordMessage> {
+        if self.email.is_empty() {
+            self.error = t("reset-email-empty");
+            self.info = String::new();
+            return Command::none();
+        }
+
+        let email = self.email.to_lowercase();
+
+        Command::perform(
+            run_blocking(move || {
+                create_mailer().and_then(|mailer| request_password_reset(&email, &*mailer))
+            }),
+            ResetPasswordMessage::ResetCodeSent,
+        )
+    }
+
+    /// Handles the result of sending a new reset code
+    fn handle_reset_code_sent(&mut self, result: Result<(), String>) {
+        match result {
+            Ok(_) => {
+                self.error = String::new();
+                self.info = t("reset-code-sent");
+            }
+            Err(err) => {
+                error!("Error sending password reset email: {}", err);
+                self.error = t("reset-email-failed");
+                self.info = String::new();
+            }
+        }
+    }
+
+    /// Handles the reset process. It checks if the fields are empty and the new password is strong,
+    /// and resets the password with the reset code in the background.
+    fn submit_reset(&mut self) -> Command<ResetPasswordMessage> {
+        if self.token.is_empty() || self.new_password.is_empty() {
+            self.error = t("reset-fill-in-fields");
+            self.info = String::new();
+        } else if let Err(err) = config().password_policy.check(&self.new_password) {
+            self.error = err;
+            self.info = String::new();
+        } else {
+            let token = self.token.trim().to_string();
+            let new_password = self.new_password.clone();
+
+            self.loading = true;
+            return Command::perform(
+                run_blocking(move || reset_password(&token, &new_password)),
+                ResetPasswordMessage::ResetFinished,
+            );
+        }
+
+        Command::none()
+    }
+
+    /// Handles the result of the reset. If the reset fails, it displays an error message.
+    fn handle_reset_finished(&mut self, result: Result<(), String>) {
+        self.loading = false;
+
+        match result {
+            Ok(_) => {
+                self.token = String::new();
+                self.new_password = String::new();
+                self.error = String::new();
+                self.info = t("reset-success");
+            }
+            Err(error) => {
+                self.error = error;
+                self.info = String::new();
+            }
+        }
+    }
+
+    /// Updates the reset password page based on the message received
+    pub fn update(&mut self, message: ResetPasswordMessage) -> Command<ResetPasswordMessage> {
+        match message {
+            // Handle email change event
+            ResetPasswordMessage::EmailChanged(email) => {
+                self.email = email;
+            }
+            // Handle reset code change event
+            ResetPasswordMessage::TokenChanged(token) => {
+                self.token = token;
+            }
+            // Handle new password change event
+            ResetPasswordMessage::NewPasswordChanged(new_password) => {
+                self.new_password = new_password;
+            }
+            // Handle resend reset code event
+            ResetPasswordMessage::ResendResetCode => {
+                return self.resend_reset_code();
+            }
+            // Handle the result of sending a new reset code
+            ResetPasswordMessage::ResetCodeSent(result) => {
+                self.handle_reset_code_sent(result);
+            }
+            // Handle reset submission event, unless a reset is already in flight
+            ResetPasswordMessage::SubmitReset => {
+                if !self.loading {
+                    return self.submit_reset();
+                }
+            }
+            // Handle the result of the reset
+            ResetPasswordMessage::ResetFinished(result) => {
+                self.handle_reset_finished(result);
+            }
+            // Is handled in the main app
+            ResetPasswordMessage::NavigateToLogin => {}
+        }
+
+        Command::none()
+    }
+
+    /// Returns the view of the reset password page
+    pub fn view(&self) -> Element<'_, ResetPasswordMessage> {
+        let input_width = 300;
+        let button_width = 140;
+        let padding = 10;
+        let spacing = 20;
+
+        let reset_password = text(t("reset-title")).size(30);
+
+        let email_input = text_input(&t("email"), self.email.as_str())
+            .style(input_style())
+            .width(input_width)
+            .padding(padding)
+            .on_input(ResetPasswordMessage::EmailChanged);
+
+        let token_input = text_input(&t("reset-code"), self.token.as_str())
+            .style(input_style())
+            .width(input_width)
+            .padding(padding)
+            .on_input(ResetPasswordMessage::TokenChanged);
+
+        let new_password_input = text_input(&t("reset-new-password"), self.new_password.as_str())
+            .style(input_style())
+            .width(input_width)
+            .padding(padding)
+            .secure(true)
+            .on_input(ResetPasswordMessage::NewPasswordChanged)
+            .on_submit(ResetPasswordMessage::SubmitReset);
+
+        let reset_button = button(text(t("reset-button")).horizontal_alignment(Horizontal::Center))
+            .width(button_width)
+            .padding(padding)
+            .on_press_maybe((!self.loading).then_some(ResetPasswordMessage::SubmitReset));
+
+        let resend_button = button(text(t("resend-code")).horizontal_alignment(Horizontal::Center))
+            .width(button_width)
+            .padding(padding)
+            .on_press(ResetPasswordMessage::ResendResetCode);
+
+        let back_button = button(text(t("back")).horizontal_alignment(Horizontal::Center))
+            .width(button_width)
+            .padding(padding)
+            .on_press(ResetPasswordMessage::NavigateToLogin);
+
+        let error_message: Element<ResetPasswordMessage> = if !self.error.is_empty() {
+            text(&self.error)
+                .size(15)
+                .style(error_color())
+                .horizontal_alignment(Horizontal::Center)
+                .into()
+        } else {
+            text("").into()
+        };
+
+        let info_message: Element<ResetPasswordMessage> = if self.loading {
+            text(t("reset-loading"))
+                .size(15)
+                .style(success_color())
+                .horizontal_alignment(Horizontal::Center)
+                .into()
+        } else if !self.info.is_empty() {
+            text(&self.info)
+                .size(15)
+                .style(success_color())
+                .horizontal_alignment(Horizontal::Center)
+                .into()
+        } else {
+            text("").into()
+        };
+
+        let reset_content = column![reset_password, email_input, token_input, new_password_input]
+            .spacing(spacing)
+            .padding(padding);
+
+        let buttons_row = row![reset_button, resend_button, back_button]
+            .spacing(spacing)
+            .padding(padding);
+
+        let content = column![reset_content, buttons_row, error_message, info_message]
+            .spacing(spacing)
+            .padding(padding)
+            .align_items(Alignment::Center);
+
+        let content = Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y();
+
+        content.into()
+    }
+}
+
+/// Getter methods for testing
+impl ResetPassword {
+    /// Gets the email
+    pub fn get_email(&self) -> &String {
+        &self.email
+    }
+
+    /// Gets the reset code
+    pub fn get_token(&self) -> &String {
+        &self.token
+    }
+
+    /// Gets the new password
+    pub fn get_new_password(&self) -> &String {
+        &self.new_password
+    }
+
+    /// Gets the error message
+    pub fn get_error(&self) -> &String {
+        &self.error
+    }
+
+    /// Gets the info message
+    pub fn get_info(&self) -> &String {
+        &self.info
+    }
+}
@@ -0,0 +1,116 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Name of the directory in the configuration directory of the user
+const APP_DIRECTORY: &str = "secse24_group08";
+
+/// Stores the session token of a remembered login on the client. The token is written to a file
+/// that only the current user can read, so the session survives a restart of the application.
+/// The key of the local cache is stored beside it, because a restored session has no password to unlock the cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionStore {
+    path: PathBuf,
+}
+
+/// Default implementation for the SessionStore struct
+impl Default for SessionStore {
+    /// The path is read from SESSION_FILE_PATH, otherwise the configuration directory of the user is used
+    fn default() -> Self {
+        let path = env::var("SESSION_FILE_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                dirs::config_dir()
+                    .unwrap_or_else(env::temp_dir)
+                    .join(APP_DIRECTORY)
+                    .join("session")
+            });
+
+        SessionStore::new(path)
+    }
+}
+
+/// Implementation of the session store
+impl SessionStore {
+    /// Create a new session store that uses the given file
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        SessionStore { path: path.into() }
+    }
+
+    /// Load the stored session token. Returns the token or none if no token is stored
+    pub fn load(&self) -> Option<String> {
+        let token = fs::read_to_string(&self.path).ok()?;
+        let token = token.trim();
+
+        if token.is_empty() {
+            None
+        } else {
+            Some(token.to_string())
+        }
+    }
+
+    /// Store a session token. An existing token is replaced. Returns nothing or an error message
+    pub fn save(&self, token: &str) -> Result<(), String> {
+        write_private_file(&self.path, token)
+    }
+
+    /// Load the stored key of the local cache. Returns the key or none if no key is stored
+    pub fn load_cache_key(&self) -> Option<String> {
+        let cache_key = fs::read_to_string(self.cache_key_path()).ok()?;
+        let cache_key = cache_key.trim();
+
+        if cache_key.is_empty() {
+            None
+        } else {
+            Some(cache_key.to_string())
+        }
+    }
+
+    /// Store the key of the local cache of the remembered session. An existing key is replaced. Returns nothing or an error message
+    pub fn save_cache_key(&self, cache_key: &str) -> Result<(), String> {
+        write_private_file(&self.cache_key_path(), cache_key)
+    }
+
+    /// Remove the stored session token and the key of the local cache. Returns nothing or an error message
+    pub fn clear(&self) -> Result<(), String> {
+        for path in [self.path.clone(), self.cache_key_path()] {
+            match fs::remove_file(&path) {
+                Ok(_) => {}
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(format!("Failed to remove session file: {}", err)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the path of the file with the key of the local cache
+    fn cache_key_path(&self) -> PathBuf {
+        self.path.with_extension("key")
+    }
+}
+
+/// Write a file that only the current user can read. An existing file is replaced. Returns nothing or an error message
+fn write_private_file(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Failed to create session directory: {}", err))?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.create(true).write(true).truncate(true);
+
+    // Only the current user may read the file
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options
+        .open(path)
+        .map_err(|err| format!("Failed to open session file: {}", err))?;
+
+    file.write_all(content.as_bytes())
+        .map_err(|err| format!("Failed to write session file: {}", err))
+}
@@ -0,0 +1,294 @@
+use std::fmt;
+
+use iced::keyboard::{key::Named, Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{t, t_args};
+
+/// Keys without a character and the names they are shown and entered with
+const NAMED_KEYS: [(Named, &str); 24] = [
+    (Named::Escape, "Esc"),
+    (Named::ArrowUp, "Up"),
+    (Named::ArrowDown, "Down"),
+    (Named::ArrowLeft, "Left"),
+    (Named::ArrowRight, "Right"),
+    (Named::Enter, "Enter"),
+    (Named::Tab, "Tab"),
+    (Named::Space, "Space"),
+    (Named::Home, "Home"),
+    (Named::End, "End"),
+    (Named::PageUp, "PageUp"),
+    (Named::PageDown, "PageDown"),
+    (Named::F1, "F1"),
+    (Named::F2, "F2"),
+    (Named::F3, "F3"),
+    (Named::F4, "F4"),
+    (Named::F5, "F5"),
+    (Named::F6, "F6"),
+    (Named::F7, "F7"),
+    (Named::F8, "F8"),
+    (Named::F9, "F9"),
+    (Named::F10, "F10"),
+    (Named::F11, "F11"),
+    (Named::F12, "F12"),
+];
+
+/// The actions that can be started with the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shortcut {
+    QuickSwitcher,
+    Back,
+    ChatsTab,
+    FriendsTab,
+    GroupsTab,
+    EditLastMessage,
+    Search,
+}
+
+/// A key together with the modifiers that have to be held, e.g. "Ctrl+K".
+/// Ctrl is the command key on macOS
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyBinding {
+    key: String,
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+}
+
+/// The key bindings of all shortcuts. Bindings missing in the preferences keep their defaults
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Shortcuts {
+    pub quick_switcher: KeyBinding,
+    pub back: KeyBinding,
+    pub chats_tab: KeyBinding,
+    pub friends_tab: KeyBinding,
+    pub groups_tab: KeyBinding,
+    pub edit_last_message: KeyBinding,
+    pub search: KeyBinding,
+}
+
+/// Implementation of the shortcut
+impl Shortcut {
+    /// All shortcuts in the order they are shown in the settings
+    pub const ALL: [Shortcut; 7] = [
+        Shortcut::QuickSwitcher,
+        Shortcut::Back,
+        Shortcut::ChatsTab,
+        Shortcut::FriendsTab,
+        Shortcut::GroupsTab,
+        Shortcut::EditLastMessage,
+        Shortcut::Search,
+    ];
+
+    /// Returns the description that is shown in the settings
+    pub fn label(&self) -> String {
+        match self {
+            Shortcut::QuickSwitcher => t("shortcut-quick-switcher"),
+            Shortcut::Back => t("shortcut-back"),
+            Shortcut::ChatsTab => t("shortcut-chats-tab"),
+            Shortcut::FriendsTab => t("shortcut-friends-tab"),
+            Shortcut::GroupsTab => t("shortcut-groups-tab"),
+            Shortcut::EditLastMessage => t("shortcut-edit-last-message"),
+            Shortcut::Search => t("shortcut-search"),
+        }
+    }
+}
+
+/// Implementation of the key binding
+impl KeyBinding {
+    /// Returns a binding of a key that is pressed with Ctrl
+    fn ctrl(key: &str) -> Self {
+        KeyBinding {
+            key: key.to_string(),
+            ctrl: true,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    /// Returns a binding of a key that is pressed without modifiers
+    fn plain(key: &str) -> Self {
+        KeyBinding {
+            key: key.to_string(),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    /// Parse a binding like "Ctrl+K", "Ctrl+Shift+F" or "Esc". Returns the binding or an error message
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || t_args("shortcut-invalid", &[("binding", value.trim().to_string())]);
+
+        let mut parts: Vec<&str> = value.split('+').map(str::trim).collect();
+        let key = parts
+            .pop()
+            .filter(|key| !key.is_empty())
+            .ok_or_else(invalid)?;
+
+        let mut binding = KeyBinding::plain("");
+        for modifier in parts {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" | "strg" | "cmd" => binding.ctrl = true,
+                "alt" | "option" => binding.alt = true,
+                "shift" => binding.shift = true,
+                _ => return Err(invalid()),
+            }
+        }
+
+        binding.key = if key.chars().count() == 1 {
+            key.to_lowercase()
+        } else {
+            NAMED_KEYS
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(key))
+                .map(|(_, name)| name.to_string())
+                .ok_or_else(invalid)?
+        };
+
+        // Without a modifier a character would trigger the shortcut while typing
+        if binding.is_character() && !binding.ctrl && !binding.alt {
+            return Err(t_args(
+                "shortcut-needs-modifier",
+                &[("binding", binding.to_string())],
+            ));
+        }
+
+        Ok(binding)
+    }
+
+    /// Returns if the binding is pressed
+    pub fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        key_name(key).as_deref() == Some(self.key.as_str())
+            && self.ctrl == modifiers.command()
+            && self.alt == modifiers.alt()
+            && self.shift == modifiers.shift()
+    }
+
+    /// Returns if the key of the binding is a character
+    fn is_character(&self) -> bool {
+        self.key.chars().count() == 1
+    }
+}
+
+/// Displays the binding as it is entered, e.g. "Ctrl+K"
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        // Characters are shown in upper case, like they are printed on the keyboard
+        if self.is_character() {
+            write!(f, "{}", self.key.to_uppercase())
+        } else {
+            write!(f, "{}", self.key)
+        }
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        KeyBinding::parse(&value)
+    }
+}
+
+impl From<KeyBinding> for String {
+    fn from(binding: KeyBinding) -> Self {
+        binding.to_string()
+    }
+}
+
+/// Default implementation for the shortcuts
+impl Default for Shortcuts {
+    fn default() -> Self {
+        Shortcuts {
+            quick_switcher: KeyBinding::ctrl("k"),
+            back: KeyBinding::plain("Esc"),
+            chats_tab: KeyBinding::ctrl("1"),
+            friends_tab: KeyBinding::ctrl("2"),
+            groups_tab: KeyBinding::ctrl("3"),
+            edit_last_message: KeyBinding::plain("Up"),
+            search: KeyBinding::ctrl("f"),
+        }
+    }
+}
+
+/// Implementation of the shortcuts
+impl Shortcuts {
+    /// Returns the binding of a shortcut
+    pub fn get(&self, shortcut: Shortcut) -> &KeyBinding {
+        match shortcut {
+            Shortcut::QuickSwitcher => &self.quick_switcher,
+            Shortcut::Back => &self.back,
+            Shortcut::ChatsTab => &self.chats_tab,
+            Shortcut::FriendsTab => &self.friends_tab,
+            Shortcut::GroupsTab => &self.groups_tab,
+            Shortcut::EditLastMessage => &self.edit_last_message,
+            Shortcut::Search => &self.search,
+        }
+    }
+
+    /// Sets the binding of a shortcut
+    pub fn set(&mut self, shortcut: Shortcut, binding: KeyBinding) {
+        match shortcut {
+            Shortcut::QuickSwitcher => self.quick_switcher = binding,
+            Shortcut::Back => self.back = binding,
+            Shortcut::ChatsTab => self.chats_tab = binding,
+            Shortcut::FriendsTab => self.friends_tab = binding,
+            Shortcut::GroupsTab => self.groups_tab = binding,
+            Shortcut::EditLastMessage => self.edit_last_message = binding,
+            Shortcut::Search => self.search = binding,
+        }
+    }
+
+    /// Checks that no two shortcuts have the same binding. Returns nothing or an error message
+    pub fn check_conflicts(&self) -> Result<(), String> {
+        for (index, shortcut) in Shortcut::ALL.iter().enumerate() {
+            let binding = self.get(*shortcut);
+            if let Some(other) = Shortcut::ALL[index + 1..]
+                .iter()
+                .find(|other| self.get(**other) == binding)
+            {
+                return Err(t_args(
+                    "shortcut-conflict",
+                    &[
+                        ("binding", binding.to_string()),
+                        ("shortcut", shortcut.label()),
+                        ("other", other.label()),
+                    ],
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the shortcut that is bound to a key press, none if the key press is not bound
+    pub fn find(&self, key: &Key, modifiers: Modifiers) -> Option<Shortcut> {
+        Shortcut::ALL
+            .into_iter()
+            .find(|shortcut| self.get(*shortcut).matches(key, modifiers))
+    }
+}
+
+/// Returns the name of a key as it is stored in a binding, none for keys that cannot be bound
+fn key_name(key: &Key) -> Option<String> {
+    match key {
+        Key::Character(character) => Some(character.to_lowercase()),
+        Key::Named(named) => NAMED_KEYS
+            .iter()
+            .find(|(key, _)| key == named)
+            .map(|(_, name)| name.to_string()),
+        Key::Unidentified => None,
+    }
+}
@@ -0,0 +1,245 @@
+use iced::{
+    alignment::Horizontal,
+    widget::{button, column, horizontal_space, row, scrollable, text},
+    Alignment, Command, Element,
+};
+use log::error;
+
+use crate::backend::{
+    crypto::{conversation::load_conversation_summaries, key_store::KeyStore},
+    database::{
+        db::format_send_date,
+        models::{ConversationSummary, User},
+    },
+};
+use crate::frontend::{
+    chat_layout::ChatId,
+    drafts::Drafts,
+    task::{run_blocking, LoadTracker},
+    theme::{error_color, success_color},
+};
+use crate::i18n::{t, t_args};
+
+/// Number of characters of the newest message that are shown in the list
+const PREVIEW_LENGTH: usize = 50;
+
+/// Represents the chat tab in the home tab. It lists the chats with friends and groups, the newest chat first
+#[derive(Debug, Clone)]
+pub struct ChatTab {
+    current_user: Option<User>,
+    conversations: Vec<ConversationSummary>,
+    // Set until the chats have been loaded for the first time
+    loading: bool,
+    // Load of the chats that is started on every tick
+    load: LoadTracker,
+    error_message: String,
+    // Drafts of the current user, shown instead of the newest message
+    drafts: Drafts,
+}
+
+/// Represents the messages that can be sent to the chat tab
+#[derive(Debug, Clone)]
+pub enum ChatTabMessage {
+    OpenConversation(ConversationSummary),
+    // Load data
+    Tick,
+    ConversationsLoaded(u64, Result<Vec<ConversationSummary>, String>),
+}
+
+/// Default implementation for the chat tab
+impl Default for ChatTab {
+    fn default() -> Self {
+        ChatTab {
+            current_user: None,
+            conversations: Vec::new(),
+            loading: true,
+            load: LoadTracker::default(),
+            error_message: String::new(),
+            drafts: Drafts::default(),
+        }
+    }
+}
+
+/// Implementation of the chat tab
+impl ChatTab {
+    /// Sets the current user
+    pub fn set_current_user(&mut self, user: User) {
+        self.current_user = Some(user);
+    }
+
+    /// Sets the drafts of the current user
+    pub fn set_drafts(&mut self, drafts: Drafts) {
+        self.drafts = drafts;
+    }
+
+    /// Loads and decrypts the conversation summaries of the current user in the background, unless they are still being loaded
+    fn load_conversations(&mut self) -> Command<ChatTabMessage> {
+        let Some(current_user) = self.current_user.as_ref() else {
+            return Command::none();
+        };
+        let current_user_id = current_user.id;
+        let Some(generation) = self.load.start() else {
+            return Command::none();
+        };
+
+        Command::perform(
+            run_blocking(move || {
+                load_conversation_summaries(&KeyStore::default(), current_user_id)
+            }),
+            move |result| ChatTabMessage::ConversationsLoaded(generation, result),
+        )
+    }
+
+    /// Handles the loaded conversations. The shown chats are kept if they could not be loaded
+    fn handle_conversations_loaded(&mut self, result: Result<Vec<ConversationSummary>, String>) {
+        match result {
+            Ok(conversations) => {
+                self.conversations = conversations;
+                self.loading = false;
+                self.error_message.clear();
+            }
+            Err(e) => {
+                error!("Error loading chats: {}", e);
+                self.error_message = t("chat-tab-load-failed");
+            }
+        }
+    }
+
+    /// Updates the chat tab based on the message
+    pub fn update(&mut self, message: ChatTabMessage) -> Command<ChatTabMessage> {
+        match message {
+            // Opening a chat is handled by the app
+            ChatTabMessage::OpenConversation(_) => Command::none(),
+            // Load data
+            ChatTabMessage::Tick => self.load_conversations(),
+            ChatTabMessage::ConversationsLoaded(generation, result) => {
+                // Chats loaded before a newer load are dropped
+                if self.load.finish(generation) {
+                    self.handle_conversations_loaded(result);
+                }
+                Command::none()
+            }
+        }
+    }
+
+    /// Returns the view of the chat tab
+    pub fn view(&self) -> Element<'_, ChatTabMessage> {
+        let button_width = 250;
+        let padding = 10;
+        let spacing = 20;
+
+        let mut chats_column = column![text(t("chat-tab-title"))].spacing(spacing);
+
+        if self.loading {
+            chats_column = chats_column.push(text(t("chat-tab-loading")));
+        } else if self.conversations.is_empty() {
+            chats_column = chats_column.push(text(t("chat-tab-empty")));
+        }
+
+        for conversation in &self.conversations {
+            let icon = if conversation.is_group {
+                '👥'
+            } else {
+                '👤'
+            };
+            let chat_button = button(
+                text(format!("{} {}", icon, conversation.display_name()))
+                    .horizontal_alignment(Horizontal::Center),
+            )
+            .width(button_width)
+            .on_press(ChatTabMessage::OpenConversation(conversation.clone()));
+
+            let preview_text = match self.drafts.get(chat_id(conversation)) {
+                Some(draft) => text(t_args("chat-tab-draft", &[("draft", preview(draft))]))
+                    .style(error_color()), // Red color
+                None => text(
+                    conversation
+                        .display_text()
+                        .as_deref()
+                        .map(preview)
+                        .unwrap_or_else(|| t("chat-tab-no-messages")),
+                ),
+            };
+
+            let send_date_text = text(
+                conversation
+                    .send_date
+                    .map(format_send_date)
+                    .unwrap_or_default(),
+            )
+            .size(15);
+
+            let unread_text = if conversation.unread_count > 0 {
+                text(t_args(
+                    "chat-tab-unread",
+                    &[("count", conversation.unread_count.to_string())],
+                ))
+                .style(success_color())
+            } else {
+                text("")
+            };
+
+            let chat_row = row![
+                chat_button,
+                preview_text,
+                horizontal_space(),
+                send_date_text,
+                unread_text
+            ]
+            .spacing(spacing)
+            .align_items(Alignment::Center);
+
+            chats_column = chats_column.push(chat_row);
+        }
+
+        let error_text = text(&self.error_message).size(15).style(error_color()); // Red color
+
+        column![error_text, scrollable(chats_column.padding(padding))]
+            .spacing(spacing)
+            .padding(padding)
+            .into()
+    }
+}
+
+/// Returns the ID of the chat of a conversation
+fn chat_id(conversation: &ConversationSummary) -> ChatId {
+    if conversation.is_group {
+        ChatId::Group(conversation.conversation_id)
+    } else {
+        ChatId::User(conversation.conversation_id)
+    }
+}
+
+/// Returns the start of a message that fits into the list
+fn preview(message: &str) -> String {
+    if message.chars().count() <= PREVIEW_LENGTH {
+        return message.to_string();
+    }
+
+    let mut preview: String = message.chars().take(PREVIEW_LENGTH).collect();
+    preview.push('…');
+    preview
+}
+
+/// Getter methods for testing
+impl ChatTab {
+    pub fn get_conversations(&self) -> &[ConversationSummary] {
+        &self.conversations
+    }
+
+    pub fn get_loading(&self) -> bool {
+        self.loading
+    }
+
+    pub fn get_error_message(&self) -> &str {
+        &self.error_message
+    }
+
+    pub fn get_drafts(&self) -> &Drafts {
+        &self.drafts
+    }
+
+    pub fn get_load(&self) -> &LoadTracker {
+        &self.load
+    }
+}
@@ -1,12 +1,13 @@
 use iced::{
     alignment::Horizontal,
     widget::{button, column, horizontal_space, pick_list, row, text, text_input},
-    Alignment, Color, Element, Length,
+    Alignment, Command, Element, Length,
 };
 use log::error;
+use serde::{Deserialize, Serialize};
 
 use crate::backend::{
-    database::models::{Group, User, UserToGroup},
+    database::models::{Group, User, UserToGroup, UserToUserFriend},
     entities::{
         group_ops::{create_group, delete_group, find_groups_by_ids},
         user_friend_ops::find_all_user_to_user_friend_entries,
@@ -16,9 +17,12 @@ use crate::backend::{
         },
     },
 };
+use crate::frontend::task::{run_blocking, LoadTracker};
+use crate::frontend::theme::{error_color, input_style, success_color};
+use crate::i18n::{t, t_args};
 
 /// Represents the group tab in the home screen
-#[derive(Default, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct GroupTab {
     current_user: Option<User>,
     group_to_chat_with: Option<Group>,
@@ -33,6 +37,10 @@ pub struct GroupTab {
     invited_groups: Vec<Group>,
     error: String,
     info: String,
+    // Set until the groups have been loaded for the first time
+    loading: bool,
+    // Load of the friends and groups that is started on every tick
+    load: LoadTracker,
 }
 
 /// Represents the messages that can be sent to the group tab
@@ -49,8 +57,59 @@ pub enum GroupTabMessage {
     LeaveGroup(Group),
     // Invited Groups
     AcceptGroup(Group),
+    // Results of the backend calls
+    GroupCreated(Result<Group, String>),
+    UserInvited(User, Group, Result<(), String>),
+    GroupLeft(Group, Result<(), String>),
+    GroupAccepted(Group, Result<(), String>),
     // Load data
     Tick(Vec<User>),
+    DataLoaded(u64, Vec<User>, Result<GroupTabData, String>),
+}
+
+/// Represents the data of the group tab that is loaded in the background
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupTabData {
+    friend_relations: Vec<UserToUserFriend>,
+    groups_of_user: Vec<Group>,
+    invited_groups: Vec<Group>,
+}
+
+/// Implementation of the group tab data
+impl GroupTabData {
+    /// Returns the friend relations of the user
+    pub fn friend_relations(&self) -> &[UserToUserFriend] {
+        &self.friend_relations
+    }
+
+    /// Returns the groups the user is a member of
+    pub fn groups_of_user(&self) -> &[Group] {
+        &self.groups_of_user
+    }
+
+    /// Returns the groups the user has been invited to
+    pub fn invited_groups(&self) -> &[Group] {
+        &self.invited_groups
+    }
+}
+
+/// Default implementation for the group tab
+impl Default for GroupTab {
+    fn default() -> Self {
+        GroupTab {
+            current_user: None,
+            group_to_chat_with: None,
+            new_group_name: String::new(),
+            friends_of_user: Vec::new(),
+            selected_user: None,
+            groups_of_user: Vec::new(),
+            invited_groups: Vec::new(),
+            error: String::new(),
+            info: String::new(),
+            loading: true,
+            load: LoadTracker::default(),
+        }
+    }
 }
 
 /// Implementation of the group tab
@@ -67,33 +126,36 @@ impl GroupTab {
         self.current_user = Some(user);
     }
 
-    /// Checks if a group has users
-    fn check_if_group_has_users(&self, group: &Group) -> bool {
-        let group = find_groups_by_ids(vec![group.id]);
-
-        match group {
-            Ok(group) => {
-                if group.is_empty() {
-                    return false;
-                }
+    /// Shows the friends and groups cached on this device until they are loaded
+    pub fn show_cached(&mut self, users: Vec<User>, data: GroupTabData) {
+        self.handle_data_loaded(users, Ok(data));
+    }
 
-                let group = group.first().unwrap();
-                let users = find_all_user_groups_of_group(group.id);
-                match users {
-                    Ok(users) => !users.is_empty(),
-                    Err(_) => false,
-                }
-            }
-            Err(_) => false,
+    /// Creates a group in the background and adds the current user to it
+    fn create_group(&mut self) -> Command<GroupTabMessage> {
+        if self.new_group_name.is_empty() {
+            self.error = t("group-tab-name-empty");
+            self.info = String::new();
+            return Command::none();
         }
+
+        let user_id = self.current_user.as_ref().unwrap().id;
+        let group_name = self.new_group_name.clone();
+
+        Command::perform(
+            run_blocking(move || create_group_of_user(user_id, &group_name)),
+            GroupTabMessage::GroupCreated,
+        )
     }
 
-    /// Handles the result of creating a group. If successful, adds the user to the group
-    fn handle_create_group_result(&mut self, result: Result<Group, String>) {
+    /// Handles the result of creating a group. If successful, adds the group to the list of groups of the user
+    fn handle_group_created(&mut self, result: Result<Group, String>) {
         match result {
             Ok(group) => {
-                self.error.clear();
-                self.add_user_to_group(group);
+                self.load.invalidate();
+                self.info = t_args("group-tab-created", &[("group", group.name.clone())]);
+                self.error = String::new();
+                self.groups_of_user.push(group);
             }
             Err(e) => {
                 error!("Error creating group: {}", e);
@@ -103,84 +165,99 @@ impl GroupTab {
         }
     }
 
-    /// Adds a user to a group. If successful, adds the group to the list of groups of the user
-    fn add_user_to_group(&mut self, group: Group) {
-        let result = create_user_group(self.current_user.as_ref().unwrap().id, group.id, true);
+    /// Invites the selected user to a group in the background
+    fn invite_user_to_group(&mut self, group: Group) -> Command<GroupTabMessage> {
+        let Some(user) = self.selected_user.take() else {
+            self.error = t("group-tab-select-user-empty");
+            self.info = String::new();
+            return Command::none();
+        };
+
+        let (user_id, group_id) = (user.id, group.id);
+
+        Command::perform(
+            run_blocking(move || create_user_group(user_id, group_id, false).map(|_| ())),
+            move |result| GroupTabMessage::UserInvited(user.clone(), group.clone(), result),
+        )
+    }
 
+    /// Handles the result of inviting a user to a group. If successful, the ohter user receives an invite
+    fn handle_user_invited(&mut self, user: User, group: Group, result: Result<(), String>) {
         match result {
             Ok(_) => {
-                self.info = format!("Group {} created", group.name);
+                self.info = t_args(
+                    "group-tab-invited-user",
+                    &[
+                        ("first_name", user.first_name),
+                        ("last_name", user.last_name),
+                        ("group", group.name),
+                    ],
+                );
                 self.error = String::new();
-                self.groups_of_user.push(group);
             }
             Err(e) => {
-                let _ = delete_group(group.id);
-                error!("Error creating group: {}", e);
-                self.error = format!("Error creating group: {}", group.name);
+                error!("Error inviting user to group: {}", e);
+                self.error = t_args(
+                    "group-tab-already-member",
+                    &[
+                        ("first_name", user.first_name),
+                        ("last_name", user.last_name),
+                        ("group", group.name),
+                    ],
+                );
                 self.info = String::new();
             }
         }
     }
 
-    /// Invites a user to a group. If successful, the ohter user receives an invite
-    fn invite_user_to_group(&mut self, group: Group) {
-        if let Some(user) = &self.selected_user {
-            match create_user_group(user.id, group.id, false) {
-                Ok(_) => {
-                    self.info = format!(
-                        "User {} {} invited to group {}",
-                        user.first_name, user.last_name, group.name
-                    );
-                    self.error = String::new();
-                }
-                Err(e) => {
-                    error!("Error inviting user to group: {}", e);
-                    self.error = format!(
-                        "User {} {} is already in group {}",
-                        user.first_name, user.last_name, group.name
-                    );
-                    self.info = String::new();
-                }
-            }
-            self.selected_user = None;
-        } else {
-            self.error = "Select a user to invite".to_string();
-            self.info = String::new();
-        }
-    }
-
-    /// Accepts an invite to a group. If successful, the user is added to the group
-    fn accept_group_invite(&mut self, group: Group) {
+    /// Accepts an invite to a group in the background
+    fn accept_group_invite(&mut self, group: Group) -> Command<GroupTabMessage> {
         let user_id = self.current_user.as_ref().unwrap().id;
+        let group_id = group.id;
 
-        match update_user_group(user_id, group.id, true) {
+        Command::perform(
+            run_blocking(move || update_user_group(user_id, group_id, true).map(|_| ())),
+            move |result| GroupTabMessage::GroupAccepted(group.clone(), result),
+        )
+    }
+
+    /// Handles the result of accepting an invite. If successful, the user is added to the group
+    fn handle_group_accepted(&mut self, group: Group, result: Result<(), String>) {
+        match result {
             Ok(_) => {
-                self.info = format!("Accepted invite to group {}", group.name);
+                self.load.invalidate();
+                self.info = t_args("group-tab-accepted", &[("group", group.name.clone())]);
                 self.invited_groups.retain(|g| g.id != group.id);
                 self.groups_of_user.push(group);
             }
             Err(e) => {
                 error!("Error accepting invite to group: {}", e);
-                self.error = format!("Error accepting invite to group: {}", group.name);
+                self.error = t_args("group-tab-accept-failed", &[("group", group.name)]);
                 self.info = String::new();
             }
         }
     }
 
-    /// Leaves a group. If successful, the user is removed from the group. If the group then has no users, it is deleted
-    fn leave_group(&mut self, group: Group) {
+    /// Leaves a group in the background. If the group then has no users, it is deleted
+    fn leave_group(&mut self, group: Group) -> Command<GroupTabMessage> {
         let user_id = self.current_user.as_ref().unwrap().id;
+        let group_id = group.id;
+
+        Command::perform(
+            run_blocking(move || leave_group(user_id, group_id)),
+            move |result| GroupTabMessage::GroupLeft(group.clone(), result),
+        )
+    }
 
-        match delete_user_group(user_id, group.id) {
+    /// Handles the result of leaving a group. If successful, the group is removed from the lists
+    fn handle_group_left(&mut self, group: Group, result: Result<(), String>) {
+        match result {
             Ok(_) => {
-                self.info = format!("Left group {}", group.name);
+                self.load.invalidate();
+                self.info = t_args("group-tab-left", &[("group", group.name.clone())]);
                 self.error = String::new();
                 self.groups_of_user.retain(|g| g.id != group.id);
                 self.invited_groups.retain(|g| g.id != group.id);
-
-                if !self.check_if_group_has_users(&group) {
-                    let _ = delete_group(group.id);
-                }
             }
             Err(e) => {
                 error!("Error leaving group: {}", e);
@@ -190,98 +267,66 @@ impl GroupTab {
         }
     }
 
-    /// Loads the groups and invited groups of a user
-    fn load_user_groups(&mut self, current_user_id: i32) {
-        match find_all_user_groups_of_user(current_user_id) {
-            Ok(user_groups) => {
-                self.load_accepted_groups(&user_groups);
-                self.load_invited_groups(&user_groups);
-            }
-            Err(e) => {
-                error!("Error loading user_groups: {}", e);
-            }
-        }
-    }
-
-    /// Loads the groups that a user has accepted invites to
-    fn load_accepted_groups(&mut self, user_groups: &[UserToGroup]) {
-        let accepted_groups_ids: Vec<i32> = user_groups
-            .iter()
-            .filter(|group| group.accepted_invite)
-            .map(|group| group.group_id)
-            .collect();
+    /// Handles the tick event. Loads the friends and the groups of the current user in the background,
+    /// unless they are still being loaded
+    fn handle_tick(&mut self, users: Vec<User>) -> Command<GroupTabMessage> {
+        let current_user_id = self.current_user.as_ref().unwrap().id;
+        let Some(generation) = self.load.start() else {
+            return Command::none();
+        };
 
-        match find_groups_by_ids(accepted_groups_ids) {
-            Ok(groups) => {
-                self.groups_of_user = groups;
-            }
-            Err(e) => {
-                error!("Error loading groups: {}", e);
-            }
-        }
+        Command::perform(
+            run_blocking(move || load_group_tab_data(current_user_id)),
+            move |result| GroupTabMessage::DataLoaded(generation, users.clone(), result),
+        )
     }
 
-    /// Loads the groups that a user has been invited to
-    fn load_invited_groups(&mut self, user_groups: &[UserToGroup]) {
-        let invited_groups_ids: Vec<i32> = user_groups
-            .iter()
-            .filter(|group| !group.accepted_invite)
-            .map(|group| group.group_id)
-            .collect();
+    /// Handles the loaded data. Fills the friends of the user and the groups of the user
+    fn handle_data_loaded(&mut self, users: Vec<User>, result: Result<GroupTabData, String>) {
+        self.loading = false;
 
-        match find_groups_by_ids(invited_groups_ids) {
-            Ok(groups) => {
-                self.invited_groups = groups;
-            }
+        // The shown data is kept if it could not be loaded
+        let data = match result {
+            Ok(data) => data,
             Err(e) => {
-                error!("Error loading invited groups: {}", e);
+                error!("Error loading groups: {}", e);
+                return;
             }
-        }
-    }
+        };
 
-    fn handle_tick(&mut self, users: Vec<User>) {
         self.clear();
 
-        let friend_relations =
-            find_all_user_to_user_friend_entries(self.current_user.as_ref().unwrap().id);
-
-        match friend_relations {
-            Ok(friends) => {
-                let accepted_friends: Vec<_> = friends
-                    .into_iter()
-                    .filter(|friend_accepted| {
-                        friend_accepted.accepted_user_one && friend_accepted.accepted_user_two
-                    })
-                    .collect();
-
-                for friend in accepted_friends {
-                    let user = users
-                        .iter()
-                        .find(|user| {
-                            user.id != self.current_user.as_ref().unwrap().id
-                                && user.id == friend.user_one_id
-                                || user.id == friend.user_two_id
-                        })
-                        .unwrap();
-
-                    self.friends_of_user.push(user.clone());
-                }
-            }
-            Err(e) => {
-                error!("Error loading friends: {}", e);
-            }
+        let accepted_friends: Vec<_> = data
+            .friend_relations
+            .into_iter()
+            .filter(|friend_accepted| {
+                friend_accepted.accepted_user_one && friend_accepted.accepted_user_two
+            })
+            .collect();
+
+        for friend in accepted_friends {
+            let user = users
+                .iter()
+                .find(|user| {
+                    user.id != self.current_user.as_ref().unwrap().id
+                        && user.id == friend.user_one_id
+                        || user.id == friend.user_two_id
+                })
+                .unwrap();
+
+            self.friends_of_user.push(user.clone());
         }
 
         // Filter out the current user from the list of all users
         self.friends_of_user
             .retain(|user| user != self.current_user.as_ref().unwrap());
 
-        let current_user_id = self.current_user.as_ref().unwrap().id;
-        self.load_user_groups(current_user_id);
+        self.groups_of_user = data.groups_of_user;
+        self.invited_groups = data.invited_groups;
     }
 
     /// Updates the group tab based on a message
-    pub fn update(&mut self, message: GroupTabMessage) {
+    pub fn update(&mut self, message: GroupTabMessage) -> Command<GroupTabMessage> {
         match message {
             GroupTabMessage::UserSelected(user) => {
                 self.selected_user = Some(user);
@@ -293,28 +338,41 @@ impl GroupTab {
                 self.group_to_chat_with = Some(group);
             }
             GroupTabMessage::CreateGroup => {
-                if self.new_group_name.is_empty() {
-                    self.error = "Group name cannot be empty".to_string();
-                    self.info = String::new();
-                    return;
-                }
-
-                let result = create_group(self.new_group_name.as_str());
-                self.handle_create_group_result(result);
+                return self.create_group();
+            }
+            GroupTabMessage::GroupCreated(result) => {
+                self.handle_group_created(result);
             }
             GroupTabMessage::InviteUserToGroup(group) => {
-                self.invite_user_to_group(group);
+                return self.invite_user_to_group(group);
+            }
+            GroupTabMessage::UserInvited(user, group, result) => {
+                self.handle_user_invited(user, group, result);
             }
             GroupTabMessage::LeaveGroup(group) => {
-                self.leave_group(group);
+                return self.leave_group(group);
+            }
+            GroupTabMessage::GroupLeft(group, result) => {
+                self.handle_group_left(group, result);
             }
             GroupTabMessage::AcceptGroup(group) => {
-                self.accept_group_invite(group);
+                return self.accept_group_invite(group);
+            }
+            GroupTabMessage::GroupAccepted(group, result) => {
+                self.handle_group_accepted(group, result);
             }
             GroupTabMessage::Tick(users) => {
-                self.handle_tick(users);
+                return self.handle_tick(users);
+            }
+            GroupTabMessage::DataLoaded(generation, users, result) => {
+                // Data loaded before a newer load or a change of the groups is dropped
+                if self.load.finish(generation) {
+                    self.handle_data_loaded(users, result);
+                }
             }
         }
+
+        Command::none()
     }
 
     /// Returns the view of the group tab
@@ -325,12 +383,13 @@ impl GroupTab {
         let spacing = 20;
 
         // Create Group
-        let group_name_input = text_input("Group name:", self.new_group_name.as_str())
+        let group_name_input = text_input(&t("group-tab-name"), self.new_group_name.as_str())
+            .style(input_style())
             .width(300)
             .padding(padding)
             .on_input(GroupTabMessage::GroupNameChanged);
 
-        let create_group_button = button("Create group")
+        let create_group_button = button(text(t("group-tab-create")))
             .width(300)
             .padding(padding)
             .on_press(GroupTabMessage::CreateGroup);
@@ -345,12 +404,12 @@ impl GroupTab {
             GroupTabMessage::UserSelected,
         )
         .width(300)
-        .placeholder("Select a friend to add to a group");
+        .placeholder(t("group-tab-select-friend"));
 
         let error_message: Element<GroupTabMessage> = if !self.error.is_empty() {
             text(&self.error)
                 .size(15)
-                .style(Color::from_rgb(1.0, 0.0, 0.0)) // Red color
+                .style(error_color())
                 .horizontal_alignment(Horizontal::Center)
                 .into()
         } else {
@@ -360,7 +419,7 @@ impl GroupTab {
         let info_message: Element<GroupTabMessage> = if !self.info.is_empty() {
             text(&self.info)
                 .size(15)
-                .style(Color::from_rgb(0.2, 0.8, 0.2)) //Green color
+                .style(success_color())
                 .horizontal_alignment(Horizontal::Center)
                 .into()
         } else {
@@ -376,7 +435,11 @@ impl GroupTab {
 
         // Groups of User
 
-        let mut group_colum = column!(text("Groups:")).spacing(spacing);
+        let mut group_colum = column!(text(t("group-tab-groups"))).spacing(spacing);
+
+        if self.loading {
+            group_colum = group_colum.push(text(t("group-tab-loading")));
+        }
 
         for group in &self.groups_of_user {
             let group_button = button(text(&group.name).horizontal_alignment(Horizontal::Center))
@@ -384,12 +447,12 @@ impl GroupTab {
                 .on_press(GroupTabMessage::ChatWithGroup(group.clone()));
 
             let add_user_button =
-                button(text("Invite user to group").horizontal_alignment(Horizontal::Center))
+                button(text(t("group-tab-invite")).horizontal_alignment(Horizontal::Center))
                     .width(interact_button_width)
                     .on_press(GroupTabMessage::InviteUserToGroup(group.clone()));
 
             let leave_group_button =
-                button(text("Leave group").horizontal_alignment(Horizontal::Center))
+                button(text(t("group-tab-leave")).horizontal_alignment(Horizontal::Center))
                     .width(interact_button_width)
                     .on_press(GroupTabMessage::LeaveGroup(group.clone()));
 
@@ -400,18 +463,18 @@ impl GroupTab {
             group_colum = group_colum.push(group_row);
         }
 
-        let mut invited_group_rows = column!(text("Invited groups:")).spacing(spacing);
+        let mut invited_group_rows = column!(text(t("group-tab-invited"))).spacing(spacing);
 
         for group in &self.invited_groups {
             let group_text = text(&group.name).width(button_width);
 
             let accept_button =
-                button(text("Accept group invite").horizontal_alignment(Horizontal::Center))
+                button(text(t("group-tab-accept")).horizontal_alignment(Horizontal::Center))
                     .width(interact_button_width)
                     .on_press(GroupTabMessage::AcceptGroup(group.clone()));
 
             let decline_button =
-                button(text("Reject group invite").horizontal_alignment(Horizontal::Center))
+                button(text(t("group-tab-reject")).horizontal_alignment(Horizontal::Center))
                     .width(interact_button_width)
                     .on_press(GroupTabMessage::LeaveGroup(group.clone()));
 
@@ -434,6 +497,16 @@ impl GroupTab {
 
 /// Geter methods for tests
 impl GroupTab {
+    /// Returns if the data is still being loaded for the first time
+    pub fn get_loading(&self) -> bool {
+        self.loading
+    }
+
+    /// Returns the load of the friends and groups
+    pub fn get_load(&self) -> &LoadTracker {
+        &self.load
+    }
+
     /// Getter for the current user
     pub fn get_current_user(&self) -> &Option<User> {
         &self.current_user
@@ -492,3 +565,70 @@ impl GroupTab {
         self.groups_of_user.push(group);
     }
 }
+
+/// Creates a group and adds the user to it. If the user cannot be added, the group is deleted again.
+/// Returns the created group or an error message
+fn create_group_of_user(user_id: i32, group_name: &str) -> Result<Group, String> {
+    let group = create_group(group_name)?;
+
+    if let Err(e) = create_user_group(user_id, group.id, true) {
+        let _ = delete_group(group.id);
+        return Err(e);
+    }
+
+    Ok(group)
+}
+
+/// Removes the user from a group. If the group then has no users, it is deleted. Returns nothing or an error message
+fn leave_group(user_id: i32, group_id: i32) -> Result<(), String> {
+    delete_user_group(user_id, group_id)?;
+
+    if !check_if_group_has_users(group_id) {
+        let _ = delete_group(group_id);
+    }
+
+    Ok(())
+}
+
+/// Checks if a group has users
+fn check_if_group_has_users(group_id: i32) -> bool {
+    let group = find_groups_by_ids(vec![group_id]);
+
+    match group {
+        Ok(group) => {
+            if group.is_empty() {
+                return false;
+            }
+
+            let group = group.first().unwrap();
+            let users = find_all_user_groups_of_group(group.id);
+            match users {
+                Ok(users) => !users.is_empty(),
+                Err(_) => false,
+            }
+        }
+        Err(_) => false,
+    }
+}
+
+/// Loads the friend relations, the groups and the group invites of a user. Returns the data or an error message
+pub fn load_group_tab_data(current_user_id: i32) -> Result<GroupTabData, String> {
+    let user_groups = find_all_user_groups_of_user(current_user_id)?;
+
+    Ok(GroupTabData {
+        friend_relations: find_all_user_to_user_friend_entries(current_user_id)?,
+        groups_of_user: load_groups(&user_groups, true)?,
+        invited_groups: load_groups(&user_groups, false)?,
+    })
+}
+
+/// Loads the groups that a user has accepted invites to or has been invited to. Returns the groups or an error message
+fn load_groups(user_groups: &[UserToGroup], accepted_invite: bool) -> Result<Vec<Group>, String> {
+    let group_ids: Vec<i32> = user_groups
+        .iter()
+        .filter(|group| group.accepted_invite == accepted_invite)
+        .map(|group| group.group_id)
+        .collect();
+
+    find_groups_by_ids(group_ids)
+}
@@ -1,3 +1,4 @@
+pub mod chat_tab;
 pub mod group_tab;
 pub mod setting_tab;
 pub mod user_tab;
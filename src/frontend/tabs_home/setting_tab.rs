@@ -1,13 +1,50 @@
+use std::collections::HashMap;
+
 use iced::{
     alignment::{self, Horizontal},
-    widget::{button, column, horizontal_space, radio, row, text, text_input, Container},
-    Alignment, Color, Element, Length,
+    widget::{
+        button, checkbox, column, horizontal_space, radio, row, text, text_input, Column, Container,
+    },
+    Alignment, Command, Element, Length, Theme,
 };
 
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
 use crate::backend::{
-    database::{db::is_valid_email, models::User},
-    entities::user_ops::{delete_user, update_email, update_password},
+    database::{
+        db::{format_send_date, is_valid_email},
+        models::{Group, NotificationSettings, Session, User},
+    },
+    entities::{
+        notification_settings_ops::{find_notification_settings, save_notification_settings},
+        pending_verification_ops::redeem_email_verification,
+        session_ops::{find_active_sessions_of_user, revoke_session},
+        two_factor_ops::{
+            confirm_totp_enrollment, disable_totp, is_totp_enabled, start_totp_enrollment,
+            TotpEnrollment,
+        },
+        user_ops::{
+            delete_user, find_user_email_by_id, find_users_by_ids, update_email, update_password,
+        },
+    },
+    mail::mailer::create_mailer,
 };
+use crate::frontend::{
+    preferences::{clamp_font_scale, NotificationPreferences, Preferences},
+    shortcuts::{KeyBinding, Shortcut, Shortcuts},
+    task::run_blocking,
+    theme::{error_color, input_style, success_color, CustomTheme, ThemeColor},
+};
+use crate::i18n::{parse_time_zone, t, t_args, Language};
+
+use super::{group_tab::load_group_tab_data, user_tab::find_friend_ids};
+
+/// Format of the times of the do-not-disturb schedule
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Change of the font scale per click
+const FONT_SCALE_STEP: f64 = 0.1;
 
 /// Represents the setting tab in the home screen
 #[derive(Debug, Clone)]
@@ -18,32 +55,133 @@ pub struct SettingTab {
     app_theme: AppTheme,
     // Update
     new_email_value: String,
+    verification_code_value: String,
     new_password_value: String,
     current_password_value: String,
     delete_button_pressed: bool,
     account_deleted: bool,
+    // Two-factor authentication
+    two_factor_enabled: bool,
+    totp_enrollment: Option<TotpEnrollment>,
+    two_factor_code_value: String,
+    two_factor_password_value: String,
+    recovery_codes: Vec<String>,
+    // Sessions
+    sessions: Vec<Session>,
+    current_session_id: Option<i32>,
+    // Notifications
+    notification_settings: Option<NotificationSettings>,
+    dnd_start_value: String,
+    dnd_end_value: String,
+    notification_friends: Vec<User>,
+    notification_groups: Vec<Group>,
+    // Preferences of this device
+    notification_preferences: NotificationPreferences,
+    sync_preferences: bool,
+    font_scale: f64,
+    // None while the language of the system is used
+    language: Option<Language>,
+    // None while the time zone of the system is used
+    time_zone: Option<String>,
+    time_zone_value: String,
+    // Keyboard shortcuts and the bindings that are entered
+    shortcuts: Shortcuts,
+    shortcut_values: HashMap<Shortcut, String>,
+    // Custom themes
+    custom_themes: Vec<CustomTheme>,
+    custom_theme: String,
+    theme_editor: CustomTheme,
+    theme_path_value: String,
+    // Set while the theme in the editor is shown instead of the app theme
+    theme_preview: bool,
+    // Set while a backend call is in flight
+    loading: bool,
 }
 
 /// Represents the messages that can be sent to the settings tab
 #[derive(Debug, Clone)]
 pub enum SettingsTabMessage {
     UpdateEmail,
+    EmailUpdateRequested(Result<String, String>),
+    VerifyEmail,
+    EmailVerified(Result<(), String>),
     UpdatePassword,
+    PasswordUpdated(Result<(), String>),
     EmailInputChanged(String),
+    VerificationCodeInputChanged(String),
     NewPasswordInputChanged(String),
     CurrentPasswordInputChanged(String),
     DeleteAccount,
+    AccountDeleted(Result<(), String>),
     ChangeTheme(AppTheme),
+    // Two-factor authentication
+    TwoFactorStatusLoaded(Result<bool, String>),
+    StartTwoFactorEnrollment,
+    TwoFactorEnrollmentStarted(Result<TotpEnrollment, String>),
+    ConfirmTwoFactorEnrollment,
+    TwoFactorEnrollmentConfirmed(Result<Vec<String>, String>),
+    DisableTwoFactor,
+    TwoFactorDisabled(Result<(), String>),
+    TwoFactorCodeInputChanged(String),
+    TwoFactorPasswordInputChanged(String),
+    // Sessions
+    SessionsLoaded(Result<Vec<Session>, String>),
+    RevokeSession(i32),
+    SessionRevoked(Result<(), String>),
+    // Notifications
+    NotificationSettingsLoaded(Result<NotificationSettingsData, String>),
+    DoNotDisturbToggled(bool),
+    DoNotDisturbStartInputChanged(String),
+    DoNotDisturbEndInputChanged(String),
+    MuteUserToggled(i32, bool),
+    MuteGroupToggled(i32, bool),
+    SaveNotificationSettings,
+    NotificationSettingsSaved(Result<(), String>),
+    // Preferences
+    NotificationsEnabledToggled(bool),
+    NotificationPreviewToggled(bool),
+    SyncPreferencesToggled(bool),
+    ChangeFontScale(f64),
+    ChangeLanguage(Option<Language>),
+    TimeZoneInputChanged(String),
+    SaveTimeZone,
+    ShortcutInputChanged(Shortcut, String),
+    SaveShortcuts,
+    ResetShortcuts,
+    // Custom themes
+    ThemeNameInputChanged(String),
+    ThemeColorInputChanged(ThemeColor, String),
+    ThemePathInputChanged(String),
+    SaveCustomTheme,
+    SelectCustomTheme(String),
+    EditCustomTheme(String),
+    DeleteCustomTheme(String),
+    ImportTheme,
+    ExportTheme,
+}
+
+/// The notification settings of the user and the conversations that can be muted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationSettingsData {
+    pub settings: NotificationSettings,
+    pub friends: Vec<User>,
+    pub groups: Vec<Group>,
 }
 
 /// The different themes that the application can have
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AppTheme {
     Moonfly,
     Oxocarbon,
     Dracula,
     KanagawaWave,
     Light,
+    // White text on black with colors that meet the WCAG contrast ratios
+    HighContrast,
+    // Light or dark, following the preference of the system
+    System,
+    // The custom theme the user selected
+    Custom,
 }
 
 /// The default implementation of the settings tab
@@ -57,9 +195,36 @@ impl Default for SettingTab {
             // Update
             new_password_value: String::new(),
             new_email_value: String::new(),
+            verification_code_value: String::new(),
             current_password_value: String::new(),
             delete_button_pressed: false,
             account_deleted: false,
+            two_factor_enabled: false,
+            totp_enrollment: None,
+            two_factor_code_value: String::new(),
+            two_factor_password_value: String::new(),
+            recovery_codes: Vec::new(),
+            sessions: Vec::new(),
+            current_session_id: None,
+            notification_settings: None,
+            dnd_start_value: String::new(),
+            dnd_end_value: String::new(),
+            notification_friends: Vec::new(),
+            notification_groups: Vec::new(),
+            notification_preferences: NotificationPreferences::default(),
+            sync_preferences: false,
+            font_scale: 1.0,
+            language: None,
+            time_zone: None,
+            time_zone_value: String::new(),
+            shortcuts: Shortcuts::default(),
+            shortcut_values: shortcut_values(&Shortcuts::default()),
+            custom_themes: Vec::new(),
+            custom_theme: String::new(),
+            theme_editor: CustomTheme::default(),
+            theme_path_value: String::new(),
+            theme_preview: false,
+            loading: false,
         }
     }
 }
@@ -71,11 +236,71 @@ impl SettingTab {
         self.current_user = Some(user);
     }
 
+    /// Set the id of the session of this device
+    pub fn set_current_session_id(&mut self, session_id: Option<i32>) {
+        self.current_session_id = session_id;
+    }
+
     /// Set the app theme
     pub fn set_app_theme(&mut self, app_theme: AppTheme) {
         self.app_theme = app_theme;
     }
 
+    /// Set the preferences of this device that are shown in the tab
+    pub fn set_preferences(&mut self, preferences: &Preferences) {
+        self.notification_preferences = preferences.notifications;
+        self.sync_preferences = preferences.sync;
+        self.font_scale = preferences.font_scale;
+        self.language = preferences.language;
+        self.time_zone = preferences.time_zone.clone();
+        self.time_zone_value = preferences.time_zone.clone().unwrap_or_default();
+        self.shortcuts = preferences.shortcuts.clone();
+        self.shortcut_values = shortcut_values(&preferences.shortcuts);
+        self.custom_themes = preferences.custom_themes.clone();
+        self.custom_theme = preferences.custom_theme.clone();
+    }
+
+    pub fn get_custom_themes(&self) -> &Vec<CustomTheme> {
+        &self.custom_themes
+    }
+
+    pub fn get_custom_theme(&self) -> &str {
+        &self.custom_theme
+    }
+
+    /// Returns the theme in the editor while it is previewed and valid
+    pub fn get_theme_preview(&self) -> Option<Theme> {
+        if !self.theme_preview {
+            return None;
+        }
+
+        self.theme_editor.to_theme().ok()
+    }
+
+    pub fn get_notification_preferences(&self) -> NotificationPreferences {
+        self.notification_preferences
+    }
+
+    pub fn get_sync_preferences(&self) -> bool {
+        self.sync_preferences
+    }
+
+    pub fn get_font_scale(&self) -> f64 {
+        self.font_scale
+    }
+
+    pub fn get_language(&self) -> Option<Language> {
+        self.language
+    }
+
+    pub fn get_time_zone(&self) -> Option<&str> {
+        self.time_zone.as_deref()
+    }
+
+    pub fn get_shortcuts(&self) -> &Shortcuts {
+        &self.shortcuts
+    }
+
     pub fn get_app_theme(&self) -> AppTheme {
         self.app_theme
     }
@@ -84,24 +309,167 @@ impl SettingTab {
         self.account_deleted
     }
 
-    /// Update the password. The password can only be updated if the fields are not empty, the new password is strong, and the current password is correct
-    fn update_password(&mut self) {
-        if self.new_password_value.is_empty() || self.current_password_value.is_empty() {
+    /// Save the theme in the editor and use it. A theme with the same name is replaced
+    fn save_custom_theme(&mut self) {
+        if let Err(e) = self.theme_editor.to_theme() {
             self.info = String::new();
-            self.error = "Password fields cannot be empty".to_string();
+            self.error = e;
             return;
         }
 
-        let result = update_password(
-            self.current_user.as_ref().unwrap().id,
-            self.current_password_value.as_str(),
-            self.new_password_value.as_str(),
-        );
+        let mut theme = self.theme_editor.clone();
+        theme.name = theme.name.trim().to_string();
+        match self.custom_themes.iter_mut().find(|t| t.name == theme.name) {
+            Some(existing) => *existing = theme.clone(),
+            None => self.custom_themes.push(theme.clone()),
+        }
+
+        self.custom_theme = theme.name.clone();
+        self.app_theme = AppTheme::Custom;
+        self.theme_preview = false;
+        self.error = String::new();
+        self.info = t_args("settings-theme-saved", &[("theme", theme.name.clone())]);
+    }
+
+    /// Remove a custom theme. If it is used, the default theme is used instead
+    fn delete_custom_theme(&mut self, name: String) {
+        self.custom_themes.retain(|theme| theme.name != name);
 
+        if self.custom_theme == name {
+            self.custom_theme = String::new();
+            if self.app_theme == AppTheme::Custom {
+                self.app_theme = AppTheme::Moonfly;
+            }
+        }
+    }
+
+    /// Use the key bindings that were entered. Nothing is changed if a binding is invalid or two shortcuts have the same binding
+    fn save_shortcuts(&mut self) {
+        let mut shortcuts = self.shortcuts.clone();
+        for shortcut in Shortcut::ALL {
+            let value = self
+                .shortcut_values
+                .get(&shortcut)
+                .map(String::as_str)
+                .unwrap_or_default();
+            match KeyBinding::parse(value) {
+                Ok(binding) => shortcuts.set(shortcut, binding),
+                Err(e) => {
+                    self.info = String::new();
+                    self.error = e;
+                    return;
+                }
+            }
+        }
+
+        if let Err(e) = shortcuts.check_conflicts() {
+            self.info = String::new();
+            self.error = e;
+            return;
+        }
+
+        self.shortcut_values = shortcut_values(&shortcuts);
+        self.shortcuts = shortcuts;
+        self.error = String::new();
+        self.info = t("settings-shortcuts-saved");
+    }
+
+    /// Use the time zone that was entered. The time zone of the system is used if none is entered
+    fn save_time_zone(&mut self) {
+        let name = self.time_zone_value.trim();
+        if name.is_empty() {
+            self.time_zone = None;
+            self.error = String::new();
+            self.info = t("settings-time-zone-system");
+            return;
+        }
+
+        match parse_time_zone(name) {
+            Ok(time_zone) => {
+                self.time_zone = Some(time_zone.name().to_string());
+                self.error = String::new();
+                self.info = t_args(
+                    "settings-time-zone-saved",
+                    &[("name", time_zone.name().to_string())],
+                );
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = e;
+            }
+        }
+    }
+
+    /// Import a theme from the TOML file into the editor. It is used once it is saved
+    fn import_theme(&mut self) {
+        if self.theme_path_value.trim().is_empty() {
+            self.info = String::new();
+            self.error = t("settings-theme-path-empty");
+            return;
+        }
+
+        match CustomTheme::import(self.theme_path_value.trim()) {
+            Ok(theme) => {
+                self.theme_editor = theme;
+                self.theme_preview = true;
+                self.error = String::new();
+                self.info = t("settings-theme-imported");
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-theme-import-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Export the theme in the editor to the TOML file
+    fn export_theme(&mut self) {
+        if self.theme_path_value.trim().is_empty() {
+            self.info = String::new();
+            self.error = t("settings-theme-path-empty");
+            return;
+        }
+
+        match self.theme_editor.export(self.theme_path_value.trim()) {
+            Ok(()) => {
+                self.error = String::new();
+                self.info = t_args(
+                    "settings-theme-exported",
+                    &[("path", self.theme_path_value.trim().to_string())],
+                );
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-theme-export-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Update the password in the background. The password can only be updated if the fields are not empty, the new password is strong, and the current password is correct
+    fn update_password(&mut self) -> Command<SettingsTabMessage> {
+        if self.new_password_value.is_empty() || self.current_password_value.is_empty() {
+            self.info = String::new();
+            self.error = t("settings-password-empty");
+            return Command::none();
+        }
+
+        let user_id = self.current_user.as_ref().unwrap().id;
+        let current_password = self.current_password_value.clone();
+        let new_password = self.new_password_value.clone();
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || update_password(user_id, &current_password, &new_password)),
+            SettingsTabMessage::PasswordUpdated,
+        )
+    }
+
+    /// Handle the result of updating the password
+    fn handle_password_updated(&mut self, result: Result<(), String>) {
         match result {
             Ok(_) => {
                 self.error = String::new();
-                self.info = "Password updated successfully".to_string();
+                self.info = t("settings-password-updated");
             }
             Err(e) => {
                 self.error = e;
@@ -110,46 +478,435 @@ impl SettingTab {
         }
     }
 
-    /// Update the email. The email can only be updated if the field is not empty and the email is valid
-    fn update_email(&mut self) {
+    /// Update the email. The email can only be updated if the field is not empty and the email is valid.
+    /// A verification code is sent to the new email in the background, the email is changed once the code is redeemed
+    fn update_email(&mut self) -> Command<SettingsTabMessage> {
         if self.new_email_value.is_empty() {
             self.info = String::new();
-            self.error = "Email and password fields cannot be empty".to_string();
-            return;
+            self.error = t("settings-email-empty");
+            return Command::none();
         }
 
         if !is_valid_email(&self.new_email_value) {
             self.info = String::new();
-            self.error = "Invalid email format".to_string();
-            return;
+            self.error = t("invalid-email-format");
+            return Command::none();
         }
 
-        let result = update_email(
-            self.current_user.as_ref().unwrap().id,
-            self.new_email_value.as_str(),
-        );
+        let user_id = self.current_user.as_ref().unwrap().id;
+        let new_email = self.new_email_value.to_lowercase();
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || {
+                create_mailer().and_then(|mailer| update_email(user_id, &new_email, &*mailer))?;
+                Ok(new_email)
+            }),
+            SettingsTabMessage::EmailUpdateRequested,
+        )
+    }
+
+    /// Handle the result of requesting the email change. Returns the new email on success
+    fn handle_email_update_requested(&mut self, result: Result<String, String>) {
+        match result {
+            Ok(new_email) => {
+                self.error = String::new();
+                self.info = t_args("settings-verification-code-sent", &[("email", new_email)]);
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-email-update-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Verify the new email in the background. The email is updated if the verification code is valid
+    fn verify_email(&mut self) -> Command<SettingsTabMessage> {
+        if self.verification_code_value.is_empty() {
+            self.info = String::new();
+            self.error = t("settings-verification-code-empty");
+            return Command::none();
+        }
+
+        let user_id = self.current_user.as_ref().unwrap().id;
+        let verification_code = self.verification_code_value.trim().to_string();
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || {
+                redeem_email_verification(user_id, &verification_code).map(|_| ())
+            }),
+            SettingsTabMessage::EmailVerified,
+        )
+    }
+
+    /// Handle the result of verifying the new email
+    fn handle_email_verified(&mut self, result: Result<(), String>) {
+        match result {
+            Ok(_) => {
+                self.error = String::new();
+                self.info = t("settings-email-updated");
+                self.new_email_value = String::new();
+                self.verification_code_value = String::new();
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-email-verify-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Load whether two-factor authentication is enabled for the current user in the background
+    pub fn load_two_factor_status(&mut self) -> Command<SettingsTabMessage> {
+        let user_id = self.current_user.as_ref().unwrap().id;
+
+        Command::perform(
+            run_blocking(move || is_totp_enabled(user_id)),
+            SettingsTabMessage::TwoFactorStatusLoaded,
+        )
+    }
+
+    /// Handle the loaded two-factor status
+    fn handle_two_factor_status_loaded(&mut self, result: Result<bool, String>) {
+        match result {
+            Ok(enabled) => {
+                self.two_factor_enabled = enabled;
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-two-factor-load-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Load the active sessions of the current user in the background
+    pub fn load_sessions(&mut self) -> Command<SettingsTabMessage> {
+        let user_id = self.current_user.as_ref().unwrap().id;
+
+        Command::perform(
+            run_blocking(move || find_active_sessions_of_user(user_id)),
+            SettingsTabMessage::SessionsLoaded,
+        )
+    }
+
+    /// Handle the loaded sessions
+    fn handle_sessions_loaded(&mut self, result: Result<Vec<Session>, String>) {
+        match result {
+            Ok(sessions) => {
+                self.sessions = sessions;
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-sessions-load-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Revoke a session of the current user in the background. The device of the session is logged out
+    fn revoke_session(&mut self, session_id: i32) -> Command<SettingsTabMessage> {
+        let user_id = self.current_user.as_ref().unwrap().id;
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || revoke_session(user_id, session_id)),
+            SettingsTabMessage::SessionRevoked,
+        )
+    }
+
+    /// Handle the result of revoking a session. The sessions are loaded again on success
+    fn handle_session_revoked(
+        &mut self,
+        result: Result<(), String>,
+    ) -> Command<SettingsTabMessage> {
+        match result {
+            Ok(_) => {
+                self.error = String::new();
+                self.info = t("settings-session-revoked");
+                self.load_sessions()
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-session-revoke-failed", &[("error", e)]);
+                Command::none()
+            }
+        }
+    }
+
+    /// Start the two-factor enrolment in the background. A new secret is created and shown as an otpauth URI
+    fn start_two_factor_enrollment(&mut self) -> Command<SettingsTabMessage> {
+        let user_id = self.current_user.as_ref().unwrap().id;
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || {
+                find_user_email_by_id(user_id)
+                    .and_then(|email| start_totp_enrollment(user_id, &email))
+            }),
+            SettingsTabMessage::TwoFactorEnrollmentStarted,
+        )
+    }
+
+    /// Handle the started two-factor enrolment
+    fn handle_two_factor_enrollment_started(&mut self, result: Result<TotpEnrollment, String>) {
+        match result {
+            Ok(enrollment) => {
+                self.error = String::new();
+                self.info = t("settings-two-factor-add-secret");
+                self.recovery_codes = Vec::new();
+                self.totp_enrollment = Some(enrollment);
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-two-factor-enable-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Confirm the two-factor enrolment with a code of the authenticator app in the background
+    fn confirm_two_factor_enrollment(&mut self) -> Command<SettingsTabMessage> {
+        if self.two_factor_code_value.trim().is_empty() {
+            self.info = String::new();
+            self.error = t("settings-two-factor-code-empty");
+            return Command::none();
+        }
+
+        let user_id = self.current_user.as_ref().unwrap().id;
+        let code = self.two_factor_code_value.trim().to_string();
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || confirm_totp_enrollment(user_id, &code)),
+            SettingsTabMessage::TwoFactorEnrollmentConfirmed,
+        )
+    }
+
+    /// Handle the confirmed two-factor enrolment. The recovery codes are shown once
+    fn handle_two_factor_enrollment_confirmed(&mut self, result: Result<Vec<String>, String>) {
+        match result {
+            Ok(recovery_codes) => {
+                self.error = String::new();
+                self.info = t("settings-two-factor-enabled");
+                self.two_factor_enabled = true;
+                self.totp_enrollment = None;
+                self.two_factor_code_value = String::new();
+                self.recovery_codes = recovery_codes;
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-two-factor-enable-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Disable two-factor authentication in the background. Requires the current password and a valid code
+    fn disable_two_factor(&mut self) -> Command<SettingsTabMessage> {
+        if self.two_factor_password_value.is_empty() || self.two_factor_code_value.trim().is_empty()
+        {
+            self.info = String::new();
+            self.error = t("settings-two-factor-disable-empty");
+            return Command::none();
+        }
+
+        let user_id = self.current_user.as_ref().unwrap().id;
+        let password = self.two_factor_password_value.clone();
+        let code = self.two_factor_code_value.trim().to_string();
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || disable_totp(user_id, &password, &code)),
+            SettingsTabMessage::TwoFactorDisabled,
+        )
+    }
+
+    /// Handle the result of disabling two-factor authentication
+    fn handle_two_factor_disabled(&mut self, result: Result<(), String>) {
+        match result {
+            Ok(_) => {
+                self.error = String::new();
+                self.info = t("settings-two-factor-disabled");
+                self.two_factor_enabled = false;
+                self.two_factor_code_value = String::new();
+                self.two_factor_password_value = String::new();
+                self.recovery_codes = Vec::new();
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-two-factor-disable-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Load the notification settings, the friends and the groups of the current user in the background
+    pub fn load_notification_settings(&mut self) -> Command<SettingsTabMessage> {
+        let user_id = self.current_user.as_ref().unwrap().id;
+
+        Command::perform(
+            run_blocking(move || load_notification_settings_data(user_id)),
+            SettingsTabMessage::NotificationSettingsLoaded,
+        )
+    }
+
+    /// Handle the loaded notification settings
+    fn handle_notification_settings_loaded(
+        &mut self,
+        result: Result<NotificationSettingsData, String>,
+    ) {
+        match result {
+            Ok(data) => {
+                self.dnd_start_value = data.settings.dnd_start.format(TIME_FORMAT).to_string();
+                self.dnd_end_value = data.settings.dnd_end.format(TIME_FORMAT).to_string();
+                self.notification_settings = Some(data.settings);
+                self.notification_friends = data.friends;
+                self.notification_groups = data.groups;
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-notifications-load-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Save the notification settings in the background. The do-not-disturb times must be valid
+    fn save_notification_settings(&mut self) -> Command<SettingsTabMessage> {
+        let Some(mut settings) = self.notification_settings.clone() else {
+            return Command::none();
+        };
+
+        let (Ok(dnd_start), Ok(dnd_end)) = (
+            NaiveTime::parse_from_str(self.dnd_start_value.trim(), TIME_FORMAT),
+            NaiveTime::parse_from_str(self.dnd_end_value.trim(), TIME_FORMAT),
+        ) else {
+            self.info = String::new();
+            self.error = t("settings-dnd-invalid");
+            return Command::none();
+        };
+
+        settings.dnd_start = dnd_start;
+        settings.dnd_end = dnd_end;
+        self.notification_settings = Some(settings.clone());
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || save_notification_settings(&settings)),
+            SettingsTabMessage::NotificationSettingsSaved,
+        )
+    }
+
+    /// Handle the result of saving the notification settings
+    fn handle_notification_settings_saved(&mut self, result: Result<(), String>) {
         match result {
             Ok(_) => {
                 self.error = String::new();
-                self.info = "Email updated successfully".to_string();
+                self.info = t("settings-notifications-saved");
+            }
+            Err(e) => {
+                self.info = String::new();
+                self.error = t_args("settings-notifications-save-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Delete the account in the background, after pressing the button twice
+    fn delete_account(&mut self) -> Command<SettingsTabMessage> {
+        if !self.delete_button_pressed {
+            self.delete_button_pressed = true;
+            return Command::none();
+        }
+
+        let user_id = self.current_user.as_ref().unwrap().id;
+
+        self.loading = true;
+        Command::perform(
+            run_blocking(move || delete_user(user_id)),
+            SettingsTabMessage::AccountDeleted,
+        )
+    }
+
+    /// Handle the result of deleting the account
+    fn handle_account_deleted(&mut self, result: Result<(), String>) {
+        match result {
+            Ok(_) => {
+                self.account_deleted = true;
             }
             Err(e) => {
                 self.info = String::new();
-                self.error = format!("Error updating email: {:?}", e);
+                self.error = t_args("settings-delete-account-failed", &[("error", e)]);
             }
         }
     }
 
     /// Update the settings tab based on the message
-    pub fn update(&mut self, message: SettingsTabMessage) {
+    pub fn update(&mut self, message: SettingsTabMessage) -> Command<SettingsTabMessage> {
         match message {
             SettingsTabMessage::ChangeTheme(app_theme) => {
                 self.app_theme = app_theme;
+                self.theme_preview = false;
+            }
+            // Custom themes are previewed while they are edited
+            SettingsTabMessage::ThemeNameInputChanged(name) => {
+                self.theme_editor.name = name;
+            }
+            SettingsTabMessage::ThemeColorInputChanged(color, value) => {
+                self.theme_editor.palette.set(color, value);
+                self.theme_preview = true;
+            }
+            SettingsTabMessage::ThemePathInputChanged(path) => {
+                self.theme_path_value = path;
+            }
+            SettingsTabMessage::SaveCustomTheme => self.save_custom_theme(),
+            SettingsTabMessage::SelectCustomTheme(name) => {
+                self.custom_theme = name;
+                self.app_theme = AppTheme::Custom;
+                self.theme_preview = false;
+            }
+            SettingsTabMessage::EditCustomTheme(name) => {
+                if let Some(theme) = self.custom_themes.iter().find(|theme| theme.name == name) {
+                    self.theme_editor = theme.clone();
+                    self.theme_preview = true;
+                }
+            }
+            SettingsTabMessage::DeleteCustomTheme(name) => self.delete_custom_theme(name),
+            SettingsTabMessage::ImportTheme => self.import_theme(),
+            SettingsTabMessage::ExportTheme => self.export_theme(),
+            // The preferences are stored by the app
+            SettingsTabMessage::NotificationsEnabledToggled(enabled) => {
+                self.notification_preferences.enabled = enabled;
+            }
+            SettingsTabMessage::NotificationPreviewToggled(show_preview) => {
+                self.notification_preferences.show_preview = show_preview;
+            }
+            SettingsTabMessage::SyncPreferencesToggled(sync) => {
+                self.sync_preferences = sync;
+            }
+            SettingsTabMessage::ChangeFontScale(font_scale) => {
+                // Rounded, so repeated steps do not add up rounding errors
+                self.font_scale = clamp_font_scale((font_scale * 10.0).round() / 10.0);
+            }
+            SettingsTabMessage::ChangeLanguage(language) => {
+                self.language = language;
+            }
+            SettingsTabMessage::TimeZoneInputChanged(time_zone) => {
+                self.time_zone_value = time_zone;
+            }
+            SettingsTabMessage::SaveTimeZone => self.save_time_zone(),
+            SettingsTabMessage::ShortcutInputChanged(shortcut, value) => {
+                self.shortcut_values.insert(shortcut, value);
+            }
+            SettingsTabMessage::SaveShortcuts => self.save_shortcuts(),
+            SettingsTabMessage::ResetShortcuts => {
+                self.shortcuts = Shortcuts::default();
+                self.shortcut_values = shortcut_values(&self.shortcuts);
+                self.error = String::new();
+                self.info = t("settings-shortcuts-reset");
             }
             // Upates the email value
             SettingsTabMessage::EmailInputChanged(email) => {
                 self.new_email_value = email;
             }
+            // Updates the verification code value
+            SettingsTabMessage::VerificationCodeInputChanged(verification_code) => {
+                self.verification_code_value = verification_code;
+            }
             // Updates the new password value
             SettingsTabMessage::NewPasswordInputChanged(new_password) => {
                 self.new_password_value = new_password;
@@ -160,31 +917,122 @@ impl SettingTab {
             }
             // Deletes the account, after pressing the button twice
             SettingsTabMessage::DeleteAccount => {
-                if !self.delete_button_pressed {
-                    self.delete_button_pressed = true;
-                    return;
-                }
-
-                let result = delete_user(self.current_user.as_ref().unwrap().id);
-                match result {
-                    Ok(_) => {
-                        self.account_deleted = true;
-                    }
-                    Err(e) => {
-                        self.info = String::new();
-                        self.error = format!("Error deleting account: {:?}", e);
-                    }
-                }
+                return self.delete_account();
+            }
+            SettingsTabMessage::AccountDeleted(result) => {
+                self.loading = false;
+                self.handle_account_deleted(result);
             }
             // Updates the email
             SettingsTabMessage::UpdateEmail => {
-                self.update_email();
+                return self.update_email();
+            }
+            SettingsTabMessage::EmailUpdateRequested(result) => {
+                self.loading = false;
+                self.handle_email_update_requested(result);
+            }
+            // Verifies the new email with the code that was sent to it
+            SettingsTabMessage::VerifyEmail => {
+                return self.verify_email();
+            }
+            SettingsTabMessage::EmailVerified(result) => {
+                self.loading = false;
+                self.handle_email_verified(result);
             }
             // Updates the password, if the old password is correct and the new password is strong
             SettingsTabMessage::UpdatePassword => {
-                self.update_password();
+                return self.update_password();
+            }
+            SettingsTabMessage::PasswordUpdated(result) => {
+                self.loading = false;
+                self.handle_password_updated(result);
+            }
+            // Starts the two-factor enrolment
+            SettingsTabMessage::StartTwoFactorEnrollment => {
+                return self.start_two_factor_enrollment();
+            }
+            SettingsTabMessage::TwoFactorEnrollmentStarted(result) => {
+                self.loading = false;
+                self.handle_two_factor_enrollment_started(result);
+            }
+            // Confirms the two-factor enrolment with a code of the authenticator app
+            SettingsTabMessage::ConfirmTwoFactorEnrollment => {
+                return self.confirm_two_factor_enrollment();
+            }
+            SettingsTabMessage::TwoFactorEnrollmentConfirmed(result) => {
+                self.loading = false;
+                self.handle_two_factor_enrollment_confirmed(result);
+            }
+            // Disables two-factor authentication, if the password and the code are correct
+            SettingsTabMessage::DisableTwoFactor => {
+                return self.disable_two_factor();
+            }
+            SettingsTabMessage::TwoFactorDisabled(result) => {
+                self.loading = false;
+                self.handle_two_factor_disabled(result);
+            }
+            // Updates the two-factor code value
+            SettingsTabMessage::TwoFactorCodeInputChanged(code) => {
+                self.two_factor_code_value = code;
+            }
+            // Updates the two-factor password value
+            SettingsTabMessage::TwoFactorPasswordInputChanged(password) => {
+                self.two_factor_password_value = password;
+            }
+            SettingsTabMessage::TwoFactorStatusLoaded(result) => {
+                self.handle_two_factor_status_loaded(result);
+            }
+            // Revokes a session of another device
+            SettingsTabMessage::RevokeSession(session_id) => {
+                return self.revoke_session(session_id);
+            }
+            SettingsTabMessage::SessionRevoked(result) => {
+                self.loading = false;
+                return self.handle_session_revoked(result);
+            }
+            SettingsTabMessage::SessionsLoaded(result) => {
+                self.handle_sessions_loaded(result);
+            }
+            SettingsTabMessage::NotificationSettingsLoaded(result) => {
+                self.handle_notification_settings_loaded(result);
+            }
+            // Turns do not disturb on or off
+            SettingsTabMessage::DoNotDisturbToggled(enabled) => {
+                if let Some(settings) = self.notification_settings.as_mut() {
+                    settings.dnd_enabled = enabled;
+                }
+            }
+            // Updates the start of do not disturb
+            SettingsTabMessage::DoNotDisturbStartInputChanged(time) => {
+                self.dnd_start_value = time;
+            }
+            // Updates the end of do not disturb
+            SettingsTabMessage::DoNotDisturbEndInputChanged(time) => {
+                self.dnd_end_value = time;
+            }
+            // Mutes or unmutes the chat with a friend
+            SettingsTabMessage::MuteUserToggled(user_id, muted) => {
+                if let Some(settings) = self.notification_settings.as_mut() {
+                    set_muted(&mut settings.muted_user_ids, user_id, muted);
+                }
+            }
+            // Mutes or unmutes a group chat
+            SettingsTabMessage::MuteGroupToggled(group_id, muted) => {
+                if let Some(settings) = self.notification_settings.as_mut() {
+                    set_muted(&mut settings.muted_group_ids, group_id, muted);
+                }
+            }
+            // Saves the notification settings
+            SettingsTabMessage::SaveNotificationSettings => {
+                return self.save_notification_settings();
+            }
+            SettingsTabMessage::NotificationSettingsSaved(result) => {
+                self.loading = false;
+                self.handle_notification_settings_saved(result);
             }
         }
+
+        Command::none()
     }
 
     /// Returns the view of the settings tab
@@ -234,33 +1082,283 @@ impl SettingTab {
         )
         .spacing(spacing);
 
+        let theme_high_contrast_radio = radio(
+            t("settings-theme-high-contrast"),
+            AppTheme::HighContrast,
+            Some(self.app_theme),
+            SettingsTabMessage::ChangeTheme,
+        )
+        .spacing(spacing);
+
+        let theme_system_radio = radio(
+            t("settings-theme-system"),
+            AppTheme::System,
+            Some(self.app_theme),
+            SettingsTabMessage::ChangeTheme,
+        )
+        .spacing(spacing);
+
         let top_row = row!(
             theme_moonfly_radio,
             theme_oxocarbon_radio,
             theme_dracula_radio,
             theme_kanagawa_wave_radio,
-            theme_light_radio
+            theme_light_radio,
+            theme_high_contrast_radio,
+            theme_system_radio
         )
         .spacing(spacing)
         .padding(padding);
 
-        let current_password_field = text_input("Old Password", &self.current_password_value)
-            .width(input_width)
+        let custom_themes_column =
+            self.custom_themes.iter().fold(
+                Column::new().spacing(spacing / 2),
+                |themes_column, theme| {
+                    let selected =
+                        self.app_theme == AppTheme::Custom && self.custom_theme == theme.name;
+                    let marker = if selected { "▶ " } else { "" };
+
+                    themes_column.push(
+                        row!(
+                            text(format!("{}{}", marker, theme.name)).width(button_width),
+                            button(
+                                text(t("settings-theme-use"))
+                                    .horizontal_alignment(alignment::Horizontal::Center)
+                            )
+                            .padding(padding / 2)
+                            .on_press_maybe((!selected).then(|| {
+                                SettingsTabMessage::SelectCustomTheme(theme.name.clone())
+                            }),),
+                            button(
+                                text(t("settings-theme-edit"))
+                                    .horizontal_alignment(alignment::Horizontal::Center)
+                            )
+                            .padding(padding / 2)
+                            .on_press(SettingsTabMessage::EditCustomTheme(theme.name.clone())),
+                            button(
+                                text(t("settings-theme-delete"))
+                                    .horizontal_alignment(alignment::Horizontal::Center)
+                            )
+                            .padding(padding / 2)
+                            .on_press(SettingsTabMessage::DeleteCustomTheme(theme.name.clone())),
+                        )
+                        .spacing(spacing)
+                        .align_items(Alignment::Center),
+                    )
+                },
+            );
+
+        let theme_colors_row =
+            ThemeColor::ALL
+                .iter()
+                .fold(row![].spacing(spacing / 2), |colors_row, &color| {
+                    colors_row.push(
+                        column![
+                            text(color.label()).size(15),
+                            text_input("#rrggbb", self.theme_editor.palette.get(color))
+                                .style(input_style())
+                                .width(input_width / 3)
+                                .padding(padding)
+                                .on_input(move |value| {
+                                    SettingsTabMessage::ThemeColorInputChanged(color, value)
+                                }),
+                        ]
+                        .spacing(5),
+                    )
+                });
+
+        let theme_editor_row = row!(
+            text_input(&t("settings-theme-name"), &self.theme_editor.name)
+                .style(input_style())
+                .width(button_width)
+                .padding(padding)
+                .on_input(SettingsTabMessage::ThemeNameInputChanged),
+            button(
+                text(t("settings-theme-save")).horizontal_alignment(alignment::Horizontal::Center)
+            )
+            .width(button_width)
             .padding(padding)
-            .secure(true)
-            .on_input(SettingsTabMessage::CurrentPasswordInputChanged);
+            .on_press(SettingsTabMessage::SaveCustomTheme),
+            text_input(&t("settings-theme-file"), &self.theme_path_value)
+                .style(input_style())
+                .width(input_width)
+                .padding(padding)
+                .on_input(SettingsTabMessage::ThemePathInputChanged),
+            button(
+                text(t("settings-theme-import"))
+                    .horizontal_alignment(alignment::Horizontal::Center)
+            )
+            .padding(padding)
+            .on_press(SettingsTabMessage::ImportTheme),
+            button(
+                text(t("settings-theme-export"))
+                    .horizontal_alignment(alignment::Horizontal::Center)
+            )
+            .padding(padding)
+            .on_press(SettingsTabMessage::ExportTheme),
+        )
+        .spacing(spacing)
+        .align_items(Alignment::Center);
+
+        let custom_theme_column = column![
+            text(t("settings-custom-themes")).size(20),
+            custom_themes_column,
+            theme_colors_row,
+            theme_editor_row
+        ]
+        .spacing(spacing / 2)
+        .align_items(Alignment::Center);
+
+        let preferences_row = row!(
+            checkbox(
+                t("settings-show-notifications"),
+                self.notification_preferences.enabled
+            )
+            .on_toggle(SettingsTabMessage::NotificationsEnabledToggled),
+            checkbox(
+                t("settings-show-preview"),
+                self.notification_preferences.show_preview
+            )
+            .on_toggle(SettingsTabMessage::NotificationPreviewToggled),
+            checkbox(t("settings-sync-preferences"), self.sync_preferences)
+                .on_toggle(SettingsTabMessage::SyncPreferencesToggled),
+        )
+        .spacing(spacing)
+        .align_items(Alignment::Center);
+
+        let font_scale_row = row!(
+            text(t("settings-font-scale")),
+            button(text("A−").horizontal_alignment(alignment::Horizontal::Center))
+                .padding(padding)
+                .on_press(SettingsTabMessage::ChangeFontScale(
+                    self.font_scale - FONT_SCALE_STEP
+                )),
+            text(format!("{:.0} %", self.font_scale * 100.0)),
+            button(text("A+").horizontal_alignment(alignment::Horizontal::Center))
+                .padding(padding)
+                .on_press(SettingsTabMessage::ChangeFontScale(
+                    self.font_scale + FONT_SCALE_STEP
+                )),
+            button(
+                text(t("settings-font-scale-reset"))
+                    .horizontal_alignment(alignment::Horizontal::Center)
+            )
+            .padding(padding)
+            .on_press(SettingsTabMessage::ChangeFontScale(1.0)),
+        )
+        .spacing(spacing)
+        .align_items(Alignment::Center);
+
+        let language_row = Language::ALL.iter().fold(
+            row!(
+                text(t("settings-language")),
+                radio(
+                    t("settings-language-system"),
+                    None,
+                    Some(self.language),
+                    SettingsTabMessage::ChangeLanguage,
+                )
+            )
+            .spacing(spacing)
+            .align_items(Alignment::Center),
+            |language_row, language| {
+                language_row.push(radio(
+                    language.name(),
+                    Some(*language),
+                    Some(self.language),
+                    SettingsTabMessage::ChangeLanguage,
+                ))
+            },
+        );
+
+        let time_zone_row = row!(
+            text(t("settings-time-zone")),
+            text_input(&t("settings-time-zone-placeholder"), &self.time_zone_value)
+                .style(input_style())
+                .width(input_width)
+                .padding(padding)
+                .on_input(SettingsTabMessage::TimeZoneInputChanged)
+                .on_submit(SettingsTabMessage::SaveTimeZone),
+            button(
+                text(t("settings-time-zone-save"))
+                    .horizontal_alignment(alignment::Horizontal::Center)
+            )
+            .width(button_width)
+            .padding(padding)
+            .on_press(SettingsTabMessage::SaveTimeZone),
+        )
+        .spacing(spacing)
+        .align_items(Alignment::Center);
+
+        let shortcuts_column = Shortcut::ALL.iter().fold(
+            column![text(t("settings-shortcuts"))]
+                .spacing(spacing)
+                .align_items(Alignment::Center),
+            |shortcuts_column, shortcut| {
+                let value = self
+                    .shortcut_values
+                    .get(shortcut)
+                    .map(String::as_str)
+                    .unwrap_or_default();
+                shortcuts_column.push(
+                    row!(
+                        text(shortcut.label()).width(input_width),
+                        text_input(&t("settings-shortcut-placeholder"), value)
+                            .style(input_style())
+                            .width(button_width * 2)
+                            .padding(padding)
+                            .on_input(|value| SettingsTabMessage::ShortcutInputChanged(
+                                *shortcut, value
+                            ))
+                            .on_submit(SettingsTabMessage::SaveShortcuts),
+                    )
+                    .spacing(spacing)
+                    .align_items(Alignment::Center),
+                )
+            },
+        );
+
+        let shortcuts_column = shortcuts_column.push(
+            row!(
+                button(
+                    text(t("settings-shortcuts-save"))
+                        .horizontal_alignment(alignment::Horizontal::Center)
+                )
+                .width(button_width)
+                .padding(padding)
+                .on_press(SettingsTabMessage::SaveShortcuts),
+                button(
+                    text(t("settings-shortcuts-reset-button"))
+                        .horizontal_alignment(alignment::Horizontal::Center)
+                )
+                .width(button_width)
+                .padding(padding)
+                .on_press(SettingsTabMessage::ResetShortcuts),
+            )
+            .spacing(spacing),
+        );
+
+        let current_password_field =
+            text_input(&t("settings-old-password"), &self.current_password_value)
+                .style(input_style())
+                .width(input_width)
+                .padding(padding)
+                .secure(true)
+                .on_input(SettingsTabMessage::CurrentPasswordInputChanged);
 
-        let new_password_field = text_input("New Password", &self.new_password_value)
+        let new_password_field = text_input(&t("settings-new-password"), &self.new_password_value)
+            .style(input_style())
             .width(input_width)
             .padding(padding)
             .secure(true)
             .on_input(SettingsTabMessage::NewPasswordInputChanged);
 
-        let update_password_button =
-            button(text("Update Password").horizontal_alignment(alignment::Horizontal::Center))
-                .width(button_width)
-                .padding(padding)
-                .on_press(SettingsTabMessage::UpdatePassword);
+        let update_password_button = button(
+            text(t("settings-update-password")).horizontal_alignment(alignment::Horizontal::Center),
+        )
+        .width(button_width)
+        .padding(padding)
+        .on_press(SettingsTabMessage::UpdatePassword);
 
         let update_password_row = row!(
             horizontal_space(),
@@ -271,41 +1369,261 @@ impl SettingTab {
         .spacing(spacing)
         .padding(padding);
 
-        let new_email_field = text_input("Email", &self.new_email_value)
+        let new_email_field = text_input(&t("email"), &self.new_email_value)
+            .style(input_style())
             .width(input_width)
             .padding(padding)
             .on_input(SettingsTabMessage::EmailInputChanged);
 
-        let update_email_button =
-            button(text("Update Email").horizontal_alignment(alignment::Horizontal::Center))
-                .width(button_width)
-                .padding(padding)
-                .on_press(SettingsTabMessage::UpdateEmail);
+        let update_email_button = button(
+            text(t("settings-update-email")).horizontal_alignment(alignment::Horizontal::Center),
+        )
+        .width(button_width)
+        .padding(padding)
+        .on_press(SettingsTabMessage::UpdateEmail);
 
         let update_email_row = row!(horizontal_space(), new_email_field, update_email_button)
             .spacing(spacing)
             .padding(padding);
 
-        let delete_button =
-            button(text("Delete Account").horizontal_alignment(alignment::Horizontal::Center))
+        let verification_code_field = text_input(
+            &t("settings-verification-code"),
+            &self.verification_code_value,
+        )
+        .style(input_style())
+        .width(input_width)
+        .padding(padding)
+        .on_input(SettingsTabMessage::VerificationCodeInputChanged);
+
+        let verify_email_button = button(
+            text(t("settings-verify-email")).horizontal_alignment(alignment::Horizontal::Center),
+        )
+        .width(button_width)
+        .padding(padding)
+        .on_press(SettingsTabMessage::VerifyEmail);
+
+        let verify_email_row = row!(
+            horizontal_space(),
+            verification_code_field,
+            verify_email_button
+        )
+        .spacing(spacing)
+        .padding(padding);
+
+        let two_factor_code_field =
+            text_input(&t("settings-two-factor-code"), &self.two_factor_code_value)
+                .style(input_style())
+                .width(input_width)
+                .padding(padding)
+                .on_input(SettingsTabMessage::TwoFactorCodeInputChanged);
+
+        let two_factor_content: Element<SettingsTabMessage> = if self.two_factor_enabled {
+            let two_factor_password_field =
+                text_input(&t("password"), &self.two_factor_password_value)
+                    .style(input_style())
+                    .width(input_width)
+                    .padding(padding)
+                    .secure(true)
+                    .on_input(SettingsTabMessage::TwoFactorPasswordInputChanged);
+
+            let disable_button = button(
+                text(t("settings-two-factor-disable"))
+                    .horizontal_alignment(alignment::Horizontal::Center),
+            )
+            .width(button_width)
+            .padding(padding)
+            .on_press(SettingsTabMessage::DisableTwoFactor);
+
+            row!(
+                horizontal_space(),
+                two_factor_password_field,
+                two_factor_code_field,
+                disable_button
+            )
+            .spacing(spacing)
+            .padding(padding)
+            .into()
+        } else if let Some(enrollment) = &self.totp_enrollment {
+            let confirm_button = button(
+                text(t("settings-two-factor-confirm"))
+                    .horizontal_alignment(alignment::Horizontal::Center),
+            )
+            .width(button_width)
+            .padding(padding)
+            .on_press(SettingsTabMessage::ConfirmTwoFactorEnrollment);
+
+            column![
+                text(t_args(
+                    "settings-two-factor-secret",
+                    &[("secret", enrollment.secret.clone())]
+                ))
+                .size(15),
+                text(&enrollment.otpauth_uri).size(15),
+                row!(horizontal_space(), two_factor_code_field, confirm_button).spacing(spacing)
+            ]
+            .spacing(spacing)
+            .padding(padding)
+            .align_items(Alignment::Center)
+            .into()
+        } else {
+            let enable_button = button(
+                text(t("settings-two-factor-enable"))
+                    .horizontal_alignment(alignment::Horizontal::Center),
+            )
+            .width(button_width)
+            .padding(padding)
+            .on_press(SettingsTabMessage::StartTwoFactorEnrollment);
+
+            row!(horizontal_space(), enable_button)
+                .spacing(spacing)
+                .padding(padding)
+                .into()
+        };
+
+        let recovery_codes: Element<SettingsTabMessage> = if !self.recovery_codes.is_empty() {
+            text(t_args(
+                "settings-recovery-codes",
+                &[("codes", self.recovery_codes.join(" "))],
+            ))
+            .size(15)
+            .horizontal_alignment(Horizontal::Center)
+            .into()
+        } else {
+            text("").into()
+        };
+
+        let sessions_column = self.sessions.iter().fold(
+            column![text(t("settings-sessions")).size(20)]
+                .spacing(spacing / 2)
+                .align_items(Alignment::Center),
+            |sessions_column, session| {
+                let session_text = text(t_args(
+                    "settings-session",
+                    &[
+                        ("device", session.device_name.clone()),
+                        ("last_active", format_send_date(session.last_seen_at)),
+                    ],
+                ))
+                .size(15);
+
+                let session_action: Element<SettingsTabMessage> =
+                    if self.current_session_id == Some(session.id) {
+                        text(t("settings-this-device")).size(15).into()
+                    } else {
+                        button(
+                            text(t("settings-revoke"))
+                                .horizontal_alignment(alignment::Horizontal::Center),
+                        )
+                        .padding(padding / 2)
+                        .on_press(SettingsTabMessage::RevokeSession(session.id))
+                        .into()
+                    };
+
+                sessions_column.push(
+                    row!(session_text, session_action)
+                        .spacing(spacing)
+                        .align_items(Alignment::Center),
+                )
+            },
+        );
+
+        let notifications_column: Element<SettingsTabMessage> = match &self.notification_settings {
+            Some(settings) => {
+                let dnd_row = row!(
+                    checkbox(t("settings-dnd"), settings.dnd_enabled)
+                        .on_toggle(SettingsTabMessage::DoNotDisturbToggled),
+                    text_input(&t("settings-dnd-from"), &self.dnd_start_value)
+                        .style(input_style())
+                        .width(button_width)
+                        .padding(padding)
+                        .on_input(SettingsTabMessage::DoNotDisturbStartInputChanged),
+                    text_input(&t("settings-dnd-to"), &self.dnd_end_value)
+                        .style(input_style())
+                        .width(button_width)
+                        .padding(padding)
+                        .on_input(SettingsTabMessage::DoNotDisturbEndInputChanged),
+                )
+                .spacing(spacing)
+                .align_items(Alignment::Center);
+
+                let mute_friends_row = self.notification_friends.iter().fold(
+                    row![text(t("settings-mute-friends")).size(15)].spacing(spacing),
+                    |mute_row, friend| {
+                        let friend_id = friend.id;
+                        mute_row.push(
+                            checkbox(
+                                format!("{} {}", friend.first_name, friend.last_name),
+                                settings.muted_user_ids.contains(&friend_id),
+                            )
+                            .on_toggle(move |muted| {
+                                SettingsTabMessage::MuteUserToggled(friend_id, muted)
+                            }),
+                        )
+                    },
+                );
+
+                let mute_groups_row = self.notification_groups.iter().fold(
+                    row![text(t("settings-mute-groups")).size(15)].spacing(spacing),
+                    |mute_row, group| {
+                        let group_id = group.id;
+                        mute_row.push(
+                            checkbox(&group.name, settings.muted_group_ids.contains(&group_id))
+                                .on_toggle(move |muted| {
+                                    SettingsTabMessage::MuteGroupToggled(group_id, muted)
+                                }),
+                        )
+                    },
+                );
+
+                let save_notifications_button = button(
+                    text(t("settings-save-notifications"))
+                        .horizontal_alignment(alignment::Horizontal::Center),
+                )
                 .width(button_width)
                 .padding(padding)
-                .on_press(SettingsTabMessage::DeleteAccount);
+                .on_press(SettingsTabMessage::SaveNotificationSettings);
+
+                column![
+                    text(t("settings-notifications")).size(20),
+                    dnd_row,
+                    mute_friends_row,
+                    mute_groups_row,
+                    save_notifications_button
+                ]
+                .spacing(spacing / 2)
+                .align_items(Alignment::Center)
+                .into()
+            }
+            None => column![].into(),
+        };
+
+        let delete_button = button(
+            text(t("settings-delete-account")).horizontal_alignment(alignment::Horizontal::Center),
+        )
+        .width(button_width)
+        .padding(padding)
+        .on_press(SettingsTabMessage::DeleteAccount);
 
         let error_message: Element<SettingsTabMessage> = if !self.error.is_empty() {
             text(&self.error)
                 .size(15)
-                .style(Color::from_rgb(1.0, 0.0, 0.0)) // Red color
+                .style(error_color())
                 .horizontal_alignment(Horizontal::Center)
                 .into()
         } else {
             text("").into()
         };
 
-        let info_message: Element<SettingsTabMessage> = if !self.info.is_empty() {
+        let info_message: Element<SettingsTabMessage> = if self.loading {
+            text(t("settings-loading"))
+                .size(15)
+                .style(success_color())
+                .horizontal_alignment(Horizontal::Center)
+                .into()
+        } else if !self.info.is_empty() {
             text(&self.info)
                 .size(15)
-                .style(Color::from_rgb(0.2, 0.8, 0.2)) //Green color
+                .style(success_color())
                 .horizontal_alignment(Horizontal::Center)
                 .into()
         } else {
@@ -313,9 +1631,9 @@ impl SettingTab {
         };
 
         let confirmation_dialog: Element<SettingsTabMessage> = if self.delete_button_pressed {
-            text("Are you sure you want to delete your account?".to_string())
+            text(t("settings-delete-confirm"))
                 .size(15)
-                .style(Color::from_rgb(1.0, 0.0, 0.0))
+                .style(error_color())
                 .horizontal_alignment(Horizontal::Center)
                 .into()
         } else {
@@ -333,8 +1651,19 @@ impl SettingTab {
 
         let content = column![
             top_row,
+            custom_theme_column,
+            preferences_row,
+            font_scale_row,
+            language_row,
+            time_zone_row,
+            shortcuts_column,
             update_password_row,
             update_email_row,
+            verify_email_row,
+            two_factor_content,
+            recovery_codes,
+            sessions_column,
+            notifications_column,
             delete_row,
             message_column
         ]
@@ -377,6 +1706,11 @@ impl SettingTab {
         &self.new_email_value
     }
 
+    /// Getter method for the verification code value
+    pub fn get_verification_code_value(&self) -> &str {
+        &self.verification_code_value
+    }
+
     /// Getter method for the current password value
     pub fn get_current_password_value(&self) -> &str {
         &self.current_password_value
@@ -386,6 +1720,31 @@ impl SettingTab {
     pub fn get_delete_button_pressed(&self) -> bool {
         self.delete_button_pressed
     }
+
+    /// Getter method for the two-factor enabled flag
+    pub fn get_two_factor_enabled(&self) -> bool {
+        self.two_factor_enabled
+    }
+
+    /// Getter method for the started two-factor enrolment
+    pub fn get_totp_enrollment(&self) -> Option<&TotpEnrollment> {
+        self.totp_enrollment.as_ref()
+    }
+
+    /// Getter method for the active sessions
+    pub fn get_sessions(&self) -> &Vec<Session> {
+        &self.sessions
+    }
+
+    /// Getter method for the recovery codes
+    pub fn get_recovery_codes(&self) -> &Vec<String> {
+        &self.recovery_codes
+    }
+
+    /// Getter method for the notification settings
+    pub fn get_notification_settings(&self) -> Option<&NotificationSettings> {
+        self.notification_settings.as_ref()
+    }
 }
 
 /// Setter methods for tests
@@ -405,3 +1764,30 @@ impl SettingTab {
         self.delete_button_pressed = delete_button_pressed;
     }
 }
+
+/// Loads the notification settings of a user, the friends and the groups of the user. Returns the data or an error message
+fn load_notification_settings_data(user_id: i32) -> Result<NotificationSettingsData, String> {
+    let group_tab_data = load_group_tab_data(user_id)?;
+
+    Ok(NotificationSettingsData {
+        settings: find_notification_settings(user_id)?,
+        friends: find_users_by_ids(find_friend_ids(user_id, group_tab_data.friend_relations()))?,
+        groups: group_tab_data.groups_of_user().to_vec(),
+    })
+}
+
+/// Add a conversation to the muted conversations or remove it
+fn set_muted(muted_ids: &mut Vec<i32>, id: i32, muted: bool) {
+    muted_ids.retain(|muted_id| *muted_id != id);
+    if muted {
+        muted_ids.push(id);
+    }
+}
+
+/// Returns the bindings of the shortcuts as they are entered
+fn shortcut_values(shortcuts: &Shortcuts) -> HashMap<Shortcut, String> {
+    Shortcut::ALL
+        .into_iter()
+        .map(|shortcut| (shortcut, shortcuts.get(shortcut).to_string()))
+        .collect()
+}
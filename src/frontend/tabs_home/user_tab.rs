@@ -1,7 +1,7 @@
 use iced::{
     alignment::Horizontal,
     widget::{button, column, horizontal_space, pick_list, row, text},
-    Alignment, Element,
+    Alignment, Command, Element,
 };
 use log::error;
 
@@ -12,9 +12,11 @@ use crate::backend::{
         find_all_user_to_user_friend_entries,
     },
 };
+use crate::frontend::task::{run_blocking, LoadTracker};
+use crate::i18n::t;
 
 /// Represents the user tab in the home tab
-#[derive(Default, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct UserTab {
     current_user: Option<User>,
     user_to_chat_with: Option<User>,
@@ -27,6 +29,10 @@ pub struct UserTab {
     friend_requests: Vec<User>,
     // Send friend request
     send_friend_request: Vec<User>,
+    // Set until the friends have been loaded for the first time
+    loading: bool,
+    // Load of the friends that is started on every tick
+    load: LoadTracker,
 }
 
 /// Represents the messages that can be sent to the user tab
@@ -35,16 +41,39 @@ pub enum UserTabMessage {
     // Selection
     UserSelected(User),
     SendFriendRequestToSelectedUser,
+    FriendRequestSent(User, Result<(), String>),
     // Friends
     ChatWithUser(User),
     RemoveFriend(User),
+    FriendRemoved(User, Result<(), String>),
     // Accept or decline friend request
     AcceptFriendRequest(User),
+    FriendRequestAccepted(User, Result<(), String>),
     DeclineFriendRequest(User),
+    FriendRequestDeclined(User, Result<(), String>),
     // Requests
     RemoveFriendRequest(User),
+    FriendRequestRemoved(User, Result<(), String>),
     // Load data
     Tick(Vec<User>),
+    FriendsLoaded(u64, Vec<User>, Result<Vec<UserToUserFriend>, String>),
+}
+
+/// Default implementation for the user tab
+impl Default for UserTab {
+    fn default() -> Self {
+        UserTab {
+            current_user: None,
+            user_to_chat_with: None,
+            all_users_not_friends: Vec::new(),
+            selected_user: None,
+            friends: Vec::new(),
+            friend_requests: Vec::new(),
+            send_friend_request: Vec::new(),
+            loading: true,
+            load: LoadTracker::default(),
+        }
+    }
 }
 
 /// Implementation of the user tab
@@ -62,19 +91,6 @@ impl UserTab {
         self.send_friend_request.clear();
     }
 
-    /// Loads all friends of the current user
-    fn load_friends_of_user(current_user_id: i32) -> Vec<UserToUserFriend> {
-        let all_user_friends = find_all_user_to_user_friend_entries(current_user_id);
-
-        match all_user_friends {
-            Ok(all_user_friends) => all_user_friends,
-            Err(e) => {
-                error!("Error loading user friends: {:?}", e);
-                vec![]
-            }
-        }
-    }
-
     /// Finds the lower and higher user id
     fn find_lower_and_higher_user_id(&mut self, other_user_id: i32) -> (i32, i32) {
         let current_user_id = self.current_user.as_ref().unwrap().id;
@@ -86,52 +102,70 @@ impl UserTab {
         }
     }
 
-    /// Accepts a friend request. Sets the lower id to the first user and the higher id to the second user
-    fn acccepte_friend_request(&mut self, other_user_id: i32) -> Result<(), String> {
-        let (lower_user_id, higher_user_id) = self.find_lower_and_higher_user_id(other_user_id);
+    /// Accepts a friend request in the background. Sets the lower id to the first user and the higher id to the second user
+    fn acccepte_friend_request(&mut self, user: User) -> Command<UserTabMessage> {
+        let (lower_user_id, higher_user_id) = self.find_lower_and_higher_user_id(user.id);
 
-        acccepte_friend_request(lower_user_id, higher_user_id)
+        Command::perform(
+            run_blocking(move || acccepte_friend_request(lower_user_id, higher_user_id)),
+            move |result| UserTabMessage::FriendRequestAccepted(user.clone(), result),
+        )
     }
 
-    /// Removes a friend request. Sets the lower id to the first user and the higher id to the second user
-    fn remove_friend_request(&mut self, other_user_id: i32) -> Result<(), String> {
-        let (lower_user_id, higher_user_id) = self.find_lower_and_higher_user_id(other_user_id);
-
-        delete_friend_to_friend_relation(lower_user_id, higher_user_id)
+    /// Removes a friend request in the background. Sets the lower id to the first user and the higher id to the second user.
+    /// The result is reported with the given message
+    fn remove_friend_request(
+        &mut self,
+        user: User,
+        on_result: fn(User, Result<(), String>) -> UserTabMessage,
+    ) -> Command<UserTabMessage> {
+        let (lower_user_id, higher_user_id) = self.find_lower_and_higher_user_id(user.id);
+
+        Command::perform(
+            run_blocking(move || delete_friend_to_friend_relation(lower_user_id, higher_user_id)),
+            move |result| on_result(user.clone(), result),
+        )
     }
 
-    /// Sends a friend request. Selected user must be set. Sets the lower id to the first user and the higher id to the second user.
-    /// Removes the user from the able to send requests list and adds the user to the sent friend requests list
-    fn send_friend_request(&mut self) {
-        if self.selected_user.is_none() {
-            return;
-        }
+    /// Sends a friend request in the background. Selected user must be set. Sets the lower id to the first user and the higher id to the second user.
+    fn send_friend_request(&mut self) -> Command<UserTabMessage> {
+        let Some(selected_user) = self.selected_user.clone() else {
+            return Command::none();
+        };
 
         // Send friend request
         let current_user_id = self.current_user.as_ref().unwrap().id;
-        let other_user_id = self.selected_user.as_ref().unwrap().id;
+        let (lower_user_id, higher_user_id) = self.find_lower_and_higher_user_id(selected_user.id);
+
+        Command::perform(
+            run_blocking(move || {
+                create_user_friend(
+                    lower_user_id,
+                    higher_user_id,
+                    current_user_id == lower_user_id,
+                    current_user_id == higher_user_id,
+                )
+                .map(|_| ())
+            }),
+            move |result| UserTabMessage::FriendRequestSent(selected_user.clone(), result),
+        )
+    }
 
-        let (lower_user_id, higher_user_id) = self.find_lower_and_higher_user_id(other_user_id);
+    /// Shows the friends cached on this device until they are loaded
+    pub fn show_cached(&mut self, users: Vec<User>, friends: Vec<UserToUserFriend>) {
+        self.handle_friends_loaded(users, Ok(friends));
+    }
 
-        let result = create_user_friend(
-            lower_user_id,
-            higher_user_id,
-            current_user_id == lower_user_id,
-            current_user_id == higher_user_id,
-        );
+    /// Handles the result of sending a friend request.
+    /// Removes the user from the able to send requests list and adds the user to the sent friend requests list
+    fn handle_friend_request_sent(&mut self, user: User, result: Result<(), String>) {
         match result {
             Ok(_) => {
+                self.load.invalidate();
                 // Remove user from able to send requests list
-                let user_that_got_friend_requst = self
-                    .all_users_not_friends
-                    .iter()
-                    .position(|u| u.id == other_user_id)
-                    .unwrap();
-                self.all_users_not_friends
-                    .remove(user_that_got_friend_requst);
+                self.all_users_not_friends.retain(|u| u.id != user.id);
                 // Add user to sent friend requests list
-                self.send_friend_request
-                    .push(self.selected_user.as_ref().unwrap().clone());
+                self.send_friend_request.push(user);
 
                 self.selected_user = None;
             }
@@ -141,10 +175,35 @@ impl UserTab {
         }
     }
 
-    /// Handles the tick event. Loads user data and filters out the current user. Fills the different lists with the user data
-    fn handle_tick(&mut self, users: Vec<User>) {
-        // Load user data asynchronously
-        let friends = Self::load_friends_of_user(self.current_user.as_ref().unwrap().id);
+    /// Handles the tick event. Loads the friends of the current user in the background, unless they are still being loaded
+    fn handle_tick(&mut self, users: Vec<User>) -> Command<UserTabMessage> {
+        let current_user_id = self.current_user.as_ref().unwrap().id;
+        let Some(generation) = self.load.start() else {
+            return Command::none();
+        };
+
+        Command::perform(
+            run_blocking(move || find_all_user_to_user_friend_entries(current_user_id)),
+            move |result| UserTabMessage::FriendsLoaded(generation, users.clone(), result),
+        )
+    }
+
+    /// Handles the loaded friends. Filters out the current user. Fills the different lists with the user data
+    fn handle_friends_loaded(
+        &mut self,
+        users: Vec<User>,
+        result: Result<Vec<UserToUserFriend>, String>,
+    ) {
+        self.loading = false;
+
+        // The shown friends are kept if they could not be loaded
+        let friends = match result {
+            Ok(all_user_friends) => all_user_friends,
+            Err(e) => {
+                error!("Error loading user friends: {:?}", e);
+                return;
+            }
+        };
 
         // Clears lists to avoid duplicates
         self.clear_lists();
@@ -158,23 +217,12 @@ impl UserTab {
 
         // Iterate over friends to fill different lists
         for friend in &friends {
-            // Check if the current user is involved in the friendship
-            let (other_user_id, current_user_accepted, other_user_accepted) =
-                if friend.user_one_id == current_user_id {
-                    (
-                        friend.user_two_id,
-                        friend.accepted_user_one,
-                        friend.accepted_user_two,
-                    )
-                } else if friend.user_two_id == current_user_id {
-                    (
-                        friend.user_one_id,
-                        friend.accepted_user_two,
-                        friend.accepted_user_one,
-                    )
-                } else {
-                    continue; // Skip if the current user is not involved
-                };
+            // Skip if the current user is not involved
+            let Some((other_user_id, current_user_accepted, other_user_accepted)) =
+                friend_relation_of(current_user_id, friend)
+            else {
+                continue;
+            };
 
             let other_user = users
                 .iter()
@@ -209,7 +257,7 @@ impl UserTab {
     }
 
     /// Updates the user tab based on the message
-    pub fn update(&mut self, message: UserTabMessage) {
+    pub fn update(&mut self, message: UserTabMessage) -> Command<UserTabMessage> {
         match message {
             // Selection
             UserTabMessage::UserSelected(user) => {
@@ -220,72 +268,83 @@ impl UserTab {
                 self.user_to_chat_with = Some(user);
             }
             UserTabMessage::SendFriendRequestToSelectedUser => {
-                self.send_friend_request();
+                return self.send_friend_request();
+            }
+            UserTabMessage::FriendRequestSent(user, result) => {
+                self.handle_friend_request_sent(user, result);
             }
             UserTabMessage::RemoveFriend(user) => {
-                let result = self.remove_friend_request(user.id);
-
-                match result {
-                    Ok(_) => {
-                        // Remove user from friends list
-                        self.friends.retain(|u| u != &user);
-                        self.all_users_not_friends.push(user);
-                    }
-                    Err(e) => {
-                        error!("Error removing friend: {:?}", e);
-                    }
-                }
+                return self.remove_friend_request(user, UserTabMessage::FriendRemoved);
             }
+            UserTabMessage::FriendRemoved(user, result) => match result {
+                Ok(_) => {
+                    self.load.invalidate();
+                    // Remove user from friends list
+                    self.friends.retain(|u| u != &user);
+                    self.all_users_not_friends.push(user);
+                }
+                Err(e) => {
+                    error!("Error removing friend: {:?}", e);
+                }
+            },
             // Accept or decline friend request
             UserTabMessage::AcceptFriendRequest(user) => {
-                let result = self.acccepte_friend_request(user.id);
-
-                match result {
-                    Ok(_) => {
-                        // Remove user from friend requests list
-                        self.friend_requests.retain(|u| u != &user);
-                        // Add user to friends list
-                        self.friends.push(user);
-                    }
-                    Err(e) => {
-                        error!("Error accepting friend request: {:?}", e);
-                    }
-                }
+                return self.acccepte_friend_request(user);
             }
-            UserTabMessage::DeclineFriendRequest(user) => {
-                let result = self.remove_friend_request(user.id);
-
-                match result {
-                    Ok(_) => {
-                        // Remove user from friend requests list
-                        self.friend_requests.retain(|u| u != &user);
-                        self.all_users_not_friends.push(user);
-                    }
-                    Err(e) => {
-                        error!("Error declining friend request: {:?}", e);
-                    }
+            UserTabMessage::FriendRequestAccepted(user, result) => match result {
+                Ok(_) => {
+                    self.load.invalidate();
+                    // Remove user from friend requests list
+                    self.friend_requests.retain(|u| u != &user);
+                    // Add user to friends list
+                    self.friends.push(user);
                 }
+                Err(e) => {
+                    error!("Error accepting friend request: {:?}", e);
+                }
+            },
+            UserTabMessage::DeclineFriendRequest(user) => {
+                return self.remove_friend_request(user, UserTabMessage::FriendRequestDeclined);
             }
+            UserTabMessage::FriendRequestDeclined(user, result) => match result {
+                Ok(_) => {
+                    self.load.invalidate();
+                    // Remove user from friend requests list
+                    self.friend_requests.retain(|u| u != &user);
+                    self.all_users_not_friends.push(user);
+                }
+                Err(e) => {
+                    error!("Error declining friend request: {:?}", e);
+                }
+            },
             // Requests
             UserTabMessage::RemoveFriendRequest(user) => {
-                let result = self.remove_friend_request(user.id);
-
-                match result {
-                    Ok(_) => {
-                        // Remove user from friend requests list
-                        self.send_friend_request.retain(|u| u != &user);
-                        self.all_users_not_friends.push(user);
-                    }
-                    Err(e) => {
-                        error!("Error removing friend request: {:?}", e);
-                    }
-                }
+                return self.remove_friend_request(user, UserTabMessage::FriendRequestRemoved);
             }
+            UserTabMessage::FriendRequestRemoved(user, result) => match result {
+                Ok(_) => {
+                    self.load.invalidate();
+                    // Remove user from friend requests list
+                    self.send_friend_request.retain(|u| u != &user);
+                    self.all_users_not_friends.push(user);
+                }
+                Err(e) => {
+                    error!("Error removing friend request: {:?}", e);
+                }
+            },
             // Load data
             UserTabMessage::Tick(users) => {
-                self.handle_tick(users);
+                return self.handle_tick(users);
+            }
+            UserTabMessage::FriendsLoaded(generation, users, result) => {
+                // Friends loaded before a newer load or a change of the lists are dropped
+                if self.load.finish(generation) {
+                    self.handle_friends_loaded(users, result);
+                }
             }
         }
+
+        Command::none()
     }
 
     /// Returns the view of the user tab
@@ -303,10 +362,10 @@ impl UserTab {
             UserTabMessage::UserSelected,
         )
         .width(300)
-        .placeholder("Select a user to add as a friend");
+        .placeholder(t("user-tab-select-user"));
 
         let send_friend_request_button =
-            button(text("Send friend request").horizontal_alignment(Horizontal::Center))
+            button(text(t("user-tab-send-request")).horizontal_alignment(Horizontal::Center))
                 .width(300)
                 .padding(padding)
                 .on_press(UserTabMessage::SendFriendRequestToSelectedUser);
@@ -317,7 +376,11 @@ impl UserTab {
             .padding(padding);
 
         // All friends row
-        let mut friends_column = column![text("Friends:")].spacing(spacing);
+        let mut friends_column = column![text(t("user-tab-friends"))].spacing(spacing);
+
+        if self.loading {
+            friends_column = friends_column.push(text(t("user-tab-loading")));
+        }
 
         for friend in &self.friends {
             let friend_button = button(
@@ -328,7 +391,7 @@ impl UserTab {
             .on_press(UserTabMessage::ChatWithUser(friend.clone()));
 
             let remove_friend_button =
-                button(text("Unfriend user").horizontal_alignment(Horizontal::Center))
+                button(text(t("user-tab-unfriend")).horizontal_alignment(Horizontal::Center))
                     .width(interact_button_width)
                     .on_press(UserTabMessage::RemoveFriend(friend.clone()));
 
@@ -340,7 +403,7 @@ impl UserTab {
         }
 
         // Friend requests
-        let mut friend_requests_column = column![text("Friend requests:")].spacing(spacing);
+        let mut friend_requests_column = column![text(t("user-tab-requests"))].spacing(spacing);
 
         for friend_request in &self.friend_requests {
             let friend_requests = text(format!(
@@ -349,12 +412,12 @@ impl UserTab {
             ));
 
             let accept_button =
-                button(text("Accept friend invite").horizontal_alignment(Horizontal::Center))
+                button(text(t("user-tab-accept")).horizontal_alignment(Horizontal::Center))
                     .width(interact_button_width)
                     .on_press(UserTabMessage::AcceptFriendRequest(friend_request.clone()));
 
             let decline_button =
-                button(text("Reject friend invite").horizontal_alignment(Horizontal::Center))
+                button(text(t("user-tab-reject")).horizontal_alignment(Horizontal::Center))
                     .width(interact_button_width)
                     .on_press(UserTabMessage::DeclineFriendRequest(friend_request.clone()));
 
@@ -367,7 +430,7 @@ impl UserTab {
 
         // Send friend requests
         let mut send_friend_request_column =
-            column![text("Sent friend requests:")].spacing(spacing);
+            column![text(t("user-tab-sent-requests"))].spacing(spacing);
 
         for send_friend_request in &self.send_friend_request {
             let send_friend_request_text = text(format!(
@@ -376,7 +439,7 @@ impl UserTab {
             ));
 
             let remove_friend_request_button =
-                button(text("Retract invite").horizontal_alignment(Horizontal::Center))
+                button(text(t("user-tab-retract")).horizontal_alignment(Horizontal::Center))
                     .width(interact_button_width)
                     .on_press(UserTabMessage::RemoveFriendRequest(
                         send_friend_request.clone(),
@@ -408,6 +471,16 @@ impl UserTab {
 
 /// Getters mainly for testing
 impl UserTab {
+    /// Returns if the data is still being loaded for the first time
+    pub fn get_loading(&self) -> bool {
+        self.loading
+    }
+
+    /// Returns the load of the friends
+    pub fn get_load(&self) -> &LoadTracker {
+        &self.load
+    }
+
     /// Returns the current user
     pub fn get_current_user(&self) -> Option<&User> {
         self.current_user.as_ref()
@@ -456,3 +529,49 @@ impl UserTab {
         self.send_friend_request.push(user);
     }
 }
+
+/// Returns the other user of a friend relation, whether the current user accepted it and whether the other user accepted it.
+/// Returns none if the current user is not involved
+fn friend_relation_of(
+    current_user_id: i32,
+    friend: &UserToUserFriend,
+) -> Option<(i32, bool, bool)> {
+    if friend.user_one_id == current_user_id {
+        Some((
+            friend.user_two_id,
+            friend.accepted_user_one,
+            friend.accepted_user_two,
+        ))
+    } else if friend.user_two_id == current_user_id {
+        Some((
+            friend.user_one_id,
+            friend.accepted_user_two,
+            friend.accepted_user_one,
+        ))
+    } else {
+        None
+    }
+}
+
+/// Returns the IDs of the users that sent the current user a friend request it has not answered yet,
+/// the same requests the user tab lists after each tick
+pub fn find_friend_request_ids(current_user_id: i32, friends: &[UserToUserFriend]) -> Vec<i32> {
+    friends
+        .iter()
+        .filter_map(|friend| friend_relation_of(current_user_id, friend))
+        .filter(|(_, current_user_accepted, _)| !current_user_accepted)
+        .map(|(other_user_id, _, _)| other_user_id)
+        .collect()
+}
+
+/// Returns the IDs of the friends of the current user
+pub fn find_friend_ids(current_user_id: i32, friends: &[UserToUserFriend]) -> Vec<i32> {
+    friends
+        .iter()
+        .filter_map(|friend| friend_relation_of(current_user_id, friend))
+        .filter(|(_, current_user_accepted, other_user_accepted)| {
+            *current_user_accepted && *other_user_accepted
+        })
+        .map(|(other_user_id, _, _)| other_user_id)
+        .collect()
+}
@@ -0,0 +1,62 @@
+/// Runs a blocking backend call, such as a database query or a password hash, on the blocking thread pool
+/// of the tokio runtime. The returned future can be passed to `Command::perform`, so the window keeps responding
+/// while the call is in flight. Returns the result of the call or an error message if the task failed
+pub async fn run_blocking<T, F>(call: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(call)
+        .await
+        .map_err(|err| format!("Background task failed: {}", err))?
+}
+
+/// Tracks the load of a view that is started on every tick. A tick is skipped while a load is in flight, so slow
+/// loads do not pile up, and the result of a load is dropped if a newer load was started or the shown data was changed since
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadTracker {
+    // Generation of the latest load, the results of older loads are stale
+    generation: u64,
+    // Set while the latest load is in flight
+    in_flight: bool,
+}
+
+/// Implementation of the load tracker
+impl LoadTracker {
+    /// Starts a load. Returns the generation to pass with the result, none if a load is already in flight
+    pub fn start(&mut self) -> Option<u64> {
+        if self.in_flight {
+            return None;
+        }
+
+        self.generation += 1;
+        self.in_flight = true;
+        Some(self.generation)
+    }
+
+    /// Returns true if the result of the load with the generation is the latest and has to be shown
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.in_flight && generation == self.generation
+    }
+
+    /// Finishes the load with the generation. Returns true if its result has to be shown, false if it is stale
+    pub fn finish(&mut self, generation: u64) -> bool {
+        if !self.is_current(generation) {
+            return false;
+        }
+
+        self.in_flight = false;
+        true
+    }
+
+    /// Marks the result of the load in flight as stale, e.g. after the shown data was changed. The next tick starts a new load
+    pub fn invalidate(&mut self) {
+        self.generation += 1;
+        self.in_flight = false;
+    }
+
+    /// Returns true while a load is in flight
+    pub fn is_in_flight(&self) -> bool {
+        self.in_flight
+    }
+}
@@ -0,0 +1,367 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::RwLock;
+
+use iced::{
+    theme::{Palette, TextInput},
+    widget::text_input,
+    Color, Theme,
+};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{t, t_args};
+
+/// Smallest contrast ratio of text to its background that WCAG 2.1 requires for normal text (level AA)
+pub const MIN_CONTRAST_RATIO: f32 = 4.5;
+
+/// Name of the high-contrast theme
+const HIGH_CONTRAST_NAME: &str = "High contrast";
+
+/// Width of the border of the focused input
+const FOCUS_BORDER_WIDTH: f32 = 3.0;
+
+lazy_static! {
+    /// The colors of error and success messages. They follow the shown theme, so the messages stay readable
+    static ref STATUS_COLORS: RwLock<StatusColors> =
+        RwLock::new(StatusColors::new(&Theme::Moonfly));
+}
+
+/// The colors of error and success messages on a theme
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatusColors {
+    pub error: Color,
+    pub success: Color,
+}
+
+/// Style of the text inputs that marks the focused input with a thick border in the primary color
+struct FocusedInput;
+
+/// A named theme with colors chosen by the user
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomTheme {
+    pub name: String,
+    pub palette: ThemePalette,
+}
+
+/// The colors of a custom theme as hex codes, e.g. "#1e1e2e"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemePalette {
+    pub background: String,
+    pub text: String,
+    pub primary: String,
+    pub success: String,
+    pub danger: String,
+}
+
+/// The colors of a palette that can be edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeColor {
+    Background,
+    Text,
+    Primary,
+    Success,
+    Danger,
+}
+
+/// Default implementation for the theme palette. The colors are those of the dark theme of iced
+impl Default for ThemePalette {
+    fn default() -> Self {
+        ThemePalette {
+            background: "#202225".to_string(),
+            text: "#e6e6e6".to_string(),
+            primary: "#5e7ce2".to_string(),
+            success: "#12664f".to_string(),
+            danger: "#c3423f".to_string(),
+        }
+    }
+}
+
+/// Implementation of the theme color
+impl ThemeColor {
+    /// All colors in the order they are shown in the editor
+    pub const ALL: [ThemeColor; 5] = [
+        ThemeColor::Background,
+        ThemeColor::Text,
+        ThemeColor::Primary,
+        ThemeColor::Success,
+        ThemeColor::Danger,
+    ];
+
+    /// Returns the name that is shown in the editor
+    pub fn label(&self) -> String {
+        match self {
+            ThemeColor::Background => t("theme-color-background"),
+            ThemeColor::Text => t("theme-color-text"),
+            ThemeColor::Primary => t("theme-color-primary"),
+            ThemeColor::Success => t("theme-color-success"),
+            ThemeColor::Danger => t("theme-color-danger"),
+        }
+    }
+}
+
+/// Implementation of the theme palette
+impl ThemePalette {
+    /// Returns the hex code of a color
+    pub fn get(&self, color: ThemeColor) -> &str {
+        match color {
+            ThemeColor::Background => &self.background,
+            ThemeColor::Text => &self.text,
+            ThemeColor::Primary => &self.primary,
+            ThemeColor::Success => &self.success,
+            ThemeColor::Danger => &self.danger,
+        }
+    }
+
+    /// Sets the hex code of a color. The code is checked when the palette is used
+    pub fn set(&mut self, color: ThemeColor, value: String) {
+        match color {
+            ThemeColor::Background => self.background = value,
+            ThemeColor::Text => self.text = value,
+            ThemeColor::Primary => self.primary = value,
+            ThemeColor::Success => self.success = value,
+            ThemeColor::Danger => self.danger = value,
+        }
+    }
+
+    /// Returns the palette for iced or an error message naming the first invalid color
+    pub fn to_palette(&self) -> Result<Palette, String> {
+        let color = |color: ThemeColor| {
+            parse_color(self.get(color)).map_err(|err| {
+                t_args(
+                    "theme-invalid-color",
+                    &[("color", color.label().to_lowercase()), ("error", err)],
+                )
+            })
+        };
+
+        Ok(Palette {
+            background: color(ThemeColor::Background)?,
+            text: color(ThemeColor::Text)?,
+            primary: color(ThemeColor::Primary)?,
+            success: color(ThemeColor::Success)?,
+            danger: color(ThemeColor::Danger)?,
+        })
+    }
+}
+
+/// Implementation of the custom theme
+impl CustomTheme {
+    /// Returns the iced theme or an error message if the name is empty or a color is invalid
+    pub fn to_theme(&self) -> Result<Theme, String> {
+        if self.name.trim().is_empty() {
+            return Err(t("theme-name-missing"));
+        }
+
+        Ok(Theme::custom(self.name.clone(), self.palette.to_palette()?))
+    }
+
+    /// Read a theme from TOML. Returns the theme or an error message
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let theme: CustomTheme =
+            toml::from_str(content).map_err(|err| format!("Invalid theme: {}", err))?;
+        theme.to_theme()?;
+        Ok(theme)
+    }
+
+    /// Write the theme as TOML. Returns the TOML or an error message
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|err| format!("Failed to serialize theme: {}", err))
+    }
+
+    /// Import a theme from a TOML file. Returns the theme or an error message
+    pub fn import(path: impl AsRef<Path>) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read theme file: {}", err))?;
+        CustomTheme::from_toml(&content)
+    }
+
+    /// Export the theme to a TOML file. An existing file is replaced. Returns nothing or an error message
+    pub fn export(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        self.to_theme()?;
+        fs::write(path, self.to_toml()?)
+            .map_err(|err| format!("Failed to write theme file: {}", err))
+    }
+}
+
+/// Implementation of the status colors
+impl StatusColors {
+    /// Returns the danger and success colors of a theme. Colors with too little contrast to the background
+    /// are mixed with the text color until they are readable
+    pub fn new(theme: &Theme) -> Self {
+        let palette = theme.palette();
+
+        StatusColors {
+            error: readable_color(palette.danger, palette.background, palette.text),
+            success: readable_color(palette.success, palette.background, palette.text),
+        }
+    }
+}
+
+impl text_input::StyleSheet for FocusedInput {
+    type Style = Theme;
+
+    fn active(&self, style: &Self::Style) -> text_input::Appearance {
+        text_input::StyleSheet::active(style, &TextInput::Default)
+    }
+
+    fn focused(&self, style: &Self::Style) -> text_input::Appearance {
+        let mut appearance = text_input::StyleSheet::focused(style, &TextInput::Default);
+        appearance.border.width = FOCUS_BORDER_WIDTH;
+        appearance.border.color = style.palette().primary;
+        appearance
+    }
+
+    fn hovered(&self, style: &Self::Style) -> text_input::Appearance {
+        text_input::StyleSheet::hovered(style, &TextInput::Default)
+    }
+
+    fn disabled(&self, style: &Self::Style) -> text_input::Appearance {
+        text_input::StyleSheet::disabled(style, &TextInput::Default)
+    }
+
+    fn placeholder_color(&self, style: &Self::Style) -> Color {
+        text_input::StyleSheet::placeholder_color(style, &TextInput::Default)
+    }
+
+    fn value_color(&self, style: &Self::Style) -> Color {
+        text_input::StyleSheet::value_color(style, &TextInput::Default)
+    }
+
+    fn disabled_color(&self, style: &Self::Style) -> Color {
+        text_input::StyleSheet::disabled_color(style, &TextInput::Default)
+    }
+
+    fn selection_color(&self, style: &Self::Style) -> Color {
+        text_input::StyleSheet::selection_color(style, &TextInput::Default)
+    }
+}
+
+/// Returns the high-contrast theme: white text on black. All colors have a contrast ratio of at least 7:1 (WCAG level AAA)
+pub fn high_contrast_theme() -> Theme {
+    Theme::custom(
+        HIGH_CONTRAST_NAME.to_string(),
+        Palette {
+            background: Color::BLACK,
+            text: Color::WHITE,
+            primary: Color::from_rgb8(0xff, 0xd7, 0x00),
+            success: Color::from_rgb8(0x4c, 0xff, 0x88),
+            danger: Color::from_rgb8(0xff, 0x7a, 0x7a),
+        },
+    )
+}
+
+/// Returns the style of the text inputs, which shows clearly which input has the keyboard focus
+pub fn input_style() -> TextInput {
+    TextInput::Custom(Box::new(FocusedInput))
+}
+
+/// Use the status colors of the shown theme
+pub fn set_status_colors(theme: &Theme) {
+    if let Ok(mut colors) = STATUS_COLORS.write() {
+        *colors = StatusColors::new(theme);
+    }
+}
+
+/// Returns the color of error messages on the shown theme
+pub fn error_color() -> Color {
+    status_colors().error
+}
+
+/// Returns the color of success messages on the shown theme
+pub fn success_color() -> Color {
+    status_colors().success
+}
+
+/// Returns the status colors of the shown theme
+fn status_colors() -> StatusColors {
+    STATUS_COLORS
+        .read()
+        .map(|colors| *colors)
+        .unwrap_or_else(|_| StatusColors::new(&Theme::Moonfly))
+}
+
+/// Returns the contrast ratio of two colors as defined by WCAG 2.1, from 1 (same luminance) to 21 (black and white)
+pub fn contrast_ratio(first: Color, second: Color) -> f32 {
+    let (first, second) = (relative_luminance(first), relative_luminance(second));
+    (first.max(second) + 0.05) / (first.min(second) + 0.05)
+}
+
+/// Returns the relative luminance of a color as defined by WCAG 2.1
+fn relative_luminance(color: Color) -> f32 {
+    let channel = |value: f32| {
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    0.2126 * channel(color.r) + 0.7152 * channel(color.g) + 0.0722 * channel(color.b)
+}
+
+/// Mixes a color with the text color in steps until it is readable on the background. The text color is used if no step is readable
+fn readable_color(color: Color, background: Color, text: Color) -> Color {
+    (0..=10)
+        .map(|step| {
+            let amount = step as f32 / 10.0;
+            Color::from_rgb(
+                color.r + (text.r - color.r) * amount,
+                color.g + (text.g - color.g) * amount,
+                color.b + (text.b - color.b) * amount,
+            )
+        })
+        .find(|mixed| contrast_ratio(*mixed, background) >= MIN_CONTRAST_RATIO)
+        .unwrap_or(text)
+}
+
+/// Parse a color in the form "#rrggbb". Returns the color or an error message
+pub fn parse_color(value: &str) -> Result<Color, String> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("\"{}\" is not a color like #1e1e2e", value));
+    }
+
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap();
+    Ok(Color::from_rgb8(channel(0), channel(2), channel(4)))
+}
+
+/// Returns if the system prefers a dark theme, none if the preference cannot be detected.
+/// SYSTEM_COLOR_SCHEME ("dark" or "light") overrides the detection
+pub fn detect_system_dark_mode() -> Option<bool> {
+    if let Ok(scheme) = env::var("SYSTEM_COLOR_SCHEME") {
+        return Some(scheme.eq_ignore_ascii_case("dark"));
+    }
+
+    if cfg!(target_os = "macos") {
+        // The key only exists while the dark mode is on
+        return Command::new("defaults")
+            .args(["read", "-g", "AppleInterfaceStyle"])
+            .output()
+            .ok()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains("Dark"));
+    }
+
+    if cfg!(target_os = "windows") {
+        return Command::new("reg")
+            .args([
+                "query",
+                r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+                "/v",
+                "AppsUseLightTheme",
+            ])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains("0x0"));
+    }
+
+    Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains("dark"))
+}
@@ -1,18 +1,37 @@
 use crate::backend::{
+    crypto::{
+        direct_message::{
+            edit_direct_message, find_conversation_fingerprints, load_direct_messages,
+            ConversationFingerprints,
+        },
+        key_store::KeyStore,
+    },
     database::{
         db::format_send_date,
         models::{User, UserMessage},
     },
-    entities::user_message_ops::{create_user_message, find_all_messages_between_users},
+    entities::conversation_ops::mark_conversation_read,
 };
+use crate::i18n::{t, t_args};
 
+use chrono::{DateTime, Local, Utc};
 use iced::{
     alignment::Horizontal,
-    widget::{button, column, horizontal_space, row, text, text_input, Scrollable},
-    Element, Length,
+    widget::{
+        button, column, horizontal_space, row,
+        scrollable::{self, RelativeOffset},
+        text, text_input, Scrollable,
+    },
+    Command, Element, Length,
 };
 use log::error;
 
+use super::chat_export::{ChatExportForm, ChatExportMessage, ChatExportTarget};
+use super::local_cache::{merge_messages, sync_start};
+use super::outbox::{deliver, Outbox, OutboxMessage, OutboxRecipient, OutboxStatus, SentMessage};
+use super::task::{run_blocking, LoadTracker};
+use super::theme::{error_color, input_style};
+
 /// Represents the state of the user chat
 #[derive(Debug, Clone)]
 pub struct UserChat {
@@ -20,15 +39,52 @@ pub struct UserChat {
     other_user: User,
     messages: Vec<UserMessage>,
     input_value: String,
+    error: String,
+    // End-to-end encryption
+    fingerprints: Option<ConversationFingerprints>,
+    // Set until the messages have been loaded for the first time
+    loading: bool,
+    // Load of the messages that is started on every tick
+    load: LoadTracker,
+    // Messages of this chat that have not been sent yet
+    outbox: Outbox,
+    // Set while the database cannot be reached and the cached messages are shown
+    offline: bool,
+    // Relative vertical scroll position of the messages, restored when the chat is shown again
+    scroll_offset: f32,
+    // Send date of the own message that is edited in the input
+    editing: Option<DateTime<Utc>>,
+    // Set while the search is open, only the messages containing it are shown
+    search_value: Option<String>,
+    // Set while the form to export the chat is open
+    export_form: Option<ChatExportForm>,
 }
 
 /// Represents the messages that can be sent to the user chat
 #[derive(Debug, Clone)]
 pub enum UserChatMessage {
     SendMessage,
+    MessageQueued(OutboxMessage),
+    MessageSent(String, Result<SentMessage, String>),
+    RetryMessage(String),
     InputChanged(String),
     Back,
+    Scrolled(f32),
+    EditLastMessage,
+    EditCancelled,
+    MessageEdited(Result<UserMessage, String>),
+    SearchOpened,
+    SearchChanged(String),
+    SearchClosed,
+    ExportOpened,
+    ExportClosed,
+    Export(ChatExportMessage),
     Tick,
+    MessagesLoaded(
+        i32,
+        u64,
+        Result<(Vec<UserMessage>, ConversationFingerprints), String>,
+    ),
 }
 
 /// Default implementation for the user chat
@@ -47,6 +103,16 @@ impl Default for UserChat {
             },
             messages: vec![],
             input_value: String::new(),
+            error: String::new(),
+            fingerprints: None,
+            loading: true,
+            load: LoadTracker::default(),
+            outbox: Outbox::default(),
+            offline: false,
+            scroll_offset: 1.0,
+            editing: None,
+            search_value: None,
+            export_form: None,
         }
     }
 }
@@ -55,36 +121,253 @@ impl Default for UserChat {
 impl UserChat {
     /// Sets the properties of the user chat
     pub fn set_properties(&mut self, current_user: User, other_user: User) {
+        if other_user.id != self.other_user.id {
+            self.load.invalidate();
+        }
         self.current_user = current_user;
         self.other_user = other_user;
     }
 
-    /// Sends a user message. If the input value is empty, it returns. Otherwise, it creates a user message and adds it to the messages vector.
-    fn send_user_message(&mut self) {
+    /// Shows the cached messages of the chat until they are synced
+    pub fn show_cached(
+        &mut self,
+        messages: Vec<UserMessage>,
+        fingerprints: ConversationFingerprints,
+    ) {
+        self.messages = messages;
+        self.fingerprints = Some(fingerprints);
+        self.loading = false;
+    }
+
+    /// Returns the ID of the scrollable with the messages. Every chat has its own, so the chats that are shown side by side
+    /// keep their scroll positions
+    pub fn scroll_id(&self) -> scrollable::Id {
+        scrollable::Id::new(format!("user_chat_{}", self.other_user.id))
+    }
+
+    /// Scrolls the messages to the position they had when the chat was shown the last time
+    pub fn restore_scroll(&self) -> Command<UserChatMessage> {
+        scrollable::snap_to(
+            self.scroll_id(),
+            RelativeOffset {
+                x: 0.0,
+                y: self.scroll_offset,
+            },
+        )
+    }
+
+    /// Returns the ID of the message input, so it can be focused by a shortcut
+    pub fn input_id(&self) -> text_input::Id {
+        text_input::Id::new(format!("user_chat_input_{}", self.other_user.id))
+    }
+
+    /// Returns the ID of the search input
+    pub fn search_id(&self) -> text_input::Id {
+        text_input::Id::new(format!("user_chat_search_{}", self.other_user.id))
+    }
+
+    /// Returns if a message is edited
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// Returns if the search is open
+    pub fn is_searching(&self) -> bool {
+        self.search_value.is_some()
+    }
+
+    /// Returns if the form to export the chat is open
+    pub fn is_exporting(&self) -> bool {
+        self.export_form.is_some()
+    }
+
+    /// Sets the text of the input, e.g. to restore the draft of the chat
+    pub fn set_input_value(&mut self, input_value: String) {
+        self.input_value = input_value;
+    }
+
+    /// Sets the offline mode, in which the cached messages can only be read and new messages are queued
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// Sets the messages of this chat that have not been sent yet
+    pub fn set_outbox(&mut self, outbox: Outbox) {
+        self.outbox = outbox;
+    }
+
+    /// Queues a user message in the outbox, so it is not lost if it cannot be sent. If the input value is empty, it returns.
+    /// While a message is edited, the edited message is sent instead.
+    fn send_user_message(&mut self) -> Command<UserChatMessage> {
         if self.input_value.is_empty() {
-            return;
+            return Command::none();
         }
 
-        let result =
-            create_user_message(self.current_user.id, self.other_user.id, &self.input_value);
+        if let Some(send_date) = self.editing.take() {
+            return self.send_edited_message(send_date);
+        }
+
+        let message = OutboxMessage::new(
+            self.current_user.id,
+            OutboxRecipient::User(self.other_user.id),
+            &std::mem::take(&mut self.input_value),
+        );
+        self.outbox.push(message.clone());
+
+        Command::perform(async move { message }, UserChatMessage::MessageQueued)
+    }
+
+    /// Encrypts and sends a queued message in the background. While offline the message stays queued.
+    fn send_from_outbox(&mut self, client_id: &str) -> Command<UserChatMessage> {
+        if self.offline {
+            return Command::none();
+        }
+
+        let Some(message) = self.outbox.start_sending(client_id) else {
+            return Command::none();
+        };
+
+        Command::perform(run_blocking(move || deliver(&message)), {
+            let client_id = client_id.to_string();
+            move |result| UserChatMessage::MessageSent(client_id.clone(), result)
+        })
+    }
+
+    /// Handles the result of sending a message. The sent message is moved from the outbox to the messages vector,
+    /// a message that could not be sent stays in the outbox and is retried later.
+    fn handle_message_sent(&mut self, client_id: &str, result: Result<SentMessage, String>) {
         match result {
-            Ok(user_message) => {
-                self.messages.push(user_message);
+            Ok(sent_message) => {
+                self.error = String::new();
+                self.outbox.remove(client_id);
+                if let SentMessage::User(user_message) = sent_message {
+                    merge_messages(&mut self.messages, vec![user_message]);
+                }
             }
             Err(e) => {
                 error!("Error sending message: {:?}", e);
+                self.error = t_args("chat-send-failed", &[("error", e)]);
+                self.outbox
+                    .record_failure(client_id, Local::now().naive_local());
             }
         }
-        self.input_value.clear();
     }
 
-    /// Handles the tick event. It finds all messages between the current user and the other user and updates the messages vector.
-    fn handle_tick(&mut self) {
-        let all_messages =
-            find_all_messages_between_users(self.current_user.id, self.other_user.id);
-        match all_messages {
-            Ok(messages) => {
-                self.messages = messages;
+    /// Puts the last own message into the input to edit it. Edits are not queued, so it is not possible while offline.
+    fn edit_last_message(&mut self) -> Command<UserChatMessage> {
+        if self.offline {
+            return Command::none();
+        }
+
+        let Some(message) = self
+            .messages
+            .iter()
+            .rev()
+            .find(|message| message.sender_id == self.current_user.id)
+        else {
+            return Command::none();
+        };
+
+        self.editing = Some(message.send_date);
+        self.input_value = message.message.clone();
+        text_input::focus(self.input_id())
+    }
+
+    /// Encrypts the edited message again and replaces the stored message in the background
+    fn send_edited_message(&mut self, send_date: DateTime<Utc>) -> Command<UserChatMessage> {
+        let current_user_id = self.current_user.id;
+        let other_user_id = self.other_user.id;
+        let message = std::mem::take(&mut self.input_value);
+
+        Command::perform(
+            run_blocking(move || {
+                edit_direct_message(
+                    &KeyStore::default(),
+                    current_user_id,
+                    other_user_id,
+                    send_date,
+                    &message,
+                )
+            }),
+            UserChatMessage::MessageEdited,
+        )
+    }
+
+    /// Handles the result of editing a message. The edited message replaces the old one.
+    fn handle_message_edited(&mut self, result: Result<UserMessage, String>) {
+        match result {
+            Ok(user_message) => {
+                self.error = String::new();
+                // A load that started before the edit would bring back the old text
+                self.load.invalidate();
+                merge_messages(&mut self.messages, vec![user_message]);
+            }
+            Err(e) => {
+                error!("Error editing message: {:?}", e);
+                self.error = t_args("chat-edit-failed", &[("error", e)]);
+            }
+        }
+    }
+
+    /// Returns if a message is shown with the current search
+    fn matches_search(&self, message: &str) -> bool {
+        match &self.search_value {
+            Some(search_value) => message
+                .to_lowercase()
+                .contains(&search_value.trim().to_lowercase()),
+            None => true,
+        }
+    }
+
+    /// Sends a queued message again at once
+    fn retry_message(&mut self, client_id: &str) -> Command<UserChatMessage> {
+        self.outbox.retry(client_id, Local::now().naive_local());
+        self.send_from_outbox(client_id)
+    }
+
+    /// Handles the tick event. It loads and decrypts the messages between the current user and the other user in the background,
+    /// together with the key fingerprints of both users. Only the messages since the last sync are loaded. The chat is marked as read.
+    /// The tick is skipped while the messages are still being loaded
+    fn handle_tick(&mut self) -> Command<UserChatMessage> {
+        let current_user_id = self.current_user.id;
+        let other_user_id = self.other_user.id;
+        let since = sync_start(&self.messages);
+        let Some(generation) = self.load.start() else {
+            return Command::none();
+        };
+
+        Command::perform(
+            run_blocking(move || {
+                mark_conversation_read(current_user_id, false, other_user_id)?;
+                let key_store = KeyStore::default();
+                let messages =
+                    load_direct_messages(&key_store, current_user_id, other_user_id, since)?;
+                let fingerprints =
+                    find_conversation_fingerprints(&key_store, current_user_id, other_user_id)?;
+                Ok((messages, fingerprints))
+            }),
+            move |result| UserChatMessage::MessagesLoaded(other_user_id, generation, result),
+        )
+    }
+
+    /// Handles the loaded messages and merges them into the messages vector. Messages of a chat that is no longer open
+    /// and messages loaded before a newer load or an edit are ignored.
+    fn handle_messages_loaded(
+        &mut self,
+        other_user_id: i32,
+        generation: u64,
+        result: Result<(Vec<UserMessage>, ConversationFingerprints), String>,
+    ) {
+        if other_user_id != self.other_user.id || !self.load.finish(generation) {
+            return;
+        }
+
+        self.loading = false;
+
+        match result {
+            Ok((messages, fingerprints)) => {
+                merge_messages(&mut self.messages, messages);
+                self.fingerprints = Some(fingerprints);
             }
             Err(e) => {
                 error!("Error loading messages: {:?}", e);
@@ -93,19 +376,79 @@ impl UserChat {
     }
 
     /// Updates the user chat based on the message
-    pub fn update(&mut self, message: UserChatMessage) {
+    pub fn update(&mut self, message: UserChatMessage) -> Command<UserChatMessage> {
         match message {
             UserChatMessage::SendMessage => {
-                self.send_user_message();
+                return self.send_user_message();
+            }
+            UserChatMessage::MessageQueued(message) => {
+                return self.send_from_outbox(&message.client_id);
+            }
+            UserChatMessage::MessageSent(client_id, result) => {
+                self.handle_message_sent(&client_id, result);
+            }
+            UserChatMessage::RetryMessage(client_id) => {
+                return self.retry_message(&client_id);
             }
             UserChatMessage::InputChanged(value) => {
                 self.input_value = value;
             }
             UserChatMessage::Back => {}
+            UserChatMessage::Scrolled(offset) => {
+                // The offset is not a number if all messages fit
+                if offset.is_finite() {
+                    self.scroll_offset = offset;
+                }
+            }
+            UserChatMessage::EditLastMessage => {
+                return self.edit_last_message();
+            }
+            UserChatMessage::EditCancelled => {
+                self.editing = None;
+                self.input_value = String::new();
+            }
+            UserChatMessage::MessageEdited(result) => {
+                self.handle_message_edited(result);
+            }
+            UserChatMessage::SearchOpened => {
+                self.search_value.get_or_insert_with(String::new);
+                return text_input::focus(self.search_id());
+            }
+            UserChatMessage::SearchChanged(value) => {
+                self.search_value = Some(value);
+            }
+            UserChatMessage::SearchClosed => {
+                self.search_value = None;
+            }
+            UserChatMessage::ExportOpened => {
+                let target = ChatExportTarget::Direct {
+                    user_id: self.current_user.id,
+                    other_user_id: self.other_user.id,
+                };
+                let chat_name = format!(
+                    "{} {}",
+                    self.other_user.first_name, self.other_user.last_name
+                );
+                self.export_form
+                    .get_or_insert_with(|| ChatExportForm::new(target, &chat_name));
+            }
+            UserChatMessage::ExportClosed => {
+                self.export_form = None;
+            }
+            UserChatMessage::Export(message) => {
+                if let Some(export_form) = &mut self.export_form {
+                    return export_form.update(message).map(UserChatMessage::Export);
+                }
+            }
             UserChatMessage::Tick => {
-                self.handle_tick();
+                return self.handle_tick();
+            }
+            UserChatMessage::MessagesLoaded(other_user_id, generation, result) => {
+                self.handle_messages_loaded(other_user_id, generation, result);
             }
         }
+
+        Command::none()
     }
 
     /// Returns the view of the user chat
@@ -115,68 +458,216 @@ impl UserChat {
         let spacing = 20;
         let text_size = 20;
 
-        let back_button = button(text("Back").horizontal_alignment(Horizontal::Center))
+        let back_button = button(text(t("back")).horizontal_alignment(Horizontal::Center))
             .width(button_width)
             .padding(padding)
             .on_press(UserChatMessage::Back);
 
-        let top_row = row!(horizontal_space(), back_button)
+        let export_button = match self.export_form {
+            Some(_) => {
+                button(text(t("chat-export-close")).horizontal_alignment(Horizontal::Center))
+                    .on_press(UserChatMessage::ExportClosed)
+            }
+            None => button(text(t("chat-export")).horizontal_alignment(Horizontal::Center))
+                .on_press(UserChatMessage::ExportOpened),
+        }
+        .padding(padding);
+
+        let top_row = row!(horizontal_space(), export_button, back_button)
             .spacing(spacing)
             .padding(padding);
 
+        let export_row: Element<UserChatMessage> = match &self.export_form {
+            Some(export_form) => export_form.view().map(UserChatMessage::Export),
+            None => column![].into(),
+        };
+
         let other_user_name = text(format!("[{}]:", self.other_user.first_name)).size(text_size);
 
-        let own_name = text("[You]:").size(text_size);
+        let own_name = text(t("chat-you")).size(text_size);
 
         let name_row = row!(other_user_name, horizontal_space(), own_name)
             .spacing(spacing)
             .padding(padding);
 
+        // Both users can compare the fingerprints to verify that nobody replaced a key
+        let fingerprint_row: Element<UserChatMessage> = match &self.fingerprints {
+            Some(fingerprints) => {
+                let other_fingerprint = fingerprints
+                    .other
+                    .clone()
+                    .unwrap_or_else(|| t("chat-no-key"));
+
+                let mut fingerprint_column = column![
+                    text(t_args(
+                        "chat-key-of",
+                        &[
+                            ("name", self.other_user.first_name.clone()),
+                            ("key", other_fingerprint),
+                        ],
+                    ))
+                    .size(15),
+                    text(t_args(
+                        "chat-your-key",
+                        &[("key", fingerprints.own.clone())]
+                    ))
+                    .size(15),
+                ];
+
+                // A changed key is shown instead of being accepted silently
+                if fingerprints.other_changed {
+                    fingerprint_column = fingerprint_column.push(
+                        text(t_args(
+                            "chat-key-changed",
+                            &[("name", self.other_user.first_name.clone())],
+                        ))
+                        .size(15)
+                        .style(error_color()),
+                    );
+                }
+                if fingerprints.own_changed {
+                    fingerprint_column = fingerprint_column.push(
+                        text(t("chat-own-key-changed"))
+                            .size(15)
+                            .style(error_color()),
+                    );
+                }
+
+                fingerprint_column.padding(padding).into()
+            }
+            None => column![].into(),
+        };
+
+        let search_row: Element<UserChatMessage> = match &self.search_value {
+            Some(search_value) => row!(
+                text_input(&t("chat-search"), search_value)
+                    .style(input_style())
+                    .id(self.search_id())
+                    .width(Length::Fill)
+                    .padding(padding)
+                    .on_input(UserChatMessage::SearchChanged),
+                button(text(t("chat-search-close")).horizontal_alignment(Horizontal::Center))
+                    .width(button_width)
+                    .padding(padding)
+                    .on_press(UserChatMessage::SearchClosed)
+            )
+            .spacing(spacing)
+            .padding(padding)
+            .into(),
+            None => column![].into(),
+        };
+
         let mut message_column = column![].spacing(spacing).padding(padding);
 
         for message in &self.messages {
+            if !self.matches_search(&message.message) {
+                continue;
+            }
+
+            let send_date = match message.edited_at {
+                Some(_) => t_args(
+                    "chat-edited",
+                    &[("date", format_send_date(message.send_date))],
+                ),
+                None => format_send_date(message.send_date),
+            };
+
             if message.sender_id == self.current_user.id {
-                let message_date =
-                    text(format!("{}:", format_send_date(message.send_date))).size(text_size);
+                let message_date = text(format!("{}:", send_date)).size(text_size);
                 let date_row = row!(horizontal_space(), message_date).padding(padding);
                 message_column = message_column.push(date_row);
 
-                let message_text = text(message.message.as_str()).size(text_size);
+                let message_text = text(message.display_text()).size(text_size);
 
                 let message_row = row!(horizontal_space(), message_text).padding(padding);
                 message_column = message_column.push(message_row);
             } else {
-                let message_date =
-                    text(format!("{}:", format_send_date(message.send_date))).size(text_size);
+                let message_date = text(format!("{}:", send_date)).size(text_size);
                 message_column = message_column.push(message_date);
 
-                let message_text = text(message.message.as_str())
+                let message_text = text(message.display_text())
                     .size(20)
                     .horizontal_alignment(Horizontal::Left);
                 message_column = message_column.push(message_text);
             }
         }
 
+        // Messages that have not been sent yet are shown below the sent ones
+        for message in self.outbox.messages() {
+            let status: Element<UserChatMessage> = match message.status {
+                OutboxStatus::Pending if message.sending => {
+                    text(t("chat-sending")).size(text_size).into()
+                }
+                OutboxStatus::Pending => text(t("chat-pending")).size(text_size).into(),
+                OutboxStatus::Failed => row!(
+                    text(t("chat-failed")).size(text_size),
+                    button(text(t("chat-retry")).horizontal_alignment(Horizontal::Center))
+                        .on_press(UserChatMessage::RetryMessage(message.client_id.clone()))
+                )
+                .spacing(spacing)
+                .into(),
+            };
+            message_column = message_column.push(row!(horizontal_space(), status).padding(padding));
+
+            let message_text = text(message.message.as_str()).size(text_size);
+            message_column =
+                message_column.push(row!(horizontal_space(), message_text).padding(padding));
+        }
+
+        if self.loading {
+            message_column = message_column.push(text(t("chat-loading")).size(text_size));
+        }
+
         let message_scrollable = Scrollable::new(message_column)
+            .id(self.scroll_id())
+            .on_scroll(|viewport| UserChatMessage::Scrolled(viewport.relative_offset().y))
             .width(Length::Fill)
             .height(Length::Fill);
 
-        let input_field = text_input("Type your message...", &self.input_value)
+        let input_field = text_input(&t("chat-input"), &self.input_value)
+            .style(input_style())
+            .id(self.input_id())
             .width(Length::Fill)
             .padding(padding)
             .on_submit(UserChatMessage::SendMessage)
             .on_input(UserChatMessage::InputChanged);
 
-        let send_button = button(text("Send").horizontal_alignment(Horizontal::Center))
+        let send_button = button(text(t("chat-send")).horizontal_alignment(Horizontal::Center))
             .width(button_width)
             .padding(padding)
             .on_press(UserChatMessage::SendMessage);
 
-        let bottom_row = row!(input_field, send_button)
-            .spacing(spacing)
-            .padding(padding);
+        let bottom_row = if self.editing.is_some() {
+            let cancel_button =
+                button(text(t("chat-edit-cancel")).horizontal_alignment(Horizontal::Center))
+                    .width(button_width)
+                    .padding(padding)
+                    .on_press(UserChatMessage::EditCancelled);
+            row!(input_field, send_button, cancel_button)
+        } else {
+            row!(input_field, send_button)
+        }
+        .spacing(spacing)
+        .padding(padding);
 
-        let content = column![top_row, name_row, message_scrollable, bottom_row];
+        let error_message: Element<UserChatMessage> = if self.offline {
+            text(t("chat-offline")).size(15).style(error_color()).into()
+        } else if !self.error.is_empty() {
+            text(&self.error).size(15).style(error_color()).into()
+        } else {
+            column![].into()
+        };
+
+        let content = column![
+            top_row,
+            export_row,
+            fingerprint_row,
+            search_row,
+            name_row,
+            message_scrollable,
+            error_message,
+            bottom_row
+        ];
 
         content.into()
     }
@@ -184,6 +675,11 @@ impl UserChat {
 
 /// Getter methods for testing
 impl UserChat {
+    /// Gets the load of the messages
+    pub fn get_load(&self) -> &LoadTracker {
+        &self.load
+    }
+
     /// Gets the messages
     pub fn get_messages(&self) -> &Vec<UserMessage> {
         &self.messages
@@ -203,6 +699,49 @@ impl UserChat {
     pub fn get_other_user(&self) -> &User {
         &self.other_user
     }
+
+    /// Gets the key fingerprints of the conversation
+    pub fn get_fingerprints(&self) -> Option<&ConversationFingerprints> {
+        self.fingerprints.as_ref()
+    }
+
+    /// Gets the error message
+    pub fn get_error(&self) -> &String {
+        &self.error
+    }
+
+    /// Gets the messages that have not been sent yet
+    pub fn get_outbox(&self) -> &Outbox {
+        &self.outbox
+    }
+
+    /// Gets the relative scroll position of the messages
+    pub fn get_scroll_offset(&self) -> f32 {
+        self.scroll_offset
+    }
+
+    /// Gets the send date of the edited message
+    pub fn get_editing(&self) -> Option<DateTime<Utc>> {
+        self.editing
+    }
+
+    /// Gets the search value
+    pub fn get_search_value(&self) -> Option<&str> {
+        self.search_value.as_deref()
+    }
+
+    /// Gets the form to export the chat
+    pub fn get_export_form(&self) -> Option<&ChatExportForm> {
+        self.export_form.as_ref()
+    }
+
+    /// Gets the messages that are shown with the current search
+    pub fn get_visible_messages(&self) -> Vec<&UserMessage> {
+        self.messages
+            .iter()
+            .filter(|message| self.matches_search(&message.message))
+            .collect()
+    }
 }
 
 /// Setter methods for testing
@@ -0,0 +1,304 @@
+# German messages of the user interface
+
+app-back-online = Du bist wieder online. Bitte melde dich erneut an.
+app-session-ended = Deine Sitzung wurde beendet. Bitte melde dich erneut an.
+
+back = Zurück
+
+chat-edit-cancel = Abbrechen
+chat-edit-failed = Nachricht konnte nicht bearbeitet werden: { $error }
+chat-edited = { $date } (bearbeitet)
+chat-export = Chat exportieren
+chat-export-close = Export schließen
+chat-failed = Fehlgeschlagen —
+chat-input = Nachricht eingeben...
+chat-key-changed = Der Schlüssel von { $name } hat sich seit eurer letzten Nachricht geändert. Vergleiche den neuen Fingerabdruck mit { $name }.
+chat-key-of = Schlüssel von { $name }: { $key }
+chat-loading = Nachrichten werden geladen...
+chat-no-key = noch kein Schlüssel veröffentlicht
+chat-offline = Du bist offline. Gespeicherte Nachrichten werden angezeigt, neue Nachrichten werden gesendet, sobald die Verbindung besteht.
+chat-own-key-changed = Dein Schlüssel hat sich seit eurer letzten Nachricht geändert, z. B. weil du dich auf einem anderen Gerät angemeldet hast.
+chat-pending = Ausstehend:
+chat-retry = Erneut senden
+chat-search = Nachrichten durchsuchen...
+chat-search-close = Schließen
+chat-send = Senden
+chat-send-failed = Die Nachricht konnte nicht gesendet werden: { $error }
+chat-sending = Wird gesendet...:
+chat-tab-draft = Entwurf: { $draft }
+chat-tab-empty = Noch keine Chats. Füge einen Freund hinzu oder tritt einer Gruppe bei.
+chat-tab-load-failed = Chats konnten nicht geladen werden
+chat-tab-loading = Chats werden geladen...
+chat-tab-no-messages = Noch keine Nachrichten
+chat-tab-title = Chats:
+chat-tab-unread = { $count } ungelesen
+chat-you = [Du]:
+chat-your-key = Dein Schlüssel: { $key }
+
+chats-close = Chat schließen
+chats-close-split = Nicht mehr nebeneinander anzeigen
+chats-home = Startseite
+chats-open = Geöffnete Chats:
+chats-open-beside = Neben dem aktuellen Chat anzeigen
+
+email = E-Mail
+
+error-email-in-use = Die E-Mail-Adresse wird bereits verwendet
+error-invalid-reset-code = Ungültiger Code zum Zurücksetzen
+error-invalid-time-zone = Unbekannte Zeitzone: { $name }
+error-invalid-two-factor-code = Ungültiger Zwei-Faktor-Code
+error-invalid-verification-code = Ungültiger Bestätigungscode
+error-old-password-mismatch = Das alte Passwort stimmt nicht überein.
+error-password-mismatch = Das Passwort stimmt nicht überein.
+error-password-reused = Das Passwort wurde kürzlich verwendet, es muss sich von den letzten { $count } Passwörtern unterscheiden
+error-reset-code-expired = Der Code zum Zurücksetzen ist abgelaufen
+error-two-factor-already-enabled = Die Zwei-Faktor-Authentifizierung ist bereits aktiviert
+error-two-factor-not-enabled = Die Zwei-Faktor-Authentifizierung ist nicht aktiviert
+error-two-factor-not-started = Die Einrichtung der Zwei-Faktor-Authentifizierung wurde nicht gestartet
+error-verification-code-cooldown = An diese E-Mail-Adresse wurde gerade ein Code gesendet. Bitte warte { $seconds } Sekunden, bevor du einen neuen anforderst.
+error-verification-code-expired = Der Bestätigungscode ist abgelaufen
+
+export-edited = bearbeitet
+export-exported-at = Exportiert am { $date }
+export-failed = Fehler beim Exportieren des Chats: { $error }
+export-format-text = Reiner Text
+export-from = Von (JJJJ-MM-TT)
+export-invalid-date = Ungültiges Datum "{ $date }", bitte verwende JJJJ-MM-TT
+export-invalid-range = Das Enddatum darf nicht vor dem Startdatum liegen
+export-no-messages = Keine Nachrichten in diesem Zeitraum
+export-path = Datei
+export-path-empty = Bitte gib eine Datei für den Export an
+export-range = Nachrichten von { $from } bis { $to }
+export-running = Chat wird exportiert...
+export-save = Exportieren
+export-saved = Chat nach { $path } exportiert
+export-title-direct = Chat mit { $name }
+export-title-group = Gruppenchat { $name }
+export-to = Bis (JJJJ-MM-TT)
+export-unknown-user = Unbekannter Benutzer
+
+group-chat-history-already-shared = Alle Mitglieder können den Verlauf, den du lesen kannst, bereits lesen
+group-chat-history-shared = Der Verlauf wurde mit den anderen Mitgliedern geteilt
+group-chat-share-failed = Der Verlauf konnte nicht geteilt werden: { $error }
+group-chat-share-history = Verlauf teilen
+group-chat-sharing = Wird geteilt...
+group-chat-users = [Benutzer]:
+group-tab-accept = Einladung annehmen
+group-tab-accept-failed = Fehler beim Annehmen der Einladung in die Gruppe: { $group }
+group-tab-accepted = Einladung in die Gruppe { $group } angenommen
+group-tab-already-member = { $first_name } { $last_name } ist bereits in der Gruppe { $group }
+group-tab-create = Gruppe erstellen
+group-tab-created = Gruppe { $group } erstellt
+group-tab-groups = Gruppen:
+group-tab-invite = Benutzer einladen
+group-tab-invited = Einladungen:
+group-tab-invited-user = { $first_name } { $last_name } wurde in die Gruppe { $group } eingeladen
+group-tab-leave = Gruppe verlassen
+group-tab-left = Gruppe { $group } verlassen
+group-tab-loading = Gruppen werden geladen...
+group-tab-name = Gruppenname:
+group-tab-name-empty = Der Gruppenname darf nicht leer sein
+group-tab-reject = Einladung ablehnen
+group-tab-select-friend = Freund für eine Gruppe auswählen
+group-tab-select-user-empty = Bitte einen Benutzer zum Einladen auswählen
+
+home-logout = Abmelden
+home-offline = Du bist offline. Gespeicherte Daten werden angezeigt, Änderungen sind erst wieder möglich, wenn die Verbindung besteht.
+home-tab-chats = Chats
+home-tab-group = Gruppen
+home-tab-settings = Einstellungen
+home-tab-user = Benutzer
+home-welcome = Willkommen { $first_name } { $last_name }!
+
+invalid-email-format = Ungültiges E-Mail-Format
+
+list-many = { $rest } und { $last }
+list-two = { $first } und { $second }
+
+login-account-disabled = Dieses Konto wurde deaktiviert. Bitte wende dich an den Betreiber.
+login-button = Anmelden
+login-confirm = Bestätigen
+login-email = E-Mail:
+login-enter-two-factor-code = Bitte gib den Code deiner Authenticator-App oder einen Wiederherstellungscode ein.
+login-failed = Anmeldung fehlgeschlagen. Bitte versuche es später erneut.
+login-failed-credentials = Anmeldung fehlgeschlagen. E-Mail oder Passwort ist falsch.
+login-fill-in-fields = Bitte E-Mail und Passwort ausfüllen.
+login-forgot-password = Passwort vergessen?
+login-invalid-two-factor-code = Ungültiger Zwei-Faktor-Code.
+login-loading = Anmeldung läuft...
+login-offline-no-data = Du bist offline und auf diesem Gerät sind keine Daten für dieses Konto gespeichert.
+login-password = Passwort:
+login-remember-me = Angemeldet bleiben
+login-reset-email-empty = Bitte gib deine E-Mail-Adresse ein, um dein Passwort zurückzusetzen.
+login-title = Anmeldung:
+login-too-many-attempts = Zu viele fehlgeschlagene Anmeldeversuche. Bitte versuche es in { $seconds } Sekunden erneut.
+login-two-factor-code = Zwei-Faktor-Code:
+login-two-factor-code-empty = Bitte gib den Zwei-Faktor-Code ein.
+login-verification-code = Bestätigungscode:
+login-verification-code-empty = Bitte gib den Bestätigungscode ein.
+login-verification-code-resent = Ein neuer Bestätigungscode wurde an deine E-Mail-Adresse gesendet.
+login-verification-email-failed = Die Bestätigungs-E-Mail konnte nicht gesendet werden.
+login-verify = Bestätigen
+login-verify-email = Bitte bestätige deine E-Mail-Adresse, bevor du dich anmeldest.
+
+message-undecryptable = [Diese Nachricht konnte nicht entschlüsselt werden]
+
+notification-direct-message = Neue Nachricht von { $name }
+notification-friend-request = Neue Freundschaftsanfrage
+notification-friend-request-body = { $name } möchte mit dir befreundet sein
+notification-group-invite = Neue Gruppeneinladung
+notification-group-invite-body = Du wurdest in { $group } eingeladen
+notification-group-message = Neue Nachricht in { $group }
+notification-hidden-preview = Öffne den Chat, um die Nachricht zu lesen
+notification-mention = { $name } hat dich in { $group } erwähnt
+
+password = Passwort
+password-common = Dieses Passwort ist zu verbreitet, wähle ein weniger vorhersehbares
+password-policy = Das Passwort muss mindestens { $length } Zeichen lang sein und mindestens { $classes } enthalten
+password-policy-digit = eine Ziffer
+password-policy-length = Das Passwort muss mindestens { $length } Zeichen lang sein
+password-policy-lowercase = einen Kleinbuchstaben
+password-policy-special = ein Sonderzeichen
+password-policy-uppercase = einen Großbuchstaben
+
+quick-switcher-close = Schließen
+quick-switcher-input = Zu einem Freund oder einer Gruppe springen...
+quick-switcher-no-results = Kein Chat gefunden
+
+register-button = Registrieren
+register-fill-in-fields = Bitte alle Felder ausfüllen
+register-first-name = Vorname
+register-last-name = Nachname
+register-loading = Registrierung läuft...
+register-success = Das Konto wurde registriert. Ein Bestätigungscode wurde an deine E-Mail-Adresse gesendet
+register-title = Registrierung:
+register-verification-email-failed = Das Konto wurde registriert, aber die Bestätigungs-E-Mail konnte nicht gesendet werden
+
+resend-code = Code erneut senden
+
+reset-button = Passwort zurücksetzen
+reset-code = Code zum Zurücksetzen
+reset-code-sent = Falls ein Konto mit dieser E-Mail-Adresse existiert, wurde ein Code zum Zurücksetzen gesendet.
+reset-email-empty = Bitte gib deine E-Mail-Adresse ein.
+reset-email-failed = Die E-Mail zum Zurücksetzen konnte nicht gesendet werden.
+reset-fill-in-fields = Bitte Code und neues Passwort ausfüllen
+reset-loading = Passwort wird zurückgesetzt...
+reset-new-password = Neues Passwort
+reset-success = Das Passwort wurde zurückgesetzt. Du kannst dich jetzt anmelden.
+reset-title = Passwort zurücksetzen:
+
+settings-custom-themes = Eigene Themes:
+settings-delete-account = Konto löschen
+settings-delete-account-failed = Fehler beim Löschen des Kontos: { $error }
+settings-delete-confirm = Bist du sicher, dass du dein Konto löschen möchtest?
+settings-dnd = Nicht stören
+settings-dnd-from = Von (HH:MM)
+settings-dnd-invalid = Die Zeiten für „Nicht stören“ müssen im Format HH:MM angegeben werden
+settings-dnd-to = Bis (HH:MM)
+settings-email-empty = E-Mail und Passwort dürfen nicht leer sein
+settings-email-update-failed = Fehler beim Ändern der E-Mail-Adresse: { $error }
+settings-email-updated = E-Mail-Adresse erfolgreich geändert
+settings-email-verify-failed = Fehler beim Bestätigen der E-Mail-Adresse: { $error }
+settings-font-scale = Schriftgröße:
+settings-font-scale-reset = Zurücksetzen
+settings-language = Sprache:
+settings-language-system = Systemsprache
+settings-loading = Bitte warten...
+settings-mute-friends = Freunde stummschalten:
+settings-mute-groups = Gruppen stummschalten:
+settings-new-password = Neues Passwort
+settings-notifications = Benachrichtigungen:
+settings-notifications-load-failed = Fehler beim Laden der Benachrichtigungseinstellungen: { $error }
+settings-notifications-save-failed = Fehler beim Speichern der Benachrichtigungseinstellungen: { $error }
+settings-notifications-saved = Benachrichtigungseinstellungen gespeichert
+settings-old-password = Altes Passwort
+settings-password-empty = Die Passwortfelder dürfen nicht leer sein
+settings-password-updated = Passwort erfolgreich geändert
+settings-recovery-codes = Wiederherstellungscodes: { $codes }
+settings-revoke = Beenden
+settings-save-notifications = Benachrichtigungen speichern
+settings-session = { $device } - zuletzt aktiv { $last_active }
+settings-session-revoke-failed = Fehler beim Beenden der Sitzung: { $error }
+settings-session-revoked = Sitzung beendet
+settings-sessions = Aktive Sitzungen:
+settings-sessions-load-failed = Fehler beim Laden der Sitzungen: { $error }
+settings-shortcut-placeholder = z. B. Strg+K
+settings-shortcuts = Tastenkürzel
+settings-shortcuts-reset = Die Standard-Tastenkürzel werden verwendet
+settings-shortcuts-reset-button = Zurücksetzen
+settings-shortcuts-save = Speichern
+settings-shortcuts-saved = Die Tastenkürzel wurden gespeichert
+settings-show-notifications = Desktop-Benachrichtigungen anzeigen
+settings-show-preview = Nachrichtenvorschau anzeigen
+settings-sync-preferences = Einstellungen mit meinem Konto synchronisieren
+settings-theme-delete = Löschen
+settings-theme-edit = Bearbeiten
+settings-theme-export = Exportieren
+settings-theme-export-failed = Fehler beim Exportieren des Themes: { $error }
+settings-theme-exported = Theme nach { $path } exportiert
+settings-theme-file = Theme-Datei (.toml)
+settings-theme-high-contrast = Hoher Kontrast
+settings-theme-import = Importieren
+settings-theme-import-failed = Fehler beim Importieren des Themes: { $error }
+settings-theme-imported = Theme importiert. Speichere es, um es zu verwenden.
+settings-theme-name = Name des Themes
+settings-theme-path-empty = Bitte gib den Pfad der Theme-Datei ein
+settings-theme-save = Theme speichern
+settings-theme-saved = Theme { $theme } gespeichert
+settings-theme-system = System
+settings-theme-use = Verwenden
+settings-this-device = Dieses Gerät
+settings-time-zone = Zeitzone:
+settings-time-zone-placeholder = Systemzeitzone, z. B. Europe/Berlin
+settings-time-zone-save = Zeitzone speichern
+settings-time-zone-saved = Zeiten werden in { $name } angezeigt
+settings-time-zone-system = Zeiten werden in der Zeitzone des Systems angezeigt
+settings-two-factor-add-secret = Füge das Geheimnis zu deiner Authenticator-App hinzu und bestätige mit einem Code
+settings-two-factor-code = Zwei-Faktor-Code
+settings-two-factor-code-empty = Der Zwei-Faktor-Code darf nicht leer sein
+settings-two-factor-confirm = 2FA bestätigen
+settings-two-factor-disable = 2FA deaktivieren
+settings-two-factor-disable-empty = Passwort und Zwei-Faktor-Code dürfen nicht leer sein
+settings-two-factor-disable-failed = Fehler beim Deaktivieren der Zwei-Faktor-Authentifizierung: { $error }
+settings-two-factor-disabled = Zwei-Faktor-Authentifizierung deaktiviert
+settings-two-factor-enable = 2FA aktivieren
+settings-two-factor-enable-failed = Fehler beim Aktivieren der Zwei-Faktor-Authentifizierung: { $error }
+settings-two-factor-enabled = Zwei-Faktor-Authentifizierung aktiviert. Bewahre deine Wiederherstellungscodes sicher auf
+settings-two-factor-load-failed = Fehler beim Laden des Zwei-Faktor-Status: { $error }
+settings-two-factor-secret = Geheimnis: { $secret }
+settings-update-email = E-Mail ändern
+settings-update-password = Passwort ändern
+settings-verification-code = Bestätigungscode
+settings-verification-code-empty = Der Bestätigungscode darf nicht leer sein
+settings-verification-code-sent = Ein Bestätigungscode wurde an { $email } gesendet
+settings-verify-email = E-Mail bestätigen
+
+shortcut-back = Zurück
+shortcut-chats-tab = Chats anzeigen
+shortcut-conflict = { $binding } wird für "{ $shortcut }" und "{ $other }" verwendet
+shortcut-edit-last-message = Letzte Nachricht bearbeiten
+shortcut-friends-tab = Freunde anzeigen
+shortcut-groups-tab = Gruppen anzeigen
+shortcut-invalid = "{ $binding }" ist kein gültiges Tastenkürzel
+shortcut-needs-modifier = { $binding } braucht Strg oder Alt, sonst wird es beim Tippen ausgelöst
+shortcut-quick-switcher = Schnellwechsler öffnen
+shortcut-search = Im Chat suchen
+
+theme-color-background = Hintergrund
+theme-color-danger = Gefahr
+theme-color-primary = Primär
+theme-color-success = Erfolg
+theme-color-text = Text
+theme-invalid-color = Ungültige Farbe { $color }: { $error }
+theme-name-missing = Das Theme braucht einen Namen
+
+user-tab-accept = Anfrage annehmen
+user-tab-friends = Freunde:
+user-tab-loading = Freunde werden geladen...
+user-tab-reject = Anfrage ablehnen
+user-tab-requests = Freundschaftsanfragen:
+user-tab-retract = Anfrage zurückziehen
+user-tab-select-user = Benutzer als Freund auswählen
+user-tab-send-request = Freundschaftsanfrage senden
+user-tab-sent-requests = Gesendete Freundschaftsanfragen:
+user-tab-unfriend = Freundschaft beenden
@@ -0,0 +1,304 @@
+# English messages of the user interface
+
+app-back-online = You are back online. Please log in again.
+app-session-ended = Your session has ended. Please log in again.
+
+back = Back
+
+chat-edit-cancel = Cancel
+chat-edit-failed = Failed to edit message: { $error }
+chat-edited = { $date } (edited)
+chat-export = Export chat
+chat-export-close = Close export
+chat-failed = Failed —
+chat-input = Type your message...
+chat-key-changed = The key of { $name } changed since your last message. Compare the new fingerprint with { $name }.
+chat-key-of = Key of { $name }: { $key }
+chat-loading = Loading messages...
+chat-no-key = no key published yet
+chat-offline = You are offline. Saved messages are shown, new messages are sent when the connection is back.
+chat-own-key-changed = Your key changed since your last message, e.g. because you logged in on another device.
+chat-pending = Pending:
+chat-retry = Retry
+chat-search = Search messages...
+chat-search-close = Close
+chat-send = Send
+chat-send-failed = Message could not be sent: { $error }
+chat-sending = Sending...:
+chat-tab-draft = Draft: { $draft }
+chat-tab-empty = No chats yet. Add a friend or join a group.
+chat-tab-load-failed = Chats could not be loaded
+chat-tab-loading = Loading chats...
+chat-tab-no-messages = No messages yet
+chat-tab-title = Chats:
+chat-tab-unread = { $count } unread
+chat-you = [You]:
+chat-your-key = Your key: { $key }
+
+chats-close = Close chat
+chats-close-split = Stop showing side by side
+chats-home = Home
+chats-open = Open chats:
+chats-open-beside = Show beside the current chat
+
+email = Email
+
+error-email-in-use = Email address already in use
+error-invalid-reset-code = Invalid reset code
+error-invalid-time-zone = Unknown time zone: { $name }
+error-invalid-two-factor-code = Invalid two-factor code
+error-invalid-verification-code = Invalid verification code
+error-old-password-mismatch = Old password does not match.
+error-password-mismatch = Password does not match.
+error-password-reused = The password was used recently, it must differ from the last { $count } passwords
+error-reset-code-expired = Reset code has expired
+error-two-factor-already-enabled = Two-factor authentication is already enabled
+error-two-factor-not-enabled = Two-factor authentication is not enabled
+error-two-factor-not-started = Two-factor enrolment has not been started
+error-verification-code-cooldown = A code was just sent to this email. Please wait { $seconds } seconds before requesting a new one.
+error-verification-code-expired = Verification code has expired
+
+export-edited = edited
+export-exported-at = Exported on { $date }
+export-failed = Error exporting chat: { $error }
+export-format-text = Plain text
+export-from = From (YYYY-MM-DD)
+export-invalid-date = Invalid date "{ $date }", please use YYYY-MM-DD
+export-invalid-range = The end date must not be before the start date
+export-no-messages = No messages in this period
+export-path = File
+export-path-empty = Please enter a file to export to
+export-range = Messages from { $from } to { $to }
+export-running = Exporting chat...
+export-save = Export
+export-saved = Chat exported to { $path }
+export-title-direct = Chat with { $name }
+export-title-group = Group chat { $name }
+export-to = To (YYYY-MM-DD)
+export-unknown-user = Unknown user
+
+group-chat-history-already-shared = All members can already read the history you can read
+group-chat-history-shared = The history has been shared with the other members
+group-chat-share-failed = History could not be shared: { $error }
+group-chat-share-history = Share history
+group-chat-sharing = Sharing...
+group-chat-users = [Users]:
+group-tab-accept = Accept group invite
+group-tab-accept-failed = Error accepting invite to group: { $group }
+group-tab-accepted = Accepted invite to group { $group }
+group-tab-already-member = User { $first_name } { $last_name } is already in group { $group }
+group-tab-create = Create group
+group-tab-created = Group { $group } created
+group-tab-groups = Groups:
+group-tab-invite = Invite user to group
+group-tab-invited = Invited groups:
+group-tab-invited-user = User { $first_name } { $last_name } invited to group { $group }
+group-tab-leave = Leave group
+group-tab-left = Left group { $group }
+group-tab-loading = Loading groups...
+group-tab-name = Group name:
+group-tab-name-empty = Group name cannot be empty
+group-tab-reject = Reject group invite
+group-tab-select-friend = Select a friend to add to a group
+group-tab-select-user-empty = Select a user to invite
+
+home-logout = Logout
+home-offline = You are offline. Saved data is shown and changes are not possible until the connection is back.
+home-tab-chats = Chats
+home-tab-group = Group
+home-tab-settings = Settings
+home-tab-user = User
+home-welcome = Welcome { $first_name } { $last_name }!
+
+invalid-email-format = Invalid email format
+
+list-many = { $rest }, and { $last }
+list-two = { $first } and { $second }
+
+login-account-disabled = This account has been disabled. Please contact the operator.
+login-button = Login
+login-confirm = Confirm
+login-email = Email:
+login-enter-two-factor-code = Please enter the code of your authenticator app or a recovery code.
+login-failed = Login failed. Please try again later.
+login-failed-credentials = Login failed. Either the email or password was incorrect.
+login-fill-in-fields = Please fill in both email and password fields.
+login-forgot-password = Forgot password?
+login-invalid-two-factor-code = Invalid two-factor code.
+login-loading = Logging in...
+login-offline-no-data = You are offline and there is no saved data for this account on this device.
+login-password = Password:
+login-remember-me = Remember me
+login-reset-email-empty = Please enter your email address to reset your password.
+login-title = Login:
+login-too-many-attempts = Too many failed login attempts. Please try again in { $seconds } seconds.
+login-two-factor-code = Two-factor code:
+login-two-factor-code-empty = Please enter the two-factor code.
+login-verification-code = Verification code:
+login-verification-code-empty = Please enter the verification code.
+login-verification-code-resent = A new verification code has been sent to your email.
+login-verification-email-failed = The verification email could not be sent.
+login-verify = Verify
+login-verify-email = Please verify your email address before logging in.
+
+message-undecryptable = [This message could not be decrypted]
+
+notification-direct-message = New message from { $name }
+notification-friend-request = New friend request
+notification-friend-request-body = { $name } wants to be your friend
+notification-group-invite = New group invite
+notification-group-invite-body = You have been invited to { $group }
+notification-group-message = New message in { $group }
+notification-hidden-preview = Open the chat to read the message
+notification-mention = { $name } mentioned you in { $group }
+
+password = Password
+password-common = This password is too common, choose a less predictable one
+password-policy = Password must be at least { $length } characters long and contain at least { $classes }
+password-policy-digit = one digit
+password-policy-length = Password must be at least { $length } characters long
+password-policy-lowercase = one lowercase letter
+password-policy-special = one special character
+password-policy-uppercase = one uppercase letter
+
+quick-switcher-close = Close
+quick-switcher-input = Jump to a friend or group...
+quick-switcher-no-results = No chat found
+
+register-button = Register
+register-fill-in-fields = Please fill in all fields
+register-first-name = First Name
+register-last-name = Last Name
+register-loading = Registering...
+register-success = Account has been registered. A verification code has been sent to your email
+register-title = Registration:
+register-verification-email-failed = Account has been registered, but the verification email could not be sent
+
+resend-code = Resend code
+
+reset-button = Reset password
+reset-code = Reset code
+reset-code-sent = If an account exists for this email, a reset code has been sent.
+reset-email-empty = Please enter your email address.
+reset-email-failed = The reset email could not be sent.
+reset-fill-in-fields = Please fill in the reset code and the new password
+reset-loading = Resetting password...
+reset-new-password = New password
+reset-success = Password has been reset. You can now log in.
+reset-title = Reset password:
+
+settings-custom-themes = Custom themes:
+settings-delete-account = Delete Account
+settings-delete-account-failed = Error deleting account: { $error }
+settings-delete-confirm = Are you sure you want to delete your account?
+settings-dnd = Do not disturb
+settings-dnd-from = From (HH:MM)
+settings-dnd-invalid = Do not disturb times must be in the format HH:MM
+settings-dnd-to = To (HH:MM)
+settings-email-empty = Email and password fields cannot be empty
+settings-email-update-failed = Error updating email: { $error }
+settings-email-updated = Email updated successfully
+settings-email-verify-failed = Error verifying email: { $error }
+settings-font-scale = Font size:
+settings-font-scale-reset = Reset
+settings-language = Language:
+settings-language-system = System language
+settings-loading = Please wait...
+settings-mute-friends = Mute friends:
+settings-mute-groups = Mute groups:
+settings-new-password = New Password
+settings-notifications = Notifications:
+settings-notifications-load-failed = Error loading notification settings: { $error }
+settings-notifications-save-failed = Error saving notification settings: { $error }
+settings-notifications-saved = Notification settings saved
+settings-old-password = Old Password
+settings-password-empty = Password fields cannot be empty
+settings-password-updated = Password updated successfully
+settings-recovery-codes = Recovery codes: { $codes }
+settings-revoke = Revoke
+settings-save-notifications = Save Notifications
+settings-session = { $device } - last active { $last_active }
+settings-session-revoke-failed = Error revoking session: { $error }
+settings-session-revoked = Session revoked
+settings-sessions = Active sessions:
+settings-sessions-load-failed = Error loading sessions: { $error }
+settings-shortcut-placeholder = e.g. Ctrl+K
+settings-shortcuts = Keyboard shortcuts
+settings-shortcuts-reset = The default shortcuts are used
+settings-shortcuts-reset-button = Reset
+settings-shortcuts-save = Save
+settings-shortcuts-saved = The shortcuts have been saved
+settings-show-notifications = Show desktop notifications
+settings-show-preview = Show message preview
+settings-sync-preferences = Sync preferences to my account
+settings-theme-delete = Delete
+settings-theme-edit = Edit
+settings-theme-export = Export
+settings-theme-export-failed = Error exporting theme: { $error }
+settings-theme-exported = Theme exported to { $path }
+settings-theme-file = Theme file (.toml)
+settings-theme-high-contrast = High contrast
+settings-theme-import = Import
+settings-theme-import-failed = Error importing theme: { $error }
+settings-theme-imported = Theme imported. Save it to use it.
+settings-theme-name = Theme name
+settings-theme-path-empty = Please enter the path of the theme file
+settings-theme-save = Save Theme
+settings-theme-saved = Theme { $theme } saved
+settings-theme-system = System
+settings-theme-use = Use
+settings-this-device = This device
+settings-time-zone = Time zone:
+settings-time-zone-placeholder = System time zone, e.g. Europe/Berlin
+settings-time-zone-save = Save time zone
+settings-time-zone-saved = Times are shown in { $name }
+settings-time-zone-system = Times are shown in the time zone of the system
+settings-two-factor-add-secret = Add the secret to your authenticator app and confirm with a code
+settings-two-factor-code = Two-factor code
+settings-two-factor-code-empty = Two-factor code cannot be empty
+settings-two-factor-confirm = Confirm 2FA
+settings-two-factor-disable = Disable 2FA
+settings-two-factor-disable-empty = Password and two-factor code cannot be empty
+settings-two-factor-disable-failed = Error disabling two-factor authentication: { $error }
+settings-two-factor-disabled = Two-factor authentication disabled
+settings-two-factor-enable = Enable 2FA
+settings-two-factor-enable-failed = Error enabling two-factor authentication: { $error }
+settings-two-factor-enabled = Two-factor authentication enabled. Store your recovery codes in a safe place
+settings-two-factor-load-failed = Error loading two-factor status: { $error }
+settings-two-factor-secret = Secret: { $secret }
+settings-update-email = Update Email
+settings-update-password = Update Password
+settings-verification-code = Verification code
+settings-verification-code-empty = Verification code cannot be empty
+settings-verification-code-sent = A verification code has been sent to { $email }
+settings-verify-email = Verify Email
+
+shortcut-back = Go back
+shortcut-chats-tab = Show the chats
+shortcut-conflict = { $binding } is used for "{ $shortcut }" and "{ $other }"
+shortcut-edit-last-message = Edit the last message
+shortcut-friends-tab = Show the friends
+shortcut-groups-tab = Show the groups
+shortcut-invalid = "{ $binding }" is not a valid shortcut
+shortcut-needs-modifier = { $binding } needs Ctrl or Alt, otherwise it is triggered while typing
+shortcut-quick-switcher = Open the quick switcher
+shortcut-search = Search in the chat
+
+theme-color-background = Background
+theme-color-danger = Danger
+theme-color-primary = Primary
+theme-color-success = Success
+theme-color-text = Text
+theme-invalid-color = Invalid { $color } color: { $error }
+theme-name-missing = The theme needs a name
+
+user-tab-accept = Accept friend invite
+user-tab-friends = Friends:
+user-tab-loading = Loading friends...
+user-tab-reject = Reject friend invite
+user-tab-requests = Friend requests:
+user-tab-retract = Retract invite
+user-tab-select-user = Select a user to add as a friend
+user-tab-send-request = Send friend request
+user-tab-sent-requests = Sent friend requests:
+user-tab-unfriend = Unfriend user
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+/// The message catalogs in the Fluent syntax. Only messages with placeholders like `{ $name }` are supported
+const ENGLISH_CATALOG: &str = include_str!("locales/en.ftl");
+const GERMAN_CATALOG: &str = include_str!("locales/de.ftl");
+
+lazy_static! {
+    static ref ENGLISH_MESSAGES: HashMap<&'static str, &'static str> =
+        parse_catalog(ENGLISH_CATALOG);
+    static ref GERMAN_MESSAGES: HashMap<&'static str, &'static str> = parse_catalog(GERMAN_CATALOG);
+    /// The time zone in which times are shown, none to use the time zone of the system
+    static ref TIME_ZONE: RwLock<Option<Tz>> = RwLock::new(None);
+}
+
+/// The language of the user interface. It is shared by all views, so it can be switched at runtime
+static CURRENT_LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// The languages the user interface is translated to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    German,
+}
+
+/// Implementation of the language
+impl Language {
+    /// All languages in the order they are shown in the language picker
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// Returns the name of the language in the language itself
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    /// Returns the ISO 639-1 code of the language, e.g. for the lang attribute of HTML documents
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    /// Returns the language of a locale like "de-DE" or "de_AT.UTF-8", none if it is not translated
+    pub fn from_locale(locale: &str) -> Option<Language> {
+        let code = locale
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match code.as_str() {
+            "en" => Some(Language::English),
+            "de" => Some(Language::German),
+            _ => None,
+        }
+    }
+
+    /// Returns the language of the system. English is used if the system language is not translated
+    pub fn detect() -> Language {
+        sys_locale::get_locale()
+            .and_then(|locale| Language::from_locale(&locale))
+            .unwrap_or(Language::English)
+    }
+
+    /// Returns the messages of the language
+    fn messages(&self) -> &'static HashMap<&'static str, &'static str> {
+        match self {
+            Language::English => &ENGLISH_MESSAGES,
+            Language::German => &GERMAN_MESSAGES,
+        }
+    }
+}
+
+/// Set the language of the user interface. Texts that are shown afterwards use the language
+pub fn set_language(language: Language) {
+    let index = match language {
+        Language::English => 0,
+        Language::German => 1,
+    };
+    CURRENT_LANGUAGE.store(index, Ordering::Relaxed);
+}
+
+/// Returns the language of the user interface
+pub fn language() -> Language {
+    match CURRENT_LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::German,
+        _ => Language::English,
+    }
+}
+
+/// Set the time zone in which times are shown. The time zone of the system is used if none is set
+pub fn set_time_zone(time_zone: Option<Tz>) {
+    if let Ok(mut current) = TIME_ZONE.write() {
+        *current = time_zone;
+    }
+}
+
+/// Returns the time zone in which times are shown, none if the time zone of the system is used
+pub fn time_zone() -> Option<Tz> {
+    TIME_ZONE.read().map(|time_zone| *time_zone).unwrap_or(None)
+}
+
+/// Parse the name of a time zone like "Europe/Berlin". Returns the time zone or an error message
+pub fn parse_time_zone(name: &str) -> Result<Tz, String> {
+    name.trim().parse::<Tz>().map_err(|_| {
+        t_args(
+            "error-invalid-time-zone",
+            &[("name", name.trim().to_string())],
+        )
+    })
+}
+
+/// Returns the date and time of a point in time in a time zone, or in the time zone of the system if none is given
+pub fn local_time(date: DateTime<Utc>, time_zone: Option<Tz>) -> NaiveDateTime {
+    match time_zone {
+        Some(time_zone) => date.with_timezone(&time_zone).naive_local(),
+        None => date.with_timezone(&Local).naive_local(),
+    }
+}
+
+/// Returns the point in time of a date and time in a time zone, or in the time zone of the system if none is given.
+/// A time that is skipped by a change to daylight saving time is taken as UTC
+pub fn utc_time(date: NaiveDateTime, time_zone: Option<Tz>) -> DateTime<Utc> {
+    let utc = match time_zone {
+        Some(time_zone) => time_zone
+            .from_local_datetime(&date)
+            .earliest()
+            .map(|date| date.with_timezone(&Utc)),
+        None => Local
+            .from_local_datetime(&date)
+            .earliest()
+            .map(|date| date.with_timezone(&Utc)),
+    };
+
+    utc.unwrap_or_else(|| date.and_utc())
+}
+
+/// Returns the message with the given key in the language of the user interface
+pub fn t(key: &str) -> String {
+    translate(language(), key, &[])
+}
+
+/// Returns the message with the given key in the language of the user interface, with its placeholders replaced
+pub fn t_args(key: &str, args: &[(&str, String)]) -> String {
+    translate(language(), key, args)
+}
+
+/// Returns the message with the given key in a language, with its placeholders replaced.
+/// A message that is not translated is shown in English, an unknown key is shown as it is
+pub fn translate(language: Language, key: &str, args: &[(&str, String)]) -> String {
+    let message = language
+        .messages()
+        .get(key)
+        .or_else(|| ENGLISH_MESSAGES.get(key))
+        .copied()
+        .unwrap_or(key);
+
+    args.iter()
+        .fold(message.to_string(), |message, (name, value)| {
+            message.replace(&format!("{{ ${} }}", name), value)
+        })
+}
+
+/// Returns the keys of the messages of a language, e.g. to check that all messages are translated
+pub fn message_keys(language: Language) -> Vec<&'static str> {
+    let mut keys: Vec<&'static str> = language.messages().keys().copied().collect();
+    keys.sort_unstable();
+    keys
+}
+
+/// Format the date when a message was sent in a language, relative to the current date:
+/// - If the message was sent today, only the time is shown
+/// - If the message was sent this year, the weekday, day and month are shown without the year
+/// - If the message was sent in a different year, the full date and time are shown
+pub fn format_date(language: Language, date: NaiveDateTime, now: NaiveDateTime) -> String {
+    match language {
+        Language::English => {
+            if date.date() == now.date() {
+                date.format("%I:%M %p").to_string()
+            } else if date.year() == now.year() {
+                date.format("%A %b %e, %I:%M %p").to_string()
+            } else {
+                date.format("%A %b %e, %Y %I:%M %p").to_string()
+            }
+        }
+        Language::German => {
+            let time = date.format("%H:%M");
+            let day = format!(
+                "{}, {}. {}",
+                german_weekday(date.weekday()),
+                date.day(),
+                GERMAN_MONTHS[date.month0() as usize]
+            );
+
+            if date.date() == now.date() {
+                time.to_string()
+            } else if date.year() == now.year() {
+                format!("{}, {}", day, time)
+            } else {
+                format!("{} {}, {}", day, date.year(), time)
+            }
+        }
+    }
+}
+
+/// Names of the months in German
+const GERMAN_MONTHS: [&str; 12] = [
+    "Januar",
+    "Februar",
+    "März",
+    "April",
+    "Mai",
+    "Juni",
+    "Juli",
+    "August",
+    "September",
+    "Oktober",
+    "November",
+    "Dezember",
+];
+
+/// Returns the name of a weekday in German
+fn german_weekday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Montag",
+        Weekday::Tue => "Dienstag",
+        Weekday::Wed => "Mittwoch",
+        Weekday::Thu => "Donnerstag",
+        Weekday::Fri => "Freitag",
+        Weekday::Sat => "Samstag",
+        Weekday::Sun => "Sonntag",
+    }
+}
+
+/// Parse a catalog with one message per line in the form `key = message`. Empty lines and comments (#) are skipped
+fn parse_catalog(catalog: &'static str) -> HashMap<&'static str, &'static str> {
+    catalog
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(" = "))
+        .map(|(key, message)| (key.trim(), message.trim()))
+        .collect()
+}
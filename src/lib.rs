@@ -1,2 +1,5 @@
+pub mod admin;
 pub mod backend;
+pub mod config;
 pub mod frontend;
+pub mod i18n;
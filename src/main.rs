@@ -1,10 +1,50 @@
-use iced::{window, Application, Settings, Size};
-use secse24_group08::frontend::app::App;
+use std::env;
+use std::process;
+
+use iced::{window, Application, Point, Settings, Size};
+use log::error;
+use secse24_group08::{
+    backend::database::migrations::check_database_at_startup,
+    config::{loader, set_config, Config},
+    frontend::{
+        app::{App, AppFlags},
+        preferences::PreferencesStore,
+        session_store::SessionStore,
+    },
+};
 
 /// Runs the application
 fn main() -> iced::Result {
+    // Loads the configuration from the config file, the environment and the command-line flags
+    let args: Vec<String> = env::args().skip(1).collect();
+    let config = match Config::load(&args) {
+        Ok((config, remaining)) if remaining.is_empty() => config,
+        Ok((_, remaining)) => {
+            eprintln!(
+                "Unknown argument: {}\n\nOptions:\n{}",
+                remaining.join(" "),
+                loader::usage()
+            );
+            process::exit(2);
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(2);
+        }
+    };
+
     // Initializes the logger
-    env_logger::init();
+    env_logger::Builder::new()
+        .parse_filters(&config.log_level)
+        .init();
+    set_config(config);
+
+    // Refuses to start with a database of another schema, the migrations are applied with `chatadmin migrate`
+    if let Err(err) = check_database_at_startup() {
+        error!("{}", err);
+        eprintln!("{}", err);
+        process::exit(1);
+    }
 
     let data = include_bytes!("icon.png");
 
@@ -14,13 +54,27 @@ fn main() -> iced::Result {
 
     let icon = icon.unwrap();
 
-    let settings = Settings::<()> {
+    // The window is opened with the size and at the position it had when it was closed
+    let preferences_store = PreferencesStore::default();
+    let geometry = preferences_store.load().window;
+    let position = match (geometry.x, geometry.y) {
+        (Some(x), Some(y)) => window::Position::Specific(Point::new(x as f32, y as f32)),
+        _ => window::Position::Centered,
+    };
+
+    let settings = Settings::<AppFlags> {
         window: window::Settings {
-            size: Size::new(1600.0, 900.0),
-            position: window::Position::Centered,
+            size: Size::new(geometry.width, geometry.height),
+            position,
             icon: Some(icon),
+            // The preferences are stored before the window is closed
+            exit_on_close_request: false,
             ..window::Settings::default()
         },
+        flags: AppFlags {
+            session_store: SessionStore::default(),
+            preferences_store,
+        },
         ..Settings::default()
     };
 
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use secse24_group08::admin::cli::{parse_args, AdminArgs, AdminCommand};
+
+    fn parse(args: &[&str]) -> Result<AdminArgs, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse(&[]).unwrap().command, AdminCommand::Help);
+        assert_eq!(parse(&["--help"]).unwrap().command, AdminCommand::Help);
+        assert_eq!(
+            parse(&["users", "list"]).unwrap().command,
+            AdminCommand::ListUsers
+        );
+        assert_eq!(
+            parse(&["users", "search", "doe"]).unwrap().command,
+            AdminCommand::SearchUsers {
+                query: "doe".to_string()
+            }
+        );
+        assert_eq!(
+            parse(&["users", "create", "John", "Doe", "john@email.de"])
+                .unwrap()
+                .command,
+            AdminCommand::CreateUser {
+                first_name: "John".to_string(),
+                last_name: "Doe".to_string(),
+                email: "john@email.de".to_string(),
+            }
+        );
+        assert_eq!(
+            parse(&["users", "disable", "3"]).unwrap().command,
+            AdminCommand::DisableUser { user_id: 3 }
+        );
+        assert_eq!(
+            parse(&["users", "reset-password", "3"]).unwrap().command,
+            AdminCommand::ResetPassword { user_id: 3 }
+        );
+        assert_eq!(
+            parse(&["users", "unlock", "John@Email.de"])
+                .unwrap()
+                .command,
+            AdminCommand::UnlockUser {
+                email: "john@email.de".to_string()
+            }
+        );
+        assert_eq!(
+            parse(&["groups", "remove-member", "1", "2"])
+                .unwrap()
+                .command,
+            AdminCommand::RemoveMember {
+                group_id: 1,
+                user_id: 2
+            }
+        );
+        assert_eq!(parse(&["stats"]).unwrap().command, AdminCommand::Stats);
+        assert_eq!(parse(&["migrate"]).unwrap().command, AdminCommand::Migrate);
+        assert_eq!(
+            parse(&["check-schema"]).unwrap().command,
+            AdminCommand::CheckSchema
+        );
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let args = parse(&["stats", "--json"]).unwrap();
+        assert!(args.json);
+        assert!(!args.confirmed);
+
+        let args = parse(&["-y", "purge", "--older-than", "30"]).unwrap();
+        assert!(args.confirmed);
+        assert_eq!(
+            args.command,
+            AdminCommand::PurgeMessages {
+                older_than_days: 30
+            }
+        );
+        assert_eq!(
+            parse(&["purge", "--older-than=7"]).unwrap().command,
+            AdminCommand::PurgeMessages { older_than_days: 7 }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_arguments() {
+        assert_eq!(
+            parse(&["users", "frob"]).unwrap_err(),
+            "Unknown command: users frob"
+        );
+        assert_eq!(
+            parse(&["users", "disable", "abc"]).unwrap_err(),
+            "Invalid user id: abc"
+        );
+        assert_eq!(
+            parse(&["groups", "members", "-1"]).unwrap_err(),
+            "Unknown option: -1"
+        );
+        assert_eq!(
+            parse(&["stats", "--verbose"]).unwrap_err(),
+            "Unknown option: --verbose"
+        );
+        assert_eq!(
+            parse(&["purge"]).unwrap_err(),
+            "The purge command needs --older-than <days>"
+        );
+        assert_eq!(
+            parse(&["purge", "--older-than", "many"]).unwrap_err(),
+            "Invalid number of days: many"
+        );
+        assert_eq!(
+            parse(&["stats", "--older-than", "3"]).unwrap_err(),
+            "The option --older-than is only valid for the purge command"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+#[cfg(test)]
+mod tests {
+    use std::io::{empty, Cursor};
+
+    use secse24_group08::{
+        admin::{
+            cli::{AdminArgs, AdminCommand},
+            commands::{format_error, run},
+        },
+        backend::entities::{
+            admin_ops::find_statistics,
+            login_throttle_ops::{find_login_failure, record_failed_login, ACCOUNT_SCOPE},
+            session_ops::{create_session, revoke_session},
+            user_ops::{
+                delete_user, find_user_by_email, is_user_disabled, search_user_accounts,
+                set_user_disabled,
+            },
+        },
+    };
+
+    fn args(command: AdminCommand, json: bool) -> AdminArgs {
+        AdminArgs {
+            command,
+            json,
+            confirmed: false,
+        }
+    }
+
+    #[test]
+    fn test_list_and_search_users() {
+        let output = run(&args(AdminCommand::ListUsers, false), &mut empty()).unwrap();
+        assert!(output.contains("John Doe"));
+        assert!(output.contains("test1@email.de"));
+
+        let output = run(
+            &args(
+                AdminCommand::SearchUsers {
+                    query: "TEST2@".to_string(),
+                },
+                true,
+            ),
+            &mut empty(),
+        )
+        .unwrap();
+        let users: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(users.as_array().unwrap().len(), 1);
+        assert_eq!(users[0]["first_name"], "Jane");
+        assert_eq!(users[0]["disabled_at"], serde_json::Value::Null);
+
+        // Wildcards are matched literally
+        assert!(search_user_accounts("%").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_manage_user() {
+        let email = "admin_tool@email.de";
+        let create = AdminCommand::CreateUser {
+            first_name: "Admin".to_string(),
+            last_name: "Tool".to_string(),
+            email: email.to_string(),
+        };
+
+        // The password is read from the input
+        assert_eq!(
+            run(&args(create.clone(), false), &mut empty()).unwrap_err(),
+            "No password was given on stdin"
+        );
+        let output = run(&args(create, true), &mut Cursor::new("StrongP@ssw0rd\n")).unwrap();
+        let user_id = find_user_by_email(email).unwrap().id;
+        let user: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(user["id"], user_id);
+
+        run(
+            &args(AdminCommand::DisableUser { user_id }, false),
+            &mut empty(),
+        )
+        .unwrap();
+        assert!(is_user_disabled(user_id).unwrap());
+        run(
+            &args(AdminCommand::EnableUser { user_id }, false),
+            &mut empty(),
+        )
+        .unwrap();
+        assert!(!is_user_disabled(user_id).unwrap());
+
+        assert!(run(
+            &args(AdminCommand::ResetPassword { user_id }, false),
+            &mut Cursor::new("weak\n"),
+        )
+        .is_err());
+        assert_eq!(
+            run(
+                &args(AdminCommand::ResetPassword { user_id }, true),
+                &mut Cursor::new("N3w!Passw0rd\n"),
+            )
+            .unwrap(),
+            format!("{{\"password_reset\":true,\"user_id\":{}}}", user_id)
+        );
+
+        // The current and the previous passwords cannot be used again
+        for password in ["N3w!Passw0rd\n", "StrongP@ssw0rd\n"] {
+            assert_eq!(
+                run(
+                    &args(AdminCommand::ResetPassword { user_id }, true),
+                    &mut Cursor::new(password),
+                )
+                .unwrap_err(),
+                "The password was used recently, it must differ from the last 5 passwords"
+            );
+        }
+
+        // Deleting needs a confirmation
+        let delete = AdminCommand::DeleteUser { user_id };
+        assert_eq!(
+            run(&args(delete.clone(), false), &mut empty()).unwrap_err(),
+            "Add --yes to delete the user and all of their messages"
+        );
+        let confirmed = AdminArgs {
+            confirmed: true,
+            ..args(delete, false)
+        };
+        assert_eq!(
+            run(&confirmed, &mut empty()).unwrap(),
+            format!("Deleted user {}", user_id)
+        );
+        assert!(find_user_by_email(email).is_err());
+
+        // Clean up if the test failed before the user was deleted
+        let _ = delete_user(user_id);
+    }
+
+    #[test]
+    fn test_unlock_user() {
+        let email = "admin_unlock@email.de";
+        for _ in 0..10 {
+            assert!(record_failed_login(email, "commands_tests").is_ok());
+        }
+        let failure = find_login_failure(ACCOUNT_SCOPE, email).unwrap().unwrap();
+        assert!(failure.locked_until.is_some());
+
+        assert_eq!(
+            run(
+                &args(
+                    AdminCommand::UnlockUser {
+                        email: email.to_string()
+                    },
+                    false
+                ),
+                &mut empty(),
+            )
+            .unwrap(),
+            format!("Unlocked {}, the failed login attempts were reset", email)
+        );
+        assert!(find_login_failure(ACCOUNT_SCOPE, email).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_groups_and_stats() {
+        let output = run(&args(AdminCommand::ListGroups, true), &mut empty()).unwrap();
+        let groups: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(groups
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|group| group["name"] == "Test group"));
+
+        let output = run(
+            &args(AdminCommand::ListMembers { group_id: 1 }, false),
+            &mut empty(),
+        )
+        .unwrap();
+        assert!(output.contains("John Doe"));
+        assert!(output.contains("Jane Doe"));
+
+        assert_eq!(
+            run(
+                &args(
+                    AdminCommand::RemoveMember {
+                        group_id: 1,
+                        user_id: i32::MAX
+                    },
+                    false
+                ),
+                &mut empty(),
+            )
+            .unwrap_err(),
+            format!("User {} is not a member of group 1", i32::MAX)
+        );
+
+        let output = run(&args(AdminCommand::Stats, true), &mut empty()).unwrap();
+        let statistics: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(statistics["users"].as_i64().unwrap() >= 2);
+        assert!(statistics["groups"].as_i64().unwrap() >= 1);
+    }
+
+    #[test]
+    fn test_stats_do_not_count_revoked_sessions() {
+        let active_before = find_statistics().unwrap().active_sessions;
+        let (session, _) = create_session(2, "Admin statistics test").unwrap();
+        assert_eq!(
+            find_statistics().unwrap().active_sessions,
+            active_before + 1
+        );
+
+        // Disabling the user revokes all of its sessions
+        assert!(set_user_disabled(2, true).is_ok());
+        assert!(find_statistics().unwrap().active_sessions <= active_before);
+
+        // Clean up
+        assert!(set_user_disabled(2, false).is_ok());
+        assert!(revoke_session(2, session.id).is_ok());
+    }
+
+    #[test]
+    fn test_purge_messages() {
+        let purge = AdminCommand::PurgeMessages {
+            older_than_days: 36500,
+        };
+        assert_eq!(
+            run(&args(purge.clone(), false), &mut empty()).unwrap_err(),
+            "Add --yes to delete messages"
+        );
+
+        let confirmed = AdminArgs {
+            confirmed: true,
+            ..args(purge, true)
+        };
+        let output = run(&confirmed, &mut empty()).unwrap();
+        let purged: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(purged["direct_messages"], 0);
+        assert_eq!(purged["group_messages"], 0);
+    }
+
+    #[test]
+    fn test_format_error() {
+        assert_eq!(format_error("failed", false), "Error: failed");
+        assert_eq!(format_error("failed", true), "{\"error\":\"failed\"}");
+    }
+}
@@ -0,0 +1,2 @@
+pub mod cli_tests;
+pub mod commands_tests;
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use secse24_group08::backend::{
+        crypto::{
+            conversation::load_conversation_summaries, group_message::send_group_message,
+            key_store::KeyStore,
+        },
+        database::models::ConversationSummary,
+        entities::{
+            conversation_ops::mark_conversation_read,
+            group_ops::{create_group, delete_group},
+            user_group_ops::create_user_group,
+        },
+    };
+
+    fn group_summary(key_store: &KeyStore, user_id: i32, group_id: i32) -> ConversationSummary {
+        load_conversation_summaries(key_store, user_id)
+            .unwrap()
+            .into_iter()
+            .find(|summary| summary.is_group && summary.conversation_id == group_id)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_conversation_summary_of_group() {
+        let directory = env::temp_dir().join("secse24_group08_conversation_keys");
+        let _ = fs::remove_dir_all(&directory);
+        let key_store = KeyStore::new(directory);
+        assert!(key_store.create_identity(1).is_ok());
+        assert!(key_store.create_identity(2).is_ok());
+
+        let group = create_group("Conversation Group").unwrap();
+        assert!(create_user_group(1, group.id, true).is_ok());
+        assert!(create_user_group(2, group.id, true).is_ok());
+
+        // A group without messages is listed too
+        let summary = group_summary(&key_store, 1, group.id);
+        assert_eq!(summary.display_name(), "Conversation Group");
+        assert_eq!(summary.message, None);
+        assert_eq!(summary.unread_count, 0);
+
+        // Messages of others are unread, the newest one is previewed
+        assert!(send_group_message(&key_store, 2, group.id, "First", None).is_ok());
+        assert!(send_group_message(&key_store, 2, group.id, "Second", None).is_ok());
+        let summary = group_summary(&key_store, 1, group.id);
+        assert_eq!(summary.message.as_deref(), Some("Second"));
+        assert_eq!(summary.sender_id, Some(2));
+        assert_eq!(summary.unread_count, 2);
+        assert_eq!(summary.group().unwrap().id, group.id);
+        assert!(summary.user().is_none());
+
+        // Own messages are never unread
+        assert_eq!(group_summary(&key_store, 2, group.id).unread_count, 0);
+
+        // Reading the chat clears the unread count
+        assert!(mark_conversation_read(1, true, group.id).is_ok());
+        assert_eq!(group_summary(&key_store, 1, group.id).unread_count, 0);
+
+        // Clean up
+        assert!(delete_group(group.id).is_ok());
+    }
+}
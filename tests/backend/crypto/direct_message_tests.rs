@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use secse24_group08::backend::{
+        crypto::{
+            direct_message::{
+                find_conversation_fingerprints, load_direct_messages, send_direct_message,
+            },
+            key_store::KeyStore,
+        },
+        entities::user_message_ops::{delete_user_message, find_all_messages_between_users},
+    };
+
+    fn setup_key_store(name: &str) -> KeyStore {
+        let directory = env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&directory);
+        KeyStore::new(directory)
+    }
+
+    #[test]
+    fn test_send_and_load_direct_message() {
+        let key_store = setup_key_store("secse24_group08_direct_message_keys");
+        assert!(key_store.create_identity(1).is_ok());
+        assert!(key_store.create_identity(2).is_ok());
+
+        let sent = send_direct_message(&key_store, 1, 2, "Hello, User 2!", None).unwrap();
+        assert_eq!(sent.message, "Hello, User 2!");
+
+        // The server only stores the ciphertext
+        let stored = find_all_messages_between_users(1, 2).unwrap();
+        let stored_message = stored.last().unwrap();
+        assert_ne!(stored_message.message, "Hello, User 2!");
+        assert!(stored_message.nonce.is_some());
+        assert_eq!(
+            stored_message.receiver_public_key,
+            key_store.load(2).unwrap().map(|key| key.public_key())
+        );
+
+        let loaded = load_direct_messages(&key_store, 2, 1, None).unwrap();
+        assert_eq!(loaded.last().unwrap().message, "Hello, User 2!");
+
+        // Replaced keys are kept on the device, so the message can still be read
+        assert!(key_store.create_identity(2).is_ok());
+        let loaded = load_direct_messages(&key_store, 2, 1, None).unwrap();
+        assert_eq!(loaded.last().unwrap().message, "Hello, User 2!");
+
+        // Messages encrypted for a key of another device cannot be read
+        let other_device = setup_key_store("secse24_group08_other_device_keys");
+        let loaded = load_direct_messages(&other_device, 2, 1, None).unwrap();
+        assert!(loaded.last().unwrap().undecryptable);
+
+        // Clean up
+        assert!(delete_user_message(1, 2).is_ok());
+    }
+
+    #[test]
+    fn test_send_direct_message_only_once() {
+        let key_store = setup_key_store("secse24_group08_client_id_keys");
+        assert!(key_store.create_identity(1).is_ok());
+        assert!(key_store.create_identity(2).is_ok());
+        let stored_before = find_all_messages_between_users(1, 2).unwrap().len();
+
+        // A message that is sent again with the same client ID is stored once
+        let sent = send_direct_message(&key_store, 1, 2, "Once", Some("client_id_test")).unwrap();
+        let sent_again =
+            send_direct_message(&key_store, 1, 2, "Once", Some("client_id_test")).unwrap();
+        assert_eq!(sent.send_date, sent_again.send_date);
+        assert_eq!(sent.nonce, sent_again.nonce);
+        assert_eq!(sent_again.message, "Once");
+        assert_eq!(
+            find_all_messages_between_users(1, 2).unwrap().len(),
+            stored_before + 1
+        );
+
+        // Another sender cannot get the stored message with the same client ID
+        assert!(send_direct_message(&key_store, 2, 1, "Once", Some("client_id_test")).is_err());
+
+        // Clean up
+        assert!(delete_user_message(1, 2).is_ok());
+    }
+
+    #[test]
+    fn test_find_conversation_fingerprints() {
+        let key_store = setup_key_store("secse24_group08_fingerprint_keys");
+        let own_key = key_store.create_identity(1).unwrap();
+        let other_key = key_store.create_identity(2).unwrap();
+
+        let fingerprints = find_conversation_fingerprints(&key_store, 1, 2).unwrap();
+        let other_fingerprints = find_conversation_fingerprints(&key_store, 2, 1).unwrap();
+
+        assert_eq!(fingerprints.own, other_fingerprints.other.unwrap());
+        assert_eq!(fingerprints.other.unwrap(), other_fingerprints.own);
+        assert_ne!(own_key.public_key(), other_key.public_key());
+    }
+
+    #[test]
+    fn test_conversation_fingerprints_show_changed_key() {
+        let key_store = setup_key_store("secse24_group08_changed_fingerprint_keys");
+        assert!(key_store.create_identity(1).is_ok());
+        assert!(key_store.create_identity(2).is_ok());
+        assert!(send_direct_message(&key_store, 1, 2, "Before the new key", None).is_ok());
+
+        let fingerprints = find_conversation_fingerprints(&key_store, 1, 2).unwrap();
+        assert!(!fingerprints.own_changed);
+        assert!(!fingerprints.other_changed);
+
+        // User 2 logs in on another device, which publishes a new key
+        let other_device = setup_key_store("secse24_group08_changed_fingerprint_device");
+        assert!(other_device.load_or_create_identity(2).is_ok());
+
+        let fingerprints = find_conversation_fingerprints(&key_store, 1, 2).unwrap();
+        assert!(!fingerprints.own_changed);
+        assert!(fingerprints.other_changed);
+        let other_fingerprints = find_conversation_fingerprints(&other_device, 2, 1).unwrap();
+        assert!(other_fingerprints.own_changed);
+
+        // A new message is encrypted for the new key
+        assert!(send_direct_message(&key_store, 1, 2, "After the new key", None).is_ok());
+        let fingerprints = find_conversation_fingerprints(&key_store, 1, 2).unwrap();
+        assert!(!fingerprints.other_changed);
+
+        // Clean up
+        assert!(delete_user_message(1, 2).is_ok());
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use secse24_group08::backend::{
+        crypto::{
+            group_message::{
+                load_group_messages, send_group_message, share_group_history, SenderKey,
+            },
+            key_store::KeyStore,
+        },
+        entities::{
+            group_message_ops::find_all_messages_of_group,
+            group_ops::{create_group, delete_group, find_group_key_epoch},
+            user_group_ops::{create_user_group, delete_user_group, update_user_group},
+        },
+    };
+
+    fn setup_key_store() -> KeyStore {
+        let directory = env::temp_dir().join("secse24_group08_group_message_keys");
+        let _ = fs::remove_dir_all(&directory);
+        let key_store = KeyStore::new(directory);
+        assert!(key_store.create_identity(1).is_ok());
+        assert!(key_store.create_identity(2).is_ok());
+        key_store
+    }
+
+    fn last_message(key_store: &KeyStore, user_id: i32, group_id: i32) -> String {
+        let message = load_group_messages(key_store, user_id, group_id, None)
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert!(!message.undecryptable);
+        message.message
+    }
+
+    fn last_message_is_undecryptable(key_store: &KeyStore, user_id: i32, group_id: i32) -> bool {
+        load_group_messages(key_store, user_id, group_id, None)
+            .unwrap()
+            .pop()
+            .unwrap()
+            .undecryptable
+    }
+
+    #[test]
+    fn test_sender_key_encrypt_decrypt() {
+        let sender_key = SenderKey::generate();
+        let encrypted = sender_key.encrypt(1, 0, 1, "Hello, group!").unwrap();
+
+        let message = sender_key
+            .decrypt(1, 0, 1, &encrypted.ciphertext, &encrypted.nonce)
+            .unwrap();
+        assert_eq!(message, "Hello, group!");
+
+        // Other key epoch
+        assert!(sender_key
+            .decrypt(1, 1, 1, &encrypted.ciphertext, &encrypted.nonce)
+            .is_err());
+        // Other key
+        assert!(SenderKey::generate()
+            .decrypt(1, 0, 1, &encrypted.ciphertext, &encrypted.nonce)
+            .is_err());
+    }
+
+    #[test]
+    fn test_group_membership_rotates_key() {
+        let key_store = setup_key_store();
+        let group = create_group("Encrypted Group").unwrap();
+
+        assert!(create_user_group(1, group.id, true).is_ok());
+        assert!(create_user_group(2, group.id, false).is_ok());
+        let epoch = find_group_key_epoch(group.id).unwrap();
+
+        // The server only stores the ciphertext
+        let sent = send_group_message(&key_store, 1, group.id, "Before joining", None).unwrap();
+        assert_eq!(sent.message, "Before joining");
+        let stored = find_all_messages_of_group(group.id).unwrap();
+        assert_ne!(stored[0].message, "Before joining");
+        assert_eq!(last_message(&key_store, 1, group.id), "Before joining");
+
+        // A new member cannot read the history
+        assert!(update_user_group(2, group.id, true).is_ok());
+        assert!(find_group_key_epoch(group.id).unwrap() > epoch);
+        assert!(last_message_is_undecryptable(&key_store, 2, group.id));
+
+        assert!(send_group_message(&key_store, 1, group.id, "After joining", None).is_ok());
+        assert_eq!(last_message(&key_store, 2, group.id), "After joining");
+
+        // Unless it is shared explicitly
+        assert!(share_group_history(&key_store, 1, group.id).unwrap() > 0);
+        let messages = load_group_messages(&key_store, 2, group.id, None).unwrap();
+        assert_eq!(messages[0].message, "Before joining");
+
+        // A removed member cannot read new messages
+        assert_eq!(delete_user_group(2, group.id).unwrap(), 1);
+        assert!(send_group_message(&key_store, 1, group.id, "After leaving", None).is_ok());
+        assert_eq!(last_message(&key_store, 1, group.id), "After leaving");
+        assert!(last_message_is_undecryptable(&key_store, 2, group.id));
+
+        // Clean up
+        assert!(delete_group(group.id).is_ok());
+    }
+
+    #[test]
+    fn test_replaced_key_keeps_group_messages_readable() {
+        let key_store = setup_key_store();
+        let group = create_group("Replaced Key Group").unwrap();
+
+        assert!(create_user_group(1, group.id, true).is_ok());
+        assert!(create_user_group(2, group.id, true).is_ok());
+        assert!(send_group_message(&key_store, 1, group.id, "Before the new key", None).is_ok());
+        assert_eq!(last_message(&key_store, 2, group.id), "Before the new key");
+
+        // The sender key was encrypted for the replaced key of the recipient, which is kept on the device
+        assert!(key_store.create_identity(2).is_ok());
+        assert_eq!(last_message(&key_store, 2, group.id), "Before the new key");
+
+        // Clean up
+        assert!(delete_group(group.id).is_ok());
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use secse24_group08::backend::crypto::identity::{fingerprint, IdentityKey};
+
+    #[test]
+    fn test_identity_key_hex_roundtrip() {
+        let identity_key = IdentityKey::generate();
+        let restored = IdentityKey::from_hex(&identity_key.to_hex()).unwrap();
+
+        assert_eq!(identity_key.public_key(), restored.public_key());
+        assert!(IdentityKey::from_hex("not a key").is_err());
+    }
+
+    #[test]
+    fn test_conversation_key_encrypt_decrypt() {
+        let alice = IdentityKey::generate();
+        let bob = IdentityKey::generate();
+
+        let alice_key = alice.conversation_key(1, 2, &bob.public_key()).unwrap();
+        let bob_key = bob.conversation_key(2, 1, &alice.public_key()).unwrap();
+
+        let encrypted = alice_key.encrypt(1, 2, "Hello, Bob!").unwrap();
+        assert_ne!(encrypted.ciphertext, "Hello, Bob!");
+
+        let message = bob_key
+            .decrypt(1, 2, &encrypted.ciphertext, &encrypted.nonce)
+            .unwrap();
+        assert_eq!(message, "Hello, Bob!");
+    }
+
+    #[test]
+    fn test_conversation_key_rejects_changed_message() {
+        let alice = IdentityKey::generate();
+        let bob = IdentityKey::generate();
+        let eve = IdentityKey::generate();
+
+        let alice_key = alice.conversation_key(1, 2, &bob.public_key()).unwrap();
+        let bob_key = bob.conversation_key(2, 1, &alice.public_key()).unwrap();
+        let eve_key = eve.conversation_key(3, 2, &bob.public_key()).unwrap();
+
+        let encrypted = alice_key.encrypt(1, 2, "Hello, Bob!").unwrap();
+
+        // Wrong direction
+        assert!(bob_key
+            .decrypt(2, 1, &encrypted.ciphertext, &encrypted.nonce)
+            .is_err());
+        // Wrong key
+        assert!(eve_key
+            .decrypt(1, 2, &encrypted.ciphertext, &encrypted.nonce)
+            .is_err());
+
+        // Changed ciphertext
+        let mut ciphertext = encrypted.ciphertext.clone();
+        let last = if ciphertext.ends_with('0') { "1" } else { "0" };
+        ciphertext.replace_range(ciphertext.len() - 1.., last);
+        assert!(bob_key
+            .decrypt(1, 2, &ciphertext, &encrypted.nonce)
+            .is_err());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let identity_key = IdentityKey::generate();
+        let result = fingerprint(&identity_key.public_key()).unwrap();
+
+        assert_eq!(result.len(), 39);
+        assert_eq!(result.split(' ').count(), 8);
+        assert_eq!(result, fingerprint(&identity_key.public_key()).unwrap());
+        assert!(fingerprint("invalid").is_err());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod conversation_tests;
+pub mod direct_message_tests;
+pub mod group_message_tests;
+pub mod identity_tests;
+pub mod password_tests;
@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod tests {
+    use secse24_group08::backend::crypto::password::{
+        hash_password, needs_rehash, verify_password,
+    };
+
+    #[test]
+    fn test_hash_and_verify_password() {
+        let hash = hash_password("StrongP@ssw0rd").unwrap();
+        assert!(hash.starts_with("$argon2id$v=19$"));
+        assert_ne!(hash, hash_password("StrongP@ssw0rd").unwrap());
+
+        assert!(verify_password("StrongP@ssw0rd", &hash).unwrap());
+        assert!(!verify_password("WrongP@ssw0rd", &hash).unwrap());
+        assert!(!needs_rehash(&hash));
+    }
+
+    #[test]
+    fn test_verify_bcrypt_password() {
+        let hash = bcrypt::hash("StrongP@ssw0rd", 4).unwrap();
+
+        assert!(verify_password("StrongP@ssw0rd", &hash).unwrap());
+        assert!(!verify_password("WrongP@ssw0rd", &hash).unwrap());
+        assert!(needs_rehash(&hash));
+    }
+
+    #[test]
+    fn test_needs_rehash_other_parameters() {
+        let hash = "$argon2id$v=19$m=8192,t=1,p=1$c2FsdHNhbHRzYWx0$2rhVTSaSUKCHdl5P2M3xCQ";
+        assert!(needs_rehash(hash));
+        assert!(needs_rehash("not a hash"));
+        assert!(verify_password("StrongP@ssw0rd", "not a hash").is_err());
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use chrono::{Local, NaiveDateTime};
+    use chrono::{Datelike, Duration, Local, NaiveDateTime, Utc};
     use secse24_group08::backend::database::db::*;
 
     #[test]
@@ -28,7 +28,8 @@ mod tests {
     fn test_is_strong_password() {
         // Strong passwords
         assert!(is_strong_password("Aa1!Aa1!"));
-        assert!(is_strong_password("P@ssw0rd123"));
+        assert!(is_strong_password("Tr0mb0ne!Lamp"));
+        assert!(is_strong_password("Ünïcödé1!")); // Length in characters, not bytes
     }
 
     #[test]
@@ -39,33 +40,49 @@ mod tests {
         assert!(!is_strong_password("Passw0rd")); // No special character
         assert!(!is_strong_password("Pass!ord")); // No digit
         assert!(!is_strong_password("Pa1!")); // Too short
+        assert!(!is_strong_password("Ää1!Ää1")); // Too short, although longer than 8 bytes
+        assert!(!is_strong_password("P@ssw0rd123")); // Common password
     }
 
     #[test]
     fn test_format_send_date() {
-        let now = Local::now().naive_local();
+        let now = Utc::now();
 
         // Test same day
         let same_day = now;
         assert_eq!(
             format_send_date(same_day),
-            same_day.format("%I:%M %p").to_string()
+            same_day
+                .with_timezone(&Local)
+                .format("%I:%M %p")
+                .to_string()
         );
 
-        // Test same year but different day
-        let same_year =
-            NaiveDateTime::parse_from_str("2024-05-24 15:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        // Test same year but different day: yesterday, or tomorrow on the first day of the year
+        let same_year = if now.with_timezone(&Local).ordinal() > 1 {
+            now - Duration::days(1)
+        } else {
+            now + Duration::days(1)
+        };
         assert_eq!(
             format_send_date(same_year),
-            same_year.format("%A %b %e, %I:%M %p").to_string()
+            same_year
+                .with_timezone(&Local)
+                .format("%A %b %e, %I:%M %p")
+                .to_string()
         );
 
         // Test different year
         let different_year =
-            NaiveDateTime::parse_from_str("2023-05-24 15:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+            NaiveDateTime::parse_from_str("2023-05-24 15:30:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap()
+                .and_utc();
         assert_eq!(
             format_send_date(different_year),
-            different_year.format("%A %b %e, %Y %I:%M %p").to_string()
+            different_year
+                .with_timezone(&Local)
+                .format("%A %b %e, %Y %I:%M %p")
+                .to_string()
         );
     }
 }
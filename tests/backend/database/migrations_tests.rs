@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use secse24_group08::backend::database::migrations::{
+        check_schema, compare_schema, expected_schema, SchemaColumn, MIGRATIONS,
+    };
+    use std::fs;
+
+    use diesel::migration::MigrationSource;
+    use diesel::pg::Pg;
+
+    fn column(
+        table_name: &str,
+        column_name: &str,
+        data_type: &str,
+        nullable: bool,
+    ) -> SchemaColumn {
+        SchemaColumn {
+            table_name: table_name.to_string(),
+            column_name: column_name.to_string(),
+            data_type: data_type.to_string(),
+            nullable,
+        }
+    }
+
+    #[test]
+    fn test_expected_schema() {
+        let schema = expected_schema();
+        assert!(schema.contains(&column("users", "disabled_at", "timestamptz", true)));
+        assert!(schema.contains(&column("users", "email", "text", false)));
+        assert!(schema.contains(&column("user_to_groups", "accepted_invite", "bool", false)));
+        assert!(schema.contains(&column(
+            "notification_settings",
+            "muted_user_ids",
+            "_int4",
+            false
+        )));
+        assert!(schema.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn test_compare_schema() {
+        let expected = vec![
+            column("users", "id", "int4", false),
+            column("users", "email", "text", false),
+            column("users", "disabled_at", "timestamp", true),
+        ];
+        assert!(compare_schema(&expected, &expected).is_empty());
+
+        let actual = vec![
+            column("users", "id", "int4", false),
+            column("users", "email", "text", true),
+            column("users", "nickname", "text", true),
+        ];
+        assert_eq!(
+            compare_schema(&expected, &actual),
+            vec![
+                "Column users.email is text NULL in the database, expected text NOT NULL",
+                "Column users.disabled_at is missing in the database",
+                "Column users.nickname is unknown to the application",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_all_migrations_embedded() {
+        let migrations = MigrationSource::<Pg>::migrations(&MIGRATIONS).unwrap();
+        let directories = fs::read_dir("migrations")
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().path().join("up.sql").exists())
+            .count();
+        assert_eq!(migrations.len(), directories);
+    }
+
+    #[test]
+    fn test_check_schema() {
+        assert_eq!(check_schema(), Ok(()));
+    }
+}
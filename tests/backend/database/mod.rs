@@ -1,2 +1,3 @@
 pub mod db_tests;
+pub mod migrations_tests;
 pub mod models_tests;
@@ -0,0 +1,184 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use chrono::{Duration, NaiveDate, TimeZone, Utc};
+    use secse24_group08::backend::{
+        crypto::key_store::KeyStore,
+        entities::{
+            export_ops::{
+                default_export_path, export_direct_chat, save_chat_export, ChatExport, ChatKind,
+                ExportFormat, ExportRange, ExportedMessage, ExportedParticipant,
+            },
+            user_message_ops::create_user_message,
+            user_ops::{create_user, delete_user, find_user_by_email},
+        },
+    };
+
+    fn sample_export() -> ChatExport {
+        let sent_at = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+
+        ChatExport {
+            kind: ChatKind::Direct,
+            chat_id: 2,
+            chat_name: "Jane Doe".to_string(),
+            exported_by: 1,
+            exported_at: sent_at + Duration::days(1),
+            from: None,
+            until: None,
+            participants: vec![
+                ExportedParticipant {
+                    id: 1,
+                    name: "John Doe".to_string(),
+                },
+                ExportedParticipant {
+                    id: 2,
+                    name: "Jane Doe".to_string(),
+                },
+            ],
+            messages: vec![
+                ExportedMessage {
+                    sender_id: 1,
+                    sender_name: "John Doe".to_string(),
+                    sent_at,
+                    edited_at: None,
+                    message: "Hello <b>Jane</b> & co".to_string(),
+                },
+                ExportedMessage {
+                    sender_id: 2,
+                    sender_name: "Jane Doe".to_string(),
+                    sent_at: sent_at + Duration::minutes(5),
+                    edited_at: Some(sent_at + Duration::minutes(6)),
+                    message: "First line\nSecond line".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json = sample_export().render(ExportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["kind"], "direct");
+        assert_eq!(value["participants"][1]["name"], "Jane Doe");
+        assert_eq!(value["messages"][0]["sender_id"], 1);
+        assert_eq!(value["messages"][0]["sender_name"], "John Doe");
+        assert_eq!(value["messages"][1]["message"], "First line\nSecond line");
+        assert!(value["messages"][0]["edited_at"].is_null());
+    }
+
+    #[test]
+    fn test_render_html() {
+        let html = sample_export().render(ExportFormat::Html).unwrap();
+
+        // The transcript is a single document with its styles inline
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(!html.contains("<link"));
+
+        // Messages are escaped, so they cannot change the document
+        assert!(html.contains("Hello &lt;b&gt;Jane&lt;/b&gt; &amp; co"));
+        assert!(!html.contains("<b>Jane</b>"));
+        assert!(html.contains("First line<br>Second line"));
+        assert_eq!(html.matches("class=\"message own\"").count(), 1);
+    }
+
+    #[test]
+    fn test_render_text() {
+        let text = sample_export().render(ExportFormat::Text).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        let first = lines
+            .iter()
+            .position(|line| line.ends_with("John Doe: Hello <b>Jane</b> & co"))
+            .unwrap();
+        assert!(lines[first].starts_with('['));
+        assert!(lines[first + 1].contains("Jane Doe"));
+        assert!(lines[first + 1].ends_with(": First line"));
+        assert_eq!(lines[first + 2], "    Second line");
+    }
+
+    #[test]
+    fn test_export_range() {
+        let range = ExportRange::from_dates(
+            NaiveDate::from_ymd_opt(2024, 5, 1),
+            NaiveDate::from_ymd_opt(2024, 5, 2),
+        )
+        .unwrap();
+        let from = range.from.unwrap();
+        let until = range.until.unwrap();
+
+        // Both days are included
+        assert_eq!(until - from, Duration::days(2));
+        assert!(range.contains(from));
+        assert!(range.contains(until - Duration::seconds(1)));
+        assert!(!range.contains(until));
+        assert!(!range.contains(from - Duration::seconds(1)));
+
+        // Open ends contain all messages before or after
+        assert!(ExportRange::default().contains(Utc::now()));
+
+        assert!(ExportRange::from_dates(
+            NaiveDate::from_ymd_opt(2024, 5, 2),
+            NaiveDate::from_ymd_opt(2024, 5, 1),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_export_direct_chat() {
+        let key_store = KeyStore::new(env::temp_dir().join("secse24_group08_export_keys"));
+        for email in ["john.export@example.com", "jane.export@example.com"] {
+            if let Ok(user) = find_user_by_email(email) {
+                let _ = delete_user(user.id);
+            }
+        }
+        let john = create_user("John", "Export", "john.export@example.com", "Password1!").unwrap();
+        let jane = create_user("Jane", "Export", "jane.export@example.com", "Password1!").unwrap();
+
+        assert!(create_user_message(john.id, jane.id, "Hi Jane", None, None).is_ok());
+        assert!(create_user_message(jane.id, john.id, "Hi John", None, None).is_ok());
+
+        let export =
+            export_direct_chat(&key_store, john.id, jane.id, ExportRange::default()).unwrap();
+        assert_eq!(export.kind, ChatKind::Direct);
+        assert_eq!(export.chat_id, jane.id);
+        assert_eq!(export.chat_name, "Jane Export");
+        assert_eq!(export.participants.len(), 2);
+        assert_eq!(export.messages.len(), 2);
+        assert_eq!(export.messages[0].sender_name, "John Export");
+        assert_eq!(export.messages[0].message, "Hi Jane");
+        assert_eq!(export.messages[1].sender_id, jane.id);
+        assert_eq!(export.messages[1].message, "Hi John");
+
+        // Only the messages in the range are exported
+        let range = ExportRange {
+            from: Some(Utc::now() + Duration::days(1)),
+            until: None,
+        };
+        let export_in_range = export_direct_chat(&key_store, john.id, jane.id, range).unwrap();
+        assert!(export_in_range.messages.is_empty());
+
+        // The export is written in the chosen format
+        let path = env::temp_dir()
+            .join("secse24_group08_export_test")
+            .join("chat.txt");
+        let _ = fs::remove_file(&path);
+        assert!(save_chat_export(&export, ExportFormat::Text, &path).is_ok());
+        assert!(fs::read_to_string(&path).unwrap().contains("Hi John"));
+
+        // Clean up
+        assert!(delete_user(john.id).is_ok());
+        assert!(delete_user(jane.id).is_ok());
+    }
+
+    #[test]
+    fn test_default_export_path() {
+        let path = default_export_path("Jane Doe/..", ExportFormat::Html);
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+
+        assert!(file_name.starts_with("chat-Jane_Doe___-"));
+        assert!(file_name.ends_with(".html"));
+    }
+}
@@ -0,0 +1 @@
+pub mod export_ops_tests;
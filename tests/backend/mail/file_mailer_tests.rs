@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use secse24_group08::backend::mail::{file_mailer::FileMailer, mailer::Mailer};
+
+    #[test]
+    fn test_file_mailer_appends_mail() {
+        let path = env::temp_dir().join("secse24_group08_file_mailer_test.log");
+        let _ = fs::remove_file(&path);
+
+        let mailer = FileMailer::new(&path);
+        assert!(mailer
+            .send("test@example.com", "Subject 1", "Body 1")
+            .is_ok());
+        assert!(mailer
+            .send("test@example.com", "Subject 2", "Body 2")
+            .is_ok());
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("To: test@example.com\nSubject: Subject 1\n\nBody 1"));
+        assert!(content.contains("To: test@example.com\nSubject: Subject 2\n\nBody 2"));
+
+        // Clean up
+        let _ = fs::remove_file(&path);
+    }
+}
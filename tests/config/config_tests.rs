@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use secse24_group08::config::{loader::load_layers, Config, PasswordPolicy};
+
+    fn flags(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_defaults_are_valid() {
+        assert!(Config::default().validate().is_empty());
+        assert_eq!(
+            load_layers(None, &|_| None, &HashMap::new()).unwrap(),
+            Config::default()
+        );
+    }
+
+    #[test]
+    fn test_load_layers_precedence() {
+        let file = "log_level = \"info\"\n\
+                    [database]\n\
+                    url = \"postgres://file/chat\"\n\
+                    pool_size = 4\n\
+                    [polling]\n\
+                    home_secs = 30\n";
+        let env = |name: &str| match name {
+            "DATABASE_URL" => Some("postgres://env/chat".to_string()),
+            "DATABASE_POOL_SIZE" => Some("6".to_string()),
+            _ => None,
+        };
+
+        let config = load_layers(
+            Some(("config.toml", file)),
+            &env,
+            &flags(&[("--pool-size", "8")]),
+        )
+        .unwrap();
+
+        assert_eq!(config.log_level, "info");
+        assert_eq!(config.database.url, "postgres://env/chat");
+        assert_eq!(config.database.pool_size, 8);
+        assert_eq!(config.polling.home_secs, 30);
+        assert_eq!(config.polling.chat_secs, 5);
+    }
+
+    #[test]
+    fn test_load_layers_invalid_values() {
+        let env = |name: &str| match name {
+            "ARGON2_ITERATIONS" => Some("high".to_string()),
+            "PASSWORD_REQUIRE_DIGIT" => Some("maybe".to_string()),
+            "NOTIFICATION_TRANSPORT" => Some("email".to_string()),
+            _ => None,
+        };
+
+        let err = load_layers(
+            Some(("config.toml", "[polling]\nchat_secs = 0\n")),
+            &env,
+            &flags(&[("--pool-size", "1"), ("--database-url", "mysql://db")]),
+        )
+        .unwrap_err();
+
+        assert!(err.starts_with("Invalid configuration:"));
+        assert!(err.contains(
+            "security.argon2_iterations (environment variable ARGON2_ITERATIONS): \"high\" is not a whole number"
+        ));
+        assert!(err.contains(
+            "password_policy.require_digit (environment variable PASSWORD_REQUIRE_DIGIT): \"maybe\" is not true or false"
+        ));
+        assert!(err.contains("database.pool_size (flag --pool-size): 1 is not between 2 and 100"));
+        assert!(err.contains(
+            "database.url (flag --database-url): must start with postgres:// or postgresql://"
+        ));
+        assert!(err.contains("polling.chat_secs (config file config.toml): must be at least 1"));
+        assert!(err.contains(
+            "notifications.transport (environment variable NOTIFICATION_TRANSPORT): \"email\" is not a notification transport, use desktop or stdout"
+        ));
+    }
+
+    #[test]
+    fn test_load_layers_unknown_key() {
+        let err = load_layers(
+            Some(("config.toml", "[database]\nhost = \"localhost\"\n")),
+            &|_| None,
+            &HashMap::new(),
+        )
+        .unwrap_err();
+
+        assert!(err.contains("config file config.toml"));
+        assert!(err.contains("host"));
+    }
+
+    #[test]
+    fn test_password_policy() {
+        let policy = PasswordPolicy::default();
+        assert!(policy.is_satisfied_by("Tr0mb0ne!Lamp"));
+        assert!(!policy.is_satisfied_by("Tr0m!"));
+        assert!(!policy.is_satisfied_by("tr0mb0ne!lamp"));
+        assert_eq!(
+            policy.check("Password1!").unwrap_err(),
+            "This password is too common, choose a less predictable one"
+        );
+        assert_eq!(
+            policy.describe(),
+            "Password must be at least 8 characters long and contain at least one uppercase letter, \
+             one lowercase letter, one digit, and one special character"
+        );
+
+        let policy = PasswordPolicy {
+            min_length: 12,
+            require_uppercase: false,
+            require_lowercase: true,
+            require_digit: true,
+            require_special: false,
+            ..PasswordPolicy::default()
+        };
+        assert!(policy.is_satisfied_by("longpassword1"));
+        assert!(!policy.is_satisfied_by("longpassword"));
+        assert_eq!(
+            policy.describe(),
+            "Password must be at least 12 characters long and contain at least one lowercase letter and one digit"
+        );
+
+        let policy = PasswordPolicy {
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_special: false,
+            reject_common: false,
+            ..PasswordPolicy::default()
+        };
+        assert!(policy.is_satisfied_by("abcdefgh"));
+        assert!(policy.is_satisfied_by("password"));
+        assert_eq!(
+            policy.describe(),
+            "Password must be at least 8 characters long"
+        );
+    }
+}
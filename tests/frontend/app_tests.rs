@@ -1,30 +1,60 @@
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use crate::frontend::command_runner::{run, run_command};
+    use std::{env, fs, thread, time::Duration};
 
-    use iced::{time, Application, Subscription};
+    use iced::{
+        keyboard::{key::Named, Key, Modifiers},
+        time, Application, Subscription,
+    };
     use secse24_group08::{
         backend::{
             database::models::{Group, User},
-            entities::user_ops::create_user,
+            entities::{
+                password_reset_ops::{create_password_reset, reset_password},
+                session_ops::create_session,
+                user_ops::{create_user, delete_user},
+            },
         },
         frontend::{
-            app::{App, Message, Page},
+            app::{App, AppFlags, Message, Page},
+            chat_layout::{ChatId, ChatLayoutMessage},
             group_chat::GroupChatMessage,
             home::{HomeMessage, TabId},
             login::LoginMessage,
+            preferences::PreferencesStore,
+            quick_switcher::QuickSwitcherMessage,
             register::RegisterMessage,
+            reset_password::ResetPasswordMessage,
+            session_store::SessionStore,
             tabs_home::{
                 group_tab::GroupTabMessage,
                 setting_tab::{AppTheme, SettingsTabMessage},
                 user_tab::UserTabMessage,
             },
+            theme::high_contrast_theme,
             user_chat::UserChatMessage,
         },
     };
 
     fn setup_app() -> (App, iced::Command<Message>) {
-        App::new(())
+        App::new(app_flags(SessionStore::new(
+            env::temp_dir().join("secse24_group08_app_tests_session"),
+        )))
+    }
+
+    // Every test uses its own preferences file that starts empty, so the tests do not change each other's preferences
+    fn app_flags(session_store: SessionStore) -> AppFlags {
+        let path = env::temp_dir().join(format!(
+            "secse24_group08_app_tests_preferences_{:?}.toml",
+            thread::current().id()
+        ));
+        let _ = fs::remove_file(&path);
+
+        AppFlags {
+            session_store,
+            preferences_store: PreferencesStore::new(path),
+        }
     }
 
     fn test_create_user(id: i32, first_name: &str, last_name: &str) -> User {
@@ -45,42 +75,196 @@ mod tests {
     #[test]
     fn test_switch_page() {
         let (mut app, _cmd) = setup_app();
-        let _ = app.update(Message::SwitchPage(Page::Register));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Register),
+        );
         assert_eq!(app.get_current_page(), Page::Register);
 
-        let _ = app.update(Message::SwitchPage(Page::Home));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Home),
+        );
         assert_eq!(app.get_current_page(), Page::Home);
     }
 
     #[test]
     fn test_handle_login_message_navigate_to_register() {
         let (mut app, _cmd) = setup_app();
-        let _ = app.update(Message::LoginMessage(LoginMessage::NavigateToRegister));
+        run(
+            |message| app.update(message),
+            Message::LoginMessage(LoginMessage::NavigateToRegister),
+        );
         assert_eq!(app.get_current_page(), Page::Register);
     }
 
     #[test]
-    fn test_handle_login_message_submit_login() {
+    fn test_handle_login_message_login_success() {
         let (mut app, _cmd) = setup_app();
 
-        app.set_current_user(test_create_user(1, "John", "Doe"));
-        let _ = app.update(Message::LoginMessage(LoginMessage::SubmitLogin));
+        run(
+            |message| app.update(message),
+            Message::LoginMessage(LoginMessage::LoginSuccess(test_create_user(
+                1, "John", "Doe",
+            ))),
+        );
 
         assert_eq!(app.get_current_page(), Page::Home);
         assert!(app.get_current_user().is_some());
     }
 
+    #[test]
+    fn test_handle_login_message_forgot_password() {
+        let (mut app, _cmd) = setup_app();
+        run(
+            |message| app.update(message),
+            Message::LoginMessage(LoginMessage::EmailChanged("test1@email.de".to_string())),
+        );
+        run(
+            |message| app.update(message),
+            Message::LoginMessage(LoginMessage::ForgotPassword),
+        );
+
+        assert_eq!(app.get_current_page(), Page::ResetPassword);
+        assert_eq!(app.get_reset_password().get_email(), "test1@email.de");
+
+        run(
+            |message| app.update(message),
+            Message::ResetPasswordMessage(ResetPasswordMessage::NavigateToLogin),
+        );
+        assert_eq!(app.get_current_page(), Page::Login);
+    }
+
+    #[test]
+    fn test_revoked_session_logs_out() {
+        let email = "app_revoked@email.de";
+        let user = create_user("John", "Doe", email, "OldP@ssw0rd").unwrap();
+
+        let (mut app, _cmd) = setup_app();
+        app.set_current_user(user.clone());
+        let (session, token) = create_session(user.id, "app_tests").unwrap();
+        app.set_session(session, token);
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Home),
+        );
+
+        let (_, token) = create_password_reset(user.id).unwrap();
+        assert!(reset_password(&token, "NewP@ssw0rd").is_ok());
+
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::Tick),
+        );
+        assert_eq!(app.get_current_page(), Page::Login);
+        assert!(app.get_current_user().is_none());
+        assert_eq!(
+            app.get_login().get_error(),
+            "Your session has ended. Please log in again."
+        );
+
+        // Clean up
+        assert!(delete_user(user.id).is_ok());
+    }
+
+    #[test]
+    fn test_connection_lost_and_back() {
+        let (mut app, _cmd) = setup_app();
+        app.set_current_user(test_create_user(1, "John", "Doe"));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Home),
+        );
+
+        run(
+            |message| app.update(message),
+            Message::ConnectionChecked(false),
+        );
+        assert!(app.get_offline());
+        assert!(app.get_home().get_offline());
+
+        // Without a session, e.g. after an offline login, the user has to log in again
+        run(
+            |message| app.update(message),
+            Message::ConnectionChecked(true),
+        );
+        assert!(!app.get_offline());
+        assert_eq!(app.get_current_page(), Page::Login);
+        assert_eq!(
+            app.get_login().get_error(),
+            "You are back online. Please log in again."
+        );
+    }
+
+    #[test]
+    fn test_remember_me_restores_session() {
+        let session_store =
+            SessionStore::new(env::temp_dir().join("secse24_group08_app_tests_remember_me"));
+        assert!(session_store.clear().is_ok());
+
+        let (mut app, _cmd) = App::new(app_flags(session_store.clone()));
+        run(
+            |message| app.update(message),
+            Message::LoginMessage(LoginMessage::EmailChanged("test1@email.de".to_string())),
+        );
+        run(
+            |message| app.update(message),
+            Message::LoginMessage(LoginMessage::PasswordChanged(
+                "n)+L8ZVWw$qKXDQo".to_string(),
+            )),
+        );
+        run(
+            |message| app.update(message),
+            Message::LoginMessage(LoginMessage::RememberMeToggled(true)),
+        );
+        run(
+            |message| app.update(message),
+            Message::LoginMessage(LoginMessage::SubmitLogin),
+        );
+        assert_eq!(app.get_current_page(), Page::Home);
+        assert!(session_store.load().is_some());
+        assert!(session_store.load_cache_key().is_some());
+        // The password is not kept in memory after the login
+        assert_eq!(app.get_login().get_password(), "");
+
+        // The stored session logs the user in at startup and unlocks the cache without the password
+        let (mut restored_app, command) = App::new(app_flags(session_store.clone()));
+        run_command(|message| restored_app.update(message), command);
+        assert_eq!(restored_app.get_current_page(), Page::Home);
+        assert_eq!(restored_app.get_current_user().unwrap().id, 1);
+        assert!(restored_app.is_cache_unlocked());
+
+        // Logging out revokes the session and removes it from the device
+        run(
+            |message| restored_app.update(message),
+            Message::HomeMessage(HomeMessage::NavigateToLogin),
+        );
+        assert!(session_store.load().is_none());
+        assert!(session_store.load_cache_key().is_none());
+
+        let (mut app, command) = App::new(app_flags(session_store.clone()));
+        run_command(|message| app.update(message), command);
+        assert_eq!(app.get_current_page(), Page::Login);
+        assert!(app.get_current_user().is_none());
+    }
+
     #[test]
     fn test_handle_register_message_navigate_to_login() {
         let (mut app, _cmd) = setup_app();
-        let _ = app.update(Message::RegisterMessage(RegisterMessage::NavigateToLogin));
+        run(
+            |message| app.update(message),
+            Message::RegisterMessage(RegisterMessage::NavigateToLogin),
+        );
         assert_eq!(app.get_current_page(), Page::Login);
     }
 
     #[test]
     fn test_handle_home_message_navigate_to_login() {
         let (mut app, _cmd) = setup_app();
-        let _ = app.update(Message::HomeMessage(HomeMessage::NavigateToLogin));
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::NavigateToLogin),
+        );
         assert_eq!(app.get_current_page(), Page::Login);
         assert!(app.get_current_user().is_none());
     }
@@ -89,9 +273,10 @@ mod tests {
     fn test_handle_home_message_tab_selected_settings() {
         let (mut app, _cmd) = setup_app();
         app.set_current_user(test_create_user(1, "John", "Doe"));
-        let _ = app.update(Message::HomeMessage(HomeMessage::TabSelected(
-            TabId::Settings,
-        )));
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::TabSelected(TabId::Settings)),
+        );
         assert_eq!(app.get_home().active_tab(), TabId::Settings);
     }
 
@@ -102,12 +287,18 @@ mod tests {
         let other_user = test_create_user(2, "Jane", "Doe");
 
         app.set_current_user(current_user.clone());
-        let _ = app.update(Message::HomeMessage(HomeMessage::UserTab(
-            UserTabMessage::SendFriendRequestToSelectedUser,
-        )));
-        let _ = app.update(Message::HomeMessage(HomeMessage::UserTab(
-            UserTabMessage::ChatWithUser(other_user),
-        )));
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::UserTab(
+                UserTabMessage::SendFriendRequestToSelectedUser,
+            )),
+        );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::UserTab(UserTabMessage::ChatWithUser(
+                other_user,
+            ))),
+        );
         app.set_current_user(current_user);
     }
 
@@ -121,12 +312,14 @@ mod tests {
         };
 
         app.set_current_user(current_user);
-        let _ = app.update(Message::HomeMessage(HomeMessage::GroupTab(
-            GroupTabMessage::ChatWithGroup(group),
-        )));
-        let _ = app.update(Message::HomeMessage(HomeMessage::GroupTab(
-            GroupTabMessage::CreateGroup,
-        )));
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::GroupTab(GroupTabMessage::ChatWithGroup(group))),
+        );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::GroupTab(GroupTabMessage::CreateGroup)),
+        );
     }
 
     #[test]
@@ -134,10 +327,22 @@ mod tests {
         let (mut app, _cmd) = setup_app();
         app.set_current_user(test_create_user(1, "John", "Doe"));
 
-        let _ = app.update(Message::SwitchPage(Page::UserChat));
-        let _ = app.update(Message::UserChatMessage(UserChatMessage::Back));
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::UserTab(UserTabMessage::ChatWithUser(
+                test_create_user(2, "Jane", "Doe"),
+            ))),
+        );
+        assert_eq!(app.get_current_page(), Page::Chats);
+
+        run(
+            |message| app.update(message),
+            Message::UserChatMessage(2, UserChatMessage::Back),
+        );
 
+        // The chat stays open in the sidebar
         assert_eq!(app.get_current_page(), Page::Home);
+        assert!(app.get_chat_layout().contains(ChatId::User(2)));
     }
 
     #[test]
@@ -145,29 +350,203 @@ mod tests {
         let (mut app, _cmd) = setup_app();
         app.set_current_user(test_create_user(1, "John", "Doe"));
 
-        let _ = app.update(Message::SwitchPage(Page::GroupChat));
-        let _ = app.update(Message::GroupChatMessage(GroupChatMessage::Back));
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::GroupTab(GroupTabMessage::ChatWithGroup(
+                Group {
+                    id: 1,
+                    name: "Group 1".to_string(),
+                },
+            ))),
+        );
+        run(
+            |message| app.update(message),
+            Message::GroupChatMessage(1, GroupChatMessage::Back),
+        );
+
+        assert_eq!(app.get_current_page(), Page::Home);
+        assert!(app.get_chat_layout().contains(ChatId::Group(1)));
+    }
+
+    #[test]
+    fn test_keyboard_shortcuts() {
+        let (mut app, _cmd) = setup_app();
+        app.set_current_user(test_create_user(1, "John", "Doe"));
+        let ctrl = |key: &str| Message::KeyPressed(Key::Character(key.into()), Modifiers::COMMAND);
+        let escape = || Message::KeyPressed(Key::Named(Named::Escape), Modifiers::empty());
+
+        // Shortcuts do not work before the login
+        run(|message| app.update(message), ctrl("k"));
+        assert!(app.get_quick_switcher().is_none());
 
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Home),
+        );
+        run(|message| app.update(message), ctrl("3"));
+        assert_eq!(app.get_home().get_active_tab(), TabId::Group);
+
+        // The quick switcher opens the chat with the selected friend
+        run(|message| app.update(message), ctrl("k"));
+        assert!(app.get_quick_switcher().is_some());
+        run(
+            |message| app.update(message),
+            Message::QuickSwitcherMessage(QuickSwitcherMessage::QueryChanged("jane".to_string())),
+        );
+        run(
+            |message| app.update(message),
+            Message::QuickSwitcherMessage(QuickSwitcherMessage::OpenSelected),
+        );
+        assert!(app.get_quick_switcher().is_none());
+        assert_eq!(app.get_current_page(), Page::Chats);
+        assert_eq!(app.get_chat_layout().focused(), Some(ChatId::User(2)));
+
+        // Escape closes the search before it leaves the chat
+        run(|message| app.update(message), ctrl("f"));
+        let chat = app.get_chat_layout().user_chat(ChatId::User(2)).unwrap();
+        assert!(chat.is_searching());
+        run(|message| app.update(message), escape());
+        let chat = app.get_chat_layout().user_chat(ChatId::User(2)).unwrap();
+        assert!(!chat.is_searching());
+        assert_eq!(app.get_current_page(), Page::Chats);
+
+        run(|message| app.update(message), escape());
+        assert_eq!(app.get_current_page(), Page::Home);
+
+        run(|message| app.update(message), ctrl("1"));
+        assert_eq!(app.get_home().get_active_tab(), TabId::Chats);
+    }
+
+    #[test]
+    fn test_chats_keep_their_state() {
+        let (mut app, _cmd) = setup_app();
+        app.set_current_user(test_create_user(1, "John", "Doe"));
+        let group = Group {
+            id: 1,
+            name: "Group 1".to_string(),
+        };
+
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::UserTab(UserTabMessage::ChatWithUser(
+                test_create_user(2, "Jane", "Doe"),
+            ))),
+        );
+        run(
+            |message| app.update(message),
+            Message::UserChatMessage(2, UserChatMessage::InputChanged("Draft".to_string())),
+        );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::GroupTab(GroupTabMessage::ChatWithGroup(
+                group.clone(),
+            ))),
+        );
+
+        // Both chats are open, the group chat is focused and the draft is kept
+        let chat_layout = app.get_chat_layout();
+        assert_eq!(chat_layout.chats().len(), 2);
+        assert_eq!(chat_layout.focused(), Some(ChatId::Group(1)));
+        assert_eq!(
+            chat_layout
+                .user_chat(ChatId::User(2))
+                .unwrap()
+                .get_input_value(),
+            "Draft"
+        );
+
+        // Show the user chat beside the group chat
+        run(
+            |message| app.update(message),
+            Message::ChatLayoutMessage(ChatLayoutMessage::OpenBeside(ChatId::User(2))),
+        );
+        assert_eq!(
+            app.get_chat_layout().visible(),
+            vec![ChatId::Group(1), ChatId::User(2)]
+        );
+
+        // Closing the focused chat focuses the chat beside it
+        run(
+            |message| app.update(message),
+            Message::ChatLayoutMessage(ChatLayoutMessage::Close(ChatId::Group(1))),
+        );
+        assert_eq!(app.get_chat_layout().visible(), vec![ChatId::User(2)]);
+        assert_eq!(app.get_current_page(), Page::Chats);
+
+        // Closing the last chat shows the home page
+        run(
+            |message| app.update(message),
+            Message::ChatLayoutMessage(ChatLayoutMessage::Close(ChatId::User(2))),
+        );
+        assert!(app.get_chat_layout().is_empty());
         assert_eq!(app.get_current_page(), Page::Home);
     }
 
+    #[test]
+    fn test_draft_restored_when_chat_is_reopened() {
+        let (mut app, _cmd) = setup_app();
+        app.set_current_user(test_create_user(1, "John", "Doe"));
+        let open_chat = Message::HomeMessage(HomeMessage::UserTab(UserTabMessage::ChatWithUser(
+            test_create_user(2, "Jane", "Doe"),
+        )));
+
+        run(|message| app.update(message), open_chat.clone());
+        run(
+            |message| app.update(message),
+            Message::UserChatMessage(2, UserChatMessage::InputChanged("Unsent".to_string())),
+        );
+        run(
+            |message| app.update(message),
+            Message::ChatLayoutMessage(ChatLayoutMessage::Close(ChatId::User(2))),
+        );
+        assert_eq!(app.get_drafts().get(ChatId::User(2)), Some("Unsent"));
+
+        // The draft is shown again and marked in the chats tab
+        run(|message| app.update(message), open_chat);
+        assert_eq!(
+            app.get_chat_layout()
+                .user_chat(ChatId::User(2))
+                .unwrap()
+                .get_input_value(),
+            "Unsent"
+        );
+        assert!(app
+            .get_home()
+            .get_chat_tab()
+            .get_drafts()
+            .contains(ChatId::User(2)));
+
+        // Clearing the input removes the draft
+        run(
+            |message| app.update(message),
+            Message::UserChatMessage(2, UserChatMessage::InputChanged(String::new())),
+        );
+        assert!(!app.get_drafts().contains(ChatId::User(2)));
+    }
+
     #[test]
     fn test_handle_settings_tab_message_delete_account_positive() {
         let (mut app, _cmd) = setup_app();
 
-        let _ = app.update(Message::SwitchPage(Page::Home));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Home),
+        );
         let user = create_user("John", "Doe", "app@email.de", "password").unwrap();
 
         app.set_current_user(user);
-        let _ = app.update(Message::HomeMessage(HomeMessage::TabSelected(
-            TabId::Settings,
-        )));
-        let _ = app.update(Message::HomeMessage(HomeMessage::SettingsTab(
-            SettingsTabMessage::DeleteAccount,
-        )));
-        let _ = app.update(Message::HomeMessage(HomeMessage::SettingsTab(
-            SettingsTabMessage::DeleteAccount,
-        )));
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::TabSelected(TabId::Settings)),
+        );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::SettingsTab(SettingsTabMessage::DeleteAccount)),
+        );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::SettingsTab(SettingsTabMessage::DeleteAccount)),
+        );
 
         assert_eq!(app.get_current_page(), Page::Login);
         assert!(app.get_current_user().is_none());
@@ -177,14 +556,19 @@ mod tests {
     fn test_handle_settings_tab_message_delete_account_negative() {
         let (mut app, _cmd) = setup_app();
 
-        let _ = app.update(Message::SwitchPage(Page::Home));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Home),
+        );
         app.set_current_user(test_create_user(1, "John", "Doe"));
-        let _ = app.update(Message::HomeMessage(HomeMessage::TabSelected(
-            TabId::Settings,
-        )));
-        let _ = app.update(Message::HomeMessage(HomeMessage::SettingsTab(
-            SettingsTabMessage::DeleteAccount,
-        )));
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::TabSelected(TabId::Settings)),
+        );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::SettingsTab(SettingsTabMessage::DeleteAccount)),
+        );
 
         assert_eq!(app.get_current_page(), Page::Home);
         assert!(app.get_current_user().is_some());
@@ -195,43 +579,106 @@ mod tests {
         let (mut app, _cmd) = setup_app();
 
         app.set_current_user(test_create_user(1, "John", "Doe"));
-        let _ = app.update(Message::SwitchPage(Page::Home));
-        let _ = app.update(Message::HomeMessage(HomeMessage::TabSelected(
-            TabId::Settings,
-        )));
-        let _ = app.update(Message::HomeMessage(HomeMessage::SettingsTab(
-            SettingsTabMessage::ChangeTheme(AppTheme::Moonfly),
-        )));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Home),
+        );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::TabSelected(TabId::Settings)),
+        );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::SettingsTab(SettingsTabMessage::ChangeTheme(
+                AppTheme::Moonfly,
+            ))),
+        );
 
         assert_eq!(
             app.get_app_theme(),
             app.get_home().get_settings_tab().get_app_theme()
         );
+
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::SettingsTab(SettingsTabMessage::ChangeTheme(
+                AppTheme::HighContrast,
+            ))),
+        );
+        assert_eq!(app.theme().palette(), high_contrast_theme().palette());
+        assert_eq!(app.get_preferences().theme, AppTheme::HighContrast);
+
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::SettingsTab(
+                SettingsTabMessage::ChangeFontScale(1.5),
+            )),
+        );
+        assert_eq!(app.get_preferences().font_scale, 1.5);
+        assert_eq!(app.scale_factor(), 1.5);
     }
 
     #[test]
-    fn test_subscription_home_page() {
-        let (mut app, _cmd) = setup_app();
-        let _ = app.update(Message::SwitchPage(Page::Home));
-        let subscription = app.subscription();
-
-        let expected_subscription =
-            time::every(Duration::from_secs(10)).map(|_| Message::HomeMessage(HomeMessage::Tick));
+    fn test_preferences_are_restored_after_restart() {
+        let session_store =
+            SessionStore::new(env::temp_dir().join("secse24_group08_app_tests_session"));
+        let flags = app_flags(session_store.clone());
+        let preferences_store = flags.preferences_store.clone();
+        let (mut app, _cmd) = App::new(flags);
 
-        assert_eq!(
-            format!("{:?}", subscription),
-            format!("{:?}", expected_subscription)
+        app.set_current_user(test_create_user(1, "John", "Doe"));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Home),
+        );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::TabSelected(TabId::Settings)),
+        );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::SettingsTab(
+                SettingsTabMessage::NotificationPreviewToggled(false),
+            )),
         );
+        run(
+            |message| app.update(message),
+            Message::HomeMessage(HomeMessage::SettingsTab(SettingsTabMessage::ChangeTheme(
+                AppTheme::Dracula,
+            ))),
+        );
+        run(
+            |message| app.update(message),
+            Message::WindowResized(1024, 768),
+        );
+        assert!(!app.get_preferences().notifications.show_preview);
+
+        // The window size is stored when the window is closed
+        run(|message| app.update(message), Message::CloseRequested);
+
+        let (restored_app, _cmd) = App::new(AppFlags {
+            session_store,
+            preferences_store,
+        });
+        let preferences = restored_app.get_preferences();
+        assert_eq!(restored_app.get_app_theme(), AppTheme::Dracula);
+        assert_eq!(preferences.last_tab, TabId::Settings);
+        assert!(!preferences.notifications.show_preview);
+        assert_eq!(preferences.window.width, 1024.0);
+        assert_eq!(preferences.window.height, 768.0);
     }
 
     #[test]
-    fn test_subscription_user_chat_page() {
+    fn test_subscription_home_page() {
         let (mut app, _cmd) = setup_app();
-        let _ = app.update(Message::SwitchPage(Page::UserChat));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Home),
+        );
         let subscription = app.subscription();
 
-        let expected_subscription = time::every(Duration::from_secs(5))
-            .map(|_| Message::UserChatMessage(UserChatMessage::Tick));
+        let expected_subscription =
+            time::every(Duration::from_secs(10)).map(|_| Message::HomeMessage(HomeMessage::Tick));
 
         assert_eq!(
             format!("{:?}", subscription),
@@ -240,13 +687,16 @@ mod tests {
     }
 
     #[test]
-    fn test_subscription_group_chat_page() {
+    fn test_subscription_chats_page() {
         let (mut app, _cmd) = setup_app();
-        let _ = app.update(Message::SwitchPage(Page::GroupChat));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Chats),
+        );
         let subscription = app.subscription();
 
         let expected_subscription = time::every(Duration::from_secs(5))
-            .map(|_| Message::GroupChatMessage(GroupChatMessage::Tick));
+            .map(|_| Message::ChatLayoutMessage(ChatLayoutMessage::Tick));
 
         assert_eq!(
             format!("{:?}", subscription),
@@ -257,7 +707,10 @@ mod tests {
     #[test]
     fn test_subscription_login_page() {
         let (mut app, _cmd) = setup_app();
-        let _ = app.update(Message::SwitchPage(Page::Login));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Login),
+        );
         let subscription = app.subscription();
 
         let expected_subscription: Subscription<Message> = Subscription::none();
@@ -271,7 +724,10 @@ mod tests {
     #[test]
     fn test_subscription_register_page() {
         let (mut app, _cmd) = setup_app();
-        let _ = app.update(Message::SwitchPage(Page::Register));
+        run(
+            |message| app.update(message),
+            Message::SwitchPage(Page::Register),
+        );
         let subscription = app.subscription();
 
         let expected_subscription: Subscription<Message> = Subscription::none();
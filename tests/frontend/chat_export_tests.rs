@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use secse24_group08::{
+        backend::entities::export_ops::ExportFormat,
+        frontend::chat_export::{ChatExportForm, ChatExportMessage, ChatExportTarget},
+    };
+
+    fn setup_form() -> ChatExportForm {
+        ChatExportForm::new(
+            ChatExportTarget::Direct {
+                user_id: 1,
+                other_user_id: 2,
+            },
+            "Jane Doe",
+        )
+    }
+
+    #[test]
+    fn test_select_format() {
+        let mut form = setup_form();
+        assert_eq!(form.get_format(), ExportFormat::Json);
+        assert!(form.get_path_value().ends_with(".json"));
+
+        // The extension follows the format
+        let _ = form.update(ChatExportMessage::FormatSelected(ExportFormat::Html));
+        assert_eq!(form.get_format(), ExportFormat::Html);
+        assert!(form.get_path_value().ends_with(".html"));
+
+        // A path without an extension gets one, another extension is kept
+        let _ = form.update(ChatExportMessage::PathChanged("/tmp/chat".to_string()));
+        let _ = form.update(ChatExportMessage::FormatSelected(ExportFormat::Text));
+        assert_eq!(form.get_path_value(), "/tmp/chat.txt");
+
+        let _ = form.update(ChatExportMessage::PathChanged("/tmp/chat.log".to_string()));
+        let _ = form.update(ChatExportMessage::FormatSelected(ExportFormat::Json));
+        assert_eq!(form.get_path_value(), "/tmp/chat.log");
+    }
+
+    #[test]
+    fn test_range() {
+        let mut form = setup_form();
+        assert!(form.range().unwrap().from.is_none());
+
+        let _ = form.update(ChatExportMessage::FromChanged("2024-05-01".to_string()));
+        let _ = form.update(ChatExportMessage::ToChanged("2024-05-03".to_string()));
+        let range = form.range().unwrap();
+        assert_eq!(
+            range.until.unwrap() - range.from.unwrap(),
+            Duration::days(3)
+        );
+
+        let _ = form.update(ChatExportMessage::ToChanged("2024-04-30".to_string()));
+        assert!(form.range().is_err());
+    }
+
+    #[test]
+    fn test_export_with_invalid_input() {
+        let mut form = setup_form();
+
+        // An invalid date is reported without exporting
+        let _ = form.update(ChatExportMessage::FromChanged("01.05.2024".to_string()));
+        let _ = form.update(ChatExportMessage::Export);
+        assert!(!form.get_error().is_empty());
+        assert!(!form.is_exporting());
+
+        // So is a missing file
+        let _ = form.update(ChatExportMessage::FromChanged(String::new()));
+        let _ = form.update(ChatExportMessage::PathChanged(" ".to_string()));
+        let _ = form.update(ChatExportMessage::Export);
+        assert!(!form.get_error().is_empty());
+        assert!(!form.is_exporting());
+    }
+
+    #[test]
+    fn test_exported() {
+        let mut form = setup_form();
+
+        let _ = form.update(ChatExportMessage::Exported(
+            Ok("/tmp/chat.json".to_string()),
+        ));
+        assert!(form.get_info().contains("/tmp/chat.json"));
+        assert!(form.get_error().is_empty());
+
+        let _ = form.update(ChatExportMessage::Exported(Err("disk full".to_string())));
+        assert!(form.get_error().contains("disk full"));
+        assert!(!form.is_exporting());
+    }
+}
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use secse24_group08::{
+        backend::database::models::{Group, User},
+        frontend::{
+            chat_layout::{ChatId, ChatLayout},
+            user_chat::UserChatMessage,
+        },
+    };
+
+    fn create_user(id: i32, first_name: &str) -> User {
+        User {
+            id,
+            first_name: first_name.to_string(),
+            last_name: "Doe".to_string(),
+        }
+    }
+
+    fn setup_chat_layout() -> ChatLayout {
+        let mut chat_layout = ChatLayout::default();
+        chat_layout.open_user_chat(create_user(1, "John"), create_user(2, "Jane"));
+        chat_layout.open_user_chat(create_user(1, "John"), create_user(3, "Jim"));
+        chat_layout.open_group_chat(
+            create_user(1, "John"),
+            Group {
+                id: 1,
+                name: "Group 1".to_string(),
+            },
+        );
+        chat_layout
+    }
+
+    #[test]
+    fn test_open_chats() {
+        let mut chat_layout = setup_chat_layout();
+
+        assert_eq!(chat_layout.chats().len(), 3);
+        assert_eq!(chat_layout.focused(), Some(ChatId::Group(1)));
+        assert_eq!(chat_layout.chats()[0].name(), "Jane Doe");
+
+        // A chat that is already open keeps its state
+        let (chat, opened) =
+            chat_layout.open_user_chat(create_user(1, "John"), create_user(2, "Jane"));
+        let _ = chat.update(UserChatMessage::InputChanged("Draft".to_string()));
+        assert!(!opened);
+        let (chat, opened) =
+            chat_layout.open_user_chat(create_user(1, "John"), create_user(2, "Jane"));
+        assert!(!opened);
+        assert_eq!(chat.get_input_value(), "Draft");
+        assert_eq!(chat_layout.chats().len(), 3);
+        assert_eq!(chat_layout.focused(), Some(ChatId::User(2)));
+    }
+
+    #[test]
+    fn test_split_view() {
+        let mut chat_layout = setup_chat_layout();
+
+        // The focused chat cannot be shown beside itself
+        chat_layout.open_beside(ChatId::Group(1));
+        assert_eq!(chat_layout.split(), None);
+
+        chat_layout.open_beside(ChatId::User(2));
+        assert_eq!(
+            chat_layout.visible(),
+            vec![ChatId::Group(1), ChatId::User(2)]
+        );
+
+        // Focusing the chat beside swaps both chats
+        chat_layout.focus(ChatId::User(2));
+        assert_eq!(
+            chat_layout.visible(),
+            vec![ChatId::User(2), ChatId::Group(1)]
+        );
+
+        chat_layout.close_split();
+        assert_eq!(chat_layout.visible(), vec![ChatId::User(2)]);
+        assert_eq!(chat_layout.chats().len(), 3);
+    }
+
+    #[test]
+    fn test_close_chats() {
+        let mut chat_layout = setup_chat_layout();
+        chat_layout.open_beside(ChatId::User(2));
+
+        // Closing the chat beside keeps the focus
+        chat_layout.close(ChatId::User(2));
+        assert_eq!(chat_layout.visible(), vec![ChatId::Group(1)]);
+
+        // Closing the focused chat focuses the last opened chat
+        chat_layout.close(ChatId::Group(1));
+        assert_eq!(chat_layout.visible(), vec![ChatId::User(3)]);
+
+        chat_layout.close(ChatId::User(3));
+        assert!(chat_layout.is_empty());
+        assert_eq!(chat_layout.focused(), None);
+    }
+
+    #[test]
+    fn test_scroll_position() {
+        let mut chat_layout = setup_chat_layout();
+        let chat = chat_layout.user_chat_mut(ChatId::User(2)).unwrap();
+
+        let _ = chat.update(UserChatMessage::Scrolled(0.25));
+        assert_eq!(chat.get_scroll_offset(), 0.25);
+
+        // All messages fit, the position is kept
+        let _ = chat.update(UserChatMessage::Scrolled(f32::NAN));
+        assert_eq!(chat.get_scroll_offset(), 0.25);
+    }
+}
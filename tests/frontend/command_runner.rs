@@ -0,0 +1,27 @@
+use std::collections::VecDeque;
+
+use iced::Command;
+use iced_runtime::command::Action;
+use tokio::runtime::Runtime;
+
+/// Passes a message to an update function and runs the returned command
+pub fn run<M>(mut update: impl FnMut(M) -> Command<M>, message: M) {
+    let command = update(message);
+    run_command(update, command);
+}
+
+/// Runs the futures of a command on a tokio runtime and passes their messages to the update function,
+/// until no further command is returned. The messages are handled in the order the futures finish
+pub fn run_command<M>(mut update: impl FnMut(M) -> Command<M>, command: Command<M>) {
+    let runtime = Runtime::new().unwrap();
+    let mut commands = VecDeque::from([command]);
+
+    while let Some(command) = commands.pop_front() {
+        for action in command.actions() {
+            if let Action::Future(future) = action {
+                let message = runtime.block_on(future);
+                commands.push_back(update(message));
+            }
+        }
+    }
+}
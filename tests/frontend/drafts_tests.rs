@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod tests {
+    use secse24_group08::frontend::{chat_layout::ChatId, drafts::Drafts};
+
+    #[test]
+    fn test_set_draft() {
+        let mut drafts = Drafts::default();
+
+        drafts.set(ChatId::User(2), "Hello");
+        drafts.set(ChatId::Group(2), "Hello group");
+        drafts.set(ChatId::User(2), "Hello again");
+        assert_eq!(drafts.get(ChatId::User(2)), Some("Hello again"));
+        assert_eq!(drafts.get(ChatId::Group(2)), Some("Hello group"));
+        assert_eq!(drafts.drafts().len(), 2);
+
+        // A blank text removes the draft
+        drafts.set(ChatId::User(2), "  ");
+        assert!(!drafts.contains(ChatId::User(2)));
+        assert_eq!(drafts.drafts().len(), 1);
+    }
+
+    #[test]
+    fn test_merge_drafts() {
+        let mut drafts = Drafts::default();
+        drafts.set(ChatId::User(2), "Newer");
+
+        let mut cached_drafts = Drafts::default();
+        cached_drafts.set(ChatId::User(2), "Older");
+        cached_drafts.set(ChatId::Group(1), "Cached");
+
+        // Drafts that are known already are kept
+        drafts.merge(cached_drafts);
+        assert_eq!(drafts.get(ChatId::User(2)), Some("Newer"));
+        assert_eq!(drafts.get(ChatId::Group(1)), Some("Cached"));
+    }
+}
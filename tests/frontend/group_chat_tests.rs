@@ -1,7 +1,11 @@
 #[cfg(test)]
 mod tests {
+    use std::env;
+
+    use crate::frontend::command_runner::run;
     use secse24_group08::{
         backend::{
+            crypto::key_store::KeyStore,
             database::models::{Group, GroupMessage, User},
             entities::group_message_ops::{create_group_message, delete_group_messages},
         },
@@ -24,6 +28,12 @@ mod tests {
     }
 
     fn setup_group_chat() -> GroupChat {
+        env::set_var(
+            "KEY_STORE_PATH",
+            env::temp_dir().join("secse24_group08_group_chat_keys"),
+        );
+        assert!(KeyStore::default().load_or_create_identity(1).is_ok());
+
         let mut group_chat = GroupChat::default();
         group_chat.set_properties(default_user(), default_group());
         group_chat
@@ -53,7 +63,10 @@ mod tests {
     #[test]
     fn test_send_group_message_empty() {
         let mut group_chat = GroupChat::default();
-        group_chat.update(GroupChatMessage::SendMessage);
+        run(
+            |message| group_chat.update(message),
+            GroupChatMessage::SendMessage,
+        );
         assert!(group_chat.get_messages().is_empty());
     }
 
@@ -62,8 +75,14 @@ mod tests {
         let mut group_chat = setup_group_chat();
         assert!(group_chat.get_messages().is_empty());
 
-        group_chat.update(GroupChatMessage::InputChanged("Hello, world!".to_string()));
-        group_chat.update(GroupChatMessage::SendMessage);
+        run(
+            |message| group_chat.update(message),
+            GroupChatMessage::InputChanged("Hello, world!".to_string()),
+        );
+        run(
+            |message| group_chat.update(message),
+            GroupChatMessage::SendMessage,
+        );
         assert!(!group_chat.get_messages().is_empty());
 
         let result = delete_group_messages(1, 1);
@@ -73,7 +92,10 @@ mod tests {
     #[test]
     fn test_update_input_changed() {
         let mut group_chat = GroupChat::default();
-        group_chat.update(GroupChatMessage::InputChanged("Hello, world!".to_string()));
+        run(
+            |message| group_chat.update(message),
+            GroupChatMessage::InputChanged("Hello, world!".to_string()),
+        );
         assert_eq!(group_chat.get_input_value(), "Hello, world!");
     }
 
@@ -82,10 +104,10 @@ mod tests {
         let mut group_chat = setup_group_chat();
         let old_message_count = group_chat.get_messages().len();
 
-        let group_messages = create_group_message(1, 1, "Hello, world!");
+        let group_messages = create_group_message(1, 1, "Hello, world!", None, None, None);
         assert!(group_messages.is_ok());
 
-        group_chat.update(GroupChatMessage::Tick);
+        run(|message| group_chat.update(message), GroupChatMessage::Tick);
         assert!(group_chat.get_messages().len() > old_message_count);
 
         let result = delete_group_messages(1, 1);
@@ -96,14 +118,32 @@ mod tests {
     fn test_send_group_message_special_chars() {
         let mut group_chat = setup_group_chat();
 
-        group_chat.update(GroupChatMessage::InputChanged("Hello, @world!".to_string()));
-        group_chat.update(GroupChatMessage::SendMessage);
+        run(
+            |message| group_chat.update(message),
+            GroupChatMessage::InputChanged("Hello, @world!".to_string()),
+        );
+        run(
+            |message| group_chat.update(message),
+            GroupChatMessage::SendMessage,
+        );
         assert!(!group_chat.get_messages().is_empty());
 
         let result = delete_group_messages(1, 1);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_share_history() {
+        let mut group_chat = setup_group_chat();
+
+        run(
+            |message| group_chat.update(message),
+            GroupChatMessage::ShareHistory,
+        );
+        assert!(group_chat.get_error().is_empty());
+        assert!(!group_chat.get_info().is_empty());
+    }
+
     #[test]
     fn test_handle_tick_failure() {
         let mut group_chat = setup_group_chat();
@@ -116,7 +156,7 @@ mod tests {
                 name: "Invalid Group".to_string(),
             },
         );
-        group_chat.update(GroupChatMessage::Tick);
+        run(|message| group_chat.update(message), GroupChatMessage::Tick);
         assert!(group_chat.get_messages().is_empty());
     }
 
@@ -135,8 +175,12 @@ mod tests {
         let current_message = GroupMessage {
             sender_id: 1,
             receiver_id: 1,
-            send_date: chrono::Utc::now().naive_utc(),
+            send_date: chrono::Utc::now(),
             message: "Hello, world!".to_string(),
+            key_epoch: None,
+            nonce: None,
+            edited_at: None,
+            undecryptable: false,
         };
 
         group_chat.push_message(current_message);
@@ -144,8 +188,12 @@ mod tests {
         let other_message = GroupMessage {
             sender_id: 2,
             receiver_id: 1,
-            send_date: chrono::Utc::now().naive_utc(),
+            send_date: chrono::Utc::now(),
             message: "Hello, world!".to_string(),
+            key_epoch: None,
+            nonce: None,
+            edited_at: None,
+            undecryptable: false,
         };
 
         group_chat.push_message(other_message);
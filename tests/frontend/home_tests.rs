@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use crate::frontend::command_runner::run;
     use secse24_group08::{
         backend::database::models::User,
         frontend::{
@@ -34,12 +35,15 @@ mod tests {
         let mut home = setup_home_with_user();
 
         // Check if user tab works
-        home.update(HomeMessage::Tick);
+        run(|message| home.update(message), HomeMessage::Tick);
 
-        home.update(HomeMessage::TabSelected(TabId::Group));
+        run(
+            |message| home.update(message),
+            HomeMessage::TabSelected(TabId::Group),
+        );
 
         // Check if group tab works
-        home.update(HomeMessage::Tick);
+        run(|message| home.update(message), HomeMessage::Tick);
     }
 
     #[test]
@@ -48,35 +52,59 @@ mod tests {
 
         assert_eq!(home.get_active_tab(), TabId::User);
 
-        home.update(HomeMessage::TabSelected(TabId::Group));
+        run(
+            |message| home.update(message),
+            HomeMessage::TabSelected(TabId::Group),
+        );
         assert_eq!(home.get_active_tab(), TabId::Group);
 
-        home.update(HomeMessage::TabSelected(TabId::Settings));
+        run(
+            |message| home.update(message),
+            HomeMessage::TabSelected(TabId::Settings),
+        );
         assert_eq!(home.get_active_tab(), TabId::Settings);
 
-        home.update(HomeMessage::TabSelected(TabId::User));
+        run(
+            |message| home.update(message),
+            HomeMessage::TabSelected(TabId::User),
+        );
         assert_eq!(home.get_active_tab(), TabId::User);
+
+        run(
+            |message| home.update(message),
+            HomeMessage::TabSelected(TabId::Chats),
+        );
+        assert_eq!(home.get_active_tab(), TabId::Chats);
     }
 
     #[test]
     fn test_update_user_tab_message() {
         let mut home = Home::default();
         let user_tab_message = UserTabMessage::SendFriendRequestToSelectedUser;
-        home.update(HomeMessage::UserTab(user_tab_message.clone()));
+        run(
+            |message| home.update(message),
+            HomeMessage::UserTab(user_tab_message.clone()),
+        );
     }
 
     #[test]
     fn test_update_group_tab_message() {
         let mut home = Home::default();
         let group_tab_message = GroupTabMessage::CreateGroup;
-        home.update(HomeMessage::GroupTab(group_tab_message.clone()));
+        run(
+            |message| home.update(message),
+            HomeMessage::GroupTab(group_tab_message.clone()),
+        );
     }
 
     #[test]
     fn test_update_settings_tab_message() {
         let mut home = Home::default();
         let settings_tab_message = SettingsTabMessage::UpdateEmail;
-        home.update(HomeMessage::SettingsTab(settings_tab_message.clone()));
+        run(
+            |message| home.update(message),
+            HomeMessage::SettingsTab(settings_tab_message.clone()),
+        );
     }
 
     #[test]
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use secse24_group08::i18n::{
+        format_date, local_time, message_keys, parse_time_zone, translate, Language,
+    };
+
+    #[test]
+    fn test_all_messages_are_translated() {
+        assert!(!message_keys(Language::English).is_empty());
+        assert_eq!(
+            message_keys(Language::English),
+            message_keys(Language::German)
+        );
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(translate(Language::English, "login-button", &[]), "Login");
+        assert_eq!(translate(Language::German, "login-button", &[]), "Anmelden");
+        assert_eq!(
+            translate(
+                Language::German,
+                "home-welcome",
+                &[
+                    ("first_name", "John".to_string()),
+                    ("last_name", "Doe".to_string())
+                ]
+            ),
+            "Willkommen John Doe!"
+        );
+        assert_eq!(
+            translate(Language::German, "unknown-key", &[]),
+            "unknown-key"
+        );
+    }
+
+    #[test]
+    fn test_language_from_locale() {
+        assert_eq!(Language::from_locale("de-DE"), Some(Language::German));
+        assert_eq!(Language::from_locale("de_AT.UTF-8"), Some(Language::German));
+        assert_eq!(Language::from_locale("en-US"), Some(Language::English));
+        assert_eq!(Language::from_locale("fr-FR"), None);
+    }
+
+    #[test]
+    fn test_format_date() {
+        let now = NaiveDate::from_ymd_opt(2024, 5, 27)
+            .unwrap()
+            .and_hms_opt(18, 0, 0)
+            .unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 5, 27)
+            .unwrap()
+            .and_hms_opt(15, 30, 0)
+            .unwrap();
+        let this_year = NaiveDate::from_ymd_opt(2024, 5, 24)
+            .unwrap()
+            .and_hms_opt(15, 30, 0)
+            .unwrap();
+        let last_year = NaiveDate::from_ymd_opt(2023, 5, 24)
+            .unwrap()
+            .and_hms_opt(15, 30, 0)
+            .unwrap();
+
+        assert_eq!(format_date(Language::English, today, now), "03:30 PM");
+        assert_eq!(
+            format_date(Language::English, this_year, now),
+            "Friday May 24, 03:30 PM"
+        );
+        assert_eq!(format_date(Language::German, today, now), "15:30");
+        assert_eq!(
+            format_date(Language::German, this_year, now),
+            "Freitag, 24. Mai, 15:30"
+        );
+        assert_eq!(
+            format_date(Language::German, last_year, now),
+            "Mittwoch, 24. Mai 2023, 15:30"
+        );
+    }
+
+    #[test]
+    fn test_local_time() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 24)
+            .unwrap()
+            .and_hms_opt(13, 30, 0)
+            .unwrap()
+            .and_utc();
+
+        let berlin = parse_time_zone("Europe/Berlin").unwrap();
+        assert_eq!(
+            local_time(date, Some(berlin)),
+            NaiveDate::from_ymd_opt(2024, 5, 24)
+                .unwrap()
+                .and_hms_opt(15, 30, 0)
+                .unwrap()
+        );
+        let new_york = parse_time_zone(" America/New_York ").unwrap();
+        assert_eq!(
+            local_time(date, Some(new_york)),
+            NaiveDate::from_ymd_opt(2024, 5, 24)
+                .unwrap()
+                .and_hms_opt(9, 30, 0)
+                .unwrap()
+        );
+        assert!(parse_time_zone("Mars/Olympus").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use chrono::NaiveDateTime;
+    use secse24_group08::{
+        backend::{
+            crypto::direct_message::ConversationFingerprints,
+            database::models::{User, UserMessage},
+        },
+        frontend::{
+            chat_layout::ChatId,
+            local_cache::{merge_messages, sync_start, CachedData, CachedUserChat, LocalCache},
+        },
+    };
+
+    fn setup_local_cache(name: &str) -> LocalCache {
+        let directory = env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&directory);
+        LocalCache::new(directory)
+    }
+
+    fn test_message(message: &str, send_date: &str) -> UserMessage {
+        UserMessage {
+            sender_id: 1,
+            receiver_id: 2,
+            message: message.to_string(),
+            send_date: NaiveDateTime::parse_from_str(send_date, "%Y-%m-%d %H:%M:%S")
+                .unwrap()
+                .and_utc(),
+            nonce: None,
+            edited_at: None,
+            sender_public_key: None,
+            receiver_public_key: None,
+            undecryptable: false,
+        }
+    }
+
+    fn undecryptable_message(send_date: &str) -> UserMessage {
+        UserMessage {
+            undecryptable: true,
+            ..test_message("", send_date)
+        }
+    }
+
+    fn test_data() -> CachedData {
+        let mut data = CachedData::new(User {
+            id: 1,
+            first_name: "First".to_string(),
+            last_name: "Last".to_string(),
+        });
+        data.user_chats.insert(
+            2,
+            CachedUserChat {
+                messages: vec![test_message("Hello", "2024-05-01 10:00:00")],
+                fingerprints: ConversationFingerprints {
+                    own: "own".to_string(),
+                    other: None,
+                    own_changed: false,
+                    other_changed: false,
+                },
+            },
+        );
+        data.drafts.set(ChatId::Group(1), "Unsent draft");
+        data
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let local_cache = setup_local_cache("secse24_group08_local_cache_save");
+        let cache = local_cache
+            .unlock("User@Example.com", "Password1!")
+            .unwrap();
+        assert_eq!(cache.load(), Ok(None));
+
+        let data = test_data();
+        assert!(cache.save(&data).is_ok());
+
+        // The same email and password unlock the data again
+        let cache = local_cache
+            .unlock("user@example.com", "Password1!")
+            .unwrap();
+        assert_eq!(cache.load(), Ok(Some(data)));
+
+        assert!(cache.remove().is_ok());
+        assert_eq!(cache.load(), Ok(None));
+    }
+
+    #[test]
+    fn test_older_snapshot_does_not_replace_newer_one() {
+        let local_cache = setup_local_cache("secse24_group08_local_cache_snapshots");
+        let cache = local_cache
+            .unlock("user@example.com", "Password1!")
+            .unwrap();
+
+        let older_data = test_data();
+        let older_version = cache.next_version();
+        let mut newer_data = test_data();
+        newer_data.drafts.set(ChatId::Group(1), "Newer draft");
+        let newer_version = cache.next_version();
+
+        // The newer snapshot is saved first, e.g. because the older one took longer to encrypt
+        assert!(cache
+            .clone()
+            .save_snapshot(newer_version, &newer_data)
+            .is_ok());
+        assert!(cache.save_snapshot(older_version, &older_data).is_ok());
+        assert_eq!(cache.load(), Ok(Some(newer_data)));
+    }
+
+    #[test]
+    fn test_unlock_with_exported_key() {
+        let local_cache = setup_local_cache("secse24_group08_local_cache_exported_key");
+        let cache = local_cache
+            .unlock("user@example.com", "Password1!")
+            .unwrap();
+        let exported_key = cache.export_key();
+
+        // The exported key unlocks the data without the password, also if it was saved later
+        let unlocked = local_cache
+            .unlock_with_key("user@example.com", &exported_key)
+            .unwrap();
+        let data = test_data();
+        assert!(unlocked.save(&data).is_ok());
+        assert_eq!(cache.load(), Ok(Some(data.clone())));
+        assert_eq!(
+            local_cache
+                .unlock("user@example.com", "Password1!")
+                .unwrap()
+                .load(),
+            Ok(Some(data))
+        );
+
+        assert!(local_cache
+            .unlock_with_key("user@example.com", "invalid")
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_with_wrong_password() {
+        let local_cache = setup_local_cache("secse24_group08_local_cache_password");
+        let cache = local_cache
+            .unlock("user@example.com", "Password1!")
+            .unwrap();
+        assert!(cache.save(&test_data()).is_ok());
+
+        let cache = local_cache
+            .unlock("user@example.com", "Password2!")
+            .unwrap();
+        assert_eq!(
+            cache.load(),
+            Err("The cache could not be decrypted".to_string())
+        );
+    }
+
+    #[test]
+    fn test_merge_messages() {
+        let mut messages = vec![
+            test_message("First", "2024-05-01 10:00:00"),
+            undecryptable_message("2024-05-01 10:05:00"),
+        ];
+
+        merge_messages(
+            &mut messages,
+            vec![
+                test_message("Third", "2024-05-01 10:10:00"),
+                test_message("Second", "2024-05-01 10:05:00"),
+            ],
+        );
+
+        let texts: Vec<&str> = messages.iter().map(|m| m.message.as_str()).collect();
+        assert_eq!(texts, vec!["First", "Second", "Third"]);
+        assert!(messages.iter().all(|message| !message.undecryptable));
+    }
+
+    #[test]
+    fn test_sync_start() {
+        assert_eq!(sync_start::<UserMessage>(&[]), None);
+
+        // Synced from a little before the newest message
+        let messages = vec![
+            test_message("First", "2024-05-01 10:00:00"),
+            test_message("Second", "2024-05-01 10:05:00"),
+        ];
+        assert_eq!(
+            sync_start(&messages),
+            Some(test_message("", "2024-05-01 10:04:00").send_date)
+        );
+
+        // Or from before the first message that could not be decrypted
+        let messages = vec![
+            undecryptable_message("2024-05-01 10:00:00"),
+            test_message("Second", "2024-05-01 10:05:00"),
+        ];
+        assert_eq!(
+            sync_start(&messages),
+            Some(test_message("", "2024-05-01 09:59:00").send_date)
+        );
+
+        // A message that only looks like the placeholder was decrypted
+        let messages = vec![
+            test_message(
+                "[This message could not be decrypted]",
+                "2024-05-01 10:00:00",
+            ),
+            test_message("Second", "2024-05-01 10:05:00"),
+        ];
+        assert_eq!(
+            sync_start(&messages),
+            Some(test_message("", "2024-05-01 10:04:00").send_date)
+        );
+    }
+}
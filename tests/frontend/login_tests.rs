@@ -1,6 +1,34 @@
 #[cfg(test)]
 mod tests {
-    use secse24_group08::frontend::login::{Login, LoginMessage};
+    use crate::frontend::command_runner::{run, run_command};
+    use diesel::prelude::*;
+    use secse24_group08::{
+        backend::database::{
+            db::establish_connection,
+            schema::{login_failures, pending_verifications, users},
+        },
+        backend::entities::{
+            login_throttle_ops::{
+                client_source, find_login_failure, record_failed_login, reset_failed_logins,
+                unlock_account, ACCOUNT_SCOPE, SOURCE_SCOPE,
+            },
+            pending_verification_ops::{
+                create_pending_verification, find_pending_verifications_of_user,
+            },
+            session_ops::hash_token,
+            two_factor_ops::{
+                confirm_totp_enrollment, generate_code_at, generate_current_code,
+                start_totp_enrollment,
+            },
+            user_ops::{
+                create_user, delete_user, find_user_with_password_by_id, set_user_disabled,
+            },
+        },
+        frontend::login::{Login, LoginMessage},
+    };
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    const TEST_SOURCE: &str = "login_tests";
 
     fn default_login_assertions(login: &Login) {
         assert_eq!(login.get_email(), "");
@@ -17,11 +45,28 @@ mod tests {
 
     fn setup_login_with_email_and_password(email: &str, password: &str) -> Login {
         let mut login = Login::default();
-        login.update(LoginMessage::EmailChanged(email.to_string()));
-        login.update(LoginMessage::PasswordChanged(password.to_string()));
+        run(
+            |message| login.update(message),
+            LoginMessage::EmailChanged(email.to_string()),
+        );
+        run(
+            |message| login.update(message),
+            LoginMessage::PasswordChanged(password.to_string()),
+        );
+        login.set_source(TEST_SOURCE.to_string());
         login
     }
 
+    fn clean_up_failed_logins(email: &str) {
+        assert!(reset_failed_logins(&email.to_lowercase()).is_ok());
+
+        // The source is not reset by a successful login
+        let mut connection = establish_connection().unwrap();
+        diesel::delete(login_failures::table.find((SOURCE_SCOPE, TEST_SOURCE)))
+            .execute(&mut connection)
+            .unwrap();
+    }
+
     #[test]
     fn test_default_login() {
         let login = Login::default();
@@ -31,52 +76,404 @@ mod tests {
     #[test]
     fn test_email_changed() {
         let mut login = Login::default();
-        login.update(LoginMessage::EmailChanged("test@example.com".to_string()));
+        run(
+            |message| login.update(message),
+            LoginMessage::EmailChanged("test@example.com".to_string()),
+        );
         assert_eq!(login.get_email(), "test@example.com");
     }
 
     #[test]
     fn test_password_changed() {
         let mut login = Login::default();
-        login.update(LoginMessage::PasswordChanged("password".to_string()));
+        run(
+            |message| login.update(message),
+            LoginMessage::PasswordChanged("password".to_string()),
+        );
         assert_eq!(login.get_password(), "password");
     }
 
     #[test]
     fn test_handle_login_empty_fields() {
         let mut login = Login::default();
-        login.update(LoginMessage::SubmitLogin);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
         assert_error_message(&login, "Please fill in both email and password fields.");
     }
 
     #[test]
     fn test_handle_login_invalid_email() {
         let mut login = setup_login_with_email_and_password("test", "password");
-        login.update(LoginMessage::SubmitLogin);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
         assert_error_message(
             &login,
             "Login failed. Either the email or password was incorrect.",
         );
+        clean_up_failed_logins("test");
     }
 
     #[test]
     fn test_handle_login_invalid_password() {
         let mut login = setup_login_with_email_and_password("test1@email.de", "password");
-        login.update(LoginMessage::SubmitLogin);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
         assert_error_message(
             &login,
             "Login failed. Either the email or password was incorrect.",
         );
+        clean_up_failed_logins("test1@email.de");
+    }
+
+    #[test]
+    fn test_handle_login_backoff_and_lockout() {
+        let email = "login_lockout@email.de";
+        let mut login = setup_login_with_email_and_password(email, "password");
+
+        // The first attempts are not delayed
+        for _ in 0..3 {
+            run(|message| login.update(message), LoginMessage::SubmitLogin);
+            assert_error_message(
+                &login,
+                "Login failed. Either the email or password was incorrect.",
+            );
+        }
+
+        // Further attempts are delayed, the email is not checked
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_error_message(
+            &login,
+            "Too many failed login attempts. Please try again in 1 seconds.",
+        );
+        let failure = find_login_failure(ACCOUNT_SCOPE, email).unwrap().unwrap();
+        assert_eq!(failure.failed_attempts, 3);
+        assert!(failure.locked_until.is_none());
+
+        // The account is locked after ten failed attempts
+        for _ in 0..7 {
+            assert!(record_failed_login(email, TEST_SOURCE).is_ok());
+        }
+        let failure = find_login_failure(ACCOUNT_SCOPE, email).unwrap().unwrap();
+        assert!(failure.locked_until.is_some());
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_error_message(
+            &login,
+            "Too many failed login attempts. Please try again in 900 seconds.",
+        );
+
+        // An administrator can unlock the account
+        assert!(unlock_account(email).is_ok());
+        assert!(find_login_failure(ACCOUNT_SCOPE, email).unwrap().is_none());
+
+        // Clean up
+        clean_up_failed_logins(email);
+    }
+
+    #[test]
+    fn test_login_success_keeps_source_failures() {
+        let email = "test1@email.de";
+        assert!(record_failed_login(email, TEST_SOURCE).is_ok());
+
+        let mut login = setup_login_with_email_and_password(email, "n)+L8ZVWw$qKXDQo");
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_eq!(login.get_found_user().id, 1);
+
+        // Only the account is reset, the failed attempts from the source still count
+        assert!(find_login_failure(ACCOUNT_SCOPE, email).unwrap().is_none());
+        let failure = find_login_failure(SOURCE_SCOPE, TEST_SOURCE)
+            .unwrap()
+            .unwrap();
+        assert_eq!(failure.failed_attempts, 1);
+
+        // Clean up
+        clean_up_failed_logins(email);
+    }
+
+    #[test]
+    fn test_client_source() {
+        // The source is the address the database sees, not a value of the client
+        let source = client_source().unwrap();
+        assert!(!source.is_empty());
+        assert_eq!(client_source().unwrap(), source);
     }
 
     #[test]
     fn test_handle_login_success() {
         let mut login = setup_login_with_email_and_password("test1@email.de", "n)+L8ZVWw$qKXDQo");
-        login.update(LoginMessage::SubmitLogin);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
         assert_eq!(login.get_found_user().id, 1);
         assert_eq!(login.get_error(), "");
     }
 
+    #[test]
+    fn test_handle_login_loading() {
+        let mut login = setup_login_with_email_and_password("test1@email.de", "n)+L8ZVWw$qKXDQo");
+
+        // The login runs in the background, further submissions are ignored until it has finished
+        let command = login.update(LoginMessage::SubmitLogin);
+        assert!(login.get_loading());
+        assert!(login.update(LoginMessage::SubmitLogin).actions().is_empty());
+
+        run_command(|message| login.update(message), command);
+        assert!(!login.get_loading());
+        assert_eq!(login.get_found_user().id, 1);
+    }
+
+    #[test]
+    fn test_handle_login_unverified_email() {
+        let email = "login_unverified@email.de";
+        let password = "StrongP@ssw0rd";
+        let user = create_user("John", "Doe", email, password).unwrap();
+        let (pending_verification, token) = create_pending_verification(user.id, email).unwrap();
+        assert_eq!(pending_verification.token_hash, hash_token(&token));
+
+        let mut login = setup_login_with_email_and_password(email, password);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_eq!(login.get_found_user().id, -1);
+        assert_eq!(login.get_unverified_user(), Some(&user));
+        assert_error_message(
+            &login,
+            "Please verify your email address before logging in.",
+        );
+
+        run(
+            |message| login.update(message),
+            LoginMessage::VerificationCodeChanged("invalid".to_string()),
+        );
+        run(
+            |message| login.update(message),
+            LoginMessage::SubmitVerificationCode,
+        );
+        assert_eq!(login.get_found_user().id, -1);
+        assert_error_message(&login, "Invalid verification code");
+
+        run(
+            |message| login.update(message),
+            LoginMessage::VerificationCodeChanged(token),
+        );
+        run(
+            |message| login.update(message),
+            LoginMessage::SubmitVerificationCode,
+        );
+        assert_eq!(login.get_found_user().id, user.id);
+        assert_eq!(login.get_unverified_user(), None);
+        assert_error_message(&login, "");
+
+        // Clean up
+        assert!(delete_user(user.id).is_ok());
+    }
+
+    #[test]
+    fn test_resend_verification_code_to_stored_email() {
+        let email = "login_resend@email.de";
+        let password = "StrongP@ssw0rd";
+        let user = create_user("John", "Doe", email, password).unwrap();
+        let (sent, _) = create_pending_verification(user.id, email).unwrap();
+
+        let mut login = setup_login_with_email_and_password(email, password);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_eq!(login.get_unverified_user(), Some(&user));
+
+        // No new code is sent right after the last one
+        run(
+            |message| login.update(message),
+            LoginMessage::ResendVerificationCode,
+        );
+        assert!(login.get_error().contains("Please wait"));
+        let pending_verifications = find_pending_verifications_of_user(user.id).unwrap();
+        assert_eq!(pending_verifications[0].token_hash, sent.token_hash);
+
+        // The cooldown is over once the last code is older
+        let mut connection = establish_connection().unwrap();
+        diesel::update(pending_verifications::table.find(&sent.token_hash))
+            .set(
+                pending_verifications::expires_at
+                    .eq(sent.expires_at - chrono::Duration::minutes(2)),
+            )
+            .execute(&mut connection)
+            .unwrap();
+
+        // The code is sent to the stored email, not to the one typed in afterwards
+        run(
+            |message| login.update(message),
+            LoginMessage::EmailChanged("attacker@email.de".to_string()),
+        );
+        run(
+            |message| login.update(message),
+            LoginMessage::ResendVerificationCode,
+        );
+        assert_eq!(login.get_error(), "");
+        let pending_verifications = find_pending_verifications_of_user(user.id).unwrap();
+        assert_eq!(pending_verifications.len(), 1);
+        assert_eq!(pending_verifications[0].email, email);
+        assert_ne!(pending_verifications[0].token_hash, sent.token_hash);
+
+        // Clean up
+        assert!(delete_user(user.id).is_ok());
+    }
+
+    #[test]
+    fn test_handle_login_disabled_account() {
+        let email = "login_disabled@email.de";
+        let password = "StrongP@ssw0rd";
+        let user = create_user("John", "Doe", email, password).unwrap();
+        assert!(set_user_disabled(user.id, true).is_ok());
+
+        let mut login = setup_login_with_email_and_password(email, password);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_eq!(login.get_found_user().id, -1);
+        assert_error_message(
+            &login,
+            "This account has been disabled. Please contact the operator.",
+        );
+
+        // An enabled account can log in again
+        assert!(set_user_disabled(user.id, false).is_ok());
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_eq!(login.get_found_user().id, user.id);
+
+        // Clean up
+        clean_up_failed_logins(email);
+        assert!(delete_user(user.id).is_ok());
+    }
+
+    #[test]
+    fn test_handle_login_rehashes_bcrypt_password() {
+        let email = "login_rehash@email.de";
+        let password = "StrongP@ssw0rd";
+        let user = create_user("John", "Doe", email, password).unwrap();
+
+        // Store the password as it was hashed before Argon2id was used
+        let mut connection = establish_connection().unwrap();
+        diesel::update(users::table.find(user.id))
+            .set(users::password.eq(bcrypt::hash(password, 4).unwrap()))
+            .execute(&mut connection)
+            .unwrap();
+
+        let mut login = setup_login_with_email_and_password(email, password);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_eq!(login.get_found_user().id, user.id);
+
+        let stored = find_user_with_password_by_id(user.id).unwrap().password;
+        assert!(stored.starts_with("$argon2id$"));
+
+        // The user can still log in with the new hash
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_eq!(login.get_found_user().id, user.id);
+
+        // Clean up
+        clean_up_failed_logins(email);
+        assert!(delete_user(user.id).is_ok());
+    }
+
+    #[test]
+    fn test_handle_login_two_factor() {
+        let email = "login_two_factor@email.de";
+        let password = "StrongP@ssw0rd";
+        let user = create_user("John", "Doe", email, password).unwrap();
+        let enrollment = start_totp_enrollment(user.id, email).unwrap();
+        let recovery_codes =
+            confirm_totp_enrollment(user.id, &generate_current_code(&enrollment.secret).unwrap())
+                .unwrap();
+
+        let mut login = setup_login_with_email_and_password(email, password);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_eq!(login.get_found_user().id, -1);
+        assert_eq!(login.get_two_factor_user(), Some(&user));
+        assert_error_message(&login, "");
+
+        run(
+            |message| login.update(message),
+            LoginMessage::TwoFactorCodeChanged("invalid".to_string()),
+        );
+        run(
+            |message| login.update(message),
+            LoginMessage::SubmitTwoFactorCode,
+        );
+        assert_eq!(login.get_found_user().id, -1);
+        assert_error_message(&login, "Invalid two-factor code.");
+
+        run(
+            |message| login.update(message),
+            LoginMessage::TwoFactorCodeChanged(recovery_codes[0].clone()),
+        );
+        run(
+            |message| login.update(message),
+            LoginMessage::SubmitTwoFactorCode,
+        );
+        assert_eq!(login.get_found_user().id, user.id);
+        assert_eq!(login.get_two_factor_user(), None);
+        assert_error_message(&login, "");
+
+        // A code of the authenticator app can only be used once, even while it is still valid
+        let next_code = generate_code_at(
+            &enrollment.secret,
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                + 30,
+        )
+        .unwrap();
+        for expected_user_id in [user.id, -1] {
+            let mut login = setup_login_with_email_and_password(email, password);
+            run(|message| login.update(message), LoginMessage::SubmitLogin);
+            run(
+                |message| login.update(message),
+                LoginMessage::TwoFactorCodeChanged(next_code.clone()),
+            );
+            run(
+                |message| login.update(message),
+                LoginMessage::SubmitTwoFactorCode,
+            );
+            assert_eq!(login.get_found_user().id, expected_user_id);
+        }
+
+        // A recovery code can only be used once
+        let mut login = setup_login_with_email_and_password(email, password);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        run(
+            |message| login.update(message),
+            LoginMessage::TwoFactorCodeChanged(recovery_codes[0].clone()),
+        );
+        run(
+            |message| login.update(message),
+            LoginMessage::SubmitTwoFactorCode,
+        );
+        assert_eq!(login.get_found_user().id, -1);
+        assert_error_message(&login, "Invalid two-factor code.");
+
+        // Clean up
+        clean_up_failed_logins(email);
+        assert!(delete_user(user.id).is_ok());
+    }
+
+    #[test]
+    fn test_forgot_password_empty_email() {
+        let mut login = Login::default();
+        run(
+            |message| login.update(message),
+            LoginMessage::ForgotPassword,
+        );
+        assert!(!login.get_reset_requested());
+        assert_error_message(
+            &login,
+            "Please enter your email address to reset your password.",
+        );
+    }
+
+    #[test]
+    fn test_forgot_password_unknown_email() {
+        let mut login = Login::default();
+        run(
+            |message| login.update(message),
+            LoginMessage::EmailChanged("unknown_forgot@email.de".to_string()),
+        );
+        run(
+            |message| login.update(message),
+            LoginMessage::ForgotPassword,
+        );
+        assert!(login.get_reset_requested());
+        assert_error_message(&login, "");
+    }
+
     #[test]
     fn test_handle_login_sql_injection() {
         // Attempt to inject SQL
@@ -84,13 +481,14 @@ mod tests {
             "test1@email.de' OR '1'='1", // Injected payload
             "anything' OR '1'='1",       // Injected payload
         );
-        login.update(LoginMessage::SubmitLogin);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
 
         assert_eq!(login.get_found_user().id, -1);
         assert_error_message(
             &login,
             "Login failed. Either the email or password was incorrect.",
         );
+        clean_up_failed_logins("test1@email.de' OR '1'='1");
     }
 
     #[test]
@@ -100,7 +498,7 @@ mod tests {
             "test1@email.de'; DROP TABLE users; --", // Injected payload
             "password",
         );
-        login.update(LoginMessage::SubmitLogin);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
 
         // Check that login was not successful and no table was dropped
         assert_eq!(login.get_found_user().id, -1);
@@ -108,21 +506,31 @@ mod tests {
             &login,
             "Login failed. Either the email or password was incorrect.",
         );
+        clean_up_failed_logins("test1@email.de'; DROP TABLE users; --");
     }
 
     #[test]
     fn test_handle_login_empty_inputs() {
         let mut login = Login::default();
-        login.update(LoginMessage::SubmitLogin);
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
         assert_error_message(&login, "Please fill in both email and password fields.");
 
-        login.update(LoginMessage::EmailChanged("test".to_string()));
-        login.update(LoginMessage::SubmitLogin);
+        run(
+            |message| login.update(message),
+            LoginMessage::EmailChanged("test".to_string()),
+        );
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
         assert_error_message(&login, "Please fill in both email and password fields.");
 
-        login.update(LoginMessage::EmailChanged("".to_string()));
-        login.update(LoginMessage::PasswordChanged("password".to_string()));
-        login.update(LoginMessage::SubmitLogin);
+        run(
+            |message| login.update(message),
+            LoginMessage::EmailChanged("".to_string()),
+        );
+        run(
+            |message| login.update(message),
+            LoginMessage::PasswordChanged("password".to_string()),
+        );
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
         assert_error_message(&login, "Please fill in both email and password fields.");
     }
 
@@ -1,7 +1,22 @@
 pub mod app_tests;
+pub mod chat_export_tests;
+pub mod chat_layout_tests;
+pub mod command_runner;
+pub mod drafts_tests;
 pub mod group_chat_tests;
 pub mod home_tests;
+pub mod i18n_tests;
+pub mod local_cache_tests;
 pub mod login_tests;
+pub mod notification_tests;
+pub mod outbox_tests;
+pub mod preferences_tests;
+pub mod quick_switcher_tests;
 pub mod register_tests;
+pub mod reset_password_tests;
+pub mod session_store_tests;
+pub mod shortcuts_tests;
 pub mod tabs_home;
+pub mod task_tests;
+pub mod theme_tests;
 pub mod user_chat_tests;
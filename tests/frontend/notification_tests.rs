@@ -0,0 +1,222 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveTime, Utc};
+
+    use secse24_group08::backend::{
+        database::models::{Group, NotificationSettings, User},
+        entities::{
+            group_ops::{create_group, delete_group},
+            notification_settings_ops::{find_notification_settings, save_notification_settings},
+            user_group_ops::create_user_group,
+        },
+    };
+    use secse24_group08::frontend::notification::{
+        activity::{
+            is_mentioned, load_activity, notifications, Activity, ActivitySnapshot, ActivityWatcher,
+        },
+        memory_notifier::MemoryNotifier,
+        notifier::{Notification, Notifier},
+    };
+    use secse24_group08::frontend::preferences::NotificationPreferences;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn user(id: i32, first_name: &str) -> User {
+        User {
+            id,
+            first_name: first_name.to_string(),
+            last_name: "Doe".to_string(),
+        }
+    }
+
+    fn snapshot(messages: Vec<Activity>, friend_requests: Vec<User>) -> ActivitySnapshot {
+        let now = Utc::now();
+
+        ActivitySnapshot {
+            checked_at: now,
+            newest_message: (!messages.is_empty()).then_some(now),
+            messages,
+            friend_requests,
+            group_invites: vec![Group {
+                id: 1,
+                name: "Test group".to_string(),
+            }],
+            settings: NotificationSettings::new(1),
+        }
+    }
+
+    fn direct_message(sender_id: i32) -> Activity {
+        Activity::DirectMessage {
+            sender_id,
+            sender_name: "Jane Doe".to_string(),
+            message: "Hello".to_string(),
+        }
+    }
+
+    fn group_message(group_id: i32, mention: bool) -> Activity {
+        Activity::GroupMessage {
+            group_id,
+            group_name: "Test group".to_string(),
+            sender_name: "Jane Doe".to_string(),
+            message: "Hello @John".to_string(),
+            mention,
+        }
+    }
+
+    #[test]
+    fn test_memory_notifier_keeps_notifications() {
+        let notifier = MemoryNotifier::default();
+        let notification = Notification {
+            summary: "Summary".to_string(),
+            body: "Body".to_string(),
+        };
+
+        assert!(notifier.notify(&notification).is_ok());
+        assert_eq!(notifier.clone().notifications(), vec![notification]);
+    }
+
+    #[test]
+    fn test_watcher_only_notifies_new_activity() {
+        let mut watcher = ActivityWatcher::default();
+        assert!(watcher.start_check());
+        assert!(!watcher.start_check());
+
+        // The first check only remembers the current state
+        let first = snapshot(vec![direct_message(2)], vec![user(2, "Jane")]);
+        assert!(watcher.update(&first).is_empty());
+        assert_eq!(watcher.since(), Some(first.checked_at));
+        assert!(watcher.start_check());
+
+        // Known friend requests and invites are not notified again
+        let second = snapshot(
+            vec![direct_message(2)],
+            vec![user(2, "Jane"), user(3, "Max")],
+        );
+        let activities = watcher.update(&second);
+        assert_eq!(
+            activities,
+            vec![
+                direct_message(2),
+                Activity::FriendRequest {
+                    user_id: 3,
+                    name: "Max Doe".to_string(),
+                }
+            ]
+        );
+        assert_eq!(watcher.since(), second.newest_message);
+    }
+
+    #[test]
+    fn test_do_not_disturb_schedule() {
+        let mut settings = NotificationSettings::new(1);
+        assert!(!settings.is_do_not_disturb(time(23, 0)));
+
+        // The default schedule spans midnight
+        settings.dnd_enabled = true;
+        assert!(settings.is_do_not_disturb(time(23, 0)));
+        assert!(settings.is_do_not_disturb(time(6, 59)));
+        assert!(!settings.is_do_not_disturb(time(7, 0)));
+        assert!(!settings.is_do_not_disturb(time(12, 0)));
+
+        settings.dnd_start = time(12, 0);
+        settings.dnd_end = time(13, 0);
+        assert!(settings.is_do_not_disturb(time(12, 30)));
+        assert!(!settings.is_do_not_disturb(time(23, 0)));
+
+        let activities = vec![direct_message(2)];
+        assert!(notifications(
+            &activities,
+            &settings,
+            &NotificationPreferences::default(),
+            time(12, 30)
+        )
+        .is_empty());
+        assert_eq!(
+            notifications(
+                &activities,
+                &settings,
+                &NotificationPreferences::default(),
+                time(14, 0)
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_muted_conversations() {
+        let mut settings = NotificationSettings::new(1);
+        settings.muted_user_ids = vec![2];
+        settings.muted_group_ids = vec![1];
+
+        let activities = vec![
+            direct_message(2),
+            direct_message(3),
+            group_message(1, false),
+            group_message(1, true),
+        ];
+        let shown = notifications(
+            &activities,
+            &settings,
+            &NotificationPreferences::default(),
+            time(12, 0),
+        );
+
+        // Mentions are shown even if the group is muted
+        assert_eq!(shown.len(), 2);
+        assert_eq!(shown[0].summary, "New message from Jane Doe");
+        assert_eq!(shown[1].summary, "Jane Doe mentioned you in Test group");
+    }
+
+    #[test]
+    fn test_notification_preferences() {
+        let settings = NotificationSettings::new(1);
+        let activities = vec![direct_message(2)];
+
+        let mut preferences = NotificationPreferences {
+            enabled: true,
+            show_preview: false,
+        };
+        let shown = notifications(&activities, &settings, &preferences, time(12, 0));
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].summary, "New message from Jane Doe");
+        assert_eq!(shown[0].body, "Open the chat to read the message");
+
+        preferences.enabled = false;
+        assert!(notifications(&activities, &settings, &preferences, time(12, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_is_mentioned() {
+        let john = user(1, "John");
+        assert!(is_mentioned("Hello @john!", &john));
+        assert!(!is_mentioned("Hello John!", &john));
+    }
+
+    #[test]
+    fn test_save_and_find_notification_settings() {
+        let mut settings = find_notification_settings(1).unwrap();
+        settings.dnd_enabled = true;
+        settings.muted_user_ids = vec![2];
+        assert!(save_notification_settings(&settings).is_ok());
+        assert_eq!(find_notification_settings(1).unwrap(), settings);
+
+        // Clean up
+        assert!(save_notification_settings(&NotificationSettings::new(1)).is_ok());
+    }
+
+    #[test]
+    fn test_load_activity_finds_group_invites() {
+        let group = create_group("Notification Group").unwrap();
+        assert!(create_user_group(1, group.id, false).is_ok());
+
+        let snapshot = load_activity(&user(1, "John"), None).unwrap();
+        assert!(snapshot.messages.is_empty());
+        assert!(snapshot.group_invites.contains(&group));
+
+        // Clean up
+        assert!(delete_group(group.id).is_ok());
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Local};
+    use secse24_group08::frontend::outbox::{Outbox, OutboxMessage, OutboxRecipient, OutboxStatus};
+
+    #[test]
+    fn test_record_failure_backs_off() {
+        let mut message = OutboxMessage::new(1, OutboxRecipient::User(2), "Hello");
+        let now = Local::now().naive_local();
+        assert!(message.is_due(now));
+
+        message.record_failure(now);
+        assert_eq!(message.next_attempt, now + Duration::seconds(5));
+        assert!(!message.is_due(now));
+
+        message.record_failure(now);
+        assert_eq!(message.next_attempt, now + Duration::seconds(10));
+
+        // After too many attempts the message has failed until it is retried
+        for _ in 0..3 {
+            message.record_failure(now);
+        }
+        assert_eq!(message.status, OutboxStatus::Failed);
+        assert!(!message.is_due(now + Duration::days(1)));
+
+        message.retry(now);
+        assert_eq!(message.status, OutboxStatus::Pending);
+        assert!(message.is_due(now));
+    }
+
+    #[test]
+    fn test_outbox_send_and_remove() {
+        let mut outbox = Outbox::default();
+        let user_message = OutboxMessage::new(1, OutboxRecipient::User(2), "Hello");
+        let group_message = OutboxMessage::new(1, OutboxRecipient::Group(3), "Hello group");
+        let now = Local::now().naive_local();
+
+        outbox.push(user_message.clone());
+        outbox.push(user_message.clone());
+        outbox.push(group_message.clone());
+        assert_eq!(outbox.messages().len(), 2);
+        let group_outbox = outbox.of_chat(1, OutboxRecipient::Group(3));
+        assert_eq!(group_outbox.messages().len(), 1);
+        assert_eq!(group_outbox.messages()[0], group_message);
+
+        // Messages that are being sent are not sent again
+        assert_eq!(outbox.start_sending_due(now).len(), 2);
+        assert!(outbox.start_sending_due(now).is_empty());
+
+        outbox.record_failure(&user_message.client_id, now);
+        assert!(outbox.start_sending_due(now).is_empty());
+        assert_eq!(
+            outbox.start_sending_due(now + Duration::seconds(5)).len(),
+            1
+        );
+
+        outbox.remove(&user_message.client_id);
+        outbox.remove(&group_message.client_id);
+        assert!(outbox.is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use secse24_group08::{
+        backend::entities::user_preferences_ops::{find_user_preferences, save_user_preferences},
+        frontend::{
+            home::TabId,
+            preferences::{Preferences, PreferencesStore, SyncedPreferences},
+            shortcuts::{KeyBinding, Shortcut},
+            tabs_home::setting_tab::AppTheme,
+            theme::{CustomTheme, ThemePalette},
+        },
+        i18n::Language,
+    };
+
+    fn test_preferences() -> Preferences {
+        let mut preferences = Preferences {
+            theme: AppTheme::Light,
+            font_scale: 1.25,
+            last_tab: TabId::Group,
+            sync: true,
+            language: Some(Language::German),
+            time_zone: Some("Europe/Berlin".to_string()),
+            ..Preferences::default()
+        };
+        preferences.window.width = 1280.0;
+        preferences.window.x = Some(40);
+        preferences.window.y = Some(20);
+        preferences.notifications.show_preview = false;
+        preferences
+            .shortcuts
+            .set(Shortcut::Search, KeyBinding::parse("Ctrl+Shift+F").unwrap());
+        preferences.custom_themes = vec![CustomTheme {
+            name: "Ocean".to_string(),
+            palette: ThemePalette::default(),
+        }];
+        preferences
+    }
+
+    #[test]
+    fn test_preferences_toml() {
+        let preferences = test_preferences();
+        let content = preferences.to_toml().unwrap();
+        assert_eq!(Preferences::from_toml(&content).unwrap(), preferences);
+
+        let mut preferences = test_preferences();
+        preferences.theme = AppTheme::Custom;
+        preferences.custom_theme = "Ocean".to_string();
+        let content = preferences.to_toml().unwrap();
+        let restored = Preferences::from_toml(&content).unwrap();
+        assert_eq!(
+            restored.selected_custom_theme(),
+            preferences.custom_themes.first()
+        );
+
+        // Missing values keep their defaults and an invalid font scale is clamped
+        let preferences =
+            Preferences::from_toml("font_scale = 10.0\n[window]\nwidth = 800.0\n").unwrap();
+        assert_eq!(preferences.font_scale, 3.0);
+        assert_eq!(preferences.window.width, 800.0);
+        assert_eq!(preferences.window.height, 900.0);
+        assert_eq!(preferences.theme, AppTheme::Moonfly);
+        assert!(preferences.notifications.enabled);
+
+        assert!(Preferences::from_toml("theme = \"Unknown\"").is_err());
+
+        // Preferences without custom themes can be written as well
+        let content = Preferences::default().to_toml().unwrap();
+        assert_eq!(
+            Preferences::from_toml(&content).unwrap(),
+            Preferences::default()
+        );
+    }
+
+    #[test]
+    fn test_apply_synced_preferences() {
+        let mut preferences = Preferences::default();
+        let synced = test_preferences().synced();
+        preferences.apply_synced(SyncedPreferences::from_toml(&synced.to_toml().unwrap()).unwrap());
+
+        assert_eq!(preferences.synced(), synced);
+        // The window geometry, the time zone and the sync option belong to the device
+        assert_eq!(preferences.window, Preferences::default().window);
+        assert_eq!(preferences.time_zone, None);
+        assert!(!preferences.sync);
+    }
+
+    #[test]
+    fn test_preferences_store_save_load() {
+        let directory = env::temp_dir().join("secse24_group08_preferences_store_test");
+        let _ = fs::remove_dir_all(&directory);
+        let preferences_store = PreferencesStore::new(directory.join("preferences.toml"));
+
+        // The defaults are used while nothing is stored
+        assert_eq!(preferences_store.load(), Preferences::default());
+
+        let preferences = test_preferences();
+        assert!(preferences_store.save(&preferences).is_ok());
+        assert_eq!(preferences_store.load(), preferences);
+
+        // An invalid file is ignored
+        fs::write(directory.join("preferences.toml"), "theme = [").unwrap();
+        assert_eq!(preferences_store.load(), Preferences::default());
+
+        // Clean up
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn test_save_and_find_user_preferences() {
+        let synced = test_preferences().synced().to_toml().unwrap();
+        assert!(save_user_preferences(2, &synced).is_ok());
+        assert_eq!(find_user_preferences(2).unwrap(), Some(synced));
+
+        // Saving again replaces the stored preferences
+        let synced = Preferences::default().synced().to_toml().unwrap();
+        assert!(save_user_preferences(2, &synced).is_ok());
+        assert_eq!(find_user_preferences(2).unwrap(), Some(synced));
+
+        assert_eq!(find_user_preferences(-1).unwrap(), None);
+    }
+}
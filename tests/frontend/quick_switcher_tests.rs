@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use secse24_group08::{
+        backend::database::models::ConversationSummary,
+        frontend::quick_switcher::{QuickSwitcher, QuickSwitcherMessage},
+    };
+
+    fn conversation(
+        is_group: bool,
+        id: i32,
+        name: &str,
+        last_name: Option<&str>,
+    ) -> ConversationSummary {
+        ConversationSummary {
+            is_group,
+            conversation_id: id,
+            name: name.to_string(),
+            last_name: last_name.map(str::to_string),
+            sender_id: None,
+            message: None,
+            send_date: None,
+            nonce: None,
+            sender_public_key: None,
+            receiver_public_key: None,
+            key_epoch: None,
+            unread_count: 0,
+            undecryptable: false,
+        }
+    }
+
+    fn setup_quick_switcher() -> QuickSwitcher {
+        QuickSwitcher::new(vec![
+            conversation(false, 2, "Jane", Some("Doe")),
+            conversation(true, 1, "Doe family", None),
+            conversation(false, 3, "Max", Some("Mustermann")),
+        ])
+    }
+
+    #[test]
+    fn test_filter_conversations() {
+        let mut quick_switcher = setup_quick_switcher();
+        assert_eq!(quick_switcher.matches().len(), 3);
+
+        // Chats whose name starts with the query are listed first
+        let _ = quick_switcher.update(QuickSwitcherMessage::QueryChanged("DOE".to_string()));
+        let matches = quick_switcher.matches();
+        assert_eq!(matches.len(), 2);
+        assert!(matches[0].is_group);
+        assert_eq!(matches[1].conversation_id, 2);
+
+        let _ = quick_switcher.update(QuickSwitcherMessage::QueryChanged("nobody".to_string()));
+        assert!(quick_switcher.matches().is_empty());
+        assert!(quick_switcher.selected().is_none());
+    }
+
+    #[test]
+    fn test_select_conversation() {
+        let mut quick_switcher = setup_quick_switcher();
+        assert_eq!(quick_switcher.selected().unwrap().conversation_id, 2);
+
+        let _ = quick_switcher.update(QuickSwitcherMessage::SelectNext);
+        assert!(quick_switcher.selected().unwrap().is_group);
+
+        // The selection wraps around
+        let _ = quick_switcher.update(QuickSwitcherMessage::SelectPrevious);
+        let _ = quick_switcher.update(QuickSwitcherMessage::SelectPrevious);
+        assert_eq!(quick_switcher.selected().unwrap().conversation_id, 3);
+
+        // A new query selects the first match
+        let _ = quick_switcher.update(QuickSwitcherMessage::QueryChanged("ma".to_string()));
+        assert_eq!(quick_switcher.get_query(), "ma");
+        assert_eq!(quick_switcher.selected().unwrap().conversation_id, 3);
+
+        let _ = quick_switcher.view();
+    }
+}
@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use crate::frontend::command_runner::run;
     use secse24_group08::{
         backend::entities::user_ops::{delete_user, find_user_by_email},
         frontend::register::{Register, RegisterMessage},
@@ -11,11 +12,26 @@ mod tests {
 
     fn submit_register(email: &str, password: &str, first_name: &str, last_name: &str) -> Register {
         let mut register = setup_register();
-        register.update(RegisterMessage::EmailChanged(email.to_string()));
-        register.update(RegisterMessage::PasswordChanged(password.to_string()));
-        register.update(RegisterMessage::FirstNameChanged(first_name.to_string()));
-        register.update(RegisterMessage::LastNameChanged(last_name.to_string()));
-        register.update(RegisterMessage::SubmitRegister);
+        run(
+            |message| register.update(message),
+            RegisterMessage::EmailChanged(email.to_string()),
+        );
+        run(
+            |message| register.update(message),
+            RegisterMessage::PasswordChanged(password.to_string()),
+        );
+        run(
+            |message| register.update(message),
+            RegisterMessage::FirstNameChanged(first_name.to_string()),
+        );
+        run(
+            |message| register.update(message),
+            RegisterMessage::LastNameChanged(last_name.to_string()),
+        );
+        run(
+            |message| register.update(message),
+            RegisterMessage::SubmitRegister,
+        );
         register
     }
 
@@ -34,7 +50,10 @@ mod tests {
     fn test_email_change() {
         let email = "test@example.com";
         let mut register = setup_register();
-        register.update(RegisterMessage::EmailChanged(email.to_string()));
+        run(
+            |message| register.update(message),
+            RegisterMessage::EmailChanged(email.to_string()),
+        );
         assert_eq!(register.get_email(), email);
     }
 
@@ -42,7 +61,10 @@ mod tests {
     fn test_password_change() {
         let password = "StrongP@ssw0rd";
         let mut register = setup_register();
-        register.update(RegisterMessage::PasswordChanged(password.to_string()));
+        run(
+            |message| register.update(message),
+            RegisterMessage::PasswordChanged(password.to_string()),
+        );
         assert_eq!(register.get_password(), password);
     }
 
@@ -50,7 +72,10 @@ mod tests {
     fn test_first_name_change() {
         let name = "John";
         let mut register = setup_register();
-        register.update(RegisterMessage::FirstNameChanged(name.to_string()));
+        run(
+            |message| register.update(message),
+            RegisterMessage::FirstNameChanged(name.to_string()),
+        );
         assert_eq!(register.get_first_name(), name);
     }
 
@@ -58,14 +83,20 @@ mod tests {
     fn test_last_name_change() {
         let name = "Doe";
         let mut register = setup_register();
-        register.update(RegisterMessage::LastNameChanged(name.to_string()));
+        run(
+            |message| register.update(message),
+            RegisterMessage::LastNameChanged(name.to_string()),
+        );
         assert_eq!(register.get_last_name(), name);
     }
 
     #[test]
     fn test_submit_register_empty_fields() {
         let mut register = setup_register();
-        register.update(RegisterMessage::SubmitRegister);
+        run(
+            |message| register.update(message),
+            RegisterMessage::SubmitRegister,
+        );
         assert_eq!(register.get_error(), "Please fill in all fields");
     }
 
@@ -87,7 +118,10 @@ mod tests {
         let register = submit_register(&email, "StrongP@ssw0rd", "John", "Doe");
 
         assert_eq!(register.get_error(), "");
-        assert_eq!(register.get_info(), "Account has been registered");
+        assert_eq!(
+            register.get_info(),
+            "Account has been registered. A verification code has been sent to your email"
+        );
 
         // Clean up
         let user = find_user_by_email(&email);
@@ -104,7 +138,10 @@ mod tests {
 
         let register = submit_register(email, password, "John", "Doe");
         assert_eq!(register.get_error(), "");
-        assert_eq!(register.get_info(), "Account has been registered");
+        assert_eq!(
+            register.get_info(),
+            "Account has been registered. A verification code has been sent to your email"
+        );
 
         let register = submit_register(email, password, "John", "Doe");
         assert_eq!(register.get_error(), "Email address already in use");
@@ -0,0 +1,192 @@
+#[cfg(test)]
+mod tests {
+    use crate::frontend::command_runner::run;
+    use secse24_group08::{
+        backend::entities::{
+            password_reset_ops::{
+                create_password_reset, find_password_resets_of_user, reset_password,
+            },
+            session_ops::hash_token,
+            user_ops::{create_user, delete_user},
+        },
+        frontend::{
+            login::{Login, LoginMessage},
+            reset_password::{ResetPassword, ResetPasswordMessage},
+        },
+    };
+
+    fn setup_reset_password() -> ResetPassword {
+        ResetPassword::default()
+    }
+
+    fn submit_reset(token: &str, new_password: &str) -> ResetPassword {
+        let mut reset_password = setup_reset_password();
+        run(
+            |message| reset_password.update(message),
+            ResetPasswordMessage::TokenChanged(token.to_string()),
+        );
+        run(
+            |message| reset_password.update(message),
+            ResetPasswordMessage::NewPasswordChanged(new_password.to_string()),
+        );
+        run(
+            |message| reset_password.update(message),
+            ResetPasswordMessage::SubmitReset,
+        );
+        reset_password
+    }
+
+    #[test]
+    fn test_initial_state() {
+        let reset_password = setup_reset_password();
+        assert_eq!(reset_password.get_email(), "");
+        assert_eq!(reset_password.get_token(), "");
+        assert_eq!(reset_password.get_new_password(), "");
+        assert_eq!(reset_password.get_error(), "");
+        assert_eq!(reset_password.get_info(), "");
+    }
+
+    #[test]
+    fn test_input_changes() {
+        let mut reset_password = setup_reset_password();
+        run(
+            |message| reset_password.update(message),
+            ResetPasswordMessage::EmailChanged("test@example.com".to_string()),
+        );
+        run(
+            |message| reset_password.update(message),
+            ResetPasswordMessage::TokenChanged("token".to_string()),
+        );
+        run(
+            |message| reset_password.update(message),
+            ResetPasswordMessage::NewPasswordChanged("password".to_string()),
+        );
+        assert_eq!(reset_password.get_email(), "test@example.com");
+        assert_eq!(reset_password.get_token(), "token");
+        assert_eq!(reset_password.get_new_password(), "password");
+    }
+
+    #[test]
+    fn test_submit_reset_empty_fields() {
+        let reset_password = submit_reset("", "");
+        assert_eq!(
+            reset_password.get_error(),
+            "Please fill in the reset code and the new password"
+        );
+    }
+
+    #[test]
+    fn test_submit_reset_weak_password() {
+        let reset_password = submit_reset("token", "weak");
+        assert_eq!(reset_password.get_error(), "Password must be at least 8 characters long and contain at least one uppercase letter, one lowercase letter, one digit, and one special character");
+    }
+
+    #[test]
+    fn test_submit_reset_invalid_token() {
+        let reset_password = submit_reset("invalid", "StrongP@ssw0rd");
+        assert_eq!(reset_password.get_error(), "Invalid reset code");
+    }
+
+    #[test]
+    fn test_submit_reset_success() {
+        let email = "reset_password_test@example.com";
+        let user = create_user("John", "Doe", email, "OldP@ssw0rd").unwrap();
+        let (password_reset, token) = create_password_reset(user.id).unwrap();
+        assert_eq!(password_reset.token_hash, hash_token(&token));
+
+        let reset_password = submit_reset(&token, "NewP@ssw0rd");
+        assert_eq!(reset_password.get_error(), "");
+        assert_eq!(
+            reset_password.get_info(),
+            "Password has been reset. You can now log in."
+        );
+        assert!(find_password_resets_of_user(user.id).unwrap().is_empty());
+
+        // The token can only be used once
+        let reset_password = submit_reset(&token, "OtherP@ssw0rd");
+        assert_eq!(reset_password.get_error(), "Invalid reset code");
+
+        let mut login = Login::default();
+        run(
+            |message| login.update(message),
+            LoginMessage::EmailChanged(email.to_string()),
+        );
+        run(
+            |message| login.update(message),
+            LoginMessage::PasswordChanged("NewP@ssw0rd".to_string()),
+        );
+        run(|message| login.update(message), LoginMessage::SubmitLogin);
+        assert_eq!(login.get_found_user().id, user.id);
+
+        // Clean up
+        assert!(delete_user(user.id).is_ok());
+    }
+
+    #[test]
+    fn test_submit_reset_twice_at_once() {
+        let user = create_user(
+            "John",
+            "Doe",
+            "reset_password_race@example.com",
+            "OldP@ssw0rd",
+        )
+        .unwrap();
+        let (_, token) = create_password_reset(user.id).unwrap();
+
+        // Only one of two submissions of the same code at the same time succeeds
+        let submissions: Vec<_> = ["NewP@ssw0rd", "OtherP@ssw0rd"]
+            .into_iter()
+            .map(|new_password| {
+                let token = token.clone();
+                std::thread::spawn(move || reset_password(&token, new_password))
+            })
+            .collect();
+        let succeeded = submissions
+            .into_iter()
+            .map(|submission| submission.join().unwrap())
+            .filter(|submission| submission.is_ok())
+            .count();
+        assert_eq!(succeeded, 1);
+
+        // Clean up
+        assert!(delete_user(user.id).is_ok());
+    }
+
+    #[test]
+    fn test_resend_reset_code_empty_email() {
+        let mut reset_password = setup_reset_password();
+        run(
+            |message| reset_password.update(message),
+            ResetPasswordMessage::ResendResetCode,
+        );
+        assert_eq!(
+            reset_password.get_error(),
+            "Please enter your email address."
+        );
+    }
+
+    #[test]
+    fn test_resend_reset_code_unknown_email() {
+        let mut reset_password = setup_reset_password();
+        run(
+            |message| reset_password.update(message),
+            ResetPasswordMessage::EmailChanged("unknown_reset@example.com".to_string()),
+        );
+        run(
+            |message| reset_password.update(message),
+            ResetPasswordMessage::ResendResetCode,
+        );
+        assert_eq!(reset_password.get_error(), "");
+        assert_eq!(
+            reset_password.get_info(),
+            "If an account exists for this email, a reset code has been sent."
+        );
+    }
+
+    #[test]
+    fn test_view() {
+        let mut reset_password = setup_reset_password();
+        reset_password.set_info("Info message".to_string());
+        let _ = reset_password.view();
+    }
+}
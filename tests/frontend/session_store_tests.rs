@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use secse24_group08::frontend::session_store::SessionStore;
+
+    #[test]
+    fn test_session_store_save_load_clear() {
+        let directory = env::temp_dir().join("secse24_group08_session_store_test");
+        let _ = fs::remove_dir_all(&directory);
+        let session_store = SessionStore::new(directory.join("session"));
+
+        assert!(session_store.load().is_none());
+
+        assert!(session_store.save("token1").is_ok());
+        assert_eq!(session_store.load(), Some("token1".to_string()));
+
+        // A new token replaces the old one
+        assert!(session_store.save("token2").is_ok());
+        assert_eq!(session_store.load(), Some("token2".to_string()));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = fs::metadata(directory.join("session")).unwrap();
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+
+        // The key of the local cache is stored beside the token and removed with it
+        assert!(session_store.load_cache_key().is_none());
+        assert!(session_store.save_cache_key("salt:key").is_ok());
+        assert_eq!(session_store.load_cache_key(), Some("salt:key".to_string()));
+
+        assert!(session_store.clear().is_ok());
+        assert!(session_store.load().is_none());
+        assert!(session_store.load_cache_key().is_none());
+        assert!(session_store.clear().is_ok());
+
+        // Clean up
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use iced::keyboard::{key::Named, Key, Modifiers};
+    use secse24_group08::frontend::shortcuts::{KeyBinding, Shortcut, Shortcuts};
+
+    #[test]
+    fn test_parse_key_binding() {
+        let binding = KeyBinding::parse("ctrl + k").unwrap();
+        assert_eq!(binding.to_string(), "Ctrl+K");
+
+        let binding = KeyBinding::parse("Ctrl+Shift+F").unwrap();
+        assert_eq!(binding.to_string(), "Ctrl+Shift+F");
+
+        let binding = KeyBinding::parse("esc").unwrap();
+        assert_eq!(binding.to_string(), "Esc");
+
+        // A character without Ctrl or Alt would be triggered while typing
+        assert!(KeyBinding::parse("K").is_err());
+        assert!(KeyBinding::parse("Shift+K").is_err());
+        assert!(KeyBinding::parse("Ctrl+").is_err());
+        assert!(KeyBinding::parse("Super+K").is_err());
+        assert!(KeyBinding::parse("Ctrl+Insert").is_err());
+    }
+
+    #[test]
+    fn test_find_shortcut() {
+        let shortcuts = Shortcuts::default();
+
+        assert_eq!(
+            shortcuts.find(&Key::Character("k".into()), Modifiers::COMMAND),
+            Some(Shortcut::QuickSwitcher)
+        );
+        assert_eq!(
+            shortcuts.find(&Key::Character("K".into()), Modifiers::COMMAND),
+            Some(Shortcut::QuickSwitcher)
+        );
+        assert_eq!(
+            shortcuts.find(&Key::Character("2".into()), Modifiers::COMMAND),
+            Some(Shortcut::FriendsTab)
+        );
+        assert_eq!(
+            shortcuts.find(&Key::Named(Named::Escape), Modifiers::empty()),
+            Some(Shortcut::Back)
+        );
+        assert_eq!(
+            shortcuts.find(&Key::Named(Named::ArrowUp), Modifiers::empty()),
+            Some(Shortcut::EditLastMessage)
+        );
+
+        // The modifiers have to match exactly
+        assert_eq!(
+            shortcuts.find(&Key::Character("k".into()), Modifiers::empty()),
+            None
+        );
+        assert_eq!(
+            shortcuts.find(
+                &Key::Character("k".into()),
+                Modifiers::COMMAND | Modifiers::SHIFT
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rebind_shortcut() {
+        let mut shortcuts = Shortcuts::default();
+        shortcuts.set(Shortcut::Search, KeyBinding::parse("Alt+S").unwrap());
+        assert!(shortcuts.check_conflicts().is_ok());
+        assert_eq!(
+            shortcuts.find(&Key::Character("s".into()), Modifiers::ALT),
+            Some(Shortcut::Search)
+        );
+        assert_eq!(
+            shortcuts.find(&Key::Character("f".into()), Modifiers::COMMAND),
+            None
+        );
+
+        // Two shortcuts cannot have the same binding
+        shortcuts.set(Shortcut::Back, KeyBinding::parse("Ctrl+K").unwrap());
+        assert!(shortcuts.check_conflicts().is_err());
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use crate::frontend::command_runner::{run, run_command};
+    use secse24_group08::{
+        backend::database::models::{ConversationSummary, User},
+        frontend::tabs_home::chat_tab::{ChatTab, ChatTabMessage},
+    };
+
+    fn setup_chat_tab() -> ChatTab {
+        let mut chat_tab = ChatTab::default();
+        chat_tab.set_current_user(User {
+            id: 1,
+            first_name: "John".to_string(),
+            last_name: "Doe".to_string(),
+        });
+        chat_tab
+    }
+
+    fn create_summary(conversation_id: i32, unread_count: i64) -> ConversationSummary {
+        ConversationSummary {
+            is_group: false,
+            conversation_id,
+            name: "Jane".to_string(),
+            last_name: Some("Doe".to_string()),
+            sender_id: None,
+            message: None,
+            send_date: None,
+            nonce: None,
+            sender_public_key: None,
+            receiver_public_key: None,
+            key_epoch: None,
+            unread_count,
+            undecryptable: false,
+        }
+    }
+
+    #[test]
+    fn test_chat_tab_default() {
+        let chat_tab = ChatTab::default();
+        assert!(chat_tab.get_loading());
+        assert!(chat_tab.get_conversations().is_empty());
+    }
+
+    #[test]
+    fn test_conversations_loaded() {
+        let mut chat_tab = setup_chat_tab();
+        let conversations = vec![create_summary(2, 3)];
+
+        // The result of the started load is shown
+        let _ = chat_tab.update(ChatTabMessage::Tick);
+        run(
+            |message| chat_tab.update(message),
+            ChatTabMessage::ConversationsLoaded(1, Ok(conversations.clone())),
+        );
+        assert!(!chat_tab.get_loading());
+        assert_eq!(chat_tab.get_conversations(), conversations.as_slice());
+        assert_eq!(chat_tab.get_conversations()[0].display_name(), "Jane Doe");
+        assert_eq!(chat_tab.get_conversations()[0].user().unwrap().id, 2);
+
+        // The shown chats are kept if they could not be loaded
+        let _ = chat_tab.update(ChatTabMessage::Tick);
+        run(
+            |message| chat_tab.update(message),
+            ChatTabMessage::ConversationsLoaded(2, Err("Error".to_string())),
+        );
+        assert_eq!(chat_tab.get_conversations(), conversations.as_slice());
+        assert_eq!(chat_tab.get_error_message(), "Chats could not be loaded");
+
+        // Results of older loads are dropped
+        let _ = chat_tab.update(ChatTabMessage::Tick);
+        run(
+            |message| chat_tab.update(message),
+            ChatTabMessage::ConversationsLoaded(2, Ok(vec![])),
+        );
+        assert_eq!(chat_tab.get_conversations(), conversations.as_slice());
+        assert!(chat_tab.get_load().is_in_flight());
+    }
+
+    #[test]
+    fn test_tick_loads_conversations() {
+        let mut chat_tab = setup_chat_tab();
+
+        // Ticks are skipped while the chats are loaded
+        let command = chat_tab.update(ChatTabMessage::Tick);
+        assert!(chat_tab.get_load().is_in_flight());
+        assert!(chat_tab.update(ChatTabMessage::Tick).actions().is_empty());
+
+        run_command(|message| chat_tab.update(message), command);
+        assert!(!chat_tab.get_load().is_in_flight());
+        assert!(!chat_tab.get_loading());
+        assert_eq!(chat_tab.get_error_message(), "");
+    }
+}
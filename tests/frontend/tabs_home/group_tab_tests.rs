@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use crate::frontend::command_runner::{run, run_command};
     use secse24_group08::{
         backend::{
             database::models::{Group, User},
@@ -8,7 +9,7 @@ mod tests {
                 user_ops::find_all_user,
             },
         },
-        frontend::tabs_home::group_tab::{GroupTab, GroupTabMessage},
+        frontend::tabs_home::group_tab::{GroupTab, GroupTabData, GroupTabMessage},
     };
 
     // Helper function to create a user
@@ -56,16 +57,22 @@ mod tests {
         let mut group_tab = GroupTab::default();
         let message = GroupTabMessage::GroupNameChanged("New Group".to_string());
 
-        group_tab.update(message);
+        run(|message| group_tab.update(message), message);
         assert_eq!(group_tab.get_new_group_name(), "New Group");
     }
 
     #[test]
     fn test_create_group() {
         let mut group_tab = setup_group_tab_with_user(1, "John", "Doe");
-        group_tab.update(GroupTabMessage::GroupNameChanged("Test Group".to_string()));
+        run(
+            |message| group_tab.update(message),
+            GroupTabMessage::GroupNameChanged("Test Group".to_string()),
+        );
 
-        group_tab.update(GroupTabMessage::CreateGroup);
+        run(
+            |message| group_tab.update(message),
+            GroupTabMessage::CreateGroup,
+        );
 
         assert_eq!(group_tab.get_error(), "");
         assert!(!group_tab.get_groups_of_user().is_empty());
@@ -79,7 +86,10 @@ mod tests {
         let mut group_tab = GroupTab::default();
         let user = create_test_user(2, "John", "Doe");
 
-        group_tab.update(GroupTabMessage::UserSelected(user.clone()));
+        run(
+            |message| group_tab.update(message),
+            GroupTabMessage::UserSelected(user.clone()),
+        );
         let result = group_tab.get_selected_user();
 
         assert!(result.is_some());
@@ -90,7 +100,10 @@ mod tests {
         let mut group_tab = GroupTab::default();
         let group = create_test_group("Test Group");
 
-        group_tab.update(GroupTabMessage::ChatWithGroup(group.clone()));
+        run(
+            |message| group_tab.update(message),
+            GroupTabMessage::ChatWithGroup(group.clone()),
+        );
         let result = group_tab.get_group_to_chat_with();
 
         assert!(result.is_some());
@@ -103,7 +116,10 @@ mod tests {
         let mut group_tab = setup_group_tab_with_user(1, "John", "Doe");
         let group = create_test_group("Test Group");
 
-        group_tab.update(GroupTabMessage::AcceptGroup(group.clone()));
+        run(
+            |message| group_tab.update(message),
+            GroupTabMessage::AcceptGroup(group.clone()),
+        );
         assert!(!group_tab.get_groups_of_user().is_empty());
         assert_eq!(group_tab.get_groups_of_user()[0], group);
 
@@ -116,7 +132,10 @@ mod tests {
         let group = create_test_group("Test Group");
 
         group_tab.push_groups_of_user(group.clone());
-        group_tab.update(GroupTabMessage::LeaveGroup(group.clone()));
+        run(
+            |message| group_tab.update(message),
+            GroupTabMessage::LeaveGroup(group.clone()),
+        );
 
         assert!(group_tab.get_groups_of_user().is_empty());
     }
@@ -126,10 +145,16 @@ mod tests {
         let mut group_tab = setup_group_tab_with_user(1, "John", "Doe");
         let invited_user = create_test_user(2, "Jane", "Doe");
 
-        group_tab.update(GroupTabMessage::UserSelected(invited_user.clone()));
+        run(
+            |message| group_tab.update(message),
+            GroupTabMessage::UserSelected(invited_user.clone()),
+        );
         let group = create_test_group("Test Group");
 
-        group_tab.update(GroupTabMessage::InviteUserToGroup(group.clone()));
+        run(
+            |message| group_tab.update(message),
+            GroupTabMessage::InviteUserToGroup(group.clone()),
+        );
         assert_eq!(
             group_tab.get_info(),
             "User Jane Doe invited to group Test Group"
@@ -146,7 +171,21 @@ mod tests {
         group_tab.push_groups_of_user(group.clone());
         let users = find_all_user().expect("Failed to find users");
 
-        group_tab.update(GroupTabMessage::Tick(users));
+        // Ticks are skipped while the groups are loaded, data of other loads is dropped
+        let command = group_tab.update(GroupTabMessage::Tick(users.clone()));
+        assert!(group_tab.get_load().is_in_flight());
+        assert!(group_tab
+            .update(GroupTabMessage::Tick(users.clone()))
+            .actions()
+            .is_empty());
+        run(
+            |message| group_tab.update(message),
+            GroupTabMessage::DataLoaded(0, users, Ok(GroupTabData::default())),
+        );
+        assert!(group_tab.get_load().is_in_flight());
+
+        run_command(|message| group_tab.update(message), command);
+        assert!(!group_tab.get_load().is_in_flight());
         assert_eq!(group_tab.get_groups_of_user().len(), 1);
 
         clean_up_group(group.id);
@@ -157,7 +196,10 @@ mod tests {
         let mut group_tab = setup_group_tab_with_user(1, "John", "Doe");
         let users = find_all_user().expect("Failed to find users");
 
-        group_tab.update(GroupTabMessage::Tick(users));
+        run(
+            |message| group_tab.update(message),
+            GroupTabMessage::Tick(users),
+        );
         group_tab.set_error("Test Error".to_string());
         group_tab.set_info("Test Info".to_string());
 
@@ -1,3 +1,4 @@
+pub mod chat_tab_tests;
 pub mod group_tab_tests;
 pub mod setting_tab_tests;
 pub mod user_tab_tests;
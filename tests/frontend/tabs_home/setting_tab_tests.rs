@@ -1,9 +1,32 @@
 #[cfg(test)]
 mod tests {
+    use crate::frontend::command_runner::{run, run_command};
     use secse24_group08::{
-        backend::{database::models::User, entities::user_ops::create_user},
-        frontend::tabs_home::setting_tab::{AppTheme, SettingTab, SettingsTabMessage},
+        backend::{
+            crypto::password::verify_password,
+            database::models::User,
+            entities::{
+                pending_verification_ops::{
+                    create_pending_verification, find_pending_verifications_of_user,
+                },
+                session_ops::{create_session, is_session_active},
+                two_factor_ops::{
+                    count_recovery_codes, generate_code_at, generate_current_code, is_totp_enabled,
+                },
+                user_ops::{
+                    create_user, delete_user, find_user_by_email, find_user_with_password_by_id,
+                },
+            },
+        },
+        frontend::{
+            preferences::Preferences,
+            shortcuts::{Shortcut, Shortcuts},
+            tabs_home::setting_tab::{AppTheme, SettingTab, SettingsTabMessage},
+            theme::ThemeColor,
+        },
+        i18n::Language,
     };
+    use std::time::{SystemTime, UNIX_EPOCH};
 
     fn create_default_setting_tab() -> SettingTab {
         SettingTab::default()
@@ -54,30 +77,202 @@ mod tests {
     }
 
     #[test]
-    fn test_update_password_success() {
+    fn test_change_language() {
         let mut setting_tab = create_default_setting_tab();
-        let user = create_test_user();
-        set_up_user_in_tab(&mut setting_tab, user);
+        assert_eq!(setting_tab.get_language(), None);
+
+        let _ = setting_tab.update(SettingsTabMessage::ChangeLanguage(Some(Language::German)));
+        assert_eq!(setting_tab.get_language(), Some(Language::German));
 
-        setting_tab.update(SettingsTabMessage::NewPasswordInputChanged(
-            "wta3xr{F)o{uDh$w".to_string(),
+        setting_tab.set_preferences(&Preferences::default());
+        assert_eq!(setting_tab.get_language(), None);
+    }
+
+    #[test]
+    fn test_save_time_zone() {
+        let mut setting_tab = create_default_setting_tab();
+
+        let _ = setting_tab.update(SettingsTabMessage::TimeZoneInputChanged(
+            "Mars/Olympus".to_string(),
         ));
-        setting_tab.update(SettingsTabMessage::CurrentPasswordInputChanged(
-            "n)+L8ZVWw$qKXDQo".to_string(),
+        let _ = setting_tab.update(SettingsTabMessage::SaveTimeZone);
+        assert_ne!(setting_tab.get_error(), "");
+        assert_eq!(setting_tab.get_time_zone(), None);
+
+        let _ = setting_tab.update(SettingsTabMessage::TimeZoneInputChanged(
+            "Europe/Berlin".to_string(),
         ));
-        setting_tab.update(SettingsTabMessage::UpdatePassword);
+        let _ = setting_tab.update(SettingsTabMessage::SaveTimeZone);
+        assert_eq!(setting_tab.get_error(), "");
+        assert_eq!(setting_tab.get_time_zone(), Some("Europe/Berlin"));
+
+        let _ = setting_tab.update(SettingsTabMessage::TimeZoneInputChanged(String::new()));
+        let _ = setting_tab.update(SettingsTabMessage::SaveTimeZone);
+        assert_eq!(setting_tab.get_time_zone(), None);
+    }
 
+    #[test]
+    fn test_change_font_scale() {
+        let mut setting_tab = create_default_setting_tab();
+        assert_eq!(setting_tab.get_font_scale(), 1.0);
+
+        let _ = setting_tab.update(SettingsTabMessage::ChangeFontScale(1.1));
+        let _ = setting_tab.update(SettingsTabMessage::ChangeFontScale(
+            setting_tab.get_font_scale() + 0.1,
+        ));
+        assert_eq!(setting_tab.get_font_scale(), 1.2);
+
+        // The font scale stays within the supported range
+        let _ = setting_tab.update(SettingsTabMessage::ChangeFontScale(0.1));
+        assert_eq!(setting_tab.get_font_scale(), 0.5);
+        let _ = setting_tab.update(SettingsTabMessage::ChangeFontScale(f64::NAN));
+        assert_eq!(setting_tab.get_font_scale(), 1.0);
+    }
+
+    #[test]
+    fn test_save_shortcuts() {
+        let mut setting_tab = create_default_setting_tab();
+
+        // Swapping two bindings is not a conflict
+        let _ = setting_tab.update(SettingsTabMessage::ShortcutInputChanged(
+            Shortcut::ChatsTab,
+            "Ctrl+2".to_string(),
+        ));
+        let _ = setting_tab.update(SettingsTabMessage::ShortcutInputChanged(
+            Shortcut::FriendsTab,
+            "ctrl+1".to_string(),
+        ));
+        let _ = setting_tab.update(SettingsTabMessage::SaveShortcuts);
         assert_eq!(setting_tab.get_error(), "");
-        assert_eq!(setting_tab.get_info(), "Password updated successfully");
+        assert_eq!(setting_tab.get_shortcuts().chats_tab.to_string(), "Ctrl+2");
+        assert_eq!(
+            setting_tab.get_shortcuts().friends_tab.to_string(),
+            "Ctrl+1"
+        );
 
-        // Clean up
-        setting_tab.update(SettingsTabMessage::NewPasswordInputChanged(
-            "n)+L8ZVWw$qKXDQo".to_string(),
+        // Invalid and conflicting bindings are not saved
+        let saved = setting_tab.get_shortcuts().clone();
+        let _ = setting_tab.update(SettingsTabMessage::ShortcutInputChanged(
+            Shortcut::Search,
+            "F".to_string(),
         ));
-        setting_tab.update(SettingsTabMessage::CurrentPasswordInputChanged(
-            "wta3xr{F)o{uDh$w".to_string(),
+        let _ = setting_tab.update(SettingsTabMessage::SaveShortcuts);
+        assert_ne!(setting_tab.get_error(), "");
+        assert_eq!(setting_tab.get_shortcuts(), &saved);
+
+        let _ = setting_tab.update(SettingsTabMessage::ShortcutInputChanged(
+            Shortcut::Search,
+            "Ctrl+K".to_string(),
         ));
-        setting_tab.update(SettingsTabMessage::UpdatePassword);
+        let _ = setting_tab.update(SettingsTabMessage::SaveShortcuts);
+        assert_ne!(setting_tab.get_error(), "");
+        assert_eq!(setting_tab.get_shortcuts(), &saved);
+
+        let _ = setting_tab.update(SettingsTabMessage::ResetShortcuts);
+        assert_eq!(setting_tab.get_shortcuts(), &Shortcuts::default());
+    }
+
+    #[test]
+    fn test_custom_theme_editor() {
+        let mut setting_tab = create_default_setting_tab();
+
+        // An invalid color is not previewed and cannot be saved
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::ThemeNameInputChanged("Ocean".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::ThemeColorInputChanged(ThemeColor::Primary, "blue".to_string()),
+        );
+        assert!(setting_tab.get_theme_preview().is_none());
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::SaveCustomTheme,
+        );
+        assert!(setting_tab.get_error().contains("Invalid primary color"));
+        assert!(setting_tab.get_custom_themes().is_empty());
+
+        // A valid theme is previewed, then saved and used
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::ThemeColorInputChanged(ThemeColor::Primary, "#0077be".to_string()),
+        );
+        assert!(setting_tab.get_theme_preview().is_some());
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::SaveCustomTheme,
+        );
+        assert_eq!(setting_tab.get_error(), "");
+        assert_eq!(setting_tab.get_custom_themes().len(), 1);
+        assert_eq!(setting_tab.get_custom_theme(), "Ocean");
+        assert_eq!(setting_tab.get_app_theme(), AppTheme::Custom);
+        assert!(setting_tab.get_theme_preview().is_none());
+
+        // Deleting the used theme switches back to the default theme
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::DeleteCustomTheme("Ocean".to_string()),
+        );
+        assert!(setting_tab.get_custom_themes().is_empty());
+        assert_eq!(setting_tab.get_app_theme(), AppTheme::Moonfly);
+    }
+
+    #[test]
+    fn test_update_password_success() {
+        // A dedicated user, so the shared test users keep their passwords and have no password history
+        if let Ok(user) = find_user_by_email("settings_password@email.de") {
+            assert!(delete_user(user.id).is_ok());
+        }
+        let user = create_user(
+            "John",
+            "Doe",
+            "settings_password@email.de",
+            "n)+L8ZVWw$qKXDQo",
+        )
+        .unwrap();
+        let user_id = user.id;
+        let mut setting_tab = create_default_setting_tab();
+        set_up_user_in_tab(&mut setting_tab, user);
+
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::NewPasswordInputChanged("wta3xr{F)o{uDh$w".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::CurrentPasswordInputChanged("n)+L8ZVWw$qKXDQo".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::UpdatePassword,
+        );
+
+        assert_eq!(setting_tab.get_error(), "");
+        assert_eq!(setting_tab.get_info(), "Password updated successfully");
+        let stored = find_user_with_password_by_id(user_id).unwrap();
+        assert!(verify_password("wta3xr{F)o{uDh$w", &stored.password).unwrap());
+
+        // The previous password is remembered and cannot be set again
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::NewPasswordInputChanged("n)+L8ZVWw$qKXDQo".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::CurrentPasswordInputChanged("wta3xr{F)o{uDh$w".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::UpdatePassword,
+        );
+        assert!(setting_tab.get_error().contains("used recently"));
+        let stored = find_user_with_password_by_id(user_id).unwrap();
+        assert!(verify_password("wta3xr{F)o{uDh$w", &stored.password).unwrap());
+
+        // Clean up
+        assert!(delete_user(user_id).is_ok());
+        assert!(find_user_by_email("settings_password@email.de").is_err());
     }
 
     #[test]
@@ -86,13 +281,18 @@ mod tests {
         let user = create_test_user();
         set_up_user_in_tab(&mut setting_tab, user);
 
-        setting_tab.update(SettingsTabMessage::NewPasswordInputChanged(
-            "weak".to_string(),
-        ));
-        setting_tab.update(SettingsTabMessage::CurrentPasswordInputChanged(
-            "n)+L8ZVWw$qKXDQo".to_string(),
-        ));
-        setting_tab.update(SettingsTabMessage::UpdatePassword);
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::NewPasswordInputChanged("weak".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::CurrentPasswordInputChanged("n)+L8ZVWw$qKXDQo".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::UpdatePassword,
+        );
 
         assert_eq!(setting_tab.get_error(), "Password must be at least 8 characters long and contain at least one uppercase letter, one lowercase letter, one digit, and one special character");
     }
@@ -103,52 +303,242 @@ mod tests {
         let user = create_test_user();
         set_up_user_in_tab(&mut setting_tab, user);
 
-        setting_tab.update(SettingsTabMessage::NewPasswordInputChanged(
-            "wta3xr{F)o{uDh$w".to_string(),
-        ));
-        setting_tab.update(SettingsTabMessage::CurrentPasswordInputChanged(
-            "wrong password".to_string(),
-        ));
-        setting_tab.update(SettingsTabMessage::UpdatePassword);
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::NewPasswordInputChanged("wta3xr{F)o{uDh$w".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::CurrentPasswordInputChanged("wrong password".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::UpdatePassword,
+        );
 
         assert_eq!(setting_tab.get_error(), "Old password does not match.");
     }
 
+    fn change_email(setting_tab: &mut SettingTab, user_id: i32, email: &str) {
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::EmailInputChanged(email.to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::UpdateEmail,
+        );
+
+        let pending_verifications = find_pending_verifications_of_user(user_id).unwrap();
+        let pending_verification = pending_verifications
+            .iter()
+            .find(|verification| verification.email == email)
+            .unwrap();
+        // Only the hash of the sent code is stored, so a new code replaces it
+        let (replaced, token) = create_pending_verification(user_id, email).unwrap();
+        assert_ne!(replaced.token_hash, pending_verification.token_hash);
+        assert_ne!(replaced.token_hash, token);
+
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::VerificationCodeInputChanged(token),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::VerifyEmail,
+        );
+    }
+
     #[test]
     fn test_update_email_success() {
         let mut setting_tab = create_default_setting_tab();
         let user = create_test_user();
         set_up_user_in_tab(&mut setting_tab, user);
 
-        setting_tab.update(SettingsTabMessage::EmailInputChanged(
-            "newuser@example.com".to_string(),
-        ));
-        setting_tab.update(SettingsTabMessage::UpdateEmail);
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::EmailInputChanged("newuser@example.com".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::UpdateEmail,
+        );
 
+        assert_eq!(setting_tab.get_error(), "");
+        assert_eq!(
+            setting_tab.get_info(),
+            "A verification code has been sent to newuser@example.com"
+        );
+
+        change_email(&mut setting_tab, 1, "newuser@example.com");
         assert_eq!(setting_tab.get_error(), "");
         assert_eq!(setting_tab.get_info(), "Email updated successfully");
+        assert_eq!(setting_tab.get_verification_code_value(), "");
 
         // Clean up
-        setting_tab.update(SettingsTabMessage::EmailInputChanged(
-            "test1@email.de".to_string(),
-        ));
-        setting_tab.update(SettingsTabMessage::UpdateEmail);
+        change_email(&mut setting_tab, 1, "test1@email.de");
         assert_eq!(setting_tab.get_error(), "");
     }
 
+    #[test]
+    fn test_verify_email_invalid_code() {
+        let mut setting_tab = create_default_setting_tab();
+        let user = create_test_user();
+        set_up_user_in_tab(&mut setting_tab, user);
+
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::VerifyEmail,
+        );
+        assert_eq!(setting_tab.get_error(), "Verification code cannot be empty");
+
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::VerificationCodeInputChanged("invalid".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::VerifyEmail,
+        );
+        assert_eq!(
+            setting_tab.get_error(),
+            "Error verifying email: Invalid verification code"
+        );
+    }
+
     #[test]
     fn test_update_email_failure() {
         let mut setting_tab = create_default_setting_tab();
         let user = create_test_user();
         set_up_user_in_tab(&mut setting_tab, user);
 
-        setting_tab.update(SettingsTabMessage::EmailInputChanged(
-            "invalid-email".to_string(),
-        ));
-        setting_tab.update(SettingsTabMessage::UpdateEmail);
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::EmailInputChanged("invalid-email".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::UpdateEmail,
+        );
         assert_eq!(setting_tab.get_error(), "Invalid email format");
     }
 
+    #[test]
+    fn test_two_factor_enable_and_disable() {
+        let password = "StrongP@ssw0rd";
+        let user = create_user("John", "Doe", "settings_two_factor@email.de", password).unwrap();
+        let user_id = user.id;
+        let mut setting_tab = create_default_setting_tab();
+        set_up_user_in_tab(&mut setting_tab, user);
+        let command = setting_tab.load_two_factor_status();
+        run_command(|message| setting_tab.update(message), command);
+        assert!(!setting_tab.get_two_factor_enabled());
+
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::StartTwoFactorEnrollment,
+        );
+        let enrollment = setting_tab.get_totp_enrollment().unwrap().clone();
+        assert!(enrollment.otpauth_uri.starts_with("otpauth://totp/"));
+        assert!(enrollment.otpauth_uri.contains(&enrollment.secret));
+
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::ConfirmTwoFactorEnrollment,
+        );
+        assert_eq!(setting_tab.get_error(), "Two-factor code cannot be empty");
+
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::TwoFactorCodeInputChanged(
+                generate_current_code(&enrollment.secret).unwrap(),
+            ),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::ConfirmTwoFactorEnrollment,
+        );
+        assert_eq!(setting_tab.get_error(), "");
+        assert!(setting_tab.get_two_factor_enabled());
+        assert!(setting_tab.get_totp_enrollment().is_none());
+        assert_eq!(setting_tab.get_recovery_codes().len(), 10);
+        assert_eq!(count_recovery_codes(user_id).unwrap(), 10);
+
+        // Disabling requires the current password
+        // The code that confirmed the enrolment cannot be used again, so the code of the next time step is used
+        let next_code = generate_code_at(
+            &enrollment.secret,
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                + 30,
+        )
+        .unwrap();
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::TwoFactorPasswordInputChanged("wrong".to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::TwoFactorCodeInputChanged(next_code),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::DisableTwoFactor,
+        );
+        assert_eq!(
+            setting_tab.get_error(),
+            "Error disabling two-factor authentication: Password does not match."
+        );
+        assert!(is_totp_enabled(user_id).unwrap());
+
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::TwoFactorPasswordInputChanged(password.to_string()),
+        );
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::DisableTwoFactor,
+        );
+        assert_eq!(setting_tab.get_error(), "");
+        assert!(!setting_tab.get_two_factor_enabled());
+        assert!(!is_totp_enabled(user_id).unwrap());
+        assert_eq!(count_recovery_codes(user_id).unwrap(), 0);
+
+        // Clean up
+        assert!(delete_user(user_id).is_ok());
+    }
+
+    #[test]
+    fn test_revoke_session() {
+        let user = create_user("John", "Doe", "settings_sessions@email.de", "password").unwrap();
+        let user_id = user.id;
+        let (current_session, _) = create_session(user_id, "Laptop").unwrap();
+        let (other_session, _) = create_session(user_id, "Desktop").unwrap();
+
+        let mut setting_tab = create_default_setting_tab();
+        set_up_user_in_tab(&mut setting_tab, user);
+        setting_tab.set_current_session_id(Some(current_session.id));
+        let command = setting_tab.load_sessions();
+        run_command(|message| setting_tab.update(message), command);
+        assert_eq!(setting_tab.get_sessions().len(), 2);
+        let _ = setting_tab.view();
+
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::RevokeSession(other_session.id),
+        );
+        assert_eq!(setting_tab.get_error(), "");
+        assert_eq!(setting_tab.get_info(), "Session revoked");
+        assert_eq!(setting_tab.get_sessions(), &vec![current_session.clone()]);
+        assert!(!is_session_active(other_session.id).unwrap());
+        assert!(is_session_active(current_session.id).unwrap());
+
+        // Clean up
+        assert!(delete_user(user_id).is_ok());
+    }
+
     #[test]
     fn test_delete_account() {
         let user_result = create_user("John", "Doe", "test3@email.de", "password");
@@ -158,10 +548,16 @@ mod tests {
         let mut setting_tab = create_default_setting_tab();
         set_up_user_in_tab(&mut setting_tab, user);
 
-        setting_tab.update(SettingsTabMessage::DeleteAccount);
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::DeleteAccount,
+        );
         assert!(setting_tab.get_delete_button_pressed());
 
-        setting_tab.update(SettingsTabMessage::DeleteAccount);
+        run(
+            |message| setting_tab.update(message),
+            SettingsTabMessage::DeleteAccount,
+        );
         assert!(setting_tab.get_account_deleted());
     }
 
@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use crate::frontend::command_runner::run;
 
     use secse24_group08::{
         backend::{
@@ -48,13 +49,19 @@ mod tests {
         let selected_user = create_test_user("Bob", "Doe", "friend2@mail.de", "wta3xr{F)o{uDh$w");
 
         user_tab.set_current_user(current_user.clone());
-        user_tab.update(UserTabMessage::UserSelected(selected_user.clone()));
-
-        user_tab.update(UserTabMessage::Tick(vec![
-            selected_user.clone(),
-            current_user.clone(),
-        ]));
-        user_tab.update(UserTabMessage::SendFriendRequestToSelectedUser);
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::UserSelected(selected_user.clone()),
+        );
+
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::Tick(vec![selected_user.clone(), current_user.clone()]),
+        );
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::SendFriendRequestToSelectedUser,
+        );
 
         assert!(!user_tab
             .get_all_users_not_friends()
@@ -73,10 +80,10 @@ mod tests {
         let other_user = create_test_user("Bob", "Doe", "friend4@mail.de", "wta3xr{F)o{uDh$w");
 
         user_tab.set_current_user(current_user.clone());
-        user_tab.update(UserTabMessage::Tick(vec![
-            other_user.clone(),
-            current_user.clone(),
-        ]));
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::Tick(vec![other_user.clone(), current_user.clone()]),
+        );
 
         assert!(user_tab.get_all_users_not_friends().contains(&other_user));
         assert!(!user_tab.get_all_users_not_friends().contains(&current_user));
@@ -84,6 +91,45 @@ mod tests {
         delete_friends(vec![current_user, other_user]);
     }
 
+    #[test]
+    fn test_friends_loaded_before_change_are_dropped() {
+        let mut user_tab = UserTab::default();
+
+        let current_user = create_test_user("Alice", "Doe", "friend15@mail.de", "wta3xr{F)o{uDh$w");
+        let other_user = create_test_user("Bob", "Doe", "friend16@mail.de", "wta3xr{F)o{uDh$w");
+        let users = vec![other_user.clone(), current_user.clone()];
+
+        user_tab.set_current_user(current_user.clone());
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::UserSelected(other_user.clone()),
+        );
+
+        // Ticks are skipped while the friends are loaded
+        let command = user_tab.update(UserTabMessage::Tick(users.clone()));
+        assert!(user_tab.get_load().is_in_flight());
+        assert!(user_tab
+            .update(UserTabMessage::Tick(users.clone()))
+            .actions()
+            .is_empty());
+
+        // The sent friend request is kept, the friends loaded before it are dropped
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::SendFriendRequestToSelectedUser,
+        );
+        assert!(!user_tab.get_load().is_in_flight());
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::FriendsLoaded(1, users, Ok(vec![])),
+        );
+        assert!(user_tab.get_send_friend_requests().contains(&other_user));
+        assert!(!user_tab.get_all_users_not_friends().contains(&other_user));
+        drop(command);
+
+        delete_friends(vec![current_user, other_user]);
+    }
+
     #[test]
     fn test_remove_friend() {
         let mut user_tab = UserTab::default();
@@ -100,9 +146,15 @@ mod tests {
         assert!(users.is_ok());
 
         let users = users.unwrap();
-        user_tab.update(UserTabMessage::Tick(users));
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::Tick(users),
+        );
 
-        user_tab.update(UserTabMessage::RemoveFriend(other_user.clone()));
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::RemoveFriend(other_user.clone()),
+        );
 
         assert!(user_tab.get_all_users_not_friends().contains(&other_user));
         assert!(!user_tab.get_friends().contains(&other_user));
@@ -118,18 +170,30 @@ mod tests {
         let other_user = create_test_user("Bob", "Doe", "friend8@mail.de", "wta3xr{F)o{uDh$w");
 
         user_tab.set_current_user(current_user.clone());
-        user_tab.update(UserTabMessage::Tick(vec![
-            other_user.clone(),
-            current_user.clone(),
-        ]));
-        user_tab.update(UserTabMessage::UserSelected(other_user.clone()));
-
-        user_tab.update(UserTabMessage::SendFriendRequestToSelectedUser);
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::Tick(vec![other_user.clone(), current_user.clone()]),
+        );
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::UserSelected(other_user.clone()),
+        );
+
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::SendFriendRequestToSelectedUser,
+        );
 
         user_tab.set_current_user(other_user.clone());
-        user_tab.update(UserTabMessage::UserSelected(current_user.clone()));
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::UserSelected(current_user.clone()),
+        );
 
-        user_tab.update(UserTabMessage::AcceptFriendRequest(current_user.clone()));
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::AcceptFriendRequest(current_user.clone()),
+        );
 
         assert!(!user_tab.get_send_friend_requests().contains(&current_user));
         assert!(user_tab.get_friends().contains(&current_user));
@@ -145,16 +209,25 @@ mod tests {
         let other_user = create_test_user("Bob", "Doe", "friend10@mail.de", "wta3xr{F)o{uDh$w");
 
         user_tab.set_current_user(current_user.clone());
-        user_tab.update(UserTabMessage::UserSelected(other_user.clone()));
-        user_tab.update(UserTabMessage::Tick(vec![
-            other_user.clone(),
-            current_user.clone(),
-        ]));
-        user_tab.update(UserTabMessage::SendFriendRequestToSelectedUser);
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::UserSelected(other_user.clone()),
+        );
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::Tick(vec![other_user.clone(), current_user.clone()]),
+        );
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::SendFriendRequestToSelectedUser,
+        );
 
         assert!(user_tab.get_send_friend_requests().contains(&other_user));
 
-        user_tab.update(UserTabMessage::RemoveFriendRequest(other_user.clone()));
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::RemoveFriendRequest(other_user.clone()),
+        );
 
         assert!(!user_tab.get_send_friend_requests().contains(&other_user));
 
@@ -169,17 +242,29 @@ mod tests {
         let other_user = create_test_user("Bob", "Doe", "friend12@mail.de", "wta3xr{F)o{uDh$w");
 
         user_tab.set_current_user(current_user.clone());
-        user_tab.update(UserTabMessage::UserSelected(other_user.clone()));
-        user_tab.update(UserTabMessage::Tick(vec![
-            other_user.clone(),
-            current_user.clone(),
-        ]));
-        user_tab.update(UserTabMessage::SendFriendRequestToSelectedUser);
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::UserSelected(other_user.clone()),
+        );
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::Tick(vec![other_user.clone(), current_user.clone()]),
+        );
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::SendFriendRequestToSelectedUser,
+        );
 
         user_tab.set_current_user(other_user.clone());
-        user_tab.update(UserTabMessage::UserSelected(current_user.clone()));
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::UserSelected(current_user.clone()),
+        );
 
-        user_tab.update(UserTabMessage::DeclineFriendRequest(current_user.clone()));
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::DeclineFriendRequest(current_user.clone()),
+        );
 
         assert!(!user_tab.get_send_friend_requests().contains(&current_user));
         assert!(!user_tab.get_friends().contains(&current_user));
@@ -196,7 +281,10 @@ mod tests {
             last_name: "Doe".to_string(),
         };
 
-        user_tab.update(UserTabMessage::UserSelected(user.clone()));
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::UserSelected(user.clone()),
+        );
 
         assert_eq!(user_tab.get_selected_user(), Some(user).as_ref());
     }
@@ -210,7 +298,10 @@ mod tests {
             last_name: "Doe".to_string(),
         };
 
-        user_tab.update(UserTabMessage::ChatWithUser(user.clone()));
+        run(
+            |message| user_tab.update(message),
+            UserTabMessage::ChatWithUser(user.clone()),
+        );
 
         assert_eq!(user_tab.get_user_to_chat_with(), Some(user).as_ref());
     }
@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod tests {
+    use secse24_group08::frontend::task::LoadTracker;
+
+    #[test]
+    fn test_load_tracker_skips_running_load() {
+        let mut load = LoadTracker::default();
+        assert!(!load.is_in_flight());
+
+        let generation = load.start().unwrap();
+        assert!(load.is_in_flight());
+        assert!(load.start().is_none());
+
+        assert!(load.finish(generation));
+        assert!(!load.is_in_flight());
+
+        // A result is only shown once
+        assert!(!load.finish(generation));
+    }
+
+    #[test]
+    fn test_load_tracker_drops_stale_results() {
+        let mut load = LoadTracker::default();
+
+        let stale = load.start().unwrap();
+        load.invalidate();
+        assert!(!load.is_in_flight());
+
+        let current = load.start().unwrap();
+        assert_ne!(stale, current);
+        assert!(!load.is_current(stale));
+        assert!(!load.finish(stale));
+        assert!(load.is_in_flight());
+        assert!(load.finish(current));
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use iced::{Color, Theme};
+    use secse24_group08::frontend::theme::{
+        contrast_ratio, detect_system_dark_mode, high_contrast_theme, parse_color, CustomTheme,
+        StatusColors, ThemeColor, ThemePalette, MIN_CONTRAST_RATIO,
+    };
+
+    fn test_theme() -> CustomTheme {
+        let mut palette = ThemePalette::default();
+        palette.set(ThemeColor::Background, "#1e1e2e".to_string());
+        CustomTheme {
+            name: "Mocha".to_string(),
+            palette,
+        }
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#ff0000").unwrap(), Color::from_rgb8(255, 0, 0));
+        assert_eq!(parse_color("1E1E2E").unwrap(), Color::from_rgb8(30, 30, 46));
+        assert!(parse_color("#fff").is_err());
+        assert!(parse_color("#gg0000").is_err());
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((contrast_ratio(Color::BLACK, Color::WHITE) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(Color::WHITE, Color::BLACK) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(Color::WHITE, Color::WHITE) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_high_contrast_theme() {
+        let palette = high_contrast_theme().palette();
+
+        // Every color meets the enhanced contrast of WCAG level AAA
+        for color in [
+            palette.text,
+            palette.primary,
+            palette.success,
+            palette.danger,
+        ] {
+            assert!(contrast_ratio(color, palette.background) >= 7.0);
+        }
+    }
+
+    #[test]
+    fn test_status_colors_are_readable() {
+        let mut palette = ThemePalette::default();
+        // Pure red on black is hard to read
+        palette.set(ThemeColor::Background, "#000000".to_string());
+        palette.set(ThemeColor::Danger, "#800000".to_string());
+        let custom_theme = CustomTheme {
+            name: "Dark red".to_string(),
+            palette,
+        }
+        .to_theme()
+        .unwrap();
+
+        for theme in [
+            Theme::Moonfly,
+            Theme::Oxocarbon,
+            Theme::Dracula,
+            Theme::KanagawaWave,
+            Theme::Light,
+            Theme::Dark,
+            high_contrast_theme(),
+            custom_theme,
+        ] {
+            let background = theme.palette().background;
+            let colors = StatusColors::new(&theme);
+            assert!(contrast_ratio(colors.error, background) >= MIN_CONTRAST_RATIO);
+            assert!(contrast_ratio(colors.success, background) >= MIN_CONTRAST_RATIO);
+        }
+    }
+
+    #[test]
+    fn test_custom_theme() {
+        let theme = test_theme();
+        let palette = theme.palette.to_palette().unwrap();
+        assert_eq!(palette.background, Color::from_rgb8(30, 30, 46));
+        assert!(theme.to_theme().is_ok());
+
+        let mut unnamed = test_theme();
+        unnamed.name = " ".to_string();
+        assert!(unnamed.to_theme().is_err());
+
+        let mut invalid = test_theme();
+        invalid.palette.set(ThemeColor::Danger, "red".to_string());
+        assert!(invalid
+            .to_theme()
+            .unwrap_err()
+            .starts_with("Invalid danger color"));
+    }
+
+    #[test]
+    fn test_import_export_theme() {
+        let directory = env::temp_dir().join("secse24_group08_theme_test");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("mocha.toml");
+
+        let theme = test_theme();
+        assert!(theme.export(&path).is_ok());
+        assert_eq!(CustomTheme::import(&path).unwrap(), theme);
+
+        // Invalid themes are neither imported nor exported
+        fs::write(
+            &path,
+            "name = \"Broken\"\n[palette]\nbackground = \"#000000\"\n",
+        )
+        .unwrap();
+        assert!(CustomTheme::import(&path).is_err());
+        assert!(CustomTheme::import(directory.join("missing.toml")).is_err());
+
+        let mut invalid = test_theme();
+        invalid.palette.set(ThemeColor::Text, "#12".to_string());
+        assert!(invalid.export(directory.join("invalid.toml")).is_err());
+
+        // Clean up
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn test_detect_system_dark_mode_override() {
+        env::set_var("SYSTEM_COLOR_SCHEME", "light");
+        assert_eq!(detect_system_dark_mode(), Some(false));
+        env::set_var("SYSTEM_COLOR_SCHEME", "Dark");
+        assert_eq!(detect_system_dark_mode(), Some(true));
+        env::remove_var("SYSTEM_COLOR_SCHEME");
+    }
+}
@@ -1,7 +1,11 @@
 #[cfg(test)]
 mod tests {
+    use std::env;
+
+    use crate::frontend::command_runner::run;
     use secse24_group08::{
         backend::{
+            crypto::key_store::KeyStore,
             database::models::{User, UserMessage},
             entities::user_message_ops::{create_user_message, delete_user_message},
         },
@@ -9,6 +13,15 @@ mod tests {
     };
 
     fn setup_chat() -> UserChat {
+        // Both users need an identity key to exchange encrypted messages
+        env::set_var(
+            "KEY_STORE_PATH",
+            env::temp_dir().join("secse24_group08_user_chat_keys"),
+        );
+        let key_store = KeyStore::default();
+        assert!(key_store.load_or_create_identity(1).is_ok());
+        assert!(key_store.load_or_create_identity(2).is_ok());
+
         let current_user = User {
             id: 1,
             first_name: "John".to_string(),
@@ -36,7 +49,10 @@ mod tests {
     #[test]
     fn test_update_input_changed() {
         let mut chat = UserChat::default();
-        chat.update(UserChatMessage::InputChanged("New input".to_string()));
+        run(
+            |message| chat.update(message),
+            UserChatMessage::InputChanged("New input".to_string()),
+        );
         assert_eq!(chat.get_input_value(), "New input");
     }
 
@@ -63,8 +79,11 @@ mod tests {
         let mut chat = setup_chat();
         assert!(chat.get_messages().is_empty());
 
-        chat.update(UserChatMessage::InputChanged("Hello".to_string()));
-        chat.update(UserChatMessage::SendMessage);
+        run(
+            |message| chat.update(message),
+            UserChatMessage::InputChanged("Hello".to_string()),
+        );
+        run(|message| chat.update(message), UserChatMessage::SendMessage);
 
         assert_eq!(chat.get_messages().is_empty(), false);
         assert_eq!(chat.get_input_value(), "");
@@ -81,10 +100,11 @@ mod tests {
 
         // Attempt SQL injection in the message
         let sql_injection_payload = "'; DROP TABLE messages; --";
-        chat.update(UserChatMessage::InputChanged(
-            sql_injection_payload.to_string(),
-        ));
-        chat.update(UserChatMessage::SendMessage);
+        run(
+            |message| chat.update(message),
+            UserChatMessage::InputChanged(sql_injection_payload.to_string()),
+        );
+        run(|message| chat.update(message), UserChatMessage::SendMessage);
 
         // Ensure that the message was not added due to SQL injection protection
         assert_eq!(chat.get_messages().is_empty(), false,);
@@ -96,23 +116,148 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_send_user_message_offline() {
+        let mut chat = setup_chat();
+        chat.set_offline(true);
+
+        run(
+            |message| chat.update(message),
+            UserChatMessage::InputChanged("Later".to_string()),
+        );
+        run(|message| chat.update(message), UserChatMessage::SendMessage);
+
+        // The message is queued instead of being lost
+        assert!(chat.get_messages().is_empty());
+        assert_eq!(chat.get_input_value(), "");
+        assert_eq!(chat.get_outbox().messages().len(), 1);
+        assert_eq!(chat.get_outbox().messages()[0].message, "Later");
+
+        // And sent when the connection is back
+        chat.set_offline(false);
+        let client_id = chat.get_outbox().messages()[0].client_id.clone();
+        run(
+            |message| chat.update(message),
+            UserChatMessage::RetryMessage(client_id),
+        );
+        assert!(chat.get_outbox().is_empty());
+        assert_eq!(chat.get_messages()[0].message, "Later");
+
+        // Clean up
+        let result = delete_user_message(1, 2);
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_handle_tick() {
         let mut chat = setup_chat();
         assert!(chat.get_messages().is_empty());
 
-        let user_message = create_user_message(1, 2, "Hello, User 2!");
+        let user_message = create_user_message(1, 2, "Hello, User 2!", None, None);
         assert!(user_message.is_ok());
+        // The send date is assigned by the database, independent of the time zone of the client
+        let send_date = user_message.unwrap().send_date;
+        assert!((chrono::Utc::now() - send_date).num_minutes().abs() < 1);
 
-        chat.update(UserChatMessage::Tick);
+        run(|message| chat.update(message), UserChatMessage::Tick);
 
         assert_eq!(chat.get_messages().is_empty(), false);
+        assert_eq!(chat.get_messages()[0].send_date, send_date);
+        assert!(chat.get_fingerprints().is_some());
 
         // Clean up
         let result = delete_user_message(1, 2);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_edit_last_message() {
+        let mut chat = setup_chat();
+        let (current_user, other_user) = (
+            chat.get_other_user().clone(),
+            chat.get_current_user().clone(),
+        );
+        chat.set_properties(current_user, other_user);
+
+        assert!(create_user_message(2, 1, "Hello, Usr 1!", None, None).is_ok());
+        run(|message| chat.update(message), UserChatMessage::Tick);
+
+        // The last own message is put into the input
+        run(
+            |message| chat.update(message),
+            UserChatMessage::EditLastMessage,
+        );
+        assert!(chat.get_editing().is_some());
+        assert_eq!(chat.get_input_value(), "Hello, Usr 1!");
+
+        run(
+            |message| chat.update(message),
+            UserChatMessage::InputChanged("Hello, User 1!".to_string()),
+        );
+        run(|message| chat.update(message), UserChatMessage::SendMessage);
+
+        assert!(chat.get_editing().is_none());
+        assert!(chat.get_input_value().is_empty());
+        assert!(chat.get_outbox().is_empty());
+        let message = chat
+            .get_messages()
+            .iter()
+            .rev()
+            .find(|message| message.sender_id == 2)
+            .unwrap();
+        assert_eq!(message.message, "Hello, User 1!");
+        assert!(message.edited_at.is_some());
+
+        // Clean up
+        let result = delete_user_message(2, 1);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_search_messages() {
+        let mut chat = setup_chat();
+        for (sender_id, message) in [(1, "Hello, Jane!"), (2, "Hi John"), (1, "See you")] {
+            chat.push_message(UserMessage {
+                sender_id,
+                receiver_id: 3 - sender_id,
+                message: message.to_string(),
+                send_date: chrono::Utc::now(),
+                nonce: None,
+                edited_at: None,
+                sender_public_key: None,
+                receiver_public_key: None,
+                undecryptable: false,
+            });
+        }
+
+        run(
+            |message| chat.update(message),
+            UserChatMessage::SearchOpened,
+        );
+        assert_eq!(chat.get_search_value(), Some(""));
+        assert_eq!(chat.get_visible_messages().len(), 3);
+
+        run(
+            |message| chat.update(message),
+            UserChatMessage::SearchChanged("h".to_string()),
+        );
+        assert_eq!(chat.get_visible_messages().len(), 2);
+
+        run(
+            |message| chat.update(message),
+            UserChatMessage::SearchChanged("JOHN".to_string()),
+        );
+        assert_eq!(chat.get_visible_messages()[0].message, "Hi John");
+        let _ = chat.view();
+
+        run(
+            |message| chat.update(message),
+            UserChatMessage::SearchClosed,
+        );
+        assert_eq!(chat.get_search_value(), None);
+        assert_eq!(chat.get_visible_messages().len(), 3);
+    }
+
     #[test]
     fn test_view() {
         let mut chat = setup_chat();
@@ -121,7 +266,12 @@ mod tests {
             sender_id: 1,
             receiver_id: 2,
             message: "Hello, User 2!".to_string(),
-            send_date: chrono::Utc::now().naive_utc(),
+            send_date: chrono::Utc::now(),
+            nonce: None,
+            edited_at: None,
+            sender_public_key: None,
+            receiver_public_key: None,
+            undecryptable: false,
         };
 
         chat.push_message(current_message);
@@ -130,7 +280,12 @@ mod tests {
             sender_id: 2,
             receiver_id: 1,
             message: "Hello, User 1!".to_string(),
-            send_date: chrono::Utc::now().naive_utc(),
+            send_date: chrono::Utc::now(),
+            nonce: None,
+            edited_at: None,
+            sender_public_key: None,
+            receiver_public_key: None,
+            undecryptable: false,
         };
 
         chat.push_message(other_message);
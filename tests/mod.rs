@@ -1,2 +1,4 @@
+pub mod admin;
 pub mod backend;
+pub mod config;
 pub mod frontend;